@@ -0,0 +1,146 @@
+//! 提取性能基准，用于对照正则与处理流程的优化效果
+//!
+//! 运行：`cargo bench --bench extraction`
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use sensitive_info_extractor::core::{ExcelInfo, ExcelReader, InfoExtractor, SheetData};
+use sensitive_info_extractor::models::Config;
+use sensitive_info_extractor::utils::{extract_bank_cards, extract_id_cards, extract_phones};
+use tempfile::NamedTempFile;
+
+/// 固定的混合文本：手机号、身份证号、银行卡号与普通文字交错
+const MIXED_TEXT: &str = "客户张三来电咨询，手机13812345678，备用+86 139-0731-5286；\
+    身份证110105199003072039，银行卡6225 8801 2345 6789 已绑定。\
+    订单号20240101123456，金额1024元，请于三个工作日内回复。";
+
+fn bench_patterns(c: &mut Criterion) {
+    let mut group = c.benchmark_group("patterns");
+    group.throughput(Throughput::Bytes(MIXED_TEXT.len() as u64));
+
+    group.bench_function("extract_phones", |b| b.iter(|| extract_phones(black_box(MIXED_TEXT))));
+    group.bench_function("extract_id_cards", |b| b.iter(|| extract_id_cards(black_box(MIXED_TEXT))));
+    group.bench_function("extract_bank_cards", |b| b.iter(|| extract_bank_cards(black_box(MIXED_TEXT))));
+
+    group.finish();
+}
+
+fn bench_extractor(c: &mut Criterion) {
+    let extractor = InfoExtractor::new(Config::default());
+
+    let mut group = c.benchmark_group("extractor");
+    group.throughput(Throughput::Bytes(MIXED_TEXT.len() as u64));
+    group.bench_function("extract", |b| b.iter(|| extractor.extract(black_box(MIXED_TEXT))));
+    group.finish();
+}
+
+/// 构造 `rows` 行数据的工作表，每 4 行中有 1 行含敏感信息
+fn build_sheet(rows: usize) -> SheetData {
+    let mut data = vec![vec!["序号".to_string(), "消息内容".to_string()]];
+    data.extend((0..rows).map(|i| {
+        let text = if i % 4 == 0 {
+            MIXED_TEXT.to_string()
+        } else {
+            format!("第{}条普通消息，没有需要提取的内容", i)
+        };
+        vec![i.to_string(), text]
+    }));
+    SheetData::new(data)
+}
+
+fn bench_sheet(c: &mut Criterion) {
+    let extractor = InfoExtractor::new(Config::default());
+
+    let mut group = c.benchmark_group("sheet");
+    group.sample_size(20);
+
+    for rows in [1_000, 10_000] {
+        let sheet = build_sheet(rows);
+        group.throughput(Throughput::Elements(rows as u64));
+        group.bench_with_input(BenchmarkId::new("extract_column", rows), &sheet, |b, sheet| {
+            b.iter(|| {
+                let column = sheet.get_column_by_name("消息内容").unwrap();
+                column
+                    .iter()
+                    .map(|(_, text)| {
+                        let (phones, id_cards, bank_cards, _) = extractor.extract(text);
+                        phones.len() + id_cards.len() + bank_cards.len()
+                    })
+                    .sum::<usize>()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+/// 200 列宽表中按列名查找：线性扫描表头与缓存的列名映射对照
+fn bench_column_lookup(c: &mut Criterion) {
+    let header: Vec<String> = (0..200).map(|i| format!("列{}", i)).collect();
+    let sheet = SheetData::new(vec![header.clone()]);
+    let target = "列199";
+
+    let mut group = c.benchmark_group("column_lookup");
+    group.bench_function("linear_scan", |b| {
+        b.iter(|| header.iter().position(|name| name == black_box(target)))
+    });
+    group.bench_function("header_map", |b| b.iter(|| sheet.get_column_index(black_box(target))));
+    group.finish();
+}
+
+/// 把 `build_sheet(rows)` 写成临时 xlsx 文件，返回的 [`NamedTempFile`] 离开作用域时删除文件
+fn write_workbook(rows: usize) -> NamedTempFile {
+    let file = tempfile::Builder::new().prefix("sie_bench_").suffix(".xlsx").tempfile().unwrap();
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    for (row, values) in build_sheet(rows).rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            worksheet.write_string(row as u32, col as u16, value).unwrap();
+        }
+    }
+    workbook.save(file.path()).unwrap();
+    file
+}
+
+/// 行数统计：完整读取工作表与只读 dimension 对照
+fn bench_row_count(c: &mut Criterion) {
+    let file = write_workbook(50_000);
+    let path = file.path();
+
+    let mut group = c.benchmark_group("row_count");
+    group.sample_size(10);
+    group.bench_function("full_range", |b| {
+        b.iter(|| ExcelReader::open(path).unwrap().row_count("Sheet1").unwrap())
+    });
+    group.bench_function("dimension", |b| {
+        b.iter(|| ExcelReader::open(path).unwrap().row_count_from_dimension("Sheet1").unwrap())
+    });
+    group.finish();
+}
+
+/// 导入文件时的信息读取（列名和行数）：只读表头和 dimension 与完整读取工作表对照
+fn bench_excel_info(c: &mut Criterion) {
+    let file = write_workbook(50_000);
+    let path = file.path();
+
+    let mut group = c.benchmark_group("excel_info");
+    group.sample_size(10);
+    group.bench_function("full_read", |b| {
+        b.iter(|| {
+            let sheet_data = ExcelReader::open(path).unwrap().read_sheet("Sheet1").unwrap();
+            (sheet_data.rows.first().cloned(), sheet_data.rows.len())
+        })
+    });
+    group.bench_function("header_and_dimension", |b| b.iter(|| ExcelInfo::from_file(path).unwrap()));
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_patterns,
+    bench_extractor,
+    bench_sheet,
+    bench_column_lookup,
+    bench_row_count,
+    bench_excel_info
+);
+criterion_main!(benches);
@@ -0,0 +1,142 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::ExcelReader;
+use crate::models::{AnnotationKey, ExtractResult};
+use crate::utils::normalize_value;
+
+/// 外部人工批注：第一个工作表的第一列为匹配键（行号或命中值），第二列为备注，首行为表头
+pub struct Annotations {
+    key: AnnotationKey,
+    notes: HashMap<String, String>,
+}
+
+impl Annotations {
+    pub fn load(path: &Path, key: AnnotationKey) -> Result<Self> {
+        let mut reader = ExcelReader::open(path)?;
+        let sheet_name = reader
+            .sheet_names()
+            .into_iter()
+            .next()
+            .with_context(|| format!("批注文件没有工作表: {}", path.display()))?;
+        let sheet_data = reader.read_sheet(&sheet_name)?;
+
+        let notes = sheet_data
+            .iter_data_rows()
+            .filter_map(|(_, row)| {
+                let note = row.get(1)?.trim();
+                let lookup_key = Self::normalize_key(key, row.first()?)?;
+                (!note.is_empty()).then(|| (lookup_key, note.to_string()))
+            })
+            .collect();
+
+        Ok(Self { key, notes })
+    }
+
+    /// 批注文件中的键统一为查找形式：行号去掉小数部分，命中值按 `normalize_value` 规范化
+    fn normalize_key(key: AnnotationKey, raw: &str) -> Option<String> {
+        let raw = raw.trim();
+        match key {
+            AnnotationKey::RowNumber => raw
+                .parse::<f64>()
+                .ok()
+                .filter(|n| *n >= 1.0 && n.fract() == 0.0)
+                .map(|n| (n as u32).to_string()),
+            AnnotationKey::Value => Some(normalize_value(raw)).filter(|v| !v.is_empty()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.notes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty()
+    }
+
+    /// 该条结果对应的备注，未匹配时为 `None`
+    pub fn note_for(&self, result: &ExtractResult) -> Option<&str> {
+        match self.key {
+            AnnotationKey::RowNumber => self.notes.get(&result.row_number.to_string()),
+            AnnotationKey::Value => result
+                .phone_numbers
+                .iter()
+                .chain(&result.id_cards)
+                .chain(&result.bank_cards)
+                .chain(&result.names)
+                .chain(&result.macau_ids)
+                .chain(&result.wechat_ids)
+                .chain(&result.custom_matches)
+                .find_map(|m| self.notes.get(&normalize_value(&m.value))),
+        }
+        .map(String::as_str)
+    }
+
+    /// 把匹配到的备注写入各条结果的 `remark`，未匹配的清空
+    pub fn apply(&self, results: &mut [ExtractResult]) {
+        for result in results {
+            result.remark = self.note_for(result).unwrap_or_default().to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MatchInfo, PatternType};
+    use crate::test_support::scratch_dir;
+    use rust_xlsxwriter::Workbook;
+    use std::path::PathBuf;
+
+    fn write_annotation_file(dir: &Path, rows: &[(&str, &str)]) -> PathBuf {
+        let path = dir.join("annotation.xlsx");
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "键").unwrap();
+        worksheet.write_string(0, 1, "备注").unwrap();
+        for (i, (key, note)) in rows.iter().enumerate() {
+            let row = i as u32 + 1;
+            match key.parse::<f64>() {
+                Ok(number) => worksheet.write_number(row, 0, number).unwrap(),
+                Err(_) => worksheet.write_string(row, 0, *key).unwrap(),
+            };
+            worksheet.write_string(row, 1, *note).unwrap();
+        }
+        workbook.save(&path).unwrap();
+        path
+    }
+
+    fn sample_results() -> Vec<ExtractResult> {
+        let mut first = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        first.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let mut second = ExtractResult::new("a.xlsx", "Sheet1", 5);
+        second.bank_cards = vec![MatchInfo::builder("6225 8801 2345 6789", PatternType::BankCard).valid(true).build()];
+        vec![first, second]
+    }
+
+    #[test]
+    fn test_annotations_by_row_number() {
+        let dir = scratch_dir();
+        let path = write_annotation_file(dir.path(), &[("2", "已核实"), ("3", "无关"), ("abc", "忽略")]);
+        let annotations = Annotations::load(&path, AnnotationKey::RowNumber).unwrap();
+        assert_eq!(annotations.len(), 2);
+
+        let mut results = sample_results();
+        annotations.apply(&mut results);
+        assert_eq!(results[0].remark, "已核实");
+        assert_eq!(results[1].remark, "");
+    }
+
+    #[test]
+    fn test_annotations_by_value() {
+        let dir = scratch_dir();
+        let path = write_annotation_file(dir.path(), &[("138 0000 0000", "测试号"), ("6225-8801-2345-6789", "误报")]);
+        let annotations = Annotations::load(&path, AnnotationKey::Value).unwrap();
+
+        let mut results = sample_results();
+        annotations.apply(&mut results);
+        assert_eq!(results[0].remark, "");
+        assert_eq!(results[1].remark, "误报");
+    }
+}
@@ -0,0 +1,248 @@
+use anyhow::{Context, Result};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use super::exporter::escape_csv_field;
+use super::{FieldCipher, ProcessingStatistics, XlsxExporter};
+use crate::models::{Config, ExtractResult, FileInfo, FileStatus};
+
+/// 归档内的文件名
+const RESULT_ENTRY: &str = "提取结果.xlsx";
+const REPORT_ENTRY: &str = "统计报告.txt";
+const RUN_INFO_ENTRY: &str = "运行信息.json";
+const FILE_LIST_ENTRY: &str = "文件清单.csv";
+
+/// 整批交付用的 zip 归档：结果 xlsx、统计报告、运行信息和源文件清单
+pub struct ResultArchive<'a> {
+    config: &'a Config,
+    results: &'a [ExtractResult],
+    statistics: &'a ProcessingStatistics,
+    files: &'a [FileInfo],
+    /// 设置后结果中的命中值、源文本、上下文、附带列和源文件路径加密写入
+    cipher: Option<&'a FieldCipher>,
+}
+
+impl<'a> ResultArchive<'a> {
+    pub fn new(
+        config: &'a Config,
+        results: &'a [ExtractResult],
+        statistics: &'a ProcessingStatistics,
+        files: &'a [FileInfo],
+    ) -> Self {
+        Self { config, results, statistics, files, cipher: None }
+    }
+
+    /// 命中值等敏感列加密后写入，算法参数记录在运行信息里
+    pub fn with_encryption(mut self, cipher: &'a FieldCipher) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// 在 `output_dir` 下写入带时间戳的归档，返回归档路径
+    pub fn export(&self, output_dir: &Path) -> Result<PathBuf> {
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let output_path = output_dir.join(format!("提取结果归档_{}.zip", timestamp));
+        self.write_to(&output_path)?;
+        tracing::info!("归档已导出到: {}", output_path.display());
+        Ok(output_path)
+    }
+
+    pub fn write_to(&self, output_path: &Path) -> Result<()> {
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut zip = ZipWriter::new(BufWriter::new(file));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+        let entries = [
+            (RESULT_ENTRY, self.result_workbook()?),
+            (REPORT_ENTRY, self.statistics.to_report_string().into_bytes()),
+            (RUN_INFO_ENTRY, self.run_info()?.into_bytes()),
+            (FILE_LIST_ENTRY, self.file_list()?.into_bytes()),
+        ];
+        for (name, content) in entries {
+            zip.start_file(name, options)?;
+            zip.write_all(&content)?;
+        }
+
+        zip.finish()?
+            .flush()
+            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+        Ok(())
+    }
+
+    /// 结果 xlsx；加密时不再脱敏、截断或拆分命中行，保证每个密文字段完整可解
+    fn result_workbook(&self) -> Result<Vec<u8>> {
+        let Some(cipher) = self.cipher else {
+            return XlsxExporter::new(self.config.clone()).export_to_buffer(self.results);
+        };
+
+        let config = Config {
+            enable_mask: false,
+            id_card_analytic_mask: false,
+            truncate_source_text: None,
+            one_match_per_row: false,
+            ..self.config.clone()
+        };
+        XlsxExporter::new(config).export_to_buffer(&cipher.encrypt_results(self.results)?)
+    }
+
+    /// 程序版本、导出时间、本次使用的配置及加密参数
+    fn run_info(&self) -> Result<String> {
+        // 推送地址中含机器人凭据，任何归档都不写出；加密归档交给第三方，本身是敏感号码的字段也不写出
+        let config = match self.cipher {
+            Some(_) => Config {
+                exclude_values: Vec::new(),
+                webhook_url: String::new(),
+                webhook_mentions: Vec::new(),
+                ..self.config.clone()
+            },
+            None => Config {
+                webhook_url: String::new(),
+                ..self.config.clone()
+            },
+        };
+        let info = serde_json::json!({
+            "version": env!("CARGO_PKG_VERSION"),
+            "exported_at": chrono::Local::now().to_rfc3339(),
+            "file_count": self.files.len(),
+            "result_count": self.results.len(),
+            "elapsed_secs": self.statistics.elapsed_secs,
+            "config": config,
+            "encryption": self.cipher.map(FieldCipher::params),
+        });
+        Ok(serde_json::to_string_pretty(&info)?)
+    }
+
+    /// 源文件清单（UTF-8 带 BOM 的 CSV）；加密归档中路径与结果中的源文件路径一样加密
+    fn file_list(&self) -> Result<String> {
+        let mut csv = String::from("\u{FEFF}文件名,路径,大小(字节),行数,状态,命中数\r\n");
+        for file in self.files {
+            let hits = file
+                .result_summary
+                .map(|s| (s.phones + s.id_cards + s.bank_cards + s.names).to_string())
+                .unwrap_or_default();
+            let path = file.file_path.display().to_string();
+            let fields = [
+                file.file_name.clone(),
+                match self.cipher {
+                    Some(cipher) => cipher.encrypt(&path)?,
+                    None => path,
+                },
+                file.file_size.map(|size| size.to_string()).unwrap_or_default(),
+                file.row_count.to_string(),
+                status_text(&file.status),
+                hits,
+            ];
+            let line: Vec<String> = fields.iter().map(|f| escape_csv_field(f)).collect();
+            csv.push_str(&line.join(","));
+            csv.push_str("\r\n");
+        }
+        Ok(csv)
+    }
+}
+
+fn status_text(status: &FileStatus) -> String {
+    match status {
+        FileStatus::Pending => "未处理".to_string(),
+        FileStatus::Processing(_) => "处理中".to_string(),
+        FileStatus::Completed => "已完成".to_string(),
+        FileStatus::Error(message) => format!("失败: {}", message),
+        FileStatus::Skipped(reason) => format!("跳过: {}", reason),
+        FileStatus::Warning(message) => format!("警告: {}", message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MatchInfo, PatternType};
+    use crate::test_support::scratch_dir;
+    use calamine::Reader;
+    use std::io::Read;
+
+    #[test]
+    fn test_archive_contains_all_entries() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let results = vec![result];
+
+        let mut file = FileInfo::from_path(PathBuf::from("/data/a,b.xlsx"));
+        file.status = FileStatus::completed();
+        let statistics = ProcessingStatistics { total_results: 1, total_phones: 1, ..Default::default() };
+        let config = Config {
+            webhook_url: "https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=secret".to_string(),
+            ..Default::default()
+        };
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("archive.zip");
+        ResultArchive::new(&config, &results, &statistics, &[file]).write_to(&path).unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let names: Vec<&str> = archive.file_names().collect();
+        for entry in [RESULT_ENTRY, REPORT_ENTRY, RUN_INFO_ENTRY, FILE_LIST_ENTRY] {
+            assert!(names.contains(&entry), "缺少 {}", entry);
+        }
+
+        let mut file_list = String::new();
+        archive.by_name(FILE_LIST_ENTRY).unwrap().read_to_string(&mut file_list).unwrap();
+        assert!(file_list.contains("\"a,b.xlsx\""));
+        assert!(file_list.contains("已完成"));
+
+        let mut run_info = String::new();
+        archive.by_name(RUN_INFO_ENTRY).unwrap().read_to_string(&mut run_info).unwrap();
+        let run_info: serde_json::Value = serde_json::from_str(&run_info).unwrap();
+        assert_eq!(run_info["result_count"], 1);
+        assert_eq!(run_info["config"]["webhook_url"], "");
+    }
+
+    #[test]
+    fn test_archive_records_encryption_params() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let config = Config {
+            exclude_values: vec!["13800138000".to_string()],
+            webhook_url: "https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=secret".to_string(),
+            webhook_mentions: vec!["13907315286".to_string()],
+            ..Default::default()
+        };
+        let statistics = ProcessingStatistics::default();
+        let cipher = FieldCipher::new("口令").unwrap();
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("archive_encrypted.zip");
+        let file = FileInfo::from_path(PathBuf::from("/客户/张三丰/a.xlsx"));
+        ResultArchive::new(&config, &[result], &statistics, &[file])
+            .with_encryption(&cipher)
+            .write_to(&path)
+            .unwrap();
+
+        let mut archive = zip::ZipArchive::new(File::open(&path).unwrap()).unwrap();
+        let mut run_info = String::new();
+        archive.by_name(RUN_INFO_ENTRY).unwrap().read_to_string(&mut run_info).unwrap();
+        let run_info: serde_json::Value = serde_json::from_str(&run_info).unwrap();
+        assert_eq!(run_info["encryption"]["algorithm"], "AES-256-GCM");
+        assert_eq!(run_info["encryption"]["salt"], cipher.params().salt);
+        assert_eq!(run_info["config"]["exclude_values"], serde_json::json!([]));
+        assert_eq!(run_info["config"]["webhook_url"], "");
+        assert_eq!(run_info["config"]["webhook_mentions"], serde_json::json!([]));
+
+        let mut file_list = String::new();
+        archive.by_name(FILE_LIST_ENTRY).unwrap().read_to_string(&mut file_list).unwrap();
+        assert!(file_list.contains("a.xlsx"));
+        assert!(!file_list.contains("张三丰"));
+
+        let mut workbook = Vec::new();
+        archive.by_name(RESULT_ENTRY).unwrap().read_to_end(&mut workbook).unwrap();
+        let mut workbook = calamine::Xlsx::new(std::io::Cursor::new(workbook)).unwrap();
+        let range = workbook.worksheet_range("Sheet1").unwrap();
+        let phone = range.get_value((1, 5)).unwrap().to_string();
+        assert_eq!(cipher.decrypt(&phone).unwrap(), "13907315286");
+        assert_eq!(range.get_value((1, 6)).unwrap().to_string(), "有效");
+    }
+}
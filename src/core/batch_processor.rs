@@ -0,0 +1,321 @@
+//! 流式处理：每处理完一个文件就把结果交给写出器，不在内存中累积全部结果，适合超大批量文件
+
+use anyhow::{bail, Result};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use super::exporter::{CsvStreamWriter, HtmlStreamWriter, JsonLinesStreamWriter, XlsxStreamWriter};
+use super::{Annotations, ProcessingControl, ProcessingStatistics, Processor};
+use crate::models::{Config, ExtractResult, FileInfo, OutputFormat};
+
+/// 按批接收结果的写出器，结果写出后即可释放
+pub trait ResultWriter {
+    /// 写出一批结果（通常为一个文件的全部结果）
+    fn write_batch(&mut self, results: &[ExtractResult]) -> Result<()>;
+
+    /// 全部批次写完后调用，补写汇总内容并落盘
+    fn finish(&mut self) -> Result<()>;
+}
+
+/// 流式处理的汇总，结果本身已写出，不再返回
+#[derive(Debug, Default)]
+pub struct StreamSummary {
+    pub statistics: ProcessingStatistics,
+    /// 处理失败的文件（文件路径, 错误信息）
+    pub failed_files: Vec<(PathBuf, String)>,
+    /// 处理成功但有警告的文件（文件路径, 警告信息）
+    pub warnings: Vec<(PathBuf, String)>,
+}
+
+/// 逐个处理文件并流式写出结果
+pub struct BatchProcessor {
+    config: Config,
+    processor: Processor,
+    control: Arc<ProcessingControl>,
+}
+
+impl BatchProcessor {
+    pub fn new(config: Config) -> Self {
+        Self {
+            processor: Processor::new(config.clone()),
+            config,
+            control: Arc::default(),
+        }
+    }
+
+    /// 使用外部持有的控制句柄，以便在处理中暂停、恢复或取消
+    pub fn with_control(mut self, control: Arc<ProcessingControl>) -> Self {
+        self.processor = self.processor.with_control(Arc::clone(&control));
+        self.control = control;
+        self
+    }
+
+    /// 逐个处理文件，每个文件处理完即写入 `writer`，写出的内容与批量处理后整体导出一致。
+    /// 单个文件失败时记录后继续，写出失败时中止；进度按已处理的文件数计算，
+    /// 取消后不再处理剩余文件，已写出的结果仍会落盘
+    pub fn process_and_stream(
+        &self,
+        files: &[FileInfo],
+        writer: &mut dyn ResultWriter,
+        progress_callback: impl Fn(&str, u8),
+    ) -> Result<StreamSummary> {
+        let start_time = Instant::now();
+        let annotations = match &self.config.annotation_file {
+            Some(path) => Some(Annotations::load(path, self.config.annotation_key)?),
+            None => None,
+        };
+
+        let mut counts = ProcessingStatistics::default();
+        let mut failed_files = Vec::new();
+
+        progress_callback("准备处理", 0);
+        for (index, file_info) in files.iter().enumerate() {
+            if self.control.is_cancelled() {
+                break;
+            }
+
+            match self.processor.process_file(file_info) {
+                Ok(mut results) => {
+                    if let Some(annotations) = &annotations {
+                        annotations.apply(&mut results);
+                    }
+                    writer.write_batch(&results)?;
+                    counts.merge(&ProcessingStatistics::from_results(&results, 0.0));
+                }
+                Err(e) => {
+                    tracing::error!("处理文件 {} 失败: {:#}", file_info.file_name, e);
+                    failed_files.push((file_info.file_path.clone(), format!("{:#}", e)));
+                }
+            }
+
+            let progress = ((index + 1) * 100 / files.len()) as u8;
+            progress_callback(&file_info.file_name, progress);
+        }
+        writer.finish()?;
+        progress_callback("处理完成", 100);
+
+        // 结果计数按批累加，扫描行数和逐文件统计取自处理器
+        let mut statistics = self.processor.generate_statistics(&[], start_time.elapsed().as_secs_f64());
+        statistics.merge(&counts);
+
+        Ok(StreamSummary {
+            statistics,
+            failed_files,
+            warnings: self.processor.take_warnings(),
+        })
+    }
+}
+
+/// 按格式创建流式写出器；Parquet 需整体写入行组，不支持流式导出
+pub fn create_stream_writer(config: &Config, format: OutputFormat, output_path: &Path) -> Result<Box<dyn ResultWriter>> {
+    Ok(match format {
+        OutputFormat::Xlsx => Box::new(XlsxStreamWriter::create(config.clone(), output_path)?),
+        OutputFormat::Csv => Box::new(CsvStreamWriter::create(config.clone(), output_path)?),
+        OutputFormat::Json => Box::new(JsonLinesStreamWriter::create(config.clone(), output_path)?),
+        OutputFormat::Parquet => bail!("Parquet 格式不支持流式导出"),
+        OutputFormat::Html => Box::new(HtmlStreamWriter::create(config.clone(), output_path)?),
+    })
+}
+
+/// 流式导出文件的扩展名；JSON 按行写出，为 JSON Lines
+pub fn stream_extension(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Xlsx => "xlsx",
+        OutputFormat::Csv => "csv",
+        OutputFormat::Json => "jsonl",
+        OutputFormat::Parquet => "parquet",
+        OutputFormat::Html => "html",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{CsvExporter, HtmlExporter, JsonExporter};
+    use crate::test_support::scratch_dir;
+    use calamine::{open_workbook, Reader, Xlsx};
+    use rust_xlsxwriter::Workbook;
+    use std::path::Path;
+
+    /// 收集全部批次，便于与批量处理的结果对照
+    #[derive(Default)]
+    struct CollectingWriter {
+        batches: Vec<Vec<ExtractResult>>,
+        finished: bool,
+    }
+
+    impl ResultWriter for CollectingWriter {
+        fn write_batch(&mut self, results: &[ExtractResult]) -> Result<()> {
+            self.batches.push(results.to_vec());
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<()> {
+            self.finished = true;
+            Ok(())
+        }
+    }
+
+    fn create_test_files(dir: &Path) -> Vec<FileInfo> {
+        [
+            ("a", ["电话13907315286", "无敏感信息", "身份证11010519491231002X"]),
+            ("b", ["18612345678和13800138000", "卡号6222021234567890128", "13907315286"]),
+        ]
+        .into_iter()
+        .map(|(name, values)| {
+            let path = dir.join(format!("{}.xlsx", name));
+            let mut workbook = Workbook::new();
+            let worksheet = workbook.add_worksheet();
+            worksheet.write_string(0, 0, "消息内容").unwrap();
+            for (row, value) in values.into_iter().enumerate() {
+                worksheet.write_string(row as u32 + 1, 0, value).unwrap();
+            }
+            workbook.save(&path).unwrap();
+            FileInfo::from_path(path)
+        })
+        .collect()
+    }
+
+    /// 去掉处理时间后序列化，两次处理的结果可直接比较
+    fn comparable(results: &[ExtractResult]) -> serde_json::Value {
+        let mut results = results.to_vec();
+        results.iter_mut().for_each(|r| r.processing_timestamp.clear());
+        serde_json::to_value(results).unwrap()
+    }
+
+    #[test]
+    fn test_stream_matches_batch_processing() {
+        let dir = scratch_dir();
+        let files = create_test_files(dir.path());
+        let config = Config::default();
+
+        let mut writer = CollectingWriter::default();
+        let summary = BatchProcessor::new(config.clone()).process_and_stream(&files, &mut writer, |_, _| {}).unwrap();
+
+        let processor = Processor::new(config);
+        let (batch_results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let batch_results: Vec<ExtractResult> =
+            batch_results.into_iter().flat_map(|(_, _, result)| result.unwrap()).collect();
+        let batch_stats = processor.generate_statistics(&batch_results, 0.0);
+
+        // 每个文件写出一批
+        assert!(writer.finished);
+        assert_eq!(writer.batches.len(), 2);
+        let streamed: Vec<ExtractResult> = writer.batches.concat();
+        assert_eq!(comparable(&streamed), comparable(&batch_results));
+
+        assert!(summary.failed_files.is_empty());
+        assert_eq!(summary.statistics.total_results, batch_stats.total_results);
+        assert_eq!(summary.statistics.total_sensitive_info(), batch_stats.total_sensitive_info());
+        assert_eq!(summary.statistics.rows_scanned, batch_stats.rows_scanned);
+        assert_eq!(summary.statistics.pivot.phones_by_carrier, batch_stats.pivot.phones_by_carrier);
+    }
+
+    #[test]
+    fn test_stream_records_failed_files() {
+        let dir = scratch_dir();
+        let mut files = create_test_files(dir.path());
+        files.insert(0, FileInfo::from_path(dir.path().join("missing.xlsx")));
+
+        let mut writer = CollectingWriter::default();
+        let summary = BatchProcessor::new(Config::default()).process_and_stream(&files, &mut writer, |_, _| {}).unwrap();
+
+        assert_eq!(summary.failed_files.len(), 1);
+        assert_eq!(summary.failed_files[0].0, files[0].file_path);
+        assert_eq!(writer.batches.len(), 2);
+    }
+
+    #[test]
+    fn test_csv_stream_matches_exporter() {
+        let dir = scratch_dir();
+        let files = create_test_files(dir.path());
+        let config = Config::default();
+        let output = dir.path().join("out.csv");
+
+        let mut collector = CollectingWriter::default();
+        BatchProcessor::new(config.clone()).process_and_stream(&files, &mut collector, |_, _| {}).unwrap();
+        let mut writer = create_stream_writer(&config, OutputFormat::Csv, &output).unwrap();
+        for batch in &collector.batches {
+            writer.write_batch(batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let streamed = std::fs::read_to_string(&output).unwrap();
+        let expected = CsvExporter::new(config).export_to_string(&collector.batches.concat()).unwrap();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_html_stream_matches_exporter() {
+        let dir = scratch_dir();
+        let files = create_test_files(dir.path());
+        let config = Config::default();
+        let output = dir.path().join("out.html");
+
+        let mut collector = CollectingWriter::default();
+        BatchProcessor::new(config.clone()).process_and_stream(&files, &mut collector, |_, _| {}).unwrap();
+        let mut writer = create_stream_writer(&config, OutputFormat::Html, &output).unwrap();
+        for batch in &collector.batches {
+            writer.write_batch(batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let streamed = std::fs::read_to_string(&output).unwrap();
+        let expected = HtmlExporter::new(config).export_to_string(&collector.batches.concat()).unwrap();
+        assert_eq!(streamed, expected);
+        assert_eq!(stream_extension(OutputFormat::Html), "html");
+    }
+
+    #[test]
+    fn test_json_lines_stream_matches_exporter() {
+        let dir = scratch_dir();
+        let files = create_test_files(dir.path());
+        let config = Config::default();
+        let output = dir.path().join("out.jsonl");
+
+        let mut writer = create_stream_writer(&config, OutputFormat::Json, &output).unwrap();
+        let mut collector = CollectingWriter::default();
+        BatchProcessor::new(config.clone()).process_and_stream(&files, &mut collector, |_, _| {}).unwrap();
+        for batch in &collector.batches {
+            writer.write_batch(batch).unwrap();
+        }
+        writer.finish().unwrap();
+
+        let streamed = std::fs::read_to_string(&output).unwrap();
+        let lines: Vec<serde_json::Value> =
+            streamed.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let expected: serde_json::Value = serde_json::from_str(
+            &JsonExporter::new(config).export_to_string(&collector.batches.concat()).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(serde_json::Value::Array(lines), expected);
+    }
+
+    #[test]
+    fn test_xlsx_stream_writes_all_rows() {
+        let dir = scratch_dir();
+        let files = create_test_files(dir.path());
+        let config = Config::default();
+        let output = dir.path().join("out.xlsx");
+
+        let mut writer = create_stream_writer(&config, OutputFormat::Xlsx, &output).unwrap();
+        let summary = BatchProcessor::new(config.clone()).process_and_stream(&files, writer.as_mut(), |_, _| {}).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&output).unwrap();
+        let sheet_names = workbook.sheet_names();
+        let range = workbook.worksheet_range(&sheet_names[0]).unwrap();
+
+        // 表头加每条结果一行，另有统计工作表
+        assert_eq!(range.height(), summary.statistics.total_results + 1);
+        assert_eq!(sheet_names.len(), 2);
+    }
+
+    #[test]
+    fn test_parquet_stream_unsupported() {
+        let dir = scratch_dir();
+        let output = dir.path().join("out.parquet");
+        assert!(create_stream_writer(&Config::default(), OutputFormat::Parquet, &output).is_err());
+        assert_eq!(stream_extension(OutputFormat::Json), "jsonl");
+    }
+}
@@ -1,40 +1,249 @@
 use anyhow::{Context, Result};
-use calamine::{open_workbook, Data, Range, Reader, Xlsx};
+use calamine::{open_workbook_auto, Data, ExcelDateTime, Range, Reader, Sheets, Xlsx};
+use chrono::NaiveDate;
+use std::cell::OnceCell;
 use std::collections::HashMap;
-use std::path::Path;
+use std::fs::File;
+use regex::Regex;
+use std::io::{BufReader, Cursor, Read, Seek};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
+
+use super::office_crypto;
+use super::preprocessor::{StripHtmlPreprocessor, TextPreprocessor};
+use super::sheet_visibility::HiddenCells;
+use crate::models::{SheetExclusion, DEFAULT_CONTEXT_CELL_SEP};
+
+/// HTML 表格的行与单元格
+static HTML_ROW: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<tr[^>]*>(.*?)</tr>").unwrap());
+static HTML_CELL: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<t[dh][^>]*>(.*?)</t[dh]>").unwrap());
+
+/// 判断文件真实格式时读取的文件头长度
+const SNIFF_LEN: usize = 4096;
+
+/// 视为日期的 Excel 序列号范围（1900-01-01 至 2064-04-08）
+const DATE_SERIAL_RANGE: std::ops::RangeInclusive<f64> = 1.0..=60000.0;
+
+/// 打开的数据源
+enum WorkbookSource {
+    /// calamine 可读取的工作簿（xlsx/xlsm/xlam/xls/ods）
+    Workbook(Box<Sheets<BufReader<File>>>),
+    /// 带密码的 xlsx，解密后的内容只保存在内存中
+    Decrypted(Box<Sheets<Cursor<Arc<[u8]>>>>),
+    /// CSV 文件（以及改了扩展名的 CSV/HTML 表格）视为只有一个工作表，工作表名取文件名
+    Csv {
+        sheet_name: String,
+        rows: Vec<Vec<String>>,
+    },
+}
+
+/// xlsx 的 zip 包，用于读取 calamine 不提供的行列隐藏属性
+enum OoxmlPackage {
+    File(PathBuf),
+    Memory(Arc<[u8]>),
+}
 
 pub struct ExcelReader {
-    workbook: Xlsx<std::io::BufReader<std::fs::File>>,
+    source: WorkbookSource,
+    /// 读取时去掉首尾的全空行
+    trim_empty_rows: bool,
+    /// 读取隐藏的行和列
+    include_hidden: bool,
+    /// 日期格式的单元格转换为 `YYYY-MM-DD`
+    convert_date_cells: bool,
+    package: Option<OoxmlPackage>,
 }
 
 impl ExcelReader {
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with_password(path, None)
+    }
+
+    /// 打开文件，加密的 xlsx 使用 `password` 解密
+    ///
+    /// 文件加密但未提供密码或密码错误时，错误可 downcast 为 [`office_crypto::DecryptError`]
+    pub fn open_with_password<P: AsRef<Path>>(path: P, password: Option<&str>) -> Result<Self> {
         let path_ref = path.as_ref();
         let file_path = path_ref.to_string_lossy().to_string();
 
-        let workbook: Xlsx<_> = open_workbook(path_ref)
-            .with_context(|| format!("无法打开Excel文件: {}", file_path))?;
+        let extension = path_ref
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        if extension == "csv" {
+            let bytes = std::fs::read(path_ref)
+                .with_context(|| format!("无法打开CSV文件: {}", file_path))?;
+            let content = String::from_utf8(bytes)
+                .with_context(|| format!("CSV文件不是 UTF-8 编码: {}", file_path))?;
+
+            return Ok(Self::from_source(WorkbookSource::Csv {
+                sheet_name: single_sheet_name(path_ref),
+                rows: parse_csv(&content),
+            }));
+        }
+
+        // .xlsm/.xlam 与 .xlsx 同为 OOXML 格式，宏内容不会被读取
+        if extension == "xlam" {
+            tracing::info!("加载项文件中的宏内容将被忽略: {}", file_path);
+        }
+
+        if office_crypto::is_encrypted_ooxml(path_ref) {
+            let bytes: Arc<[u8]> = office_crypto::decrypt_ooxml(path_ref, password)
+                .with_context(|| format!("无法解密Excel文件: {}", file_path))?
+                .into();
+            let workbook = Xlsx::new(Cursor::new(bytes.clone()))
+                .with_context(|| format!("无法打开Excel文件: {}", file_path))?;
+
+            let mut reader = Self::from_source(WorkbookSource::Decrypted(Box::new(Sheets::Xlsx(workbook))));
+            reader.package = Some(OoxmlPackage::Memory(bytes));
+            return Ok(reader);
+        }
+
+        match open_workbook_auto(path_ref) {
+            Ok(workbook) => {
+                let package = matches!(workbook, Sheets::Xlsx(_)).then(|| OoxmlPackage::File(path_ref.to_path_buf()));
+                let mut reader = Self::from_source(WorkbookSource::Workbook(Box::new(workbook)));
+                reader.package = package;
+                Ok(reader)
+            }
+            Err(e) => match sniff_disguised_format(path_ref) {
+                Some(format) => Self::open_disguised(path_ref, format),
+                None => Err(e).with_context(|| format!("无法打开Excel文件: {}", file_path)),
+            },
+        }
+    }
+
+    /// 按真实格式读取改了扩展名的 HTML 表格或 CSV
+    fn open_disguised(path: &Path, format: DisguisedFormat) -> Result<Self> {
+        let file_path = path.display();
+        let content = std::fs::read(path).with_context(|| format!("无法打开文件: {}", file_path))?;
+        let Ok(content) = String::from_utf8(content) else {
+            anyhow::bail!(
+                "文件实际是{}而不是 Excel 文件，且不是 UTF-8 编码，请用 Excel 另存为 xlsx 后重试: {}",
+                format.label(),
+                file_path
+            );
+        };
+
+        let rows = match format {
+            DisguisedFormat::Html => parse_html_table(&content),
+            DisguisedFormat::Csv => parse_csv(&content),
+        };
+        if rows.is_empty() {
+            anyhow::bail!("文件实际是{}而不是 Excel 文件，且未找到表格数据: {}", format.label(), file_path);
+        }
+
+        tracing::warn!("文件实际是{}，已按{}读取: {}", format.label(), format.label(), file_path);
+        Ok(Self::from_source(WorkbookSource::Csv {
+            sheet_name: single_sheet_name(path),
+            rows,
+        }))
+    }
+
+    fn from_source(source: WorkbookSource) -> Self {
+        Self {
+            source,
+            trim_empty_rows: true,
+            include_hidden: true,
+            convert_date_cells: true,
+            package: None,
+        }
+    }
+
+    /// 是否去掉首尾的全空行（默认开启），行数统计同样按此计算
+    pub fn with_trim_empty_rows(mut self, trim_empty_rows: bool) -> Self {
+        self.trim_empty_rows = trim_empty_rows;
+        self
+    }
+
+    /// 是否读取隐藏的行和列（默认读取）；关闭后隐藏单元格按空单元格处理，表头行始终保留。
+    /// 仅 xlsx/xlsm 支持，其他格式忽略此设置
+    pub fn with_include_hidden(mut self, include_hidden: bool) -> Self {
+        self.include_hidden = include_hidden;
+        self
+    }
 
-        Ok(Self { workbook })
+    /// 是否把日期格式单元格中的序列号转换为 `YYYY-MM-DD`（默认开启）；关闭后保留序列号原样
+    pub fn with_convert_date_cells(mut self, convert_date_cells: bool) -> Self {
+        self.convert_date_cells = convert_date_cells;
+        self
     }
 
     pub fn sheet_names(&self) -> Vec<String> {
-        self.workbook.sheet_names().to_vec()
+        match &self.source {
+            WorkbookSource::Workbook(workbook) => workbook.sheet_names().to_vec(),
+            WorkbookSource::Decrypted(workbook) => workbook.sheet_names().to_vec(),
+            WorkbookSource::Csv { sheet_name, .. } => vec![sheet_name.clone()],
+        }
+    }
+
+    /// 读取工作簿中的工作表区域（CSV 无区域，返回 `None`）
+    fn worksheet_range(&mut self, sheet_name: &str) -> Result<Option<Range<Data>>> {
+        match &mut self.source {
+            WorkbookSource::Workbook(workbook) => {
+                let range = workbook
+                    .worksheet_range(sheet_name)
+                    .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+                Ok(Some(range))
+            }
+            WorkbookSource::Decrypted(workbook) => {
+                let range = workbook
+                    .worksheet_range(sheet_name)
+                    .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+                Ok(Some(range))
+            }
+            WorkbookSource::Csv { .. } => Ok(None),
+        }
+    }
+
+    fn csv_rows(&self) -> &[Vec<String>] {
+        match &self.source {
+            WorkbookSource::Csv { rows, .. } => rows,
+            WorkbookSource::Workbook(_) | WorkbookSource::Decrypted(_) => &[],
+        }
     }
 
     pub fn read_sheet(&mut self, sheet_name: &str) -> Result<SheetData> {
-        let range = self.workbook
-            .worksheet_range(sheet_name)
-            .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+        let sheet_data = match self.worksheet_range(sheet_name)? {
+            Some(range) => {
+                let mut rows = Self::range_to_rows(&range, self.convert_date_cells);
+                let start = range.start().unwrap_or((0, 0));
+                if !self.include_hidden
+                    && let Some(hidden) = self.hidden_cells(sheet_name)
+                {
+                    blank_hidden_cells(&mut rows, start, &hidden);
+                }
+                SheetData::new(rows).with_origin(start.0 as usize, start.1 as usize)
+            }
+            None => SheetData::new(self.csv_rows().to_vec()),
+        };
 
-        let rows = Self::range_to_rows(&range);
+        if self.trim_empty_rows {
+            Ok(sheet_data.trim_empty_rows())
+        } else {
+            Ok(sheet_data)
+        }
+    }
 
-        Ok(SheetData {
-            rows,
-        })
+    /// 工作表的隐藏行列；非 xlsx 格式或读取失败时返回 `None`（按全部可见处理）
+    fn hidden_cells(&self, sheet_name: &str) -> Option<HiddenCells> {
+        let hidden = match self.package.as_ref()? {
+            OoxmlPackage::File(path) => File::open(path)
+                .map_err(anyhow::Error::from)
+                .and_then(|file| HiddenCells::read(BufReader::new(file), sheet_name)),
+            OoxmlPackage::Memory(bytes) => HiddenCells::read(Cursor::new(bytes.clone()), sheet_name),
+        };
+        match hidden {
+            Ok(hidden) => Some(hidden),
+            Err(e) => {
+                tracing::warn!("无法读取工作表 {} 的隐藏行列，按全部可见处理: {:#}", sheet_name, e);
+                None
+            }
+        }
     }
 
-    fn range_to_rows(range: &Range<Data>) -> Vec<Vec<String>> {
+    fn range_to_rows(range: &Range<Data>, convert_date_cells: bool) -> Vec<Vec<String>> {
         let mut rows = Vec::new();
 
         let start = range.start().unwrap_or((0, 0));
@@ -45,7 +254,12 @@ impl ExcelReader {
             for col in start.1..=end.1 {
                 let cell_value = range
                     .get_value((row, col))
-                    .map(Self::data_to_string)
+                    .map(|data| match data {
+                        Data::DateTime(date_time) if convert_date_cells => {
+                            date_cell_string(date_time).unwrap_or_else(|| Self::data_to_string(data))
+                        }
+                        _ => Self::data_to_string(data),
+                    })
                     .unwrap_or_default();
                 row_data.push(cell_value);
             }
@@ -75,9 +289,12 @@ impl ExcelReader {
     }
 
     pub fn read_column_names(&mut self, sheet_name: &str) -> Result<Vec<String>> {
-        let range = self.workbook
-            .worksheet_range(sheet_name)
-            .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+        if let Some(columns) = self.declared_header(sheet_name)? {
+            return Ok(columns);
+        }
+        let Some(range) = self.worksheet_range(sheet_name)? else {
+            return Ok(self.csv_rows().first().cloned().unwrap_or_default());
+        };
 
         let mut columns = Vec::new();
 
@@ -94,72 +311,463 @@ impl ExcelReader {
         Ok(columns)
     }
 
+    /// 工作表总行数（含表头），开启去除空行时不计首尾的全空行
     pub fn row_count(&mut self, sheet_name: &str) -> Result<usize> {
-        let range = self.workbook
-            .worksheet_range(sheet_name)
-            .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+        let Some(range) = self.worksheet_range(sheet_name)? else {
+            let rows = self.csv_rows();
+            if self.trim_empty_rows {
+                return Ok(non_empty_span(rows.iter().map(|row| row.iter().all(String::is_empty))));
+            }
+            return Ok(rows.len());
+        };
 
-        let start = range.start().unwrap_or((0, 0));
-        let end = range.end().unwrap_or((0, 0));
+        if self.trim_empty_rows {
+            return Ok(non_empty_span(
+                range.rows().map(|row| row.iter().all(|cell| Self::data_to_string(cell).is_empty())),
+            ));
+        }
 
-        let count = if end.0 >= start.0 {
-            (end.0 - start.0) as usize
-        } else {
-            0
+        let count = match (range.start(), range.end()) {
+            (Some(start), Some(end)) if end.0 >= start.0 => (end.0 - start.0 + 1) as usize,
+            _ => 0,
         };
 
         Ok(count)
     }
+
+    /// 只看前两行推测表头所在行，规则见 [`SheetData::detect_header_row`]
+    pub fn detect_header_row(&mut self, sheet_name: &str) -> Result<usize> {
+        let rows: Vec<Vec<String>> = match self.worksheet_range(sheet_name)? {
+            Some(range) => range
+                .rows()
+                .take(2)
+                .map(|row| row.iter().map(Self::data_to_string).collect())
+                .collect(),
+            None => self.csv_rows().iter().take(2).cloned().collect(),
+        };
+        Ok(detect_header_index(&rows))
+    }
+
+    /// 工作表行数（含表头）的快速估算：xlsx 直接取 `<dimension>` 声明的区域，不解析单元格；
+    /// 其他格式或没有 dimension 时回退为 [`Self::row_count`]
+    ///
+    /// dimension 区域不去掉首尾的全空行，结果可能略多于实际数据行，适合用于进度计算
+    pub fn row_count_from_dimension(&mut self, sheet_name: &str) -> Result<usize> {
+        match self.declared_row_count(sheet_name)? {
+            Some(count) => Ok(count),
+            None => self.row_count(sheet_name),
+        }
+    }
+
+    /// xlsx 工作表 `<dimension>` 声明的行数；不是 xlsx 或没有 dimension 时返回 `None`
+    pub fn declared_row_count(&mut self, sheet_name: &str) -> Result<Option<usize>> {
+        let dimensions = match &mut self.source {
+            WorkbookSource::Workbook(workbook) => match workbook.as_mut() {
+                Sheets::Xlsx(xlsx) => xlsx.worksheet_cells_reader(sheet_name).map(|reader| reader.dimensions()),
+                _ => return Ok(None),
+            },
+            WorkbookSource::Decrypted(workbook) => match workbook.as_mut() {
+                Sheets::Xlsx(xlsx) => xlsx.worksheet_cells_reader(sheet_name).map(|reader| reader.dimensions()),
+                _ => return Ok(None),
+            },
+            WorkbookSource::Csv { .. } => return Ok(None),
+        }
+        .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+
+        // 没有 dimension 时 calamine 给出 A1:A1，与只有一个单元格的工作表无法区分，交给完整读取
+        if dimensions.start == dimensions.end {
+            return Ok(None);
+        }
+        Ok(Some((dimensions.end.0 - dimensions.start.0 + 1) as usize))
+    }
+
+    /// xlsx 工作表的首个非空行，只解析到该行为止，列范围取 `<dimension>` 声明的区域；
+    /// 不是 xlsx 或没有 dimension 时返回 `None`
+    fn declared_header(&mut self, sheet_name: &str) -> Result<Option<Vec<String>>> {
+        match &mut self.source {
+            WorkbookSource::Workbook(workbook) => match workbook.as_mut() {
+                Sheets::Xlsx(xlsx) => xlsx_declared_header(xlsx, sheet_name),
+                _ => Ok(None),
+            },
+            WorkbookSource::Decrypted(workbook) => match workbook.as_mut() {
+                Sheets::Xlsx(xlsx) => xlsx_declared_header(xlsx, sheet_name),
+                _ => Ok(None),
+            },
+            WorkbookSource::Csv { .. } => Ok(None),
+        }
+    }
+
+    /// 去掉前 `header_rows` 行表头后的数据行数
+    pub fn data_row_count(&mut self, sheet_name: &str, header_rows: usize) -> Result<usize> {
+        Ok(self.row_count(sheet_name)?.saturating_sub(header_rows))
+    }
 }
 
-#[derive(Debug, Clone)]
+/// 非空且不是数字的单元格视为文本，表头行应至少有一个
+fn is_text_cell(value: &str) -> bool {
+    let value = value.trim();
+    !value.is_empty() && value.parse::<f64>().is_err()
+}
+
+/// 逐个读取单元格直到首个非空行结束，见 [`ExcelReader::declared_header`]
+fn xlsx_declared_header<RS: Read + Seek>(xlsx: &mut Xlsx<RS>, sheet_name: &str) -> Result<Option<Vec<String>>> {
+    let mut cells = xlsx
+        .worksheet_cells_reader(sheet_name)
+        .with_context(|| format!("无法读取工作表: {}", sheet_name))?;
+    let dimensions = cells.dimensions();
+    // 与 `declared_row_count` 相同，A1:A1 可能是缺少 dimension，交给完整读取
+    if dimensions.start == dimensions.end {
+        return Ok(None);
+    }
+
+    let first_col = dimensions.start.1;
+    let mut columns = vec![String::new(); (dimensions.end.1 - first_col + 1) as usize];
+    let mut header_row = None;
+    while let Some(cell) = cells.next_cell().with_context(|| format!("无法读取工作表: {}", sheet_name))? {
+        let value = Data::from(cell.get_value().clone());
+        if value == Data::Empty {
+            continue;
+        }
+        let (row, col) = cell.get_position();
+        if *header_row.get_or_insert(row) != row {
+            break;
+        }
+        if let Some(column) = col.checked_sub(first_col).and_then(|index| columns.get_mut(index as usize)) {
+            *column = ExcelReader::data_to_string(&value);
+        }
+    }
+    Ok(Some(columns))
+}
+
+/// 第 0 行含文本单元格时为表头；否则第 1 行含文本单元格时取第 1 行；都没有时默认第 0 行
+fn detect_header_index(rows: &[Vec<String>]) -> usize {
+    let has_text = |index: usize| rows.get(index).is_some_and(|row| row.iter().any(|cell| is_text_cell(cell)));
+    if !has_text(0) && has_text(1) { 1 } else { 0 }
+}
+
+/// 清空隐藏行列中的单元格（保留首行表头），行号不变；`start` 为区域左上角的绝对坐标
+fn blank_hidden_cells(rows: &mut [Vec<String>], start: (u32, u32), hidden: &HiddenCells) {
+    if hidden.is_empty() {
+        return;
+    }
+    for (row_index, row) in rows.iter_mut().enumerate().skip(1) {
+        for (col_index, cell) in row.iter_mut().enumerate() {
+            if hidden.is_hidden(start.0 + row_index as u32, start.1 + col_index as u32) {
+                cell.clear();
+            }
+        }
+    }
+}
+
+/// 日期格式单元格的 `YYYY-MM-DD` 文本，时间部分舍去；按工作簿的日期系统（1900 或 1904）换算。
+/// 时长格式、序列号超出日期范围或为不存在的 1900-02-29 时返回 `None`
+fn date_cell_string(date_time: &ExcelDateTime) -> Option<String> {
+    if !date_time.is_datetime() || !DATE_SERIAL_RANGE.contains(&date_time.as_f64()) {
+        return None;
+    }
+    let (year, month, day, ..) = date_time.to_ymd_hms_milli();
+    NaiveDate::from_ymd_opt(year.into(), month.into(), day.into()).map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// 按行是否为空的序列，计算去掉首尾空行后剩余的行数
+fn non_empty_span(empty_flags: impl Iterator<Item = bool>) -> usize {
+    let mut first = None;
+    let mut last = 0;
+    for (index, is_empty) in empty_flags.enumerate() {
+        if !is_empty {
+            first.get_or_insert(index);
+            last = index;
+        }
+    }
+    first.map_or(0, |first| last - first + 1)
+}
+
+/// 单工作表数据源的工作表名，取文件名（不含扩展名）
+fn single_sheet_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "Sheet1".to_string())
+}
+
+/// 改了扩展名、伪装成 Excel 的文件的真实格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DisguisedFormat {
+    Html,
+    Csv,
+}
+
+impl DisguisedFormat {
+    fn label(&self) -> &'static str {
+        match self {
+            DisguisedFormat::Html => "HTML 表格",
+            DisguisedFormat::Csv => "CSV 文本",
+        }
+    }
+}
+
+/// 按文件头判断无法作为工作簿打开的文件是否为 HTML 表格或 CSV；
+/// zip（`PK`，真正的 xlsx）和 OLE 复合文件（xls）返回 `None`
+fn sniff_disguised_format(path: &Path) -> Option<DisguisedFormat> {
+    let mut head = Vec::with_capacity(SNIFF_LEN);
+    File::open(path).ok()?.take(SNIFF_LEN as u64).read_to_end(&mut head).ok()?;
+    detect_disguised_format(&head)
+}
+
+fn detect_disguised_format(head: &[u8]) -> Option<DisguisedFormat> {
+    const OLE_MAGIC: [u8; 4] = [0xD0, 0xCF, 0x11, 0xE0];
+    if head.starts_with(b"PK") || head.starts_with(&OLE_MAGIC) {
+        return None;
+    }
+
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches(['\u{FEFF}', ' ', '\t', '\r', '\n']).to_ascii_lowercase();
+    if text.starts_with("<!doctype html") || text.starts_with("<html") || text.contains("<table") {
+        return Some(DisguisedFormat::Html);
+    }
+
+    // 纯文本且首行带逗号或制表符时按 CSV 处理
+    let is_text = !head.contains(&0);
+    let first_line = text.lines().next().unwrap_or_default();
+    (is_text && (first_line.contains(',') || first_line.contains('\t'))).then_some(DisguisedFormat::Csv)
+}
+
+/// 解析 HTML 中所有 `<tr>` 行，单元格去掉标签并还原实体
+fn parse_html_table(content: &str) -> Vec<Vec<String>> {
+    let rows = HTML_ROW
+        .captures_iter(content)
+        .map(|row| {
+            HTML_CELL
+                .captures_iter(&row[1])
+                .map(|cell| StripHtmlPreprocessor.process(&cell[1]).trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|row| !row.is_empty())
+        .collect();
+    finish_rows(rows)
+}
+
+/// 解析 UTF-8 CSV 文本（支持引号、转义引号和引号内换行），行按最长行补齐
+fn parse_csv(content: &str) -> Vec<Vec<String>> {
+    let content = content.strip_prefix('\u{FEFF}').unwrap_or(content);
+
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_quotes = true,
+            ',' => row.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+
+    finish_rows(rows)
+}
+
+/// 整理解析出的行：表头去掉空白，各行补齐到相同列数
+fn finish_rows(mut rows: Vec<Vec<String>>) -> Vec<Vec<String>> {
+    // 列名去掉首尾空白及残留的 BOM（如重复写入的 BOM），保证按列名匹配
+    if let Some(header) = rows.first_mut() {
+        for name in header.iter_mut() {
+            *name = name
+                .trim_matches(|c: char| c.is_whitespace() || c == '\u{FEFF}')
+                .to_string();
+        }
+    }
+
+    let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut rows {
+        row.resize(width, String::new());
+    }
+
+    rows
+}
+
+#[derive(Debug)]
 pub struct SheetData {
     pub rows: Vec<Vec<String>>,
+    /// `rows[0]` 在工作表中的行索引（从 0 开始）：已用区域不从第 1 行开始或丢弃了前面的行时不为 0
+    pub first_row: usize,
+    /// 第 0 列在工作表中的列索引（从 0 开始）
+    pub first_col: usize,
+    /// 列名到列索引的映射，首次按列名查找时构建
+    header_map_cache: OnceCell<HashMap<String, usize>>,
+}
+
+/// 克隆时不复制缓存，由副本在首次查找时按自身表头重建
+impl Clone for SheetData {
+    fn clone(&self) -> Self {
+        Self::new(self.rows.clone()).with_origin(self.first_row, self.first_col)
+    }
 }
 
 impl SheetData {
+    pub fn new(rows: Vec<Vec<String>>) -> Self {
+        Self {
+            rows,
+            first_row: 0,
+            first_col: 0,
+            header_map_cache: OnceCell::new(),
+        }
+    }
+
+    /// 设置数据左上角在工作表中的位置，用于换算 Excel 行号和单元格位置
+    pub fn with_origin(mut self, first_row: usize, first_col: usize) -> Self {
+        self.first_row = first_row;
+        self.first_col = first_col;
+        self
+    }
+
     pub fn column_names(&self) -> Vec<String> {
         self.rows.first().cloned().unwrap_or_default()
     }
 
+    /// 列名到列索引的映射；列名重复时取最左侧的一列
+    pub fn header_map(&self) -> HashMap<String, usize> {
+        self.cached_header_map().clone()
+    }
+
+    fn cached_header_map(&self) -> &HashMap<String, usize> {
+        self.header_map_cache.get_or_init(|| {
+            let mut map = HashMap::new();
+            for (index, name) in self.column_names().into_iter().enumerate() {
+                map.entry(name).or_insert(index);
+            }
+            map
+        })
+    }
+
     pub fn get_column_index(&self, column_name: &str) -> Option<usize> {
-        self.rows.first()?.iter().position(|c| c == column_name)
+        self.cached_header_map().get(column_name).copied()
+    }
+
+    /// 按前两行推测表头所在行（0 或 1）
+    pub fn detect_header_row(&self) -> usize {
+        detect_header_index(&self.rows[..self.rows.len().min(2)])
+    }
+
+    /// 丢弃表头之前的 `count` 行，使第 `count` 行成为表头
+    pub fn skip_rows(mut self, count: usize) -> SheetData {
+        let count = count.min(self.rows.len());
+        self.rows.drain(..count);
+        let (first_row, first_col) = (self.first_row + count, self.first_col);
+        SheetData::new(self.rows).with_origin(first_row, first_col)
+    }
+
+    /// 去掉首尾所有单元格都为空的行，中间的空行保留；去掉的前导行计入 `first_row`，行号不变
+    pub fn trim_empty_rows(&self) -> SheetData {
+        let is_blank = |row: &Vec<String>| row.iter().all(String::is_empty);
+        let Some(first) = self.rows.iter().position(|row| !is_blank(row)) else {
+            return SheetData::new(Vec::new());
+        };
+        let last = self.rows.iter().rposition(|row| !is_blank(row)).unwrap_or(first);
+
+        SheetData::new(self.rows[first..=last].to_vec()).with_origin(self.first_row + first, self.first_col)
+    }
+
+    /// 去掉左右两侧所有单元格都为空的列（含表头），中间的空列保留
+    pub fn trim_empty_columns(&self) -> SheetData {
+        let width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let is_blank = |col: usize| {
+            self.rows
+                .iter()
+                .all(|row| row.get(col).is_none_or(String::is_empty))
+        };
+        let Some(first) = (0..width).position(|col| !is_blank(col)) else {
+            return SheetData::new(Vec::new());
+        };
+        let last = (0..width).rposition(|col| !is_blank(col)).unwrap_or(first);
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let end = row.len().min(last + 1);
+                row.get(first..end).map(<[String]>::to_vec).unwrap_or_default()
+            })
+            .collect();
+        SheetData::new(rows).with_origin(self.first_row, self.first_col + first)
+    }
+
+    /// 遍历所有行（含表头），索引 0 为表头
+    pub fn iter_rows(&self) -> impl Iterator<Item = (usize, &[String])> {
+        self.rows.iter().map(Vec::as_slice).enumerate()
+    }
+
+    /// 遍历数据行（跳过表头），索引为从 1 开始、相对 `first_row` 的行号
+    pub fn iter_data_rows(&self) -> impl Iterator<Item = (usize, &[String])> {
+        self.iter_rows().skip(1).map(|(index, row)| (index + 1, row))
     }
 
     pub fn get_column_by_name(&self, column_name: &str) -> Result<Vec<(usize, String)>> {
         let col_index = self.get_column_index(column_name)
             .with_context(|| format!("列不存在: {}", column_name))?;
 
-        let mut result = Vec::new();
-
-        for (row_index, row) in self.rows.iter().enumerate().skip(1) {
-            if col_index < row.len() {
-                result.push((row_index, row[col_index].clone()));
-            } else {
-                result.push((row_index, String::new()));
-            }
-        }
+        let result = self
+            .iter_data_rows()
+            .map(|(row_number, row)| {
+                let value = row.get(col_index).cloned().unwrap_or_default();
+                (row_number - 1, value)
+            })
+            .collect();
 
         Ok(result)
     }
 
-    pub fn get_context(&self, row_index: usize, context_lines: usize) -> (Vec<String>, Vec<String>) {
+    /// 取 `row_index` 之前 `before_lines` 行和之后 `after_lines` 行作为上下文，各列以默认分隔符连接
+    pub fn get_context(&self, row_index: usize, before_lines: usize, after_lines: usize) -> (Vec<String>, Vec<String>) {
+        self.get_context_with_separator(row_index, before_lines, after_lines, DEFAULT_CONTEXT_CELL_SEP)
+    }
+
+    /// 同 `get_context`，行内各列以 `cell_sep` 连接
+    pub fn get_context_with_separator(
+        &self,
+        row_index: usize,
+        before_lines: usize,
+        after_lines: usize,
+        cell_sep: &str,
+    ) -> (Vec<String>, Vec<String>) {
         let mut before = Vec::new();
         let mut after = Vec::new();
 
-        for i in (1..=context_lines).rev() {
+        for i in (1..=before_lines).rev() {
             let idx = row_index + 1;
-            if idx > i {
-                if let Some(row) = self.rows.get(idx - i) {
-                    before.push(row.join(" | "));
-                }
+            if idx > i
+                && let Some(row) = self.rows.get(idx - i)
+            {
+                before.push(row.join(cell_sep));
             }
         }
 
-        for i in 1..=context_lines {
+        for i in 1..=after_lines {
             let idx = row_index + 1 + i;
             if let Some(row) = self.rows.get(idx) {
-                after.push(row.join(" | "));
+                after.push(row.join(cell_sep));
             }
         }
 
@@ -167,26 +775,60 @@ impl SheetData {
     }
 }
 
+/// 推荐目标列的关键词，按优先级从高到低排列（列名包含即命中）
+const RECOMMENDED_KEYWORDS: [&str; 8] = ["消息内容", "内容", "正文", "详情", "备注", "说明", "文本", "描述"];
+
+/// 列名的推荐优先级，数值越小越优先；完全等于"消息内容"最优先，不含任何关键词时为 `None`
+pub fn recommendation_rank(column: &str) -> Option<usize> {
+    if column == RECOMMENDED_KEYWORDS[0] {
+        return Some(0);
+    }
+
+    RECOMMENDED_KEYWORDS
+        .iter()
+        .position(|keyword| column.contains(keyword))
+        .map(|index| index + 1)
+}
+
+/// 按推荐优先级选出的目标列；界面的列选择和未指定目标列时的处理共用
+pub fn find_recommended_column(columns: &[String]) -> Option<&String> {
+    columns
+        .iter()
+        .filter_map(|col| recommendation_rank(col).map(|rank| (rank, col)))
+        .min_by_key(|(rank, _)| *rank)
+        .map(|(_, col)| col)
+}
+
 #[derive(Debug, Clone)]
 pub struct ExcelInfo {
     pub sheet_names: Vec<String>,
     pub sheet_columns: HashMap<String, Vec<String>>,
+    /// 各工作表的数据行数（不含表头），xlsx 按 dimension 估算
     pub sheet_row_counts: HashMap<String, usize>,
 }
 
 impl ExcelInfo {
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_file_with_password(path, None, &SheetExclusion::default())
+    }
+
+    /// 只读取未被排除的工作表的列名和行数
+    pub fn from_file_with_password<P: AsRef<Path>>(
+        path: P,
+        password: Option<&str>,
+        exclusion: &SheetExclusion,
+    ) -> Result<Self> {
         let path_ref = path.as_ref();
 
-        let mut reader = ExcelReader::open(path_ref)?;
-        let sheet_names = reader.sheet_names();
+        let mut reader = ExcelReader::open_with_password(path_ref, password)?;
+        let sheet_names = exclusion.filter(reader.sheet_names());
 
         let mut sheet_columns = HashMap::new();
         let mut sheet_row_counts = HashMap::new();
 
         for sheet_name in &sheet_names {
             let columns = reader.read_column_names(sheet_name)?;
-            let row_count = reader.row_count(sheet_name)?;
+            let row_count = reader.row_count_from_dimension(sheet_name)?.saturating_sub(1);
 
             sheet_columns.insert(sheet_name.clone(), columns);
             sheet_row_counts.insert(sheet_name.clone(), row_count);
@@ -213,15 +855,212 @@ impl ExcelInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn test_find_recommended_column() {
+        let columns = vec![
+            "姓名".to_string(),
+            "消息内容".to_string(),
+            "时间".to_string(),
+        ];
+
+        let recommended = find_recommended_column(&columns);
+        assert_eq!(recommended, Some(&"消息内容".to_string()));
+    }
+
+    #[test]
+    fn test_find_recommended_column_priority() {
+        let columns = vec![
+            "短信消息内容".to_string(),
+            "消息内容".to_string(),
+        ];
+        assert_eq!(find_recommended_column(&columns), Some(&"消息内容".to_string()));
+
+        let columns = vec![
+            "描述".to_string(),
+            "正文".to_string(),
+            "内容摘要".to_string(),
+        ];
+        assert_eq!(find_recommended_column(&columns), Some(&"内容摘要".to_string()));
+
+        let columns = vec!["备注".to_string(), "详情".to_string()];
+        assert_eq!(find_recommended_column(&columns), Some(&"详情".to_string()));
+
+        let columns = vec!["姓名".to_string(), "时间".to_string()];
+        assert_eq!(find_recommended_column(&columns), None);
+    }
+    use calamine::ExcelDateTimeType;
+
+    #[test]
+    fn test_parse_csv() {
+        let rows = parse_csv("\u{FEFF}姓名,消息内容\r\n张三,\"电话13812345678, \"\"备注\"\"\"\r\n李四,\"第一行\n第二行\"\n王五\n");
+
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0], vec!["姓名", "消息内容"]);
+        assert_eq!(rows[1], vec!["张三", "电话13812345678, \"备注\""]);
+        assert_eq!(rows[2], vec!["李四", "第一行\n第二行"]);
+        assert_eq!(rows[3], vec!["王五", ""]);
+    }
+
+    #[test]
+    fn test_detect_disguised_format() {
+        assert_eq!(detect_disguised_format(b"PK\x03\x04rest"), None);
+        assert_eq!(detect_disguised_format(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1]), None);
+        assert_eq!(
+            detect_disguised_format("\u{FEFF}\r\n<!DOCTYPE html><html>".as_bytes()),
+            Some(DisguisedFormat::Html)
+        );
+        assert_eq!(
+            detect_disguised_format(b"<meta charset=utf-8>\n<table border=1>"),
+            Some(DisguisedFormat::Html)
+        );
+        assert_eq!(detect_disguised_format("姓名,消息内容\n张三,你好".as_bytes()), Some(DisguisedFormat::Csv));
+        assert_eq!(detect_disguised_format(b"\x00\x01binary"), None);
+    }
+
+    #[test]
+    fn test_open_html_disguised_as_xlsx() {
+        let dir = scratch_dir();
+        let path = dir.path().join("disguised_html.xlsx");
+        std::fs::write(
+            &path,
+            "<html><body><table>\
+             <tr><th>姓名</th><th> 消息内容 </th></tr>\
+             <tr><td>张三</td><td><b>电话</b>13812345678 &amp; 备注</td></tr>\
+             <tr><td>李四</td></tr>\
+             </table></body></html>",
+        )
+        .unwrap();
+
+        let mut reader = ExcelReader::open(&path).unwrap();
+        let sheet_name = reader.sheet_names().remove(0);
+        let sheet_data = reader.read_sheet(&sheet_name).unwrap();
+        assert_eq!(sheet_data.get_column_index("消息内容"), Some(1));
+        assert_eq!(sheet_data.rows[1], vec!["张三", "电话13812345678 & 备注"]);
+        assert_eq!(sheet_data.rows[2], vec!["李四", ""]);
+    }
+
+    #[test]
+    fn test_date_cell_string() {
+        let date = |serial: f64| date_cell_string(&ExcelDateTime::new(serial, ExcelDateTimeType::DateTime, false));
+        assert_eq!(date(1.0).as_deref(), Some("1900-01-01"));
+        assert_eq!(date(59.0).as_deref(), Some("1900-02-28"));
+        assert_eq!(date(60.0), None);
+        assert_eq!(date(61.0).as_deref(), Some("1900-03-01"));
+        assert_eq!(date(44927.0).as_deref(), Some("2023-01-01"));
+        assert_eq!(date(44927.99).as_deref(), Some("2023-01-01"));
+        assert_eq!(date(60000.0).as_deref(), Some("2064-04-08"));
+        assert_eq!(date(0.5), None);
+        assert_eq!(date(60001.0), None);
+        assert_eq!(date_cell_string(&ExcelDateTime::new(1.5, ExcelDateTimeType::TimeDelta, false)), None);
+    }
+
+    #[test]
+    fn test_date_cell_string_1904_system() {
+        // 1904 日期系统的序列号比 1900 日期系统少 1462 天
+        let date = |serial: f64| date_cell_string(&ExcelDateTime::new(serial, ExcelDateTimeType::DateTime, true));
+        assert_eq!(date(43465.0).as_deref(), Some("2023-01-01"));
+        assert_eq!(date(1.0).as_deref(), Some("1904-01-02"));
+    }
+
+    #[test]
+    fn test_declared_header_matches_full_read() {
+        let dir = scratch_dir();
+        let path = dir.path().join("declared_header.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        // 已用区域从 C3 开始，表头中间有空列，数据行比表头宽
+        worksheet.write_string(2, 2, "姓名").unwrap();
+        worksheet.write_string(2, 4, "消息内容").unwrap();
+        worksheet.write_string(3, 2, "张三").unwrap();
+        worksheet.write_string(3, 5, "电话13812345678").unwrap();
+        workbook.save(&path).unwrap();
+
+        let mut reader = ExcelReader::open(&path).unwrap();
+        let header = reader.declared_header("Sheet1").unwrap();
+        let sheet_data = reader.read_sheet("Sheet1").unwrap();
+
+        assert_eq!(header.as_deref(), Some(sheet_data.rows[0].as_slice()));
+        assert_eq!(sheet_data.rows[0], vec!["姓名", "", "消息内容", ""]);
+
+        let mut reader = ExcelReader::from_source(WorkbookSource::Csv {
+            sheet_name: "Sheet1".to_string(),
+            rows: parse_csv("消息内容\n你好\n"),
+        });
+        assert_eq!(reader.declared_header("Sheet1").unwrap(), None);
+        assert_eq!(reader.read_column_names("Sheet1").unwrap(), vec!["消息内容"]);
+    }
+
+    #[test]
+    fn test_exclude_hidden_rows_and_columns() {
+        let dir = scratch_dir();
+        let path = dir.path().join("hidden_cells.xlsx");
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = [
+            ["姓名", "备注", "消息内容"],
+            ["张三", "a", "13812345678"],
+            ["李四", "b", "13912345678"],
+            ["王五", "c", "13712345678"],
+            ["赵六", "d", "13612345678"],
+        ];
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                worksheet.write_string(row as u32, col as u16, *value).unwrap();
+            }
+        }
+        worksheet.set_row_hidden(2).unwrap();
+        worksheet.set_row_hidden(4).unwrap();
+        worksheet.set_column_hidden(1).unwrap();
+        workbook.save(&path).unwrap();
+
+        let sheet_data = ExcelReader::open(&path).unwrap().read_sheet("Sheet1").unwrap();
+        assert_eq!(sheet_data.rows[2], vec!["李四", "b", "13912345678"]);
+
+        // 中间的隐藏行清空后保留，末尾的隐藏行随空行去掉，其余行的位置不变
+        let mut reader = ExcelReader::open(&path).unwrap().with_include_hidden(false);
+        let sheet_data = reader.read_sheet("Sheet1").unwrap();
+        assert_eq!(sheet_data.rows.len(), 4);
+        assert_eq!(sheet_data.first_row, 0);
+        assert_eq!(sheet_data.rows[0], vec!["姓名", "备注", "消息内容"]);
+        assert_eq!(sheet_data.rows[1], vec!["张三", "", "13812345678"]);
+        assert_eq!(sheet_data.rows[2], vec!["", "", ""]);
+        assert_eq!(sheet_data.rows[3], vec!["王五", "", "13712345678"]);
+    }
+
+    #[test]
+    fn test_detect_header_row() {
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+
+        // 第 0 行是文本表头
+        let sheet_data = SheetData::new(vec![row(&["姓名", "消息内容"]), row(&["1", "电话13812345678"])]);
+        assert_eq!(sheet_data.detect_header_row(), 0);
+
+        // 第 0 行全是数字，表头在第 1 行
+        let sheet_data = SheetData::new(vec![row(&["2024", "1.5", ""]), row(&["序号", "消息内容", ""]), row(&["1", "你好", ""])]);
+        assert_eq!(sheet_data.detect_header_row(), 1);
+        let sheet_data = sheet_data.skip_rows(1);
+        assert_eq!(sheet_data.get_column_index("消息内容"), Some(1));
+        assert_eq!(sheet_data.rows.len(), 2);
+        assert_eq!(sheet_data.first_row, 1);
+
+        // 数字与文本混合的第 0 行仍视为表头
+        let sheet_data = SheetData::new(vec![row(&["1", "消息内容"]), row(&["序号", "备注"])]);
+        assert_eq!(sheet_data.detect_header_row(), 0);
+
+        // 前两行都没有文本时默认第 0 行
+        let sheet_data = SheetData::new(vec![row(&["1", "2"]), row(&["3", " "])]);
+        assert_eq!(sheet_data.detect_header_row(), 0);
+        assert_eq!(SheetData::new(Vec::new()).detect_header_row(), 0);
+    }
 
     #[test]
     fn test_sheet_data_column_names() {
-        let sheet_data = SheetData {
-            rows: vec![
-                vec!["姓名".to_string(), "消息内容".to_string()],
-                vec!["张三".to_string(), "电话13812345678".to_string()],
-            ],
-        };
+        let sheet_data = SheetData::new(vec![
+            vec!["姓名".to_string(), "消息内容".to_string()],
+            vec!["张三".to_string(), "电话13812345678".to_string()],
+        ]);
 
         let columns = sheet_data.column_names();
         assert_eq!(columns.len(), 2);
@@ -231,15 +1070,97 @@ mod tests {
 
     #[test]
     fn test_sheet_data_get_column_index() {
-        let sheet_data = SheetData {
-            rows: vec![
-                vec!["姓名".to_string(), "消息内容".to_string()],
-                vec!["张三".to_string(), "电话13812345678".to_string()],
-            ],
-        };
+        let sheet_data = SheetData::new(vec![
+            vec!["姓名".to_string(), "消息内容".to_string()],
+            vec!["张三".to_string(), "电话13812345678".to_string()],
+        ]);
 
         assert_eq!(sheet_data.get_column_index("姓名"), Some(0));
         assert_eq!(sheet_data.get_column_index("消息内容"), Some(1));
         assert_eq!(sheet_data.get_column_index("不存在"), None);
     }
+
+    #[test]
+    fn test_sheet_data_header_map() {
+        let mut header: Vec<String> = (0..200).map(|i| format!("列{}", i)).collect();
+        header.push("列0".to_string());
+        let sheet_data = SheetData::new(vec![header, vec!["值".to_string(); 201]]);
+
+        let map = sheet_data.header_map();
+        assert_eq!(map.len(), 200);
+        for i in 0..200 {
+            assert_eq!(map[&format!("列{}", i)], i);
+            assert_eq!(sheet_data.get_column_index(&format!("列{}", i)), Some(i));
+        }
+
+        // 克隆后按副本的表头重新构建
+        let mut cloned = sheet_data.clone();
+        cloned.rows[0] = vec!["消息内容".to_string()];
+        assert_eq!(cloned.get_column_index("消息内容"), Some(0));
+        assert_eq!(cloned.get_column_index("列1"), None);
+    }
+
+    #[test]
+    fn test_trim_empty_rows_keeps_interior() {
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let sheet_data = SheetData::new(vec![
+            row(&["", ""]),
+            row(&["姓名", "消息内容"]),
+            row(&["张三", "电话13812345678"]),
+            row(&["", ""]),
+            row(&["李四", ""]),
+            row(&["", ""]),
+            row(&[]),
+        ]);
+
+        let trimmed = sheet_data.trim_empty_rows();
+        assert_eq!(trimmed.rows.len(), 4);
+        assert_eq!(trimmed.rows[0], ["姓名", "消息内容"]);
+        assert_eq!(trimmed.first_row, 1);
+        assert_eq!(trimmed.rows[2], ["", ""]);
+        assert_eq!(trimmed.rows[3], ["李四", ""]);
+        assert_eq!(trimmed.get_column_index("消息内容"), Some(1));
+
+        assert!(SheetData::new(vec![row(&["", ""])]).trim_empty_rows().rows.is_empty());
+
+        assert_eq!(non_empty_span(sheet_data.rows.iter().map(|r| r.iter().all(String::is_empty))), 4);
+    }
+
+    #[test]
+    fn test_trim_empty_columns() {
+        let row = |cells: &[&str]| cells.iter().map(|c| c.to_string()).collect::<Vec<_>>();
+        let sheet_data = SheetData::new(vec![
+            row(&["", "姓名", "", "消息内容", ""]),
+            row(&["", "张三", "", "电话13812345678", ""]),
+            row(&["", "李四"]),
+        ]);
+
+        let trimmed = sheet_data.trim_empty_columns();
+        assert_eq!(trimmed.rows[0], ["姓名", "", "消息内容"]);
+        assert_eq!(trimmed.rows[1], ["张三", "", "电话13812345678"]);
+        assert_eq!(trimmed.rows[2], ["李四"]);
+    }
+
+    #[test]
+    fn test_sheet_data_iter_rows() {
+        let sheet_data = SheetData::new(vec![
+            vec!["姓名".to_string(), "消息内容".to_string()],
+            vec!["张三".to_string(), "电话13812345678".to_string()],
+            vec!["李四".to_string()],
+        ]);
+
+        let rows: Vec<_> = sheet_data.iter_rows().collect();
+        assert_eq!(rows.len(), 3);
+        for (index, row) in rows {
+            assert_eq!(row, sheet_data.rows[index].as_slice());
+        }
+
+        let data_rows: Vec<_> = sheet_data.iter_data_rows().collect();
+        assert_eq!(data_rows.len(), 2);
+        assert_eq!(data_rows[0], (2, sheet_data.rows[1].as_slice()));
+        assert_eq!(data_rows[1], (3, sheet_data.rows[2].as_slice()));
+
+        let column = sheet_data.get_column_by_name("消息内容").unwrap();
+        assert_eq!(column, vec![(1, "电话13812345678".to_string()), (2, String::new())]);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,1624 @@
+use anyhow::{bail, Context, Result};
+use arrow_array::{ArrayRef, BooleanArray, RecordBatch, StringArray, UInt32Array, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use rust_xlsxwriter::FormatBorder;
+use rust_xlsxwriter::*;
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{IdCardProfile, Masker, ProcessingStatistics, ResultWriter};
+use crate::models::{Config, ExportStyle, ExtractResult, MatchInfo, OutputFormat, PatternType};
+
+/// 导出表头
+const HEADERS: [&str; 18] = [
+    "处理时间", "源文件名", "工作表", "行号", "单元格位置", "手机号", "手机号有效性",
+    "身份证号", "身份证有效性", "银行卡号", "银行卡有效性",
+    "姓名", "姓名有效性",
+    "源文本", "上文", "下文", "疑似占位号码", "异常标记",
+];
+
+/// 源文件完整路径列，可按配置关闭
+const SOURCE_PATH_HEADER: &str = "源文件路径";
+
+/// 启用澳门身份证号提取时的附加列
+const MACAU_ID_HEADERS: [&str; 2] = ["澳门身份证号", "澳门身份证有效性"];
+
+/// 启用微信号提取时的附加列
+const WECHAT_ID_HEADERS: [&str; 2] = ["微信号", "微信号有效性"];
+
+/// 配置了自定义规则时的附加列，命中写作 `规则名: 值`
+const CUSTOM_MATCHES_HEADER: &str = "自定义规则命中";
+
+/// 设置批注文件时的人工备注列
+const REMARK_HEADER: &str = "备注";
+
+/// 透视分组统计工作表名
+const STATISTICS_SHEET_NAME: &str = "统计";
+
+/// 分析型脱敏附加列
+const ANALYTIC_HEADERS: [&str; 3] = ["身份证省份", "身份证性别", "身份证年龄段"];
+
+const ROW_NUMBER_COLUMN: u16 = 3;
+const SOURCE_TEXT_COLUMN: u16 = 13;
+/// 自动换行的文本列：源文本、上文、下文
+const TEXT_COLUMNS: [u16; 3] = [13, 14, 15];
+const VALIDITY_COLUMNS: [u16; 4] = [6, 8, 10, 12];
+/// 以醒目颜色标出的列：疑似占位号码、异常标记
+const WARNING_COLUMNS: [u16; 2] = [16, 17];
+
+/// 结果导出器
+pub trait ResultExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()>;
+
+    /// 输出文件扩展名（不含点）
+    fn extension(&self) -> &str;
+}
+
+fn ensure_not_empty(results: &[ExtractResult]) -> Result<()> {
+    if results.is_empty() {
+        bail!("没有可导出的结果");
+    }
+    Ok(())
+}
+
+/// 实际导出的行：启用 `one_match_per_row` 时每条命中拆成单独一行
+fn export_rows<'a>(config: &Config, results: &'a [ExtractResult]) -> Cow<'a, [ExtractResult]> {
+    if config.one_match_per_row {
+        Cow::Owned(results.iter().flat_map(ExtractResult::split_per_match).collect())
+    } else {
+        Cow::Borrowed(results)
+    }
+}
+
+/// 一类命中的两列表头：默认为命中值和有效性，开启 `split_valid_columns` 时为有效值和无效值
+fn value_headers(config: &Config, [value, validity]: [&str; 2]) -> [String; 2] {
+    if config.split_valid_columns {
+        [format!("有效{}", value), format!("无效{}", value)]
+    } else {
+        [value.to_string(), validity.to_string()]
+    }
+}
+
+/// 当前配置下的导出表头，附带的源数据列追加在最后
+fn export_headers(config: &Config) -> Vec<String> {
+    let mut headers: Vec<String> = HEADERS.iter().map(|h| h.to_string()).collect();
+    for col in VALIDITY_COLUMNS.map(usize::from) {
+        let [value, validity] = value_headers(config, [HEADERS[col - 1], HEADERS[col]]);
+        headers[col - 1] = value;
+        headers[col] = validity;
+    }
+    if config.export_source_path {
+        headers.push(SOURCE_PATH_HEADER.to_string());
+    }
+    if config.enable_macau_id {
+        headers.extend(value_headers(config, MACAU_ID_HEADERS));
+    }
+    if config.enable_wechat {
+        headers.extend(value_headers(config, WECHAT_ID_HEADERS));
+    }
+    if !config.custom_patterns.is_empty() {
+        headers.push(CUSTOM_MATCHES_HEADER.to_string());
+    }
+    if config.id_card_analytic_mask {
+        headers.extend(ANALYTIC_HEADERS.iter().map(|h| h.to_string()));
+    }
+    if config.annotation_file.is_some() {
+        headers.push(REMARK_HEADER.to_string());
+    }
+    headers.extend(config.include_source_columns.iter().cloned());
+    headers
+}
+
+/// 一类命中的两列：默认为命中值和有效性；开启 `split_valid_columns` 时按有效性分成有效值和无效值，
+/// `show` 为该类命中值的显示方式
+fn value_cells(
+    config: &Config,
+    matches: &[MatchInfo],
+    validity: String,
+    show: impl Fn(&[MatchInfo]) -> String,
+) -> [String; 2] {
+    if config.split_valid_columns {
+        let (valid, invalid): (Vec<MatchInfo>, Vec<MatchInfo>) = matches.iter().cloned().partition(|m| m.is_valid);
+        [show(&valid), show(&invalid)]
+    } else {
+        [show(matches), validity]
+    }
+}
+
+/// 单条结果的导出文本，与 `export_headers` 按列对应
+fn export_cells(config: &Config, result: &ExtractResult) -> Vec<String> {
+    let display = |matches: &[MatchInfo]| display_values_str(config, matches);
+    let id_cards = |matches: &[MatchInfo]| {
+        if config.id_card_analytic_mask {
+            matches
+                .iter()
+                .map(|m| Masker::mask_id_card_analytic(&m.value))
+                .collect::<Vec<_>>()
+                .join(", ")
+        } else {
+            display_values_str(config, matches)
+        }
+    };
+    let names = |matches: &[MatchInfo]| matches.iter().map(|m| m.value.as_str()).collect::<Vec<_>>().join(", ");
+
+    let mut cells = vec![
+        result.processing_timestamp.clone(),
+        result.source_file.clone(),
+        result.sheet_name.clone(),
+        result.row_number.to_string(),
+        result.cell_ref().unwrap_or_default(),
+    ];
+    cells.extend(value_cells(config, &result.phone_numbers, result.phone_validity_str(), display));
+    cells.extend(value_cells(config, &result.id_cards, result.id_card_validity_str(), id_cards));
+    cells.extend(value_cells(config, &result.bank_cards, result.bank_card_validity_str(), display));
+    cells.extend(value_cells(config, &result.names, result.names_validity_str(), names));
+    cells.extend([
+        result.source_text.clone(),
+        result.context_before.join(&config.context_row_sep),
+        result.context_after.join(&config.context_row_sep),
+        result.suspicious_str(),
+        result.anomaly_str(),
+    ]);
+
+    if config.export_source_path {
+        cells.push(result.source_path.clone());
+    }
+
+    if config.enable_macau_id {
+        cells.extend(value_cells(config, &result.macau_ids, result.macau_id_validity_str(), display));
+    }
+
+    if config.enable_wechat {
+        cells.extend(value_cells(config, &result.wechat_ids, result.wechat_id_validity_str(), display));
+    }
+
+    if !config.custom_patterns.is_empty() {
+        cells.push(
+            result
+                .custom_matches
+                .iter()
+                .map(|m| format!("{}: {}", m.rule.as_deref().unwrap_or_default(), display(std::slice::from_ref(m))))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+    }
+
+    if config.id_card_analytic_mask {
+        let profiles: Vec<IdCardProfile> = result
+            .id_cards
+            .iter()
+            .filter_map(|m| IdCardProfile::parse_now(&m.value))
+            .collect();
+
+        let join = |f: fn(&IdCardProfile) -> &str| {
+            profiles.iter().map(f).collect::<Vec<_>>().join(", ")
+        };
+
+        cells.push(join(|p| p.province));
+        cells.push(join(|p| p.gender));
+        cells.push(join(|p| &p.age_group));
+    }
+
+    if config.annotation_file.is_some() {
+        cells.push(result.remark.clone());
+    }
+
+    cells.extend(config.include_source_columns.iter().map(|name| {
+        result.extra_columns.get(name).cloned().unwrap_or_default()
+    }));
+
+    cells
+}
+
+/// 超过 `max_chars` 个字符时截断并加省略号，未超过或未设置上限时返回 `None`
+fn truncate_text(text: &str, max_chars: Option<usize>) -> Option<String> {
+    let max_chars = max_chars?;
+    let (cut, _) = text.char_indices().nth(max_chars)?;
+    Some(format!("{}…", &text[..cut]))
+}
+
+/// 按配置对命中值脱敏后的结果副本，供逐条输出命中值的格式使用；不导出路径时清空路径
+fn masked_results(config: &Config, results: &[ExtractResult]) -> Vec<ExtractResult> {
+    let mut results = results.to_vec();
+
+    for result in &mut results {
+        if !config.export_source_path {
+            result.source_path.clear();
+        }
+        if config.enable_mask {
+            for m in result
+                .phone_numbers
+                .iter_mut()
+                .chain(&mut result.id_cards)
+                .chain(&mut result.bank_cards)
+                .chain(&mut result.macau_ids)
+                .chain(&mut result.wechat_ids)
+                .chain(&mut result.custom_matches)
+            {
+                m.value = Masker::mask_with_rule(&m.value, &config.mask_rule);
+            }
+        }
+        if config.id_card_analytic_mask {
+            for m in &mut result.id_cards {
+                m.value = Masker::mask_id_card_analytic(&m.value);
+            }
+        }
+    }
+
+    results
+}
+
+/// JSON 输出的结果：需要脱敏或隐藏路径时为处理过的副本
+fn json_rows<'a>(config: &Config, results: &'a [ExtractResult]) -> Cow<'a, [ExtractResult]> {
+    let rows = export_rows(config, results);
+    if config.enable_mask || config.id_card_analytic_mask || !config.export_source_path {
+        Cow::Owned(masked_results(config, &rows))
+    } else {
+        rows
+    }
+}
+
+/// 表格中显示的命中值：按配置脱敏，再按 `pretty_format_output` 分组
+fn display_values_str(config: &Config, matches: &[MatchInfo]) -> String {
+    matches
+        .iter()
+        .map(|m| {
+            let value = if config.enable_mask {
+                Masker::mask_with_rule(&m.value, &config.mask_rule)
+            } else {
+                m.value.clone()
+            };
+            if config.pretty_format_output {
+                pretty_value(&value, m.source_pattern)
+            } else {
+                value
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// 便于阅读的分组形式：手机号按 3-4-4、银行卡号每 4 位一组，其余原样；
+/// 脱敏用的 `*` 视作普通字符，长度不符的手机号保持原样
+fn pretty_value(value: &str, pattern: PatternType) -> String {
+    let compact: Vec<char> = value.chars().filter(|c| !c.is_whitespace() && *c != '-').collect();
+    let group = |chars: &[char], sizes: &mut dyn Iterator<Item = usize>| {
+        let mut groups = Vec::new();
+        let mut rest = chars;
+        while !rest.is_empty() {
+            let (head, tail) = rest.split_at(sizes.next().unwrap_or(4).min(rest.len()));
+            groups.push(head.iter().collect::<String>());
+            rest = tail;
+        }
+        groups.join(" ")
+    };
+
+    match pattern {
+        PatternType::Phone => match compact.len() {
+            11 => group(&compact, &mut [3, 4, 4].into_iter()),
+            14 if compact.starts_with(&['+', '8', '6']) => {
+                format!("+86 {}", group(&compact[3..], &mut [3, 4, 4].into_iter()))
+            }
+            _ => value.to_string(),
+        },
+        PatternType::BankCard => group(&compact, &mut std::iter::repeat(4)),
+        _ => value.to_string(),
+    }
+}
+
+/// 配置中的 `#RRGGBB` 颜色，格式错误时使用 `fallback`
+fn style_color(hex: &str, fallback: Color) -> Color {
+    ExportStyle::parse_color(hex).map_or(fallback, rgb_color)
+}
+
+fn rgb_color([r, g, b]: [u8; 3]) -> Color {
+    Color::RGB(u32::from_be_bytes([0, r, g, b]))
+}
+
+/// 结果表和统计表共用的表头格式
+fn header_format(style: &ExportStyle) -> Format {
+    let format = Format::new()
+        .set_background_color(style_color(&style.header_background, Color::RGB(0x4472C4)))
+        .set_font_color(style_color(&style.header_font_color, Color::White))
+        .set_border(FormatBorder::Thin);
+    if style.header_bold { format.set_bold() } else { format }
+}
+
+/// 一种行底色下结果行用到的全部格式，按底色预先创建，大量行时复用
+struct RowFormats {
+    plain: Format,
+    valid: Format,
+    invalid: Format,
+    suspicious: Format,
+    /// 源文本和上下文列；开启换行时自动换行并顶端对齐
+    wrap: Format,
+}
+
+impl RowFormats {
+    fn new(style: &ExportStyle, fill: Option<&str>, wrap_text: bool) -> Self {
+        let base = match fill.and_then(ExportStyle::parse_color) {
+            Some(rgb) => Format::new().set_background_color(rgb_color(rgb)),
+            None => Format::new(),
+        };
+        Self {
+            valid: base.clone().set_font_color(style_color(&style.valid_color, Color::Green)),
+            invalid: base.clone().set_font_color(style_color(&style.invalid_color, Color::Red)),
+            suspicious: base.clone().set_font_color(Color::Orange),
+            wrap: if wrap_text { base.clone().set_text_wrap().set_align(FormatAlign::Top) } else { base.clone() },
+            plain: base,
+        }
+    }
+
+    /// 下标 0 为无底色，其后依次对应 `row_colors` 中的规则
+    fn for_style(style: &ExportStyle, wrap_text: bool) -> Vec<Self> {
+        std::iter::once(Self::new(style, None, wrap_text))
+            .chain(style.row_colors.iter().map(|rule| Self::new(style, Some(&rule.color), wrap_text)))
+            .collect()
+    }
+
+    /// 按第一条匹配的底色规则选取格式
+    fn select<'a>(formats: &'a [Self], style: &ExportStyle, result: &ExtractResult) -> &'a Self {
+        let index = style.row_colors.iter().position(|rule| rule.matches(result)).map_or(0, |i| i + 1);
+        &formats[index]
+    }
+}
+
+/// Excel 导出
+pub struct XlsxExporter {
+    config: Config,
+}
+
+impl XlsxExporter {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    fn write_headers(&self, worksheet: &mut Worksheet) -> Result<()> {
+        let header_format = header_format(&self.config.export_style);
+
+        for (col, header) in export_headers(&self.config).iter().enumerate() {
+            worksheet.write_string_with_format(0, col as u16, header, &header_format)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_result_row(
+        &self,
+        worksheet: &mut Worksheet,
+        row: u32,
+        result: &ExtractResult,
+        formats: &RowFormats,
+    ) -> Result<()> {
+        let validity_columns = self.validity_columns();
+        let split = self.config.split_valid_columns;
+
+        for (col, value) in export_cells(&self.config, result).iter().enumerate() {
+            let col = col as u16;
+
+            if col == ROW_NUMBER_COLUMN {
+                worksheet.write_number_with_format(row, col, result.row_number, &formats.plain)?;
+            } else if split && validity_columns.contains(&col) && !value.is_empty() {
+                // 拆分后有效性列为无效值，其前一列为有效值
+                worksheet.write_string_with_format(row, col, value, &formats.invalid)?;
+            } else if split && validity_columns.contains(&(col + 1)) && !value.is_empty() {
+                worksheet.write_string_with_format(row, col, value, &formats.valid)?;
+            } else if !split && validity_columns.contains(&col) {
+                Self::write_validity_cell(worksheet, row, col, value, formats)?;
+            } else if col == SOURCE_TEXT_COLUMN {
+                match truncate_text(value, self.config.truncate_source_text) {
+                    Some(truncated) => {
+                        worksheet.write_string_with_format(row, col, &truncated, &formats.wrap)?;
+                        worksheet.insert_note(row, col, &Note::new(value).add_author_prefix(false))?;
+                    }
+                    None => {
+                        worksheet.write_string_with_format(row, col, value, &formats.wrap)?;
+                    }
+                }
+            } else if TEXT_COLUMNS.contains(&col) {
+                worksheet.write_string_with_format(row, col, value, &formats.wrap)?;
+            } else if WARNING_COLUMNS.contains(&col) && !value.is_empty() {
+                worksheet.write_string_with_format(row, col, value, &formats.suspicious)?;
+            } else {
+                worksheet.write_string_with_format(row, col, value, &formats.plain)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 各类命中的有效性列（拆分时为无效值列），含按配置追加的澳门身份证号和微信号
+    fn validity_columns(&self) -> Vec<u16> {
+        let headers = export_headers(&self.config);
+        let extra_column = |pair: [&str; 2]| {
+            let [_, validity] = value_headers(&self.config, pair);
+            headers.iter().position(|h| *h == validity).map(|col| col as u16)
+        };
+        VALIDITY_COLUMNS
+            .into_iter()
+            .chain(extra_column(MACAU_ID_HEADERS))
+            .chain(extra_column(WECHAT_ID_HEADERS))
+            .collect()
+    }
+
+    fn write_validity_cell(
+        worksheet: &mut Worksheet,
+        row: u32,
+        col: u16,
+        validity: &str,
+        formats: &RowFormats,
+    ) -> Result<()> {
+        if validity.contains("无效") {
+            worksheet.write_string_with_format(row, col, validity, &formats.invalid)?;
+        } else if !validity.is_empty() {
+            worksheet.write_string_with_format(row, col, validity, &formats.valid)?;
+        } else {
+            worksheet.write_string_with_format(row, col, "", &formats.plain)?;
+        }
+        Ok(())
+    }
+
+    fn apply_formatting(&self, worksheet: &mut Worksheet) -> Result<()> {
+        const COLUMN_WIDTHS: [(u16, f64); 18] = [
+            (0, 26.0), (1, 20.0), (2, 15.0), (3, 8.0), (4, 16.0), (5, 20.0), (6, 12.0),
+            (7, 22.0), (8, 12.0), (9, 22.0), (10, 12.0),
+            (11, 15.0), (12, 12.0),
+            (13, 50.0), (14, 30.0), (15, 30.0), (16, 22.0), (17, 30.0),
+        ];
+
+        for (col, width) in COLUMN_WIDTHS {
+            let width = if TEXT_COLUMNS.contains(&col) {
+                width.min(self.config.text_column_max_width)
+            } else if self.config.split_valid_columns && VALIDITY_COLUMNS.contains(&col) {
+                // 拆分后有效性列放无效值，与前一列命中值同宽
+                COLUMN_WIDTHS[col as usize - 1].1
+            } else {
+                width
+            };
+            worksheet.set_column_width(col, width)?;
+        }
+
+        let last_col = export_headers(&self.config).len() as u16 - 1;
+        let first_extra_col = last_col + 1 - self.config.include_source_columns.len() as u16;
+        for col in COLUMN_WIDTHS.len() as u16..first_extra_col {
+            worksheet.set_column_width(col, 12.0)?;
+        }
+        if self.config.export_source_path {
+            worksheet.set_column_width(COLUMN_WIDTHS.len() as u16, 40.0)?;
+        }
+        if self.config.split_valid_columns {
+            for col in self.validity_columns().into_iter().filter(|col| !VALIDITY_COLUMNS.contains(col)) {
+                worksheet.set_column_width(col - 1, 20.0)?;
+                worksheet.set_column_width(col, 20.0)?;
+            }
+        }
+        for col in first_extra_col..=last_col {
+            worksheet.set_column_width(col, 20.0)?;
+        }
+
+        // 不设置固定行高，换行的行由 Excel 按内容自适应
+        worksheet.set_freeze_panes(1, 0)?;
+        worksheet.autofilter(0, 0, 0, last_col)?;
+
+        Ok(())
+    }
+
+    /// 结果工作表加统计工作表
+    fn build_workbook(&self, results: &[ExtractResult]) -> Result<Workbook> {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+
+        self.write_headers(worksheet)?;
+
+        let style = &self.config.export_style;
+        let formats = RowFormats::for_style(style, self.config.wrap_text_in_export);
+        for (row_index, result) in export_rows(&self.config, results).iter().enumerate() {
+            let row = row_index as u32 + 1;
+            self.write_result_row(worksheet, row, result, RowFormats::select(&formats, style, result))?;
+        }
+
+        self.apply_formatting(worksheet)?;
+
+        let stats = ProcessingStatistics::from_results(results, 0.0);
+        Self::write_statistics_sheet(&mut workbook, &stats, &self.config.export_style)?;
+        Ok(workbook)
+    }
+
+    /// 导出到内存，供写入归档、HTTP 响应等场景使用
+    pub fn export_to_buffer(&self, results: &[ExtractResult]) -> Result<Vec<u8>> {
+        ensure_not_empty(results)?;
+        Ok(self.build_workbook(results)?.save_to_buffer()?)
+    }
+
+    /// 统计工作表：先是按类型的汇总表，再是各分组小表，依次纵向排列，之间空一行
+    fn write_statistics_sheet(workbook: &mut Workbook, stats: &ProcessingStatistics, style: &ExportStyle) -> Result<()> {
+        let title_format = Format::new().set_bold();
+        let header_format = header_format(style);
+
+        let worksheet = workbook.add_worksheet().set_name(STATISTICS_SHEET_NAME)?;
+        worksheet.set_column_width(0, 20.0)?;
+        worksheet.set_column_width(1, 10.0)?;
+        worksheet.set_column_width(2, 10.0)?;
+
+        worksheet.write_string_with_format(0, 0, "敏感信息汇总", &title_format)?;
+        for (col, header) in ["类型", "总数", "有效"].into_iter().enumerate() {
+            worksheet.write_string_with_format(1, col as u16, header, &header_format)?;
+        }
+        let mut row = 2u32;
+        for [label, total, valid] in stats.summary_table() {
+            worksheet.write_string(row, 0, &label)?;
+            worksheet.write_number(row, 1, total.parse::<f64>().unwrap_or_default())?;
+            worksheet.write_number(row, 2, valid.parse::<f64>().unwrap_or_default())?;
+            row += 1;
+        }
+        row += 1;
+
+        for (title, group, counts) in stats.pivot.tables() {
+            worksheet.write_string_with_format(row, 0, title, &title_format)?;
+            worksheet.write_string_with_format(row + 1, 0, group, &header_format)?;
+            worksheet.write_string_with_format(row + 1, 1, "数量", &header_format)?;
+            row += 2;
+
+            for (name, count) in counts {
+                worksheet.write_string(row, 0, name)?;
+                worksheet.write_number(row, 1, count as f64)?;
+                row += 1;
+            }
+            row += 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl ResultExporter for XlsxExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        ensure_not_empty(results)?;
+
+        self.build_workbook(results)?
+            .save(output_path)
+            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+
+        tracing::info!("结果已导出到: {}", output_path.display());
+        Ok(())
+    }
+
+    fn extension(&self) -> &str {
+        "xlsx"
+    }
+}
+
+/// CSV 导出（UTF-8 带 BOM，便于 Excel 直接打开）
+pub struct CsvExporter {
+    config: Config,
+}
+
+impl CsvExporter {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    fn write_record<W: Write>(writer: &mut W, fields: &[&str]) -> std::io::Result<()> {
+        let line = fields
+            .iter()
+            .map(|field| escape_csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",");
+        write!(writer, "{}\r\n", line)
+    }
+}
+
+/// 含逗号、引号或换行的字段加引号，内部引号加倍
+pub fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+impl CsvExporter {
+    /// 写出 BOM 和表头
+    fn write_header<W: Write>(config: &Config, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all("\u{FEFF}".as_bytes())?;
+        let headers = export_headers(config);
+        let headers: Vec<&str> = headers.iter().map(String::as_str).collect();
+        Self::write_record(writer, &headers)
+    }
+
+    fn write_rows<W: Write>(config: &Config, writer: &mut W, results: &[ExtractResult]) -> std::io::Result<()> {
+        for result in export_rows(config, results).iter() {
+            let cells = export_cells(config, result);
+            let fields: Vec<&str> = cells.iter().map(String::as_str).collect();
+            Self::write_record(writer, &fields)?;
+        }
+        Ok(())
+    }
+
+    /// 写出 BOM、表头和各结果行
+    fn write_to<W: Write>(&self, writer: &mut W, results: &[ExtractResult]) -> std::io::Result<()> {
+        Self::write_header(&self.config, writer)?;
+        Self::write_rows(&self.config, writer, results)
+    }
+
+    /// 导出为字符串（同样带 BOM），供直接写入 HTTP 响应等场景使用
+    pub fn export_to_string(&self, results: &[ExtractResult]) -> Result<String> {
+        ensure_not_empty(results)?;
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer, results)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+impl ResultExporter for CsvExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        ensure_not_empty(results)?;
+
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        self.write_to(&mut writer, results)?;
+
+        writer.flush()
+            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+
+        tracing::info!("结果已导出到: {}", output_path.display());
+        Ok(())
+    }
+
+    fn extension(&self) -> &str {
+        "csv"
+    }
+}
+
+/// JSON 导出（启用脱敏时命中值同样脱敏）
+pub struct JsonExporter {
+    config: Config,
+}
+
+impl JsonExporter {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    fn write_to<W: Write>(&self, writer: W, results: &[ExtractResult]) -> serde_json::Result<()> {
+        serde_json::to_writer_pretty(writer, &*json_rows(&self.config, results))
+    }
+
+    /// 导出为 JSON 字符串，供直接写入 HTTP 响应等场景使用
+    pub fn export_to_string(&self, results: &[ExtractResult]) -> Result<String> {
+        ensure_not_empty(results)?;
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer, results)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+impl ResultExporter for JsonExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        ensure_not_empty(results)?;
+
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        self.write_to(&mut writer, results)?;
+
+        writer.flush()
+            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+
+        tracing::info!("结果已导出到: {}", output_path.display());
+        Ok(())
+    }
+
+    fn extension(&self) -> &str {
+        "json"
+    }
+}
+
+/// Parquet 导出：长表，每条命中一行，便于 Spark/DuckDB 直接分析
+pub struct ParquetExporter {
+    config: Config,
+}
+
+impl ParquetExporter {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    fn schema() -> Arc<Schema> {
+        Arc::new(Schema::new(vec![
+            Field::new("source_file", DataType::Utf8, false),
+            Field::new("sheet_name", DataType::Utf8, false),
+            Field::new("row_number", DataType::UInt32, false),
+            Field::new("pattern", DataType::Utf8, false),
+            Field::new("value", DataType::Utf8, false),
+            Field::new("is_valid", DataType::Boolean, false),
+            Field::new("suspicious", DataType::Boolean, false),
+            Field::new("start", DataType::UInt64, false),
+            Field::new("end", DataType::UInt64, false),
+            Field::new("validation_error", DataType::Utf8, true),
+            Field::new("source_text", DataType::Utf8, false),
+            Field::new("cell_ref", DataType::Utf8, true),
+            Field::new("source_path", DataType::Utf8, true),
+            Field::new("remark", DataType::Utf8, true),
+        ]))
+    }
+
+    /// 把结果展开为每条命中一行的列式数据
+    fn build_batch(&self, results: &[ExtractResult]) -> Result<RecordBatch> {
+        let results = masked_results(&self.config, &export_rows(&self.config, results));
+
+        let hits: Vec<(&ExtractResult, &MatchInfo)> = results
+            .iter()
+            .flat_map(|result| {
+                result
+                    .phone_numbers
+                    .iter()
+                    .chain(&result.id_cards)
+                    .chain(&result.bank_cards)
+                    .chain(&result.names)
+                    .chain(&result.macau_ids)
+                    .chain(&result.wechat_ids)
+                    .chain(&result.custom_matches)
+                    .map(move |m| (result, m))
+            })
+            .collect();
+
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from_iter_values(hits.iter().map(|(r, _)| r.source_file.as_str()))),
+            Arc::new(StringArray::from_iter_values(hits.iter().map(|(r, _)| r.sheet_name.as_str()))),
+            Arc::new(UInt32Array::from_iter_values(hits.iter().map(|(r, _)| r.row_number))),
+            Arc::new(StringArray::from_iter_values(hits.iter().map(|(_, m)| m.source_pattern.as_str()))),
+            Arc::new(StringArray::from_iter_values(hits.iter().map(|(_, m)| m.value.as_str()))),
+            Arc::new(BooleanArray::from_iter(hits.iter().map(|(_, m)| Some(m.is_valid)))),
+            Arc::new(BooleanArray::from_iter(hits.iter().map(|(_, m)| Some(m.suspicious)))),
+            Arc::new(UInt64Array::from_iter_values(hits.iter().map(|(_, m)| m.position.0 as u64))),
+            Arc::new(UInt64Array::from_iter_values(hits.iter().map(|(_, m)| m.position.1 as u64))),
+            Arc::new(StringArray::from_iter(
+                hits.iter().map(|(_, m)| m.error_detail.as_ref().map(|e| e.to_string())),
+            )),
+            Arc::new(StringArray::from_iter_values(hits.iter().map(|(r, _)| r.source_text.as_str()))),
+            Arc::new(StringArray::from_iter(hits.iter().map(|(r, _)| r.cell_ref()))),
+            Arc::new(StringArray::from_iter(
+                hits.iter().map(|(r, _)| Some(r.source_path.as_str()).filter(|path| !path.is_empty())),
+            )),
+            Arc::new(StringArray::from_iter(
+                hits.iter().map(|(r, _)| Some(r.remark.as_str()).filter(|remark| !remark.is_empty())),
+            )),
+        ];
+
+        Ok(RecordBatch::try_new(Self::schema(), columns)?)
+    }
+}
+
+impl ResultExporter for ParquetExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        ensure_not_empty(results)?;
+
+        let batch = self.build_batch(results)?;
+
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()
+            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+
+        tracing::info!("结果已导出到: {}", output_path.display());
+        Ok(())
+    }
+
+    fn extension(&self) -> &str {
+        "parquet"
+    }
+}
+
+/// HTML 导出：带表头的单个表格，可直接用浏览器打开；列与内容同 CSV 导出
+pub struct HtmlExporter {
+    config: Config,
+}
+
+/// 转义 HTML 文本中的特殊字符
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl HtmlExporter {
+    pub fn new(config: Config) -> Self {
+        Self { config }
+    }
+
+    /// 写出文档开头、样式和表头，表头配色与 xlsx 导出相同
+    fn write_header<W: Write>(config: &Config, writer: &mut W) -> std::io::Result<()> {
+        let style = &config.export_style;
+        let css_color = |hex: &str, fallback: &str| {
+            ExportStyle::parse_color(hex).map_or(fallback.to_string(), |[r, g, b]| format!("#{:02x}{:02x}{:02x}", r, g, b))
+        };
+        write!(
+            writer,
+            "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>敏感信息提取结果</title>\n\
+             <style>\ntable {{ border-collapse: collapse; font-size: 13px; }}\n\
+             th, td {{ border: 1px solid #ccc; padding: 4px 8px; vertical-align: top; white-space: pre-wrap; }}\n\
+             th {{ background: {}; color: {}; font-weight: {}; position: sticky; top: 0; }}\n\
+             </style>\n</head>\n<body>\n<table>\n<thead>\n",
+            css_color(&style.header_background, "#4472c4"),
+            css_color(&style.header_font_color, "#ffffff"),
+            if style.header_bold { "bold" } else { "normal" },
+        )?;
+        Self::write_row(writer, "th", &export_headers(config))?;
+        writer.write_all(b"</thead>\n<tbody>\n")
+    }
+
+    fn write_rows<W: Write>(config: &Config, writer: &mut W, results: &[ExtractResult]) -> std::io::Result<()> {
+        for result in export_rows(config, results).iter() {
+            Self::write_row(writer, "td", &export_cells(config, result))?;
+        }
+        Ok(())
+    }
+
+    fn write_row<W: Write>(writer: &mut W, tag: &str, cells: &[String]) -> std::io::Result<()> {
+        writer.write_all(b"<tr>")?;
+        for cell in cells {
+            write!(writer, "<{tag}>{}</{tag}>", escape_html(cell))?;
+        }
+        writer.write_all(b"</tr>\n")
+    }
+
+    fn write_footer<W: Write>(writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(b"</tbody>\n</table>\n</body>\n</html>\n")
+    }
+
+    fn write_to<W: Write>(&self, writer: &mut W, results: &[ExtractResult]) -> std::io::Result<()> {
+        Self::write_header(&self.config, writer)?;
+        Self::write_rows(&self.config, writer, results)?;
+        Self::write_footer(writer)
+    }
+
+    /// 导出为 HTML 字符串，供直接写入 HTTP 响应等场景使用
+    pub fn export_to_string(&self, results: &[ExtractResult]) -> Result<String> {
+        ensure_not_empty(results)?;
+        let mut buffer = Vec::new();
+        self.write_to(&mut buffer, results)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+}
+
+impl ResultExporter for HtmlExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        ensure_not_empty(results)?;
+
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        self.write_to(&mut writer, results)?;
+
+        writer.flush()
+            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+
+        tracing::info!("结果已导出到: {}", output_path.display());
+        Ok(())
+    }
+
+    fn extension(&self) -> &str {
+        "html"
+    }
+}
+
+/// 一次调用导出多种格式，输出文件为 `base_path` 加各自扩展名
+pub struct MultiExporter {
+    exporters: Vec<Box<dyn ResultExporter>>,
+}
+
+impl MultiExporter {
+    pub fn new(exporters: Vec<Box<dyn ResultExporter>>) -> Self {
+        Self { exporters }
+    }
+
+    /// 按 `Config::output_formats` 构建
+    pub fn from_config(config: &Config) -> Self {
+        let exporters = config
+            .output_formats
+            .iter()
+            .map(|&format| Self::exporter_for(config, format))
+            .collect();
+
+        Self::new(exporters)
+    }
+
+    /// 只导出指定的一种格式
+    pub fn for_format(config: &Config, format: OutputFormat) -> Self {
+        Self::new(vec![Self::exporter_for(config, format)])
+    }
+
+    fn exporter_for(config: &Config, format: OutputFormat) -> Box<dyn ResultExporter> {
+        match format {
+            OutputFormat::Xlsx => Box::new(XlsxExporter::new(config.clone())),
+            OutputFormat::Csv => Box::new(CsvExporter::new(config.clone())),
+            OutputFormat::Json => Box::new(JsonExporter::new(config.clone())),
+            OutputFormat::Parquet => Box::new(ParquetExporter::new(config.clone())),
+            OutputFormat::Html => Box::new(HtmlExporter::new(config.clone())),
+        }
+    }
+
+    pub fn export(&self, results: &[ExtractResult], base_path: &Path) -> Result<Vec<PathBuf>> {
+        if self.exporters.is_empty() {
+            bail!("未选择导出格式");
+        }
+
+        let mut output_paths = Vec::with_capacity(self.exporters.len());
+
+        for exporter in &self.exporters {
+            let mut path = OsString::from(base_path.as_os_str());
+            path.push(".");
+            path.push(exporter.extension());
+
+            let output_path = PathBuf::from(path);
+            exporter.export(results, &output_path)?;
+            output_paths.push(output_path);
+        }
+
+        Ok(output_paths)
+    }
+}
+
+impl ResultExporter for MultiExporter {
+    fn export(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        MultiExporter::export(self, results, output_path).map(|_| ())
+    }
+
+    fn extension(&self) -> &str {
+        ""
+    }
+}
+
+/// 流式写出 xlsx：结果工作表使用常量内存模式，写完的行即刷到临时文件，不在内存中累积；
+/// 列宽、冻结和筛选在创建时设置，`finish` 时补写统计工作表并保存
+pub struct XlsxStreamWriter {
+    exporter: XlsxExporter,
+    workbook: Workbook,
+    formats: Vec<RowFormats>,
+    output_path: PathBuf,
+    next_row: u32,
+    /// 已写出结果的累计统计，用于统计工作表
+    stats: ProcessingStatistics,
+}
+
+impl XlsxStreamWriter {
+    pub fn create(config: Config, output_path: &Path) -> Result<Self> {
+        let exporter = XlsxExporter::new(config);
+        let mut workbook = Workbook::new();
+        // 常量内存模式只能按行顺序写入，格式设置须在写数据之前完成
+        let worksheet = workbook.add_worksheet_with_constant_memory();
+        exporter.apply_formatting(worksheet)?;
+        exporter.write_headers(worksheet)?;
+        let formats = RowFormats::for_style(&exporter.config.export_style, exporter.config.wrap_text_in_export);
+
+        Ok(Self {
+            exporter,
+            workbook,
+            formats,
+            output_path: output_path.to_path_buf(),
+            next_row: 1,
+            stats: ProcessingStatistics::default(),
+        })
+    }
+}
+
+impl ResultWriter for XlsxStreamWriter {
+    fn write_batch(&mut self, results: &[ExtractResult]) -> Result<()> {
+        let config = &self.exporter.config;
+        let worksheet = self.workbook.worksheet_from_index(0)?;
+        for result in export_rows(config, results).iter() {
+            let formats = RowFormats::select(&self.formats, &config.export_style, result);
+            self.exporter.write_result_row(worksheet, self.next_row, result, formats)?;
+            self.next_row += 1;
+        }
+        self.stats.merge(&ProcessingStatistics::from_results(results, 0.0));
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        XlsxExporter::write_statistics_sheet(&mut self.workbook, &self.stats, &self.exporter.config.export_style)?;
+        self.workbook
+            .save(&self.output_path)
+            .with_context(|| format!("无法保存文件: {}", self.output_path.display()))?;
+
+        tracing::info!("结果已流式导出到: {}", self.output_path.display());
+        Ok(())
+    }
+}
+
+/// 流式写出 CSV，格式与 [`CsvExporter`] 相同
+pub struct CsvStreamWriter {
+    config: Config,
+    writer: BufWriter<File>,
+    output_path: PathBuf,
+}
+
+impl CsvStreamWriter {
+    /// 创建文件并写出 BOM 和表头
+    pub fn create(config: Config, output_path: &Path) -> Result<Self> {
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        CsvExporter::write_header(&config, &mut writer)?;
+
+        Ok(Self {
+            config,
+            writer,
+            output_path: output_path.to_path_buf(),
+        })
+    }
+}
+
+impl ResultWriter for CsvStreamWriter {
+    fn write_batch(&mut self, results: &[ExtractResult]) -> Result<()> {
+        CsvExporter::write_rows(&self.config, &mut self.writer, results)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .with_context(|| format!("无法保存文件: {}", self.output_path.display()))?;
+
+        tracing::info!("结果已流式导出到: {}", self.output_path.display());
+        Ok(())
+    }
+}
+
+/// 流式写出 HTML，格式与 [`HtmlExporter`] 相同，`finish` 时补写表格和文档结尾
+pub struct HtmlStreamWriter {
+    config: Config,
+    writer: BufWriter<File>,
+    output_path: PathBuf,
+}
+
+impl HtmlStreamWriter {
+    /// 创建文件并写出文档开头和表头
+    pub fn create(config: Config, output_path: &Path) -> Result<Self> {
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        let mut writer = BufWriter::new(file);
+        HtmlExporter::write_header(&config, &mut writer)?;
+
+        Ok(Self {
+            config,
+            writer,
+            output_path: output_path.to_path_buf(),
+        })
+    }
+}
+
+impl ResultWriter for HtmlStreamWriter {
+    fn write_batch(&mut self, results: &[ExtractResult]) -> Result<()> {
+        HtmlExporter::write_rows(&self.config, &mut self.writer, results)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        HtmlExporter::write_footer(&mut self.writer)?;
+        self.writer
+            .flush()
+            .with_context(|| format!("无法保存文件: {}", self.output_path.display()))?;
+
+        tracing::info!("结果已流式导出到: {}", self.output_path.display());
+        Ok(())
+    }
+}
+
+/// 流式写出 JSON Lines，每行一条结果，脱敏规则与 [`JsonExporter`] 相同
+pub struct JsonLinesStreamWriter {
+    config: Config,
+    writer: BufWriter<File>,
+    output_path: PathBuf,
+}
+
+impl JsonLinesStreamWriter {
+    pub fn create(config: Config, output_path: &Path) -> Result<Self> {
+        let file = File::create(output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+
+        Ok(Self {
+            config,
+            writer: BufWriter::new(file),
+            output_path: output_path.to_path_buf(),
+        })
+    }
+}
+
+impl ResultWriter for JsonLinesStreamWriter {
+    fn write_batch(&mut self, results: &[ExtractResult]) -> Result<()> {
+        for result in json_rows(&self.config, results).iter() {
+            serde_json::to_writer(&mut self.writer, result)?;
+            self.writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        self.writer
+            .flush()
+            .with_context(|| format!("无法保存文件: {}", self.output_path.display()))?;
+
+        tracing::info!("结果已流式导出到: {}", self.output_path.display());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CustomPattern;
+    use crate::test_support::scratch_dir;
+
+    fn sample_results() -> Vec<ExtractResult> {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.source_text = "电话13907315286, \"备注\"".to_string();
+        result.phone_numbers = vec![
+            MatchInfo::builder("13907315286", PatternType::Phone)
+                .valid(true)
+                .position(2, 13)
+                .build(),
+        ];
+        vec![result]
+    }
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("普通文本"), "普通文本");
+        assert_eq!(escape_csv_field("a,b"), "\"a,b\"");
+        assert_eq!(escape_csv_field("说\"你好\""), "\"说\"\"你好\"\"\"");
+        assert_eq!(escape_csv_field("第一行\n第二行"), "\"第一行\n第二行\"");
+    }
+
+    #[test]
+    fn test_multi_exporter_writes_all_formats() {
+        let dir = scratch_dir();
+        let base_path = dir.path().join("结果.v1");
+
+        let config = Config {
+            output_formats: vec![OutputFormat::Xlsx, OutputFormat::Csv, OutputFormat::Json],
+            ..Default::default()
+        };
+        let paths = MultiExporter::from_config(&config)
+            .export(&sample_results(), &base_path)
+            .unwrap();
+
+        assert_eq!(
+            paths,
+            vec![dir.path().join("结果.v1.xlsx"), dir.path().join("结果.v1.csv"), dir.path().join("结果.v1.json")]
+        );
+
+        let csv = std::fs::read_to_string(&paths[1]).unwrap();
+        let mut lines = csv.lines();
+        assert!(lines.next().unwrap().starts_with("\u{FEFF}处理时间,源文件名,工作表,行号"));
+        assert!(lines.next().unwrap().contains("\"电话13907315286, \"\"备注\"\"\""));
+
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&paths[2]).unwrap()).unwrap();
+        assert_eq!(json[0]["phone_numbers"][0]["value"], "13907315286");
+        assert!(json[0]["timestamp"].is_string());
+    }
+
+    #[test]
+    fn test_html_export() {
+        let html = HtmlExporter::new(Config::default()).export_to_string(&sample_results()).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<th>处理时间</th><th>源文件名</th>"));
+        assert!(html.contains("<td>电话13907315286, &quot;备注&quot;</td>"));
+        assert!(html.contains("th { background: #4472c4; color: #ffffff; font-weight: bold;"));
+        assert!(html.trim_end().ends_with("</html>"));
+        assert_eq!(escape_html("<b>A&B</b>"), "&lt;b&gt;A&amp;B&lt;/b&gt;");
+    }
+
+    #[test]
+    fn test_parquet_export_one_row_per_hit() {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("parquet.parquet");
+
+        let mut results = sample_results();
+        results[0].id_cards = vec![
+            MatchInfo::builder("110105199003072039", PatternType::IdCard)
+                .valid(true)
+                .position(20, 38)
+                .build(),
+        ];
+        ParquetExporter::new(Config::default()).export(&results, &path).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        assert_eq!(reader.metadata().file_metadata().num_rows(), 2);
+
+        let schema = reader.metadata().file_metadata().schema_descr();
+        let names: Vec<&str> = schema.columns().iter().map(|c| c.name()).collect();
+        assert_eq!(names[..5], ["source_file", "sheet_name", "row_number", "pattern", "value"]);
+    }
+
+    #[test]
+    fn test_source_path_column_optional() {
+        let mut results = sample_results();
+        results[0].source_path = "/data/2024/a.xlsx".to_string();
+
+        let config = Config::default();
+        let headers = export_headers(&config);
+        let column = headers.iter().position(|h| h == SOURCE_PATH_HEADER).unwrap();
+        assert_eq!(column, HEADERS.len());
+        assert_eq!(export_cells(&config, &results[0])[column], "/data/2024/a.xlsx");
+        assert_eq!(masked_results(&config, &results)[0].source_path, "/data/2024/a.xlsx");
+
+        let config = Config {
+            export_source_path: false,
+            ..Default::default()
+        };
+        assert!(!export_headers(&config).contains(&SOURCE_PATH_HEADER.to_string()));
+        assert_eq!(export_cells(&config, &results[0]).len(), HEADERS.len());
+        assert!(masked_results(&config, &results)[0].source_path.is_empty());
+    }
+
+    #[test]
+    fn test_remark_column() {
+        let mut results = sample_results();
+        results[0].remark = "已核实".to_string();
+        assert!(!export_headers(&Config::default()).contains(&REMARK_HEADER.to_string()));
+
+        let config = Config {
+            annotation_file: Some(PathBuf::from("批注.xlsx")),
+            include_source_columns: vec!["姓名".to_string()],
+            ..Default::default()
+        };
+        let headers = export_headers(&config);
+        let column = headers.iter().position(|h| h == REMARK_HEADER).unwrap();
+        assert_eq!(column, headers.len() - 2);
+        assert_eq!(export_cells(&config, &results[0])[column], "已核实");
+    }
+
+    #[test]
+    fn test_macau_id_columns() {
+        let mut results = sample_results();
+        results[0].macau_ids = vec![MatchInfo::builder("12345675", PatternType::MacauId).valid(true).build()];
+
+        assert!(!export_headers(&Config::default()).contains(&MACAU_ID_HEADERS[0].to_string()));
+
+        let config = Config {
+            enable_macau_id: true,
+            enable_mask: true,
+            ..Default::default()
+        };
+        let headers = export_headers(&config);
+        let column = headers.iter().position(|h| h == MACAU_ID_HEADERS[0]).unwrap();
+        let cells = export_cells(&config, &results[0]);
+        assert_eq!(cells.len(), headers.len());
+        assert_eq!(cells[column], "123*5675");
+        assert_eq!(cells[column + 1], "有效");
+        assert_eq!(masked_results(&config, &results)[0].macau_ids[0].value, "123*5675");
+    }
+
+    #[test]
+    fn test_wechat_id_columns() {
+        let mut results = sample_results();
+        results[0].wechat_ids = vec![MatchInfo::builder("johnny_123", PatternType::WechatId).valid(true).build()];
+
+        assert!(!export_headers(&Config::default()).contains(&WECHAT_ID_HEADERS[0].to_string()));
+
+        let config = Config {
+            enable_macau_id: true,
+            enable_wechat: true,
+            ..Default::default()
+        };
+        let headers = export_headers(&config);
+        let column = headers.iter().position(|h| h == WECHAT_ID_HEADERS[0]).unwrap();
+        assert!(column > headers.iter().position(|h| h == MACAU_ID_HEADERS[0]).unwrap());
+        let cells = export_cells(&config, &results[0]);
+        assert_eq!(cells.len(), headers.len());
+        assert_eq!(cells[column], "johnny_123");
+        assert_eq!(cells[column + 1], "有效");
+    }
+
+    #[test]
+    fn test_custom_matches_column() {
+        let mut results = sample_results();
+        results[0].custom_matches = vec![MatchInfo::builder("EMP-1234", PatternType::Custom).valid(true).rule("工号").build()];
+
+        assert!(!export_headers(&Config::default()).contains(&CUSTOM_MATCHES_HEADER.to_string()));
+
+        let config = Config {
+            custom_patterns: vec![CustomPattern {
+                name: "工号".to_string(),
+                regex: r"EMP-\d+".to_string(),
+                length: Default::default(),
+            }],
+            ..Default::default()
+        };
+        let headers = export_headers(&config);
+        let column = headers.iter().position(|h| h == CUSTOM_MATCHES_HEADER).unwrap();
+        let cells = export_cells(&config, &results[0]);
+        assert_eq!(cells.len(), headers.len());
+        assert_eq!(cells[column], "工号: EMP-1234");
+    }
+
+    #[test]
+    fn test_split_valid_columns() {
+        let mut results = sample_results();
+        results[0].phone_numbers.push(MatchInfo::builder("1390731528", PatternType::Phone).build());
+        results[0].wechat_ids = vec![
+            MatchInfo::builder("johnny_123", PatternType::WechatId).valid(true).build(),
+            MatchInfo::builder("1abc", PatternType::WechatId).build(),
+        ];
+
+        let config = Config {
+            split_valid_columns: true,
+            enable_wechat: true,
+            ..Default::default()
+        };
+        let headers = export_headers(&config);
+        let cells = export_cells(&config, &results[0]);
+        assert_eq!(headers.len(), export_headers(&Config { enable_wechat: true, ..Default::default() }).len());
+        assert_eq!(cells.len(), headers.len());
+        assert_eq!(headers[5..13], ["有效手机号", "无效手机号", "有效身份证号", "无效身份证号",
+            "有效银行卡号", "无效银行卡号", "有效姓名", "无效姓名"]);
+        assert_eq!((cells[5].as_str(), cells[6].as_str()), ("13907315286", "1390731528"));
+        assert_eq!((cells[7].as_str(), cells[8].as_str()), ("", ""));
+
+        let column = headers.iter().position(|h| h == "有效微信号").unwrap();
+        assert_eq!(headers[column + 1], "无效微信号");
+        assert_eq!((cells[column].as_str(), cells[column + 1].as_str()), ("johnny_123", "1abc"));
+
+        let exporter = XlsxExporter::new(config);
+        assert_eq!(exporter.validity_columns(), [6, 8, 10, 12, column as u16 + 1]);
+        assert!(!exporter.export_to_buffer(&results).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_xlsx_statistics_sheet() {
+        use calamine::{open_workbook, Reader, Xlsx};
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("pivot.xlsx");
+
+        let mut results = sample_results();
+        results[0].phone_numbers.push(MatchInfo::builder("18612345678", PatternType::Phone).valid(true).build());
+        results[0].id_cards = vec![MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build()];
+        XlsxExporter::new(Config::default()).export(&results, &path).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        assert_eq!(workbook.sheet_names()[1], STATISTICS_SHEET_NAME);
+        let range = workbook.worksheet_range(STATISTICS_SHEET_NAME).unwrap();
+        let cell = |row: u32, col: u32| range.get_value((row, col)).map(|v| v.to_string()).unwrap_or_default();
+        assert_eq!(cell(0, 0), "敏感信息汇总");
+        assert_eq!((cell(2, 0), cell(2, 1), cell(2, 2)), ("手机号".to_string(), "2".to_string(), "2".to_string()));
+        assert_eq!(cell(7, 0), "微信号");
+        assert_eq!(cell(8, 0), "合计");
+        assert_eq!(cell(10, 0), "身份证号按省份");
+        assert_eq!((cell(12, 0), cell(12, 1)), ("北京".to_string(), "1".to_string()));
+        assert_eq!(cell(14, 0), "手机号按运营商");
+        assert_eq!(cell(16, 0), "中国移动");
+        assert_eq!(cell(17, 0), "中国联通");
+    }
+
+    #[test]
+    fn test_row_colors_by_pattern_type() {
+        use crate::models::RowColorRule;
+        use std::io::Read;
+
+        let mut results = sample_results();
+        let mut with_id_card = results[0].clone();
+        with_id_card.id_cards = vec![MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build()];
+        results.push(with_id_card);
+        results.push(ExtractResult::new("a.xlsx", "Sheet1", 9));
+
+        let mut config = Config::default();
+        config.export_style.row_colors = vec![
+            RowColorRule { types: vec![PatternType::IdCard], exclusive: false, color: "#FCE4D6".into() },
+            RowColorRule { types: vec![PatternType::Phone], exclusive: true, color: "#E2EFDA".into() },
+        ];
+        let style = &config.export_style;
+        let formats = RowFormats::for_style(style, true);
+        assert_eq!(formats.len(), 3);
+        let selected: Vec<usize> = results
+            .iter()
+            .map(|r| formats.iter().position(|f| std::ptr::eq(f, RowFormats::select(&formats, style, r))).unwrap())
+            .collect();
+        assert_eq!(selected, [2, 1, 0]);
+
+        let buffer = XlsxExporter::new(config).export_to_buffer(&results).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(buffer)).unwrap();
+        let mut styles = String::new();
+        archive.by_name("xl/styles.xml").unwrap().read_to_string(&mut styles).unwrap();
+        assert!(styles.contains("FFFCE4D6"));
+        assert!(styles.contains("FFE2EFDA"));
+    }
+
+    #[test]
+    fn test_pretty_format_output() {
+        assert_eq!(pretty_value("13812345678", PatternType::Phone), "138 1234 5678");
+        assert_eq!(pretty_value("+86 13812345678", PatternType::Phone), "+86 138 1234 5678");
+        assert_eq!(pretty_value("138****5678", PatternType::Phone), "138 **** 5678");
+        assert_eq!(pretty_value("1381234567", PatternType::Phone), "1381234567");
+        assert_eq!(pretty_value("6225-8801-2345-6789", PatternType::BankCard), "6225 8801 2345 6789");
+        assert_eq!(pretty_value("6225880123456789012", PatternType::BankCard), "6225 8801 2345 6789 012");
+        assert_eq!(pretty_value("110105199003072039", PatternType::IdCard), "110105199003072039");
+
+        let mut results = sample_results();
+        results[0].bank_cards = vec![MatchInfo::builder("4111111111111111", PatternType::BankCard).valid(true).build()];
+        let config = Config { pretty_format_output: true, ..Default::default() };
+        let cells = export_cells(&config, &results[0]);
+        assert_eq!(cells[5], "139 0731 5286");
+        assert_eq!(cells[9], "4111 1111 1111 1111");
+        // 内部值不变
+        assert_eq!(results[0].phone_numbers[0].value, "13907315286");
+        assert_eq!(export_cells(&Config::default(), &results[0])[5], "13907315286");
+    }
+
+    #[test]
+    fn test_truncate_text() {
+        assert_eq!(truncate_text("电话13907315286", None), None);
+        assert_eq!(truncate_text("电话13907315286", Some(20)), None);
+        assert_eq!(truncate_text("电话139", Some(5)), None);
+        assert_eq!(truncate_text("电话13907315286", Some(4)).as_deref(), Some("电话13…"));
+    }
+
+    #[test]
+    fn test_xlsx_truncates_source_text() {
+        use calamine::{open_workbook, Reader, Xlsx};
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("truncate.xlsx");
+        let config = Config {
+            truncate_source_text: Some(4),
+            ..Default::default()
+        };
+        XlsxExporter::new(config).export(&sample_results(), &path).unwrap();
+
+        let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+        let range = workbook.worksheet_range("Sheet1").unwrap();
+        assert_eq!(
+            range.get_value((1, SOURCE_TEXT_COLUMN as u32)).unwrap().to_string(),
+            "电话13…"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_wrap_keeps_source_text() {
+        use calamine::{open_workbook, Reader, Xlsx};
+
+        let mut results = sample_results();
+        results[0].source_text = "第一行\n电话13907315286\n第三行".to_string();
+        results[0].context_before = vec!["上文\n两行".to_string()];
+
+        let dir = scratch_dir();
+        for wrap_text_in_export in [true, false] {
+            let path = dir.path().join(format!("wrap_{}.xlsx", wrap_text_in_export));
+            let config = Config { wrap_text_in_export, ..Default::default() };
+            XlsxExporter::new(config).export(&results, &path).unwrap();
+
+            let mut workbook: Xlsx<_> = open_workbook(&path).unwrap();
+            let range = workbook.worksheet_range("Sheet1").unwrap();
+            let cell = |col: u16| range.get_value((1, col as u32)).unwrap().to_string();
+            assert_eq!(cell(SOURCE_TEXT_COLUMN), results[0].source_text);
+            assert_eq!(cell(TEXT_COLUMNS[1]), "上文\n两行");
+        }
+    }
+
+    #[test]
+    fn test_context_row_separator() {
+        let mut results = sample_results();
+        results[0].context_before = vec!["张三 | 上一行".to_string(), "李四 | 再上一行".to_string()];
+
+        let cells = export_cells(&Config::default(), &results[0]);
+        assert_eq!(cells[TEXT_COLUMNS[1] as usize], "张三 | 上一行\n李四 | 再上一行");
+
+        let config = Config {
+            context_row_sep: " // ".to_string(),
+            ..Default::default()
+        };
+        let csv = CsvExporter::new(config).export_to_string(&results).unwrap();
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.contains(",张三 | 上一行 // 李四 | 再上一行,"));
+    }
+
+    #[test]
+    fn test_json_export_applies_mask() {
+        let config = Config {
+            enable_mask: true,
+            ..Default::default()
+        };
+        let masked = masked_results(&config, &sample_results());
+        assert_eq!(masked[0].phone_numbers[0].value, "139****5286");
+    }
+
+    #[test]
+    fn test_export_to_memory() {
+        let results = sample_results();
+
+        let csv = CsvExporter::new(Config::default()).export_to_string(&results).unwrap();
+        assert!(csv.starts_with('\u{FEFF}'));
+        assert_eq!(csv.lines().count(), 2);
+        assert!(csv.contains("\"电话13907315286, \"\"备注\"\"\""));
+
+        let config = Config {
+            enable_mask: true,
+            ..Default::default()
+        };
+        let json = JsonExporter::new(config).export_to_string(&results).unwrap();
+        let parsed: Vec<ExtractResult> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[0].phone_numbers[0].value, "139****5286");
+
+        let xlsx = XlsxExporter::new(Config::default()).export_to_buffer(&results).unwrap();
+        assert!(xlsx.starts_with(b"PK"));
+
+        assert!(CsvExporter::new(Config::default()).export_to_string(&[]).is_err());
+        assert!(JsonExporter::new(Config::default()).export_to_string(&[]).is_err());
+    }
+
+    #[test]
+    fn test_multi_exporter_requires_format() {
+        let config = Config {
+            output_formats: Vec::new(),
+            ..Default::default()
+        };
+        let result = MultiExporter::from_config(&config).export(&sample_results(), Path::new("unused"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_one_match_per_row_splits_hits() {
+        let text = "客户张三今天来电，手机13907315286，备用号码13800138000，身份证110105199003072039已核实";
+        let phone_start = text.find("13907315286").unwrap();
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 5);
+        result.source_text = text.to_string();
+        result.context_before = vec!["上一行".to_string()];
+        result.phone_numbers = vec![
+            MatchInfo::builder("13907315286", PatternType::Phone)
+                .valid(true)
+                .position(phone_start, phone_start + 11)
+                .build(),
+            // 位置缺失时按值查找
+            MatchInfo::builder("13800138000", PatternType::Phone).valid(true).build(),
+        ];
+        result.id_cards = vec![MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build()];
+        let results = vec![result];
+
+        let config = Config::default();
+        assert_eq!(export_rows(&config, &results).len(), 1);
+
+        let config = Config {
+            one_match_per_row: true,
+            ..Default::default()
+        };
+        let rows = export_rows(&config, &results);
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|r| r.match_count() == 1 && r.row_number == 5));
+        assert!(rows.iter().all(|r| r.context_before == ["上一行"]));
+
+        assert_eq!(rows[0].source_text, "…户张三今天来电，手机13907315286，备用号码13800…");
+        assert_eq!(rows[1].phone_numbers[0].value, "13800138000");
+        assert!(rows[1].source_text.contains("13800138000"));
+        assert!(rows[2].source_text.ends_with("110105199003072039已核实"));
+
+        let cells = export_cells(&config, &rows[1]);
+        assert_eq!(cells[5], "13800138000");
+        assert_eq!(cells[7], "");
+    }
+}
@@ -1,28 +1,143 @@
-use super::validator::Validator;
+use super::validators::{ValidationError, Validator};
 use super::NameExtractor;
-use crate::models::{Config, MatchInfo};
-use crate::utils::{extract_bank_cards, extract_id_cards, extract_phones};
+use crate::models::{Config, CustomPattern, MatchInfo, MatchInfoBuilder, PatternType};
+use crate::utils::{
+    clean_digits, extract_bank_cards, extract_id_cards, extract_macau_ids, extract_phones, extract_wechat_ids, normalize_id_card,
+    normalize_value, PHONE_COUNTRY_CODE,
+};
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// `InfoExtractor::extract` 调用的累计统计（不含单独提取的澳门身份证号和微信号）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ExtractionStats {
+    pub cells_processed: usize,
+    pub non_empty_cells: usize,
+    pub cells_with_matches: usize,
+    pub total_matches: usize,
+}
+
+impl ExtractionStats {
+    /// 有命中的单元格占非空单元格的百分比，没有非空单元格时为 0
+    pub fn hit_rate(&self) -> f64 {
+        if self.non_empty_cells == 0 {
+            return 0.0;
+        }
+        self.cells_with_matches as f64 / self.non_empty_cells as f64 * 100.0
+    }
+
+    /// 自 `earlier` 取得以来新增的统计
+    pub fn since(&self, earlier: &Self) -> Self {
+        Self {
+            cells_processed: self.cells_processed.saturating_sub(earlier.cells_processed),
+            non_empty_cells: self.non_empty_cells.saturating_sub(earlier.non_empty_cells),
+            cells_with_matches: self.cells_with_matches.saturating_sub(earlier.cells_with_matches),
+            total_matches: self.total_matches.saturating_sub(earlier.total_matches),
+        }
+    }
+}
 
 pub struct InfoExtractor {
     config: Config,
     name_extractor: NameExtractor,
+    /// 排除列表的规范化值
+    excluded: HashSet<String>,
+    /// 编译后的自定义规则
+    custom_patterns: Vec<(CustomPattern, Regex)>,
+    cells_processed: AtomicUsize,
+    non_empty_cells: AtomicUsize,
+    cells_with_matches: AtomicUsize,
+    total_matches: AtomicUsize,
 }
 
 impl InfoExtractor {
     pub fn new(config: Config) -> Self {
-        let name_extractor = NameExtractor::new(config.api_host.clone(), config.enable_name);
-        Self { config, name_extractor }
+        let name_extractor = NameExtractor::new(config.api_host.clone(), config.enable_name)
+            .with_field_mapping(config.api_field_mapping.clone());
+        let excluded = config.exclude_values.iter().map(|v| normalize_value(v)).collect();
+        let custom_patterns = config
+            .custom_patterns
+            .iter()
+            .filter_map(|pattern| match Regex::new(&pattern.regex) {
+                Ok(regex) => Some((pattern.clone(), regex)),
+                Err(e) => {
+                    tracing::warn!("自定义规则 {} 的正则无效，已跳过: {}", pattern.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self {
+            config,
+            name_extractor,
+            excluded,
+            custom_patterns,
+            cells_processed: AtomicUsize::new(0),
+            non_empty_cells: AtomicUsize::new(0),
+            cells_with_matches: AtomicUsize::new(0),
+            total_matches: AtomicUsize::new(0),
+        }
     }
 
+    /// 自创建或上次 `reset_stats` 以来的提取统计
+    pub fn get_stats(&self) -> ExtractionStats {
+        ExtractionStats {
+            cells_processed: self.cells_processed.load(Ordering::Relaxed),
+            non_empty_cells: self.non_empty_cells.load(Ordering::Relaxed),
+            cells_with_matches: self.cells_with_matches.load(Ordering::Relaxed),
+            total_matches: self.total_matches.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn reset_stats(&self) {
+        for counter in [&self.cells_processed, &self.non_empty_cells, &self.cells_with_matches, &self.total_matches] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    fn record_stats(&self, text: &str, matches: usize) {
+        self.cells_processed.fetch_add(1, Ordering::Relaxed);
+        if !text.trim().is_empty() {
+            self.non_empty_cells.fetch_add(1, Ordering::Relaxed);
+        }
+        if matches > 0 {
+            self.cells_with_matches.fetch_add(1, Ordering::Relaxed);
+            self.total_matches.fetch_add(matches, Ordering::Relaxed);
+        }
+    }
+
+    /// 替换姓名提取器（如测试中使用模拟提取器）
+    pub fn with_name_extractor(mut self, name_extractor: NameExtractor) -> Self {
+        self.name_extractor = name_extractor;
+        self
+    }
+
+    /// 提取一个单元格中的手机号、身份证号、银行卡号和姓名
+    ///
+    /// 短于 `min_cell_length` 个字符的单元格不做正则匹配（姓名仍交给姓名提取器），
+    /// 超过 `max_cell_length` 个字符的只处理开头部分
     pub fn extract(&self, text: &str) -> (Vec<MatchInfo>, Vec<MatchInfo>, Vec<MatchInfo>, Vec<MatchInfo>) {
-        let phones = if self.config.enable_phone {
-            self.extract_phones(text)
+        let bounded = self.bounded_text(text);
+        if bounded.len() < text.len() {
+            tracing::warn!(
+                "单元格长度 {} 字符超过上限，只提取前 {} 个字符",
+                text.chars().count(),
+                self.config.max_cell_length
+            );
+        }
+        let text = bounded;
+        let min_len = self.config.min_cell_length;
+        let scan = text.chars().take(min_len).count() >= min_len;
+
+        let phones = if scan && self.config.enable_phone {
+            Self::dedup_overlapping(self.extract_phones(text))
         } else {
             Vec::new()
         };
 
-        let id_cards = if self.config.enable_id_card {
-            self.extract_id_cards(text)
+        let id_cards = if scan && self.config.enable_id_card {
+            Self::dedup_overlapping(self.extract_id_cards(text))
         } else {
             Vec::new()
         };
@@ -33,8 +148,8 @@ impl InfoExtractor {
             .map(|m| m.position)
             .collect();
 
-        let bank_cards = if self.config.enable_bank_card {
-            self.extract_bank_cards_filtered(text, &valid_id_card_positions)
+        let bank_cards = if scan && self.config.enable_bank_card {
+            Self::dedup_overlapping(self.extract_bank_cards_filtered(text, &valid_id_card_positions))
         } else {
             Vec::new()
         };
@@ -45,12 +160,96 @@ impl InfoExtractor {
             Vec::new()
         };
 
+        let [phones, id_cards, bank_cards, names] = Self::merge_sources([phones, id_cards, bank_cards, names]);
+
+        // 排除放在最后，被排除的有效身份证号仍会阻止其区间被识别为银行卡号
+        let phones = self.without_excluded(phones);
+        let id_cards = self.without_excluded(id_cards);
+        let bank_cards = self.without_excluded(bank_cards);
+        let names = self.without_excluded(names);
+
+        self.record_stats(text, phones.len() + id_cards.len() + bank_cards.len() + names.len());
         (phones, id_cards, bank_cards, names)
     }
 
+    /// 提取澳门居民身份证号，未启用时为空
+    pub fn extract_macau_ids(&self, text: &str) -> Vec<MatchInfo> {
+        if !self.config.enable_macau_id {
+            return Vec::new();
+        }
+
+        let macau_ids = extract_macau_ids(self.bounded_text(text))
+            .into_iter()
+            .map(|(value, start, end)| {
+                let is_valid = Validator::validate_macau_id(value);
+                self.build_match(value, is_valid, (start, end), PatternType::MacauId)
+            })
+            .collect();
+        self.without_excluded(Self::dedup_overlapping(macau_ids))
+    }
+
+    /// 提取“微信号”前缀后的微信号，未启用时为空
+    pub fn extract_wechat_ids(&self, text: &str) -> Vec<MatchInfo> {
+        if !self.config.enable_wechat {
+            return Vec::new();
+        }
+
+        let wechat_ids = extract_wechat_ids(self.bounded_text(text))
+            .into_iter()
+            .map(|(value, start, end)| {
+                let is_valid = Validator::validate_wechat_id(value);
+                self.build_match(value, is_valid, (start, end), PatternType::WechatId)
+            })
+            .collect();
+        self.without_excluded(Self::dedup_overlapping(wechat_ids))
+    }
+
+    /// 按自定义规则提取，不满足规则长度约束的命中直接丢弃；未配置规则时为空
+    pub fn extract_custom_matches(&self, text: &str) -> Vec<MatchInfo> {
+        let text = self.bounded_text(text);
+        let matches = self
+            .custom_patterns
+            .iter()
+            .flat_map(|(pattern, regex)| {
+                regex
+                    .find_iter(text)
+                    .filter(|m| !m.as_str().is_empty() && pattern.length.allows(m.as_str()))
+                    .map(|m| {
+                        self.match_builder(m.as_str(), (m.start(), m.end()), PatternType::Custom)
+                            .valid(true)
+                            .rule(&pattern.name)
+                            .build()
+                    })
+            })
+            .collect();
+        self.without_excluded(matches)
+    }
+
+    /// 超过 `max_cell_length` 个字符时截取开头部分，截断点总在完整字符之后
+    fn bounded_text<'a>(&self, text: &'a str) -> &'a str {
+        match text.char_indices().nth(self.config.max_cell_length) {
+            Some((end, _)) => &text[..end],
+            None => text,
+        }
+    }
+
+    /// 剔除命中排除列表的值
+    fn without_excluded(&self, mut matches: Vec<MatchInfo>) -> Vec<MatchInfo> {
+        if !self.excluded.is_empty() {
+            matches.retain(|m| !self.excluded.contains(&normalize_value(&m.value)));
+        }
+        matches
+    }
+
     fn extract_bank_cards_filtered(&self, text: &str, exclude_positions: &[(usize, usize)]) -> Vec<MatchInfo> {
         extract_bank_cards(text)
             .into_iter()
+            .filter(|(value, _, _)| self.config.bank_card_length.allows(value))
+            // 短于策略最短位数的候选直接丢弃（15 位 Amex 卡号除外），默认策略下不报告 13-15 位数字
+            .filter(|(value, _, _)| {
+                clean_digits(value).len() >= self.config.card_length_policy.min_length()
+                    || Validator::is_amex_number(value)
+            })
             .filter(|(_, start, end)| {
                 // 检查是否与任何有效身份证号位置重叠
                 !exclude_positions.iter().any(|(id_start, id_end)| {
@@ -59,18 +258,120 @@ impl InfoExtractor {
                 })
             })
             .map(|(value, start, end)| {
-                let is_valid = Validator::validate_bank_card(&value);
-                MatchInfo::new(value, is_valid, start, end)
+                let validation = Validator::validate_bank_card_detailed(value, self.config.card_length_policy);
+                self.build_checked_match(value, validation, (start, end), PatternType::BankCard)
             })
             .collect()
     }
 
+    fn build_match(
+        &self,
+        value: &str,
+        is_valid: bool,
+        (start, end): (usize, usize),
+        pattern: PatternType,
+    ) -> MatchInfo {
+        self.match_builder(value, (start, end), pattern).valid(is_valid).build()
+    }
+
+    /// 按详细校验结果构建命中，无效时记录具体原因
+    fn build_checked_match(
+        &self,
+        value: &str,
+        validation: Result<(), ValidationError>,
+        span: (usize, usize),
+        pattern: PatternType,
+    ) -> MatchInfo {
+        self.match_builder(value, span, pattern)
+            .valid(validation.is_ok())
+            .error_detail(validation.err())
+            .build()
+    }
+
+    /// 已填好位置和疑似占位标记的构建器
+    fn match_builder(&self, value: &str, (start, end): (usize, usize), pattern: PatternType) -> MatchInfoBuilder {
+        let suspicious = Validator::is_suspicious_number(value, self.config.suspicious_run_length as usize);
+        MatchInfo::builder(value, pattern).position(start, end).suspicious(suspicious)
+    }
+
+    /// 去掉被更长命中（或区间相同的命中）完全包含的命中，结果按起始位置排序
+    fn dedup_overlapping(mut matches: Vec<MatchInfo>) -> Vec<MatchInfo> {
+        // 起始相同时长的在前，被包含的命中总在包含它的命中之后
+        matches.sort_by_key(|m| (m.position.0, std::cmp::Reverse(m.position.1)));
+
+        let mut kept: Vec<MatchInfo> = Vec::with_capacity(matches.len());
+        for m in matches {
+            let contained = kept
+                .iter()
+                .any(|k| k.position.0 <= m.position.0 && m.position.1 <= k.position.1);
+            if !contained {
+                kept.push(m);
+            }
+        }
+        kept
+    }
+
+    /// 合并各来源（正则类与 API 类）的命中并去重，返回与输入一一对应的各来源命中
+    ///
+    /// 正则类与 API 类的命中位置重叠（有一方无位置时按值相同）视为重复，保留置信度更高者，
+    /// 相同时保留靠前的来源。同类来源之间不在此取舍：同一类型的重叠由 `dedup_overlapping` 处理，
+    /// 不同正则类型可以合法重叠（如无效身份证号同时是银行卡号）
+    fn merge_sources<const N: usize>(sources: [Vec<MatchInfo>; N]) -> [Vec<MatchInfo>; N] {
+        let conflicts = |a: &MatchInfo, b: &MatchInfo| {
+            if a.source_pattern.is_api() == b.source_pattern.is_api() {
+                return false;
+            }
+            if !a.is_located() || !b.is_located() {
+                return a.value == b.value;
+            }
+            a.position.0 < b.position.1 && b.position.0 < a.position.1
+        };
+
+        let keep: Vec<Vec<bool>> = sources
+            .iter()
+            .enumerate()
+            .map(|(source, matches)| {
+                matches
+                    .iter()
+                    .map(|m| {
+                        !sources.iter().enumerate().any(|(other_source, others)| {
+                            other_source != source
+                                && others.iter().any(|other| {
+                                    conflicts(m, other)
+                                        && (other.confidence_score() > m.confidence_score()
+                                            || (other.confidence_score() == m.confidence_score()
+                                                && other_source < source))
+                                })
+                        })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut keep = keep.into_iter();
+        sources.map(|matches| {
+            let flags = keep.next().unwrap_or_default();
+            matches
+                .into_iter()
+                .zip(flags)
+                .filter_map(|(m, kept)| kept.then_some(m))
+                .collect()
+        })
+    }
+
     fn extract_phones(&self, text: &str) -> Vec<MatchInfo> {
         extract_phones(text)
             .into_iter()
             .map(|(value, start, end)| {
-                let is_valid = Validator::validate_phone(&value);
-                MatchInfo::new(value, is_valid, start, end)
+                // 按配置只保留国家码之后的号码部分
+                let (value, start) = match PHONE_COUNTRY_CODE.find(value) {
+                    Some(prefix) if self.config.strip_phone_country_code => {
+                        (&value[prefix.end()..], start + prefix.end())
+                    }
+                    _ => (value, start),
+                };
+                let validation = Validator::validate_phone_detailed(value);
+                self.build_checked_match(value, validation, (start, end), PatternType::Phone)
             })
             .collect()
     }
@@ -79,8 +380,11 @@ impl InfoExtractor {
         extract_id_cards(text)
             .into_iter()
             .map(|(value, start, end)| {
-                let is_valid = Validator::validate_id_card(&value);
-                MatchInfo::new(value, is_valid, start, end)
+                // 末位小写 x 统一为大写，同一号码的两种写法去重、统计时视为相同
+                let value = normalize_id_card(value);
+                let validation =
+                    Validator::validate_id_card_with_region_check(&value, !self.config.accept_unknown_id_region);
+                self.build_checked_match(&value, validation, (start, end), PatternType::IdCard)
             })
             .collect()
     }
@@ -89,6 +393,8 @@ impl InfoExtractor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::{CardLengthPolicy, LengthLimit};
+    use std::collections::HashMap;
 
     fn create_extractor() -> InfoExtractor {
         InfoExtractor::new(Config::default())
@@ -105,6 +411,128 @@ mod tests {
         assert!(phones[1].is_valid);
     }
 
+    #[test]
+    fn test_phone_with_country_code() {
+        let text = "联系+86 13812345678";
+
+        let (phones, _, _, _) = create_extractor().extract(text);
+        assert_eq!(phones.len(), 1);
+        assert_eq!(phones[0].value, "+86 13812345678");
+        assert!(phones[0].is_valid);
+
+        let config = Config {
+            strip_phone_country_code: true,
+            ..Default::default()
+        };
+        let (phones, _, _, _) = InfoExtractor::new(config).extract(text);
+        assert_eq!(phones.len(), 1);
+        assert_eq!(phones[0].value, "13812345678");
+        assert!(phones[0].is_valid);
+        assert_eq!(&text[phones[0].position.0..phones[0].position.1], "13812345678");
+    }
+
+    #[test]
+    fn test_dedup_overlapping() {
+        let matches = vec![
+            MatchInfo::builder("13812345678", PatternType::Phone).valid(true).position(10, 21).build(),
+            MatchInfo::builder("+86 13812345678", PatternType::Phone).valid(true).position(6, 21).build(),
+            MatchInfo::builder("15912345678", PatternType::Phone).valid(true).position(30, 41).build(),
+            MatchInfo::builder("15912345678", PatternType::Phone).valid(true).position(30, 41).build(),
+        ];
+
+        let deduped = InfoExtractor::dedup_overlapping(matches);
+        let values: Vec<&str> = deduped.iter().map(|m| m.value.as_str()).collect();
+        assert_eq!(values, ["+86 13812345678", "15912345678"]);
+    }
+
+    #[test]
+    fn test_merge_sources_overlap() {
+        let api_name = |value: &str, start: usize, confidence: f64| {
+            MatchInfo::builder(value, PatternType::NameApi)
+                .valid(confidence >= 0.8)
+                .position(start, start + value.len())
+                .confidence(confidence)
+                .build()
+        };
+
+        // 无效号码与高置信度姓名重叠：保留姓名
+        let [phones, names] = InfoExtractor::merge_sources([
+            vec![MatchInfo::builder("12345678901", PatternType::Phone).valid(false).position(0, 11).build()],
+            vec![api_name("12345678901", 0, 0.9)],
+        ]);
+        assert!(phones.is_empty());
+        assert_eq!(names.len(), 1);
+
+        // 有效号码与低置信度姓名部分重叠：保留号码
+        let [phones, names] = InfoExtractor::merge_sources([
+            vec![MatchInfo::builder("13812345678", PatternType::Phone).valid(true).position(3, 14).build()],
+            vec![api_name("张三138", 0, 0.6), api_name("李四", 20, 0.6)],
+        ]);
+        assert_eq!(phones.len(), 1);
+        let values: Vec<&str> = names.iter().map(|m| m.value.as_str()).collect();
+        assert_eq!(values, ["李四"]);
+
+        // 置信度相同时保留靠前的来源；姓名无位置时按值去重
+        let unlocated = MatchInfo::builder("13812345678", PatternType::NameApi).valid(true).confidence(1.0).build();
+        let [phones, names] = InfoExtractor::merge_sources([
+            vec![MatchInfo::builder("13812345678", PatternType::Phone).valid(true).position(0, 11).build()],
+            vec![unlocated],
+        ]);
+        assert_eq!(phones.len(), 1);
+        assert!(names.is_empty());
+
+        // 正则类之间的重叠不做取舍
+        let [id_cards, bank_cards] = InfoExtractor::merge_sources([
+            vec![MatchInfo::builder("110105199003072030", PatternType::IdCard).valid(false).position(0, 18).build()],
+            vec![MatchInfo::builder("110105199003072030", PatternType::BankCard).valid(true).position(0, 18).build()],
+        ]);
+        assert_eq!(id_cards.len(), 1);
+        assert_eq!(bank_cards.len(), 1);
+    }
+
+    #[test]
+    fn test_api_name_duplicating_regex_hit_removed() {
+        let text = "联系人13907315286";
+        let responses = HashMap::from([(text.to_string(), vec!["13907315286".to_string()])]);
+        let extractor = InfoExtractor::new(Config {
+            enable_name: true,
+            ..Default::default()
+        })
+        .with_name_extractor(NameExtractor::new_mock(responses));
+
+        let (phones, _, _, names) = extractor.extract(text);
+        assert_eq!(phones.len(), 1);
+        assert!(names.is_empty());
+    }
+
+    #[test]
+    fn test_exclude_values() {
+        let config = Config {
+            exclude_values: vec!["138-1234-5678".to_string(), "4111 1111 1111 1111".to_string()],
+            ..Default::default()
+        };
+        let text = "总机+86 13812345678，手机15912345678，测试卡4111111111111111";
+        let (phones, _, bank_cards, _) = InfoExtractor::new(config).extract(text);
+
+        let values: Vec<&str> = phones.iter().map(|m| m.value.as_str()).collect();
+        assert_eq!(values, ["15912345678"]);
+        assert!(bank_cards.is_empty());
+    }
+
+    #[test]
+    fn test_source_pattern() {
+        let extractor = create_extractor();
+        let (phones, id_cards, bank_cards, _) =
+            extractor.extract("电话13907315286，身份证110105199003072039，卡号4111111111111111");
+
+        assert_eq!(phones[0].source_pattern.as_str(), "PHONE");
+        assert_eq!(id_cards[0].source_pattern, PatternType::IdCard);
+        assert_eq!(bank_cards[0].source_pattern.as_str(), "BANK_CARD");
+
+        let json = serde_json::to_value(&bank_cards[0]).unwrap();
+        assert_eq!(json["pattern"], "BANK_CARD");
+    }
+
     #[test]
     fn test_extract_id_cards() {
         let extractor = create_extractor();
@@ -115,6 +543,69 @@ mod tests {
         assert!(id_cards[0].is_valid);
     }
 
+    #[test]
+    fn test_extraction_stats() {
+        let extractor = create_extractor();
+        for text in ["电话13907315286", "无敏感信息", "  ", "卡号 4111111111111111，电话13812345678", "备注"] {
+            extractor.extract(text);
+        }
+
+        let stats = extractor.get_stats();
+        assert_eq!(stats.cells_processed, 5);
+        assert_eq!(stats.non_empty_cells, 4);
+        assert_eq!(stats.cells_with_matches, 2);
+        assert_eq!(stats.total_matches, 3);
+        assert_eq!(stats.hit_rate(), 50.0);
+
+        extractor.reset_stats();
+        assert_eq!(extractor.get_stats(), ExtractionStats::default());
+    }
+
+    #[test]
+    fn test_cell_length_limits() {
+        let extractor = InfoExtractor::new(Config { min_cell_length: 12, ..Default::default() });
+        assert!(extractor.extract("13907315286").0.is_empty());
+        assert!(extractor.extract("电话139").0.is_empty());
+        assert_eq!(extractor.extract("电话13907315286").0.len(), 1);
+        assert_eq!(extractor.get_stats().cells_processed, 3);
+
+        let extractor = InfoExtractor::new(Config { max_cell_length: 20, ..Default::default() });
+        let text = format!("电话13907315286，{}备用13812345678", "很长的内容".repeat(10));
+        let (phones, _, _, _) = extractor.extract(&text);
+        assert_eq!(phones.len(), 1);
+        assert_eq!(phones[0].value, "13907315286");
+        assert_eq!(extractor.bounded_text(&text).chars().count(), 20);
+        assert_eq!(extractor.bounded_text("短文本"), "短文本");
+    }
+
+    #[test]
+    fn test_lowercase_x_id_card_normalized() {
+        let extractor = create_extractor();
+        let (_, lower, _, _) = extractor.extract("身份证：11010519900307005x");
+        let (_, upper, _, _) = extractor.extract("身份证：11010519900307005X");
+
+        assert_eq!(lower.len(), 1);
+        assert!(lower[0].is_valid);
+        assert_eq!(lower[0].value, "11010519900307005X");
+        assert_eq!(lower[0].position, upper[0].position);
+
+        let values: HashSet<&str> = lower.iter().chain(&upper).map(|m| m.value.as_str()).collect();
+        assert_eq!(values.len(), 1);
+    }
+
+    #[test]
+    fn test_id_card_error_detail() {
+        let extractor = create_extractor();
+        let (_, id_cards, _, _) = extractor.extract("身份证：110105199003072038");
+
+        assert_eq!(id_cards.len(), 1);
+        assert!(!id_cards[0].is_valid);
+        assert_eq!(id_cards[0].error_detail, Some(ValidationError::InvalidChecksum));
+
+        let json = serde_json::to_value(&id_cards[0]).unwrap();
+        assert_eq!(json["validation_error"], "InvalidChecksum");
+    }
+
     #[test]
     fn test_extract_bank_cards() {
         let extractor = create_extractor();
@@ -125,6 +616,141 @@ mod tests {
         assert!(bank_cards[0].is_valid);
     }
 
+    #[test]
+    fn test_extract_macau_ids() {
+        let text = "澳门身份证1234567(5)，电话13812345678，旧号12345674";
+        assert!(create_extractor().extract_macau_ids(text).is_empty());
+
+        let extractor = InfoExtractor::new(Config {
+            enable_macau_id: true,
+            ..Default::default()
+        });
+        let macau_ids = extractor.extract_macau_ids(text);
+        assert_eq!(macau_ids.len(), 2);
+        assert_eq!(macau_ids[0].value, "1234567(5)");
+        assert!(macau_ids[0].is_valid);
+        assert_eq!(macau_ids[0].source_pattern.as_str(), "MACAU_ID");
+        assert!(!macau_ids[1].is_valid);
+    }
+
+    #[test]
+    fn test_extract_wechat_ids() {
+        let text = "微信号: johnny_123，备注随机字母abc，微信ab";
+        assert!(create_extractor().extract_wechat_ids(text).is_empty());
+
+        let extractor = InfoExtractor::new(Config {
+            enable_wechat: true,
+            ..Default::default()
+        });
+        let wechat_ids = extractor.extract_wechat_ids(text);
+        assert_eq!(wechat_ids.len(), 2);
+        assert_eq!(wechat_ids[0].value, "johnny_123");
+        assert!(wechat_ids[0].is_valid);
+        assert_eq!(wechat_ids[0].source_pattern.as_str(), "WECHAT_ID");
+        assert!(!wechat_ids[1].is_valid);
+    }
+
+    #[test]
+    fn test_bank_card_length_limit() {
+        let extractor = InfoExtractor::new(Config {
+            bank_card_length: LengthLimit {
+                min_len: Some(17),
+                max_len: None,
+            },
+            ..Default::default()
+        });
+
+        let (_, _, bank_cards, _) = extractor.extract("卡号4111111111111111，另一张6217000010012345678");
+        assert_eq!(bank_cards.len(), 1);
+        assert_eq!(bank_cards[0].value, "6217000010012345678");
+    }
+
+    #[test]
+    fn test_custom_pattern_length_limit() {
+        let extractor = InfoExtractor::new(Config {
+            custom_patterns: vec![
+                CustomPattern {
+                    name: "工号".to_string(),
+                    regex: r"EMP-\d+".to_string(),
+                    length: LengthLimit {
+                        min_len: Some(7),
+                        max_len: Some(8),
+                    },
+                },
+                CustomPattern {
+                    name: "坏规则".to_string(),
+                    regex: "(".to_string(),
+                    length: LengthLimit::default(),
+                },
+            ],
+            ..Default::default()
+        });
+
+        // 长度按去掉 `-` 后计算：EMP12 为 5 位、EMP1234 为 7 位、EMP123456 为 9 位
+        let matches = extractor.extract_custom_matches("EMP-12，EMP-1234，EMP-123456");
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].value, "EMP-1234");
+        assert_eq!(matches[0].rule.as_deref(), Some("工号"));
+        assert_eq!(matches[0].source_pattern, PatternType::Custom);
+        assert!(create_extractor().extract_custom_matches("EMP-1234").is_empty());
+    }
+
+    #[test]
+    fn test_card_length_policy() {
+        let text = "老卡4222 2222 2222 2，电话13907315286";
+        let (_, _, bank_cards, _) = create_extractor().extract(text);
+        assert!(bank_cards.is_empty());
+        // 非 Amex 的 15 位编号在默认策略下不报告
+        let (_, _, bank_cards, _) = create_extractor().extract("编号512345678901234");
+        assert!(bank_cards.is_empty());
+
+        let extractor = InfoExtractor::new(Config {
+            card_length_policy: CardLengthPolicy::Extended13to19,
+            ..Default::default()
+        });
+        let (phones, _, bank_cards, _) = extractor.extract(text);
+        assert_eq!(bank_cards.len(), 1);
+        assert_eq!(bank_cards[0].value, "4222 2222 2222 2");
+        assert!(bank_cards[0].is_valid);
+        assert_eq!(phones.len(), 1);
+    }
+
+    #[test]
+    fn test_suspicious_numbers_marked() {
+        let extractor = create_extractor();
+        let text = "测试号13812345678，真实号13907315286";
+        let (phones, _, _, _) = extractor.extract(text);
+
+        assert_eq!(phones.len(), 2);
+        assert!(phones[0].suspicious);
+        assert!(!phones[1].suspicious);
+
+        let config = Config {
+            suspicious_run_length: 0,
+            ..Default::default()
+        };
+        let (phones, _, _, _) = InfoExtractor::new(config).extract(text);
+        assert!(phones.iter().all(|m| !m.suspicious));
+    }
+
+    #[test]
+    fn test_unknown_id_region_accepted_only_when_configured() {
+        let text = "身份证910105199003072038，另一个110105199003072039";
+
+        let (_, id_cards, _, _) = create_extractor().extract(text);
+        assert_eq!(id_cards.len(), 2);
+        assert!(!id_cards[0].is_valid);
+        assert_eq!(id_cards[0].error_detail, Some(ValidationError::InvalidRegion));
+        assert!(id_cards[1].is_valid);
+
+        let extractor = InfoExtractor::new(Config {
+            accept_unknown_id_region: true,
+            ..Default::default()
+        });
+        let (_, id_cards, _, _) = extractor.extract(text);
+        assert!(id_cards.iter().all(|m| m.is_valid && m.error_detail.is_none() && !m.suspicious));
+    }
+
     #[test]
     fn test_valid_id_card_not_matched_as_bank_card() {
         let extractor = create_extractor();
@@ -0,0 +1,227 @@
+//! 导出结果的字段级加密
+//!
+//! 口令经 Argon2id 派生 256 位密钥，每个字段单独用 AES-256-GCM 加密，随机 12 字节 nonce
+//! 放在密文之前，整体 base64 编码后写入单元格。派生参数（含盐）记录在归档的运行信息里，
+//! 接收方凭口令和这些参数即可解密。
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Nonce};
+use anyhow::{anyhow, bail, Context, Result};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+use crate::models::ExtractResult;
+
+const CIPHER_ALGORITHM: &str = "AES-256-GCM";
+const KDF_ALGORITHM: &str = "Argon2id";
+/// Argon2 1.3 版本号
+const KDF_VERSION: u32 = 0x13;
+const ENCODING: &str = "base64(nonce || ciphertext || tag)";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+
+/// 解密所需的算法参数，随归档一起交付
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    pub algorithm: String,
+    pub kdf: String,
+    pub kdf_version: u32,
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// base64 编码的盐
+    pub salt: String,
+    pub nonce_len: usize,
+    pub encoding: String,
+}
+
+impl CipherParams {
+    /// Argon2 默认强度，每次生成新的随机盐
+    fn generate() -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            algorithm: CIPHER_ALGORITHM.to_string(),
+            kdf: KDF_ALGORITHM.to_string(),
+            kdf_version: KDF_VERSION,
+            memory_kib: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            salt: BASE64.encode(salt),
+            nonce_len: NONCE_LEN,
+            encoding: ENCODING.to_string(),
+        }
+    }
+}
+
+/// 用口令派生的密钥逐个加密字段
+#[derive(Clone)]
+pub struct FieldCipher {
+    cipher: Aes256Gcm,
+    params: CipherParams,
+}
+
+impl FieldCipher {
+    /// 用新的随机盐派生密钥，用于加密
+    pub fn new(passphrase: &str) -> Result<Self> {
+        Self::with_params(passphrase, CipherParams::generate())
+    }
+
+    /// 按已有参数派生密钥，用于解密对方交付的结果
+    pub fn with_params(passphrase: &str, params: CipherParams) -> Result<Self> {
+        if passphrase.is_empty() {
+            bail!("加密口令不能为空");
+        }
+        if params.algorithm != CIPHER_ALGORITHM
+            || params.kdf != KDF_ALGORITHM
+            || params.kdf_version != KDF_VERSION
+            || params.nonce_len != NONCE_LEN
+        {
+            bail!("不支持的加密参数: {} / {} v{}", params.algorithm, params.kdf, params.kdf_version);
+        }
+
+        let salt = BASE64.decode(&params.salt).context("盐不是有效的 base64")?;
+        let argon2_params = Params::new(params.memory_kib, params.iterations, params.parallelism, Some(KEY_LEN))
+            .map_err(|e| anyhow!("Argon2 参数无效: {}", e))?;
+        let mut key = [0u8; KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| anyhow!("密钥派生失败: {}", e))?;
+
+        let cipher = Aes256Gcm::new_from_slice(&key).expect("密钥长度为 32 字节");
+        Ok(Self { cipher, params })
+    }
+
+    pub fn params(&self) -> &CipherParams {
+        &self.params
+    }
+
+    /// 加密单个字段，空字段保持为空
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        if plaintext.is_empty() {
+            return Ok(String::new());
+        }
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("字段加密失败"))?;
+
+        let mut data = nonce.to_vec();
+        data.extend_from_slice(&ciphertext);
+        Ok(BASE64.encode(data))
+    }
+
+    pub fn decrypt(&self, field: &str) -> Result<String> {
+        if field.is_empty() {
+            return Ok(String::new());
+        }
+        let data = BASE64.decode(field.trim()).context("密文不是有效的 base64")?;
+        if data.len() < NONCE_LEN {
+            bail!("密文长度不足");
+        }
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("解密失败：口令错误或密文已损坏"))?;
+        String::from_utf8(plaintext).context("解密结果不是 UTF-8 文本")
+    }
+
+    /// 命中值、源文本、上下文、附带列的值、源文件路径和备注加密后的结果副本；
+    /// 文件名、工作表名、行号、有效性等保持明文，附带列的列名作为表头也保持明文
+    pub fn encrypt_results(&self, results: &[ExtractResult]) -> Result<Vec<ExtractResult>> {
+        let mut results = results.to_vec();
+        for result in &mut results {
+            for m in result
+                .phone_numbers
+                .iter_mut()
+                .chain(&mut result.id_cards)
+                .chain(&mut result.bank_cards)
+                .chain(&mut result.names)
+                .chain(&mut result.macau_ids)
+                .chain(&mut result.wechat_ids)
+                .chain(&mut result.custom_matches)
+            {
+                m.value = self.encrypt(&m.value)?;
+            }
+            result.source_text = self.encrypt(&result.source_text)?;
+            for line in result.context_before.iter_mut().chain(&mut result.context_after) {
+                *line = self.encrypt(line)?;
+            }
+            for value in result.extra_columns.values_mut() {
+                *value = self.encrypt(value)?;
+            }
+            // 路径中常含人名或客户名
+            result.source_path = self.encrypt(&result.source_path)?;
+            result.remark = self.encrypt(&result.remark)?;
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MatchInfo, PatternType};
+
+    #[test]
+    fn test_encrypt_results_round_trip() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.source_text = "电话13907315286".to_string();
+        result.phone_numbers = vec![
+            MatchInfo::builder("13907315286", PatternType::Phone)
+                .valid(true)
+                .position(2, 13)
+                .build(),
+        ];
+        result.context_before = vec!["上一行".to_string(), String::new()];
+
+        let cipher = FieldCipher::new("口令123").unwrap();
+        let encrypted = cipher.encrypt_results(&[result]).unwrap();
+        assert_eq!(encrypted[0].source_file, "a.xlsx");
+        assert!(encrypted[0].phone_numbers[0].is_valid);
+        assert_ne!(encrypted[0].phone_numbers[0].value, "13907315286");
+        assert!(encrypted[0].context_before[1].is_empty());
+
+        // 接收方只凭口令和交付的参数解密
+        let params: CipherParams =
+            serde_json::from_str(&serde_json::to_string(cipher.params()).unwrap()).unwrap();
+        let receiver = FieldCipher::with_params("口令123", params.clone()).unwrap();
+        assert_eq!(receiver.decrypt(&encrypted[0].phone_numbers[0].value).unwrap(), "13907315286");
+        assert_eq!(receiver.decrypt(&encrypted[0].source_text).unwrap(), "电话13907315286");
+        assert_eq!(receiver.decrypt(&encrypted[0].context_before[0]).unwrap(), "上一行");
+
+        let wrong = FieldCipher::with_params("口令124", params).unwrap();
+        assert!(wrong.decrypt(&encrypted[0].source_text).is_err());
+        assert!(FieldCipher::new("").is_err());
+    }
+
+    #[test]
+    fn test_encrypt_results_leaves_no_plaintext() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.source_path = "/客户/张三丰/a.xlsx".to_string();
+        result.source_text = "电话13907315286".to_string();
+        result.context_before = vec!["上一行".to_string()];
+        result.context_after = vec!["下一行".to_string()];
+        result.extra_columns = [("姓名".to_string(), "李四光".to_string())].into();
+        result.remark = "已核实本人".to_string();
+        result.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        result.names = vec![MatchInfo::builder("王五", PatternType::NameApi).valid(true).build()];
+
+        let cipher = FieldCipher::new("口令123").unwrap();
+        let encrypted = cipher.encrypt_results(&[result]).unwrap();
+        let json = serde_json::to_string(&encrypted).unwrap();
+        for plaintext in ["张三丰", "13907315286", "上一行", "下一行", "李四光", "已核实本人", "王五"] {
+            assert!(!json.contains(plaintext), "明文 {} 未加密", plaintext);
+        }
+
+        assert_eq!(cipher.decrypt(&encrypted[0].source_path).unwrap(), "/客户/张三丰/a.xlsx");
+        assert_eq!(cipher.decrypt(&encrypted[0].extra_columns["姓名"]).unwrap(), "李四光");
+        assert_eq!(cipher.decrypt(&encrypted[0].remark).unwrap(), "已核实本人");
+    }
+}
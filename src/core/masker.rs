@@ -0,0 +1,163 @@
+use chrono::NaiveDate;
+
+use super::validators::Validator;
+use crate::models::MaskRule;
+
+/// 掩码字符
+pub const MASK_CHAR: char = '*';
+
+pub struct Masker;
+
+impl Masker {
+    /// 保留前 `keep_prefix` 位和后 `keep_suffix` 位，其余替换为掩码字符
+    ///
+    /// 保留位数之和不小于文本长度时整体掩码，避免原样输出
+    pub fn mask(value: &str, keep_prefix: usize, keep_suffix: usize) -> String {
+        let chars: Vec<char> = value.chars().collect();
+        let len = chars.len();
+
+        if keep_prefix + keep_suffix >= len {
+            return MASK_CHAR.to_string().repeat(len);
+        }
+
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| {
+                if i < keep_prefix || i >= len - keep_suffix {
+                    c
+                } else {
+                    MASK_CHAR
+                }
+            })
+            .collect()
+    }
+
+    pub fn mask_with_rule(value: &str, rule: &MaskRule) -> String {
+        Self::mask(value, rule.keep_prefix, rule.keep_suffix)
+    }
+
+    /// 分析型脱敏下的身份证掩码：仅保留首尾各一位
+    pub fn mask_id_card_analytic(id_card: &str) -> String {
+        Self::mask(id_card, 1, 1)
+    }
+}
+
+/// 从身份证号解析出的非直接标识信息
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdCardProfile {
+    pub province: &'static str,
+    pub gender: &'static str,
+    /// 年龄段，如 "30-39岁"
+    pub age_group: String,
+}
+
+impl IdCardProfile {
+    /// 解析有效身份证号，无效号码返回 `None`
+    pub fn parse(id_card: &str, today: NaiveDate) -> Option<Self> {
+        if !Validator::validate_id_card(id_card) {
+            return None;
+        }
+
+        let province = province_name(&id_card[0..2])?;
+
+        let birth_date = NaiveDate::parse_from_str(&id_card[6..14], "%Y%m%d").ok()?;
+        let age = today.years_since(birth_date)?;
+        let decade = age / 10 * 10;
+
+        let gender_digit = id_card[16..17].parse::<u32>().ok()?;
+        let gender = if gender_digit % 2 == 1 { "男" } else { "女" };
+
+        Some(Self {
+            province,
+            gender,
+            age_group: format!("{}-{}岁", decade, decade + 9),
+        })
+    }
+
+    /// 以当前日期解析
+    pub fn parse_now(id_card: &str) -> Option<Self> {
+        Self::parse(id_card, chrono::Local::now().date_naive())
+    }
+}
+
+fn province_name(code: &str) -> Option<&'static str> {
+    let name = match code {
+        "11" => "北京",
+        "12" => "天津",
+        "13" => "河北",
+        "14" => "山西",
+        "15" => "内蒙古",
+        "21" => "辽宁",
+        "22" => "吉林",
+        "23" => "黑龙江",
+        "31" => "上海",
+        "32" => "江苏",
+        "33" => "浙江",
+        "34" => "安徽",
+        "35" => "福建",
+        "36" => "江西",
+        "37" => "山东",
+        "41" => "河南",
+        "42" => "湖北",
+        "43" => "湖南",
+        "44" => "广东",
+        "45" => "广西",
+        "46" => "海南",
+        "50" => "重庆",
+        "51" => "四川",
+        "52" => "贵州",
+        "53" => "云南",
+        "54" => "西藏",
+        "61" => "陕西",
+        "62" => "甘肃",
+        "63" => "青海",
+        "64" => "宁夏",
+        "65" => "新疆",
+        "71" => "台湾",
+        "81" => "香港",
+        "82" => "澳门",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(Masker::mask("13812345678", 3, 4), "138****5678");
+        assert_eq!(Masker::mask("1234", 3, 4), "****");
+        assert_eq!(Masker::mask("", 3, 4), "");
+    }
+
+    #[test]
+    fn test_mask_with_rule() {
+        let rule = MaskRule {
+            keep_prefix: 6,
+            keep_suffix: 4,
+        };
+        assert_eq!(Masker::mask_with_rule("6225880137492615", &rule), "622588******2615");
+        assert_eq!(Masker::mask_with_rule("13812", &rule), "*****");
+        assert_eq!(Masker::mask_with_rule("13812345678", &MaskRule::default()), "138****5678");
+    }
+
+    #[test]
+    fn test_mask_id_card_analytic() {
+        assert_eq!(Masker::mask_id_card_analytic("110105199003072039"), "1****************9");
+    }
+
+    #[test]
+    fn test_id_card_profile() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 7).unwrap();
+        let profile = IdCardProfile::parse("110105199003072039", today).unwrap();
+
+        assert_eq!(profile.province, "北京");
+        assert_eq!(profile.gender, "男");
+        assert_eq!(profile.age_group, "30-39岁");
+
+        assert!(IdCardProfile::parse("110105199003072038", today).is_none());
+    }
+}
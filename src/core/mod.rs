@@ -1,10 +1,35 @@
 mod excel_reader;
 mod extractor;
-pub mod validator;
+pub mod validators;
 mod processor;
 mod name_extractor;
+mod masker;
+mod exporter;
+mod preprocessor;
+mod annotation;
+mod archive;
+mod batch_processor;
+mod field_cipher;
+mod phone_segments;
+mod sheet_visibility;
+pub mod office_crypto;
+pub mod webhook;
 
-pub use excel_reader::{ExcelInfo, ExcelReader};
-pub use extractor::InfoExtractor;
+pub use excel_reader::{find_recommended_column, recommendation_rank, ExcelInfo, ExcelReader, SheetData};
+pub use annotation::Annotations;
+pub use archive::ResultArchive;
+pub use batch_processor::{create_stream_writer, stream_extension, BatchProcessor, ResultWriter, StreamSummary};
+pub use field_cipher::FieldCipher;
+pub use exporter::{
+    CsvExporter, CsvStreamWriter, HtmlExporter, HtmlStreamWriter, JsonExporter, JsonLinesStreamWriter, MultiExporter,
+    ParquetExporter, ResultExporter, XlsxExporter, XlsxStreamWriter,
+};
+pub use extractor::{ExtractionStats, InfoExtractor};
+pub use masker::{IdCardProfile, Masker};
 pub use name_extractor::NameExtractor;
-pub use processor::{ProcessingStatistics, Processor};
+pub use preprocessor::{
+    Base64DecodePreprocessor, StripHtmlPreprocessor, TextPreprocessor, TrimWhitespacePreprocessor,
+};
+pub use processor::{
+    panic_message, PivotStatistics, ProcessingControl, ProcessingStatistics, Processor, SheetStatistics,
+};
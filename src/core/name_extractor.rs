@@ -1,25 +1,63 @@
-use crate::models::MatchInfo;
+use crate::models::{ApiFieldMapping, MatchInfo, PatternType};
 use reqwest::blocking::Client;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-/// 姓名提取 API 请求体
-#[derive(Debug, Serialize)]
-struct NameExtractRequest {
-    text: String,
-}
-
-/// 姓名提取 API 响应体
-#[derive(Debug, Deserialize)]
-#[allow(dead_code)]
+/// 姓名提取 API 响应内容（按字段映射从 JSON 中取出）
+///
+/// `positions` 为每个姓名在原文中的字节区间 `[start, end)`，与 `names` 一一对应；
+/// 旧版服务端不返回该字段时，按姓名在原文中的首次出现位置定位
+#[derive(Debug)]
 struct NameExtractResponse {
     names: Vec<String>,
     confidence: f64,
-    #[serde(default)]
-    review_id: Option<i64>,
-    #[serde(default)]
-    is_duplicate: Option<bool>,
+    positions: Option<Vec<[usize; 2]>>,
+}
+
+impl NameExtractResponse {
+    fn from_value(value: &Value, mapping: &ApiFieldMapping) -> Result<Self, String> {
+        let names = lookup_path(value, &mapping.names_path)
+            .and_then(Value::as_array)
+            .ok_or_else(|| format!("响应缺少姓名字段: {}", mapping.names_path))?
+            .iter()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect();
+
+        let confidence = if mapping.confidence_path.is_empty() {
+            1.0
+        } else {
+            lookup_path(value, &mapping.confidence_path)
+                .and_then(Value::as_f64)
+                .ok_or_else(|| format!("响应缺少置信度字段: {}", mapping.confidence_path))?
+        };
+
+        let positions = if mapping.positions_path.is_empty() {
+            None
+        } else {
+            lookup_path(value, &mapping.positions_path)
+                .and_then(|v| serde_json::from_value(v.clone()).ok())
+        };
+
+        Ok(Self {
+            names,
+            confidence,
+            positions,
+        })
+    }
+}
+
+/// 按 `.` 分隔的路径取值，数组元素用下标访问
+fn lookup_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        })
 }
 
 /// 姓名提取 API 健康检查响应
@@ -29,10 +67,19 @@ struct HealthResponse {
     status: String,
 }
 
+/// 姓名提取的实际来源
+enum NameExtractorBackend {
+    /// 请求 HTTP 服务
+    Http(Client),
+    /// 按原文查表返回预设姓名，用于测试
+    Mock(HashMap<String, Vec<String>>),
+}
+
 pub struct NameExtractor {
-    client: Client,
+    backend: NameExtractorBackend,
     api_host: String,
     enabled: bool,
+    field_mapping: ApiFieldMapping,
     /// 失败请求计数器（用于统计）
     failed_count: AtomicUsize,
 }
@@ -47,13 +94,31 @@ impl NameExtractor {
             .unwrap_or_else(|_| Client::new());
 
         Self {
-            client,
+            backend: NameExtractorBackend::Http(client),
             api_host: api_host.into(),
             enabled,
+            field_mapping: ApiFieldMapping::default(),
             failed_count: AtomicUsize::new(0),
         }
     }
 
+    /// 不发起网络请求的模拟提取器：按原文在 `responses` 中查找姓名，未命中时返回空
+    pub fn new_mock(responses: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            backend: NameExtractorBackend::Mock(responses),
+            api_host: String::new(),
+            enabled: true,
+            field_mapping: ApiFieldMapping::default(),
+            failed_count: AtomicUsize::new(0),
+        }
+    }
+
+    /// 设置请求/响应字段映射，用于对接字段名不同的服务
+    pub fn with_field_mapping(mut self, field_mapping: ApiFieldMapping) -> Self {
+        self.field_mapping = field_mapping;
+        self
+    }
+
     /// 获取失败计数
     #[allow(dead_code)]
     pub fn failed_count(&self) -> usize {
@@ -66,16 +131,23 @@ impl NameExtractor {
         self.failed_count.store(0, Ordering::Relaxed);
     }
 
-    /// 检查 API 连接状态
-    pub fn check_connection(&self) -> Result<String, String> {
+    /// 检查 API 连接状态，成功时一并返回健康检查请求的耗时
+    pub fn check_connection(&self) -> Result<(String, Duration), String> {
+        let client = match &self.backend {
+            NameExtractorBackend::Http(client) => client,
+            NameExtractorBackend::Mock(_) => return Ok(("模拟服务".to_string(), Duration::ZERO)),
+        };
+
         let url = format!("http://{}/api/health", self.api_host);
 
-        match self.client.get(&url).timeout(Duration::from_secs(5)).send() {
+        let started = Instant::now();
+        match client.get(&url).timeout(Duration::from_secs(5)).send() {
             Ok(response) => {
+                let latency = started.elapsed();
                 if response.status().is_success() {
                     match response.json::<HealthResponse>() {
-                        Ok(health) => Ok(format!("连接正常: {}", health.status)),
-                        Err(_) => Ok("连接正常".to_string()),
+                        Ok(health) => Ok((format!("连接正常: {}", health.status), latency)),
+                        Err(_) => Ok(("连接正常".to_string(), latency)),
                     }
                 } else {
                     Err(format!("API 返回状态码: {}", response.status()))
@@ -91,16 +163,37 @@ impl NameExtractor {
             return Vec::new();
         }
 
+        match &self.backend {
+            NameExtractorBackend::Http(client) => self.request_names(client, text),
+            NameExtractorBackend::Mock(responses) => {
+                let response = NameExtractResponse {
+                    names: responses.get(text).cloned().unwrap_or_default(),
+                    confidence: 1.0,
+                    positions: None,
+                };
+                Self::build_matches(text, response)
+            }
+        }
+    }
+
+    fn request_names(&self, client: &Client, text: &str) -> Vec<MatchInfo> {
         let url = format!("http://{}/api/extract", self.api_host);
 
-        let request = NameExtractRequest {
-            text: text.to_string(),
-        };
+        let mut request = Map::new();
+        request.insert(
+            self.field_mapping.request_text_field.clone(),
+            Value::String(text.to_string()),
+        );
 
-        match self.client.post(&url).json(&request).send() {
+        match client.post(&url).json(&request).send() {
             Ok(response) => {
                 if response.status().is_success() {
-                    match response.json::<NameExtractResponse>() {
+                    let parsed = response
+                        .json::<Value>()
+                        .map_err(|e| e.to_string())
+                        .and_then(|value| NameExtractResponse::from_value(&value, &self.field_mapping));
+
+                    match parsed {
                         Ok(extract_response) => {
                             tracing::debug!(
                                 "姓名提取成功: names={:?}, confidence={}",
@@ -108,13 +201,7 @@ impl NameExtractor {
                                 extract_response.confidence
                             );
 
-                            extract_response
-                                .names
-                                .into_iter()
-                                .map(|name| {
-                                    MatchInfo::simple(name, extract_response.confidence >= 0.8)
-                                })
-                                .collect()
+                            Self::build_matches(text, extract_response)
                         }
                         Err(e) => {
                             self.failed_count.fetch_add(1, Ordering::Relaxed);
@@ -138,6 +225,48 @@ impl NameExtractor {
             }
         }
     }
+
+    /// 将 API 响应转换为命中信息，优先使用服务端返回的位置
+    fn build_matches(text: &str, response: NameExtractResponse) -> Vec<MatchInfo> {
+        let confidence = response.confidence;
+        let is_valid = confidence >= 0.8;
+        let positions = response
+            .positions
+            .filter(|positions| positions.len() == response.names.len());
+
+        // 回退定位时记录每个姓名的下次搜索起点，使重复姓名依次对应后续出现位置
+        let mut search_from: HashMap<String, usize> = HashMap::new();
+
+        response
+            .names
+            .into_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let api_position = positions
+                    .as_ref()
+                    .map(|positions| (positions[index][0], positions[index][1]))
+                    .filter(|&(start, end)| text.get(start..end) == Some(name.as_str()));
+
+                let position = api_position.or_else(|| {
+                    let from = search_from.get(&name).copied().unwrap_or(0);
+                    text[from..]
+                        .find(&name)
+                        .map(|offset| (from + offset, from + offset + name.len()))
+                });
+
+                let builder = MatchInfo::builder(name.clone(), PatternType::NameApi)
+                    .valid(is_valid)
+                    .confidence(confidence);
+                match position {
+                    Some((start, end)) => {
+                        search_from.insert(name, end);
+                        builder.position(start, end).build()
+                    }
+                    None => builder.build(),
+                }
+            })
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -158,6 +287,130 @@ mod tests {
         assert!(result.is_empty());
     }
 
+    #[test]
+    fn test_mock_backend() {
+        let responses = HashMap::from([("张三和李四参加会议".to_string(), vec!["张三".to_string(), "李四".to_string()])]);
+        let extractor = NameExtractor::new_mock(responses);
+
+        let names = extractor.extract("张三和李四参加会议");
+        assert_eq!(names.len(), 2);
+        assert_eq!(names[1].value, "李四");
+        assert_eq!(names[1].position, (9, 15));
+        assert!(names[1].is_valid);
+
+        assert!(extractor.extract("没有预设的文本").is_empty());
+        let (message, latency) = extractor.check_connection().unwrap();
+        assert_eq!(message, "模拟服务");
+        assert_eq!(latency, Duration::ZERO);
+    }
+
+    /// 启动只响应一次请求的本地 HTTP 服务，返回其地址
+    fn spawn_mock_server(body: &'static str) -> String {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        });
+
+        addr
+    }
+
+    #[test]
+    fn test_check_connection_reports_latency() {
+        let addr = spawn_mock_server(r#"{"status":"ok"}"#);
+        let (message, latency) = NameExtractor::new(addr, true).check_connection().unwrap();
+        assert_eq!(message, "连接正常: ok");
+        assert!(latency < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_extract_with_api_positions() {
+        let addr = spawn_mock_server(r#"{"names":["李四"],"confidence":0.95,"positions":[[9,15]]}"#);
+        let extractor = NameExtractor::new(addr, true);
+
+        let text = "张三和李四参加会议";
+        let result = extractor.extract(text);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].value, "李四");
+        assert_eq!(result[0].position, (9, 15));
+        assert_eq!(&text[9..15], "李四");
+    }
+
+    #[test]
+    fn test_build_matches_falls_back_to_find() {
+        let text = "张三说李四和张三都到了";
+        let response = NameExtractResponse {
+            names: vec!["张三".to_string(), "李四".to_string(), "张三".to_string()],
+            confidence: 0.9,
+            positions: None,
+        };
+
+        let result = NameExtractor::build_matches(text, response);
+        assert_eq!(result[0].position, (0, 6));
+        assert_eq!(result[1].position, (9, 15));
+        assert_eq!(result[2].position, (18, 24));
+        assert!(result.iter().all(|m| m.is_valid));
+    }
+
+    #[test]
+    fn test_response_with_custom_field_mapping() {
+        let mapping = ApiFieldMapping {
+            request_text_field: "content".to_string(),
+            names_path: "data.persons".to_string(),
+            confidence_path: "data.scores.0".to_string(),
+            positions_path: String::new(),
+        };
+        let value = serde_json::json!({
+            "data": { "persons": ["王五"], "scores": [0.6] }
+        });
+
+        let response = NameExtractResponse::from_value(&value, &mapping).unwrap();
+        assert_eq!(response.names, vec!["王五".to_string()]);
+        assert!((response.confidence - 0.6).abs() < f64::EPSILON);
+        assert!(response.positions.is_none());
+
+        let missing = serde_json::json!({ "names": ["王五"] });
+        assert!(NameExtractResponse::from_value(&missing, &mapping).is_err());
+    }
+
+    #[test]
+    fn test_default_field_mapping() {
+        let value = serde_json::json!({
+            "names": ["张三"], "confidence": 0.9, "positions": [[0, 6]], "review_id": 1
+        });
+
+        let response = NameExtractResponse::from_value(&value, &ApiFieldMapping::default()).unwrap();
+        assert_eq!(response.names, vec!["张三".to_string()]);
+        assert_eq!(response.positions, Some(vec![[0, 6]]));
+    }
+
     #[test]
     fn test_failed_count() {
         let extractor = NameExtractor::new("localhost:8080", true);
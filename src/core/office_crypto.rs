@@ -0,0 +1,508 @@
+//! 带密码 xlsx 的解密（ECMA-376 Agile Encryption，Office 2010 起的默认加密方式）
+//!
+//! 加密后的 xlsx 是一个 OLE 复合文件，`EncryptionInfo` 流描述加密参数，
+//! `EncryptedPackage` 流是加密后的原始 zip 包。解密结果只保存在内存中，不落盘。
+
+use aes::{Aes128, Aes192, Aes256};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use cbc::cipher::block_padding::NoPadding;
+use cbc::cipher::{BlockDecryptMut, KeyIvInit};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader as XmlReader;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::collections::HashMap;
+use std::io::{self, Read, Seek};
+use std::path::Path;
+use thiserror::Error;
+
+const ENCRYPTION_INFO_STREAM: &str = "/EncryptionInfo";
+const ENCRYPTED_PACKAGE_STREAM: &str = "/EncryptedPackage";
+
+/// 口令哈希迭代次数上限（MS-OFFCRYPTO 规定不超过 10,000,000），防止构造的文件让解密长时间占满 CPU
+const MAX_SPIN_COUNT: usize = 10_000_000;
+
+/// 加密包按 4096 字节分段，每段使用独立 IV
+const SEGMENT_LENGTH: usize = 4096;
+
+const VERIFIER_INPUT_BLOCK_KEY: [u8; 8] = [0xfe, 0xa7, 0xd2, 0x76, 0x3b, 0x4b, 0x9e, 0x79];
+const VERIFIER_HASH_BLOCK_KEY: [u8; 8] = [0xd7, 0xaa, 0x0f, 0x6d, 0x30, 0x61, 0x34, 0x4e];
+const ENCRYPTED_KEY_BLOCK_KEY: [u8; 8] = [0x14, 0x6e, 0x0b, 0xe7, 0xab, 0xac, 0xd0, 0xd6];
+
+/// 解密失败原因
+#[derive(Debug, Error)]
+pub enum DecryptError {
+    #[error("文件已加密，需要密码")]
+    PasswordRequired,
+    #[error("密码错误")]
+    WrongPassword,
+    #[error("不支持的加密方式: {0}")]
+    Unsupported(String),
+    #[error("加密文件格式错误: {0}")]
+    Malformed(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl DecryptError {
+    /// 是否需要（重新）输入密码
+    pub fn needs_password(&self) -> bool {
+        matches!(self, Self::PasswordRequired | Self::WrongPassword)
+    }
+}
+
+/// 是否为加密的 OOXML 文件（普通 .xls 同为复合文件，但没有 `EncryptedPackage` 流）
+pub fn is_encrypted_ooxml(path: &Path) -> bool {
+    cfb::open(path).is_ok_and(|file| file.is_stream(ENCRYPTED_PACKAGE_STREAM))
+}
+
+/// 用密码解密，返回原始 xlsx 字节
+pub fn decrypt_ooxml(path: &Path, password: Option<&str>) -> Result<Vec<u8>, DecryptError> {
+    let mut compound = cfb::open(path)?;
+
+    let encryption_info = read_stream(&mut compound, ENCRYPTION_INFO_STREAM)?;
+    let info = AgileInfo::parse(&encryption_info)?;
+
+    let password = password.ok_or(DecryptError::PasswordRequired)?;
+    let secret_key = info.secret_key(password)?;
+
+    let package = read_stream(&mut compound, ENCRYPTED_PACKAGE_STREAM)?;
+    info.decrypt_package(&secret_key, &package)
+}
+
+fn read_stream<F: Read + Seek>(compound: &mut cfb::CompoundFile<F>, name: &str) -> Result<Vec<u8>, DecryptError> {
+    let mut data = Vec::new();
+    compound.open_stream(name)?.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HashAlgorithm {
+    /// 较早版本的 Office 及部分第三方工具写出的文件使用 SHA-1
+    Sha1,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    fn parse(name: &str) -> Result<Self, DecryptError> {
+        match name {
+            "SHA1" => Ok(Self::Sha1),
+            "SHA256" => Ok(Self::Sha256),
+            "SHA384" => Ok(Self::Sha384),
+            "SHA512" => Ok(Self::Sha512),
+            other => Err(DecryptError::Unsupported(format!("哈希算法 {}", other))),
+        }
+    }
+
+    fn digest(self, parts: &[&[u8]]) -> Vec<u8> {
+        fn run<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+
+        match self {
+            Self::Sha1 => run::<Sha1>(parts),
+            Self::Sha256 => run::<Sha256>(parts),
+            Self::Sha384 => run::<Sha384>(parts),
+            Self::Sha512 => run::<Sha512>(parts),
+        }
+    }
+}
+
+/// `keyData` 或口令 `encryptedKey` 节点的公共参数
+#[derive(Debug)]
+struct CipherParams {
+    salt: Vec<u8>,
+    hash: HashAlgorithm,
+    key_bytes: usize,
+    block_size: usize,
+}
+
+impl CipherParams {
+    fn from_attributes(attributes: &HashMap<String, String>) -> Result<Self, DecryptError> {
+        let cipher = required(attributes, "cipherAlgorithm")?;
+        if cipher != "AES" {
+            return Err(DecryptError::Unsupported(format!("加密算法 {}", cipher)));
+        }
+        let chaining = required(attributes, "cipherChaining")?;
+        if chaining != "ChainingModeCBC" {
+            return Err(DecryptError::Unsupported(format!("分组模式 {}", chaining)));
+        }
+
+        Ok(Self {
+            salt: base64_attr(attributes, "saltValue")?,
+            hash: HashAlgorithm::parse(required(attributes, "hashAlgorithm")?)?,
+            key_bytes: number_attr(attributes, "keyBits")? / 8,
+            block_size: number_attr(attributes, "blockSize")?,
+        })
+    }
+
+    /// 哈希结果截断或以 0x36 补齐到指定长度
+    fn fit(mut bytes: Vec<u8>, len: usize) -> Vec<u8> {
+        bytes.resize(len, 0x36);
+        bytes
+    }
+}
+
+#[derive(Debug)]
+struct AgileInfo {
+    key_data: CipherParams,
+    password: CipherParams,
+    spin_count: u32,
+    encrypted_verifier_input: Vec<u8>,
+    encrypted_verifier_hash: Vec<u8>,
+    encrypted_key: Vec<u8>,
+}
+
+impl AgileInfo {
+    fn parse(data: &[u8]) -> Result<Self, DecryptError> {
+        if data.len() < 8 {
+            return Err(DecryptError::Malformed("EncryptionInfo 过短".to_string()));
+        }
+        let (major, minor) = (u16::from_le_bytes([data[0], data[1]]), u16::from_le_bytes([data[2], data[3]]));
+        if (major, minor) != (4, 4) {
+            return Err(DecryptError::Unsupported(format!("EncryptionInfo 版本 {}.{}", major, minor)));
+        }
+
+        let mut key_data = None;
+        let mut encrypted_key = None;
+
+        let mut reader = XmlReader::from_reader(&data[8..]);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(element) | Event::Empty(element)) => {
+                    match element.local_name().as_ref() {
+                        b"keyData" => key_data = Some(xml_attributes(&element)),
+                        b"encryptedKey" => encrypted_key = Some(xml_attributes(&element)),
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Ok(_) => {}
+                Err(e) => return Err(DecryptError::Malformed(e.to_string())),
+            }
+        }
+
+        let key_data = key_data.ok_or_else(|| DecryptError::Malformed("缺少 keyData".to_string()))?;
+        let encrypted_key = encrypted_key
+            .ok_or_else(|| DecryptError::Unsupported("仅支持密码加密".to_string()))?;
+        let spin_count = number_attr(&encrypted_key, "spinCount")?;
+        if spin_count > MAX_SPIN_COUNT {
+            return Err(DecryptError::Malformed(format!("spinCount {} 超过上限 {}", spin_count, MAX_SPIN_COUNT)));
+        }
+
+        Ok(Self {
+            key_data: CipherParams::from_attributes(&key_data)?,
+            password: CipherParams::from_attributes(&encrypted_key)?,
+            spin_count: spin_count as u32,
+            encrypted_verifier_input: base64_attr(&encrypted_key, "encryptedVerifierHashInput")?,
+            encrypted_verifier_hash: base64_attr(&encrypted_key, "encryptedVerifierHashValue")?,
+            encrypted_key: base64_attr(&encrypted_key, "encryptedKeyValue")?,
+        })
+    }
+
+    /// 校验密码并解出包的密钥
+    fn secret_key(&self, password: &str) -> Result<Vec<u8>, DecryptError> {
+        let params = &self.password;
+        let password_utf16: Vec<u8> = password.encode_utf16().flat_map(u16::to_le_bytes).collect();
+
+        let mut hash = params.hash.digest(&[&params.salt, &password_utf16]);
+        for iterator in 0..self.spin_count {
+            hash = params.hash.digest(&[&iterator.to_le_bytes(), &hash]);
+        }
+
+        let derive = |block_key: &[u8]| {
+            CipherParams::fit(params.hash.digest(&[&hash, block_key]), params.key_bytes)
+        };
+        let iv = CipherParams::fit(params.salt.clone(), params.block_size);
+
+        let verifier_input = aes_cbc_decrypt(&derive(&VERIFIER_INPUT_BLOCK_KEY), &iv, &self.encrypted_verifier_input)?;
+        let verifier_hash = aes_cbc_decrypt(&derive(&VERIFIER_HASH_BLOCK_KEY), &iv, &self.encrypted_verifier_hash)?;
+
+        let salt_len = params.salt.len().min(verifier_input.len());
+        let expected = params.hash.digest(&[&verifier_input[..salt_len]]);
+        if verifier_hash.get(..expected.len()) != Some(expected.as_slice()) {
+            return Err(DecryptError::WrongPassword);
+        }
+
+        let mut key = aes_cbc_decrypt(&derive(&ENCRYPTED_KEY_BLOCK_KEY), &iv, &self.encrypted_key)?;
+        key.truncate(self.key_data.key_bytes);
+        Ok(key)
+    }
+
+    fn decrypt_package(&self, secret_key: &[u8], package: &[u8]) -> Result<Vec<u8>, DecryptError> {
+        let Some((size, encrypted)) = package.split_first_chunk::<8>() else {
+            return Err(DecryptError::Malformed("EncryptedPackage 过短".to_string()));
+        };
+        let size = u64::from_le_bytes(*size) as usize;
+
+        let mut output = Vec::with_capacity(encrypted.len());
+        for (index, segment) in encrypted.chunks(SEGMENT_LENGTH).enumerate() {
+            let iv = CipherParams::fit(
+                self.key_data.hash.digest(&[&self.key_data.salt, &(index as u32).to_le_bytes()]),
+                self.key_data.block_size,
+            );
+            output.extend(aes_cbc_decrypt(secret_key, &iv, segment)?);
+        }
+
+        if output.len() < size {
+            return Err(DecryptError::Malformed("解密后的数据长度不足".to_string()));
+        }
+        output.truncate(size);
+        Ok(output)
+    }
+}
+
+fn aes_cbc_decrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, DecryptError> {
+    fn run<C>(key: &[u8], iv: &[u8], buffer: &mut [u8]) -> Result<(), DecryptError>
+    where
+        cbc::Decryptor<C>: KeyIvInit + BlockDecryptMut,
+        C: cbc::cipher::BlockCipher + cbc::cipher::BlockDecryptMut,
+    {
+        cbc::Decryptor::<C>::new_from_slices(key, iv)
+            .map_err(|e| DecryptError::Malformed(e.to_string()))?
+            .decrypt_padded_mut::<NoPadding>(buffer)
+            .map_err(|e| DecryptError::Malformed(e.to_string()))?;
+        Ok(())
+    }
+
+    let mut buffer = data.to_vec();
+    match key.len() {
+        16 => run::<Aes128>(key, iv, &mut buffer)?,
+        24 => run::<Aes192>(key, iv, &mut buffer)?,
+        32 => run::<Aes256>(key, iv, &mut buffer)?,
+        other => return Err(DecryptError::Unsupported(format!("密钥长度 {} 位", other * 8))),
+    }
+    Ok(buffer)
+}
+
+fn xml_attributes(element: &BytesStart) -> HashMap<String, String> {
+    element
+        .attributes()
+        .flatten()
+        .map(|attribute| {
+            let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_string();
+            let value = String::from_utf8_lossy(&attribute.value).to_string();
+            (key, value)
+        })
+        .collect()
+}
+
+fn required<'a>(attributes: &'a HashMap<String, String>, name: &str) -> Result<&'a str, DecryptError> {
+    attributes
+        .get(name)
+        .map(String::as_str)
+        .ok_or_else(|| DecryptError::Malformed(format!("缺少属性 {}", name)))
+}
+
+fn number_attr(attributes: &HashMap<String, String>, name: &str) -> Result<usize, DecryptError> {
+    required(attributes, name)?
+        .parse()
+        .map_err(|_| DecryptError::Malformed(format!("属性 {} 不是数字", name)))
+}
+
+fn base64_attr(attributes: &HashMap<String, String>, name: &str) -> Result<Vec<u8>, DecryptError> {
+    BASE64
+        .decode(required(attributes, name)?)
+        .map_err(|e| DecryptError::Malformed(format!("属性 {} 解码失败: {}", name, e)))
+}
+
+/// 错误链中是否包含需要输入密码的解密错误
+pub fn is_password_error(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<DecryptError>()
+        .is_some_and(DecryptError::needs_password)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ExcelReader;
+    use cbc::cipher::BlockEncryptMut;
+    use rust_xlsxwriter::Workbook;
+    use std::io::Write;
+    use crate::test_support::scratch_dir;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    const SPIN_COUNT: u32 = 1000;
+
+    fn encrypt(key: &[u8], iv: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut buffer = data.to_vec();
+        buffer.resize(data.len().div_ceil(16) * 16, 0);
+        let len = buffer.len();
+        cbc::Encryptor::<Aes256>::new_from_slices(key, iv)
+            .unwrap()
+            .encrypt_padded_mut::<NoPadding>(&mut buffer, len)
+            .unwrap();
+        buffer
+    }
+
+    /// 按 Agile Encryption（AES-256 + 指定哈希算法）把 xlsx 加密为复合文件
+    fn write_encrypted(plain: &[u8], password: &str, hash_name: &str, path: &Path) {
+        let hash = HashAlgorithm::parse(hash_name).unwrap();
+        let hash_size = hash.digest(&[]).len();
+        let key_salt = [0x11u8; 16];
+        let password_salt = [0x22u8; 16];
+        let secret_key = [0x33u8; 32];
+        let verifier_input = [0x44u8; 16];
+
+        let password_utf16: Vec<u8> = password.encode_utf16().flat_map(u16::to_le_bytes).collect();
+        let mut h = hash.digest(&[&password_salt, &password_utf16]);
+        for iterator in 0..SPIN_COUNT {
+            h = hash.digest(&[&iterator.to_le_bytes(), &h]);
+        }
+        let derive = |block_key: &[u8]| CipherParams::fit(hash.digest(&[&h, block_key]), 32);
+
+        let verifier_hash = hash.digest(&[&verifier_input]);
+        let encrypted_input = encrypt(&derive(&VERIFIER_INPUT_BLOCK_KEY), &password_salt, &verifier_input);
+        let encrypted_hash = encrypt(&derive(&VERIFIER_HASH_BLOCK_KEY), &password_salt, &verifier_hash);
+        let encrypted_key = encrypt(&derive(&ENCRYPTED_KEY_BLOCK_KEY), &password_salt, &secret_key);
+
+        let mut package = (plain.len() as u64).to_le_bytes().to_vec();
+        for (index, segment) in plain.chunks(SEGMENT_LENGTH).enumerate() {
+            let iv = CipherParams::fit(hash.digest(&[&key_salt, &(index as u32).to_le_bytes()]), 16);
+            package.extend(encrypt(&secret_key, &iv, segment));
+        }
+
+        let params = |salt: &[u8]| {
+            format!(
+                r#"saltSize="16" blockSize="16" keyBits="256" hashSize="{}" cipherAlgorithm="AES" cipherChaining="ChainingModeCBC" hashAlgorithm="{}" saltValue="{}""#,
+                hash_size,
+                hash_name,
+                BASE64.encode(salt)
+            )
+        };
+        let xml = format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>"#,
+                r#"<encryption xmlns="http://schemas.microsoft.com/office/2006/encryption" "#,
+                r#"xmlns:p="http://schemas.microsoft.com/office/2006/keyEncryptor/password">"#,
+                r#"<keyData {}/>"#,
+                r#"<keyEncryptors><keyEncryptor uri="http://schemas.microsoft.com/office/2006/keyEncryptor/password">"#,
+                r#"<p:encryptedKey spinCount="{}" {} encryptedVerifierHashInput="{}" "#,
+                r#"encryptedVerifierHashValue="{}" encryptedKeyValue="{}"/>"#,
+                r#"</keyEncryptor></keyEncryptors></encryption>"#,
+            ),
+            params(&key_salt),
+            SPIN_COUNT,
+            params(&password_salt),
+            BASE64.encode(&encrypted_input),
+            BASE64.encode(&encrypted_hash),
+            BASE64.encode(&encrypted_key),
+        );
+
+        let mut info = vec![4, 0, 4, 0, 0x40, 0, 0, 0];
+        info.extend(xml.as_bytes());
+
+        let mut compound = cfb::create(path).unwrap();
+        compound.create_stream(ENCRYPTION_INFO_STREAM).unwrap().write_all(&info).unwrap();
+        compound.create_stream(ENCRYPTED_PACKAGE_STREAM).unwrap().write_all(&package).unwrap();
+        compound.flush().unwrap();
+    }
+
+    /// 加密后的样例文件放在返回的临时目录中，目录随 [`TempDir`] 一起删除
+    fn encrypted_fixture(password: &str, hash_name: &str) -> (TempDir, PathBuf, Vec<u8>) {
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "消息内容").unwrap();
+        worksheet.write_string(1, 0, "电话13907315286").unwrap();
+        let plain = workbook.save_to_buffer().unwrap();
+
+        let dir = scratch_dir();
+        let path = dir.path().join("encrypted.xlsx");
+        write_encrypted(&plain, password, hash_name, &path);
+        (dir, path, plain)
+    }
+
+    #[test]
+    fn test_decrypt_with_password() {
+        let (_dir, path, plain) = encrypted_fixture("密码123", "SHA512");
+
+        assert!(is_encrypted_ooxml(&path));
+        assert!(plain.len() > SEGMENT_LENGTH, "样例需跨越多个分段");
+        assert_eq!(decrypt_ooxml(&path, Some("密码123")).unwrap(), plain);
+
+        assert!(matches!(decrypt_ooxml(&path, None), Err(DecryptError::PasswordRequired)));
+        assert!(matches!(decrypt_ooxml(&path, Some("wrong")), Err(DecryptError::WrongPassword)));
+    }
+
+    #[test]
+    fn test_excel_reader_opens_encrypted_file() {
+        let (_dir, path, _) = encrypted_fixture("secret", "SHA512");
+
+        let error = ExcelReader::open(&path).err().unwrap();
+        assert!(is_password_error(&error));
+        let error = ExcelReader::open_with_password(&path, Some("nope")).err().unwrap();
+        assert!(is_password_error(&error));
+
+        let mut reader = ExcelReader::open_with_password(&path, Some("secret")).unwrap();
+        let sheet = reader.read_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.rows[1][0], "电话13907315286");
+    }
+
+    #[test]
+    fn test_decrypt_sha1_file() {
+        let (_dir, path, plain) = encrypted_fixture("密码123", "SHA1");
+
+        assert_eq!(decrypt_ooxml(&path, Some("密码123")).unwrap(), plain);
+        assert!(matches!(decrypt_ooxml(&path, Some("wrong")), Err(DecryptError::WrongPassword)));
+    }
+
+    #[test]
+    fn test_decrypt_independently_encrypted_files() {
+        // 由 tests/fixtures/make_encrypted_fixtures.py 独立生成，参数与 Excel 2010（SHA-1 + AES-128）
+        // 和 Excel 2013 起（SHA-512 + AES-256）的默认值一致
+        for name in ["encrypted_sha1_aes128.xlsx", "encrypted_sha512_aes256.xlsx"] {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+            assert!(is_encrypted_ooxml(&path), "{}", name);
+            assert!(matches!(decrypt_ooxml(&path, Some("wrong")), Err(DecryptError::WrongPassword)), "{}", name);
+
+            let mut reader = ExcelReader::open_with_password(&path, Some("密码123")).unwrap();
+            let sheet = reader.read_sheet("Sheet1").unwrap();
+            assert_eq!(sheet.rows[1][0], "电话13907315286", "{}", name);
+            assert_eq!(sheet.rows.len(), 202, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_spin_count_above_limit_is_rejected() {
+        let (_dir, path, _) = encrypted_fixture("secret", "SHA512");
+        let info = read_stream(&mut cfb::open(&path).unwrap(), ENCRYPTION_INFO_STREAM).unwrap();
+
+        let with_spin_count = |count: &str| {
+            let xml = String::from_utf8_lossy(&info[8..])
+                .replace(&format!(r#"spinCount="{}""#, SPIN_COUNT), &format!(r#"spinCount="{}""#, count));
+            [&info[..8], xml.as_bytes()].concat()
+        };
+        assert!(AgileInfo::parse(&with_spin_count("10000000")).is_ok());
+        for count in ["10000001", "4294967296"] {
+            assert!(matches!(AgileInfo::parse(&with_spin_count(count)), Err(DecryptError::Malformed(_))), "{}", count);
+        }
+    }
+
+    #[test]
+    fn test_hash_algorithms_match_known_digests() {
+        // FIPS 180 的 "abc" 样例，确认各算法名对应的实现无误
+        let hex = |bytes: Vec<u8>| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        let digest = |name: &str| hex(HashAlgorithm::parse(name).unwrap().digest(&[b"a", b"bc"]));
+
+        assert_eq!(digest("SHA1"), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(digest("SHA256"), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert!(matches!(HashAlgorithm::parse("MD5"), Err(DecryptError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_plain_files_are_not_encrypted() {
+        let dir = scratch_dir();
+        let path = dir.path().join("plain.xlsx");
+        Workbook::new().save(&path).unwrap();
+        assert!(!is_encrypted_ooxml(&path));
+    }
+}
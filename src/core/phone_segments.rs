@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// 号段表文件名，放在程序所在目录下即可覆盖内置号段
+pub const PHONE_SEGMENTS_FILE: &str = "号段表.json";
+
+/// 内置号段表，格式与外部号段表相同；号段可写单个（"147"）或区间（"134-139"）
+const BUILTIN_SEGMENTS: &str = r#"{
+    "prefixes": ["13", "14", "15", "16", "17", "18", "19"],
+    "carriers": {
+        "中国移动": ["134-139", "147", "148", "150-152", "157-159", "172", "178", "182-184", "187", "188", "195", "197", "198"],
+        "中国联通": ["130-132", "145", "146", "155", "156", "166", "175", "176", "185", "186", "196"],
+        "中国电信": ["133", "149", "153", "173", "174", "177", "180", "181", "189", "190", "191", "193", "199"],
+        "中国广电": ["192"],
+        "虚拟运营商": ["162", "165", "167", "170", "171"]
+    }
+}"#;
+
+/// 程序使用的号段表：程序目录下有 `号段表.json` 时读取它，否则使用内置号段
+pub static PHONE_SEGMENTS: LazyLock<PhoneSegments> = LazyLock::new(|| {
+    match default_segments_path() {
+        Some(path) => PhoneSegments::load_or_builtin(&path),
+        None => PhoneSegments::builtin(),
+    }
+});
+
+fn default_segments_path() -> Option<PathBuf> {
+    Some(std::env::current_exe().ok()?.parent()?.join(PHONE_SEGMENTS_FILE))
+}
+
+#[derive(Deserialize)]
+struct SegmentFile {
+    prefixes: Vec<String>,
+    #[serde(default)]
+    carriers: BTreeMap<String, Vec<String>>,
+}
+
+/// 手机号有效前缀和号段到运营商的映射
+#[derive(Debug, Clone)]
+pub struct PhoneSegments {
+    prefixes: Vec<String>,
+    carriers: Vec<(String, Vec<RangeInclusive<u32>>)>,
+}
+
+impl PhoneSegments {
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN_SEGMENTS).expect("内置号段表格式错误")
+    }
+
+    /// 读取外部号段表；文件不存在时静默使用内置号段，格式错误时记录警告后使用内置号段
+    pub fn load_or_builtin(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::builtin();
+        }
+
+        match Self::load(path) {
+            Ok(segments) => {
+                tracing::info!("已从 {} 加载号段表", path.display());
+                segments
+            }
+            Err(e) => {
+                tracing::warn!("号段表 {} 无效，使用内置号段: {:#}", path.display(), e);
+                Self::builtin()
+            }
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("无法读取号段表: {}", path.display()))?;
+        Self::from_json(&content)
+    }
+
+    /// 解析号段表；无法识别的前缀或号段跳过并记录警告，没有任何有效前缀时报错
+    pub fn from_json(content: &str) -> Result<Self> {
+        let file: SegmentFile = serde_json::from_str(content).context("号段表 JSON 格式错误")?;
+
+        let prefixes: Vec<String> = file
+            .prefixes
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| {
+                let valid = (1..=11).contains(&p.len()) && p.chars().all(|c| c.is_ascii_digit());
+                if !valid {
+                    tracing::warn!("忽略无效的号段前缀: {:?}", p);
+                }
+                valid
+            })
+            .collect();
+        if prefixes.is_empty() {
+            anyhow::bail!("号段表没有有效的前缀");
+        }
+
+        let carriers = file
+            .carriers
+            .into_iter()
+            .map(|(carrier, segments)| {
+                let ranges = segments
+                    .iter()
+                    .filter_map(|segment| {
+                        let range = parse_segment(segment);
+                        if range.is_none() {
+                            tracing::warn!("忽略 {} 的无效号段: {:?}", carrier, segment);
+                        }
+                        range
+                    })
+                    .collect();
+                (carrier, ranges)
+            })
+            .collect();
+
+        Ok(Self { prefixes, carriers })
+    }
+
+    /// 11 位号码是否以有效前缀开头
+    pub fn has_valid_prefix(&self, number: &str) -> bool {
+        self.prefixes.iter().any(|prefix| number.starts_with(prefix.as_str()))
+    }
+
+    /// 号码前三位所属运营商，未收录的号段返回 `None`
+    pub fn carrier(&self, number: &str) -> Option<&str> {
+        let segment: u32 = number.get(..3)?.parse().ok()?;
+        self.carriers
+            .iter()
+            .find(|(_, ranges)| ranges.iter().any(|range| range.contains(&segment)))
+            .map(|(carrier, _)| carrier.as_str())
+    }
+}
+
+/// "147" 或 "134-139" 形式的三位号段
+fn parse_segment(segment: &str) -> Option<RangeInclusive<u32>> {
+    let parse = |s: &str| {
+        let s = s.trim();
+        (s.len() == 3).then(|| s.parse::<u32>().ok()).flatten()
+    };
+    match segment.split_once('-') {
+        Some((start, end)) => {
+            let (start, end) = (parse(start)?, parse(end)?);
+            (start <= end).then_some(start..=end)
+        }
+        None => parse(segment).map(|s| s..=s),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn test_custom_segments() {
+        let segments = PhoneSegments::from_json(
+            r#"{"prefixes": ["13", "141", "abc"], "carriers": {"测试运营商": ["130-132", "141", "99x"]}}"#,
+        )
+        .unwrap();
+
+        assert!(segments.has_valid_prefix("13112345678"));
+        assert!(segments.has_valid_prefix("14112345678"));
+        assert!(!segments.has_valid_prefix("14212345678"));
+        assert_eq!(segments.carrier("13212345678"), Some("测试运营商"));
+        assert_eq!(segments.carrier("13312345678"), None);
+
+        assert!(PhoneSegments::from_json(r#"{"prefixes": ["x"]}"#).is_err());
+        assert!(PhoneSegments::from_json("不是 JSON").is_err());
+    }
+
+    #[test]
+    fn test_load_falls_back_to_builtin() {
+        let dir = scratch_dir();
+        let missing = dir.path().join("missing_segments.json");
+        assert!(PhoneSegments::load_or_builtin(&missing).has_valid_prefix("19912345678"));
+
+        let broken = dir.path().join("broken_segments.json");
+        std::fs::write(&broken, "{\"prefixes\": ").unwrap();
+        let segments = PhoneSegments::load_or_builtin(&broken);
+        assert_eq!(segments.carrier("19212345678"), Some("中国广电"));
+    }
+}
@@ -0,0 +1,115 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use regex::Regex;
+use std::borrow::Cow;
+use std::sync::LazyLock;
+
+/// HTML 标签
+static HTML_TAG: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^<>]*>").unwrap());
+
+/// 疑似 Base64 的片段：至少 16 个字符，避免把普通单词或数字当作编码
+static BASE64_TOKEN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z0-9+/]{16,}={0,2}").unwrap());
+
+/// 单元格文本提取前的预处理，用于去掉遮蔽敏感信息的领域噪声
+///
+/// 预处理只影响提取，导出的源文本仍为单元格原文；命中位置对应预处理后的文本
+pub trait TextPreprocessor: Send + Sync {
+    fn process(&self, text: &str) -> String;
+}
+
+/// 依次应用预处理器，没有预处理器时不复制文本
+pub fn apply_preprocessors<'a>(preprocessors: &[Box<dyn TextPreprocessor>], text: &'a str) -> Cow<'a, str> {
+    preprocessors
+        .iter()
+        .fold(Cow::Borrowed(text), |text, preprocessor| Cow::Owned(preprocessor.process(&text)))
+}
+
+/// 去掉 HTML 标签并还原常见实体
+pub struct StripHtmlPreprocessor;
+
+impl TextPreprocessor for StripHtmlPreprocessor {
+    fn process(&self, text: &str) -> String {
+        HTML_TAG
+            .replace_all(text, "")
+            .replace("&nbsp;", " ")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&#39;", "'")
+            .replace("&amp;", "&")
+    }
+}
+
+/// 把疑似 Base64 的片段替换为解码后的文本；纯数字、长度不合法或解码后不是可读 UTF-8 文本的片段保持原样
+pub struct Base64DecodePreprocessor;
+
+impl Base64DecodePreprocessor {
+    fn decode(token: &str) -> Option<String> {
+        if !token.len().is_multiple_of(4) || token.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let decoded = String::from_utf8(BASE64.decode(token).ok()?).ok()?;
+        if decoded.chars().any(|c| c.is_control() && !c.is_whitespace()) {
+            return None;
+        }
+        Some(decoded)
+    }
+}
+
+impl TextPreprocessor for Base64DecodePreprocessor {
+    fn process(&self, text: &str) -> String {
+        BASE64_TOKEN
+            .replace_all(text, |caps: &regex::Captures| {
+                let token = &caps[0];
+                Self::decode(token).unwrap_or_else(|| token.to_string())
+            })
+            .into_owned()
+    }
+}
+
+/// 去掉首尾空白，并把连续空白（含全角空格）合并为一个空格
+pub struct TrimWhitespacePreprocessor;
+
+impl TextPreprocessor for TrimWhitespacePreprocessor {
+    fn process(&self, text: &str) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_html() {
+        let text = "<p>客户&nbsp;<b>张三</b></p><br/>电话：13907315286 &amp; a&lt;b";
+        assert_eq!(StripHtmlPreprocessor.process(text), "客户 张三电话：13907315286 & a<b");
+    }
+
+    #[test]
+    fn test_base64_decode() {
+        let encoded = BASE64.encode("电话13907315286");
+        let text = format!("备注 {} 结束", encoded);
+        assert_eq!(Base64DecodePreprocessor.process(&text), "备注 电话13907315286 结束");
+
+        // 号码本身和非 Base64 片段保持原样
+        let text = "卡号6217000010012345678，ID abcdefghijklmnop1";
+        assert_eq!(Base64DecodePreprocessor.process(text), text);
+    }
+
+    #[test]
+    fn test_trim_whitespace() {
+        assert_eq!(TrimWhitespacePreprocessor.process("  电话\u{3000}\u{3000}138 1234\t\n5678  "), "电话 138 1234 5678");
+    }
+
+    #[test]
+    fn test_preprocessor_chain() {
+        let preprocessors: Vec<Box<dyn TextPreprocessor>> =
+            vec![Box::new(StripHtmlPreprocessor), Box::new(TrimWhitespacePreprocessor)];
+
+        let text = "  <div>电话</div>   <span>13907315286</span>  ";
+        assert_eq!(apply_preprocessors(&preprocessors, text), "电话 13907315286");
+        assert!(matches!(apply_preprocessors(&[], text), Cow::Borrowed(_)));
+    }
+}
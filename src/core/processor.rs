@@ -1,22 +1,219 @@
 use anyhow::{bail, Context, Result};
 use rayon::prelude::*;
-use rust_xlsxwriter::FormatBorder;
-use rust_xlsxwriter::*;
-use std::path::Path;
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Instant;
+use serde::Serialize;
+use std::any::Any;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Condvar, Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use super::{ExcelReader, InfoExtractor};
-use crate::models::{Config, ExtractResult, FileInfo};
+use super::preprocessor::{apply_preprocessors, TextPreprocessor};
+use super::validators::Validator;
+use super::{
+    find_recommended_column, ExcelReader, ExtractionStats, IdCardProfile, InfoExtractor, ParquetExporter,
+    ResultExporter, SheetData, XlsxExporter,
+};
+use crate::models::{Config, ExportConflictStrategy, ExtractResult, FileInfo};
+use crate::utils::{format_elapsed_time, format_large_number, sanitize_filename};
+
+/// 每个文件的处理结果（文件名, 文件路径, 提取结果）
+pub type FileProcessingResult = (String, PathBuf, Result<Vec<ExtractResult>>);
+
+/// 读取与提取流水线中最多缓存的已读工作表数
+const PIPELINE_DEPTH: usize = 1;
+
+/// 处理失败的文件自动重试前的等待时间
+const RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// 行级进度回调（本次处理的行数, 当前文件名）
+type RowProgressCallback<'a> = &'a dyn Fn(usize, &str);
+
+/// 新结果回调，参数为自上次回调以来新增的结果
+type ResultCallback = Box<dyn Fn(&[ExtractResult]) + Send + Sync>;
+
+/// 分组小表（标题, 分组列名, 按数量降序的分组计数）
+pub type PivotTable<'a> = (&'static str, &'static str, Vec<(&'a str, usize)>);
+
+/// 提取单个工作表时各行共用的定位信息
+struct SheetContext<'a> {
+    file_info: &'a FileInfo,
+    sheet_name: &'a str,
+    sheet_data: &'a SheetData,
+    /// 目标列索引
+    col_index: usize,
+    /// 附带列（列名, 在当前工作表中的列索引）
+    extra_columns: Vec<(String, Option<usize>)>,
+}
+
+/// 处理过程的暂停/取消控制，界面线程与处理线程共享同一份
+#[derive(Debug, Default)]
+pub struct ProcessingControl {
+    paused: AtomicBool,
+    cancelled: AtomicBool,
+    lock: Mutex<()>,
+    wakeup: Condvar,
+}
+
+impl ProcessingControl {
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        self.notify();
+    }
+
+    /// 取消处理；暂停中的处理线程会被唤醒并退出
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        self.notify();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 暂停时阻塞到恢复或取消为止，返回是否已取消
+    pub fn wait_if_paused(&self) -> bool {
+        if self.is_paused() && !self.is_cancelled() {
+            let mut guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+            while self.is_paused() && !self.is_cancelled() {
+                guard = self.wakeup.wait(guard).unwrap_or_else(PoisonError::into_inner);
+            }
+        }
+        self.is_cancelled()
+    }
+
+    /// 持锁后再通知，避免等待方检查标志与进入等待之间漏掉唤醒
+    fn notify(&self) {
+        let _guard = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        self.wakeup.notify_all();
+    }
+}
 
 pub struct Processor {
     config: Config,
+    /// 本处理器已扫描的数据行数（跨文件累计）
+    rows_scanned: AtomicUsize,
+    control: Arc<ProcessingControl>,
+    /// 提取前依次作用于单元格文本的预处理器
+    preprocessors: Vec<Box<dyn TextPreprocessor>>,
+    /// 处理成功但需要提示的文件（文件路径, 警告信息）
+    warnings: Mutex<Vec<(PathBuf, String)>>,
+    /// 各文件的单元格提取统计，按文件路径（不同目录下的同名文件分开统计）
+    file_stats: Mutex<BTreeMap<String, ExtractionStats>>,
+    /// 各工作表的统计，按（文件路径, 工作表名）
+    sheet_stats: Mutex<BTreeMap<(String, String), SheetStatistics>>,
+    /// 处理中按批报告新结果，供界面实时更新统计
+    result_callback: Option<ResultCallback>,
 }
 
 impl Processor {
     pub fn new(config: Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            rows_scanned: AtomicUsize::new(0),
+            control: Arc::default(),
+            preprocessors: Vec::new(),
+            warnings: Mutex::default(),
+            file_stats: Mutex::default(),
+            sheet_stats: Mutex::default(),
+            result_callback: None,
+        }
+    }
+
+    /// 取出处理过程中产生的文件警告（文件名, 警告信息）
+    pub fn take_warnings(&self) -> Vec<(PathBuf, String)> {
+        std::mem::take(&mut *self.warnings.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// 使用外部持有的控制句柄，以便在处理中暂停、恢复或取消
+    pub fn with_control(mut self, control: Arc<ProcessingControl>) -> Self {
+        self.control = control;
+        self
+    }
+
+    /// 处理中随进度回调按批报告新结果；并行处理时各文件的批次交错到达
+    pub fn with_result_callback(mut self, callback: impl Fn(&[ExtractResult]) + Send + Sync + 'static) -> Self {
+        self.result_callback = Some(Box::new(callback));
+        self
+    }
+
+    /// 设置单元格文本的预处理器，按顺序在提取前应用
+    pub fn with_text_preprocessors(mut self, preprocessors: Vec<Box<dyn TextPreprocessor>>) -> Self {
+        self.preprocessors = preprocessors;
+        self
+    }
+
+    /// 处理多个文件：开启 `force_sequential` 或 `max_threads` 为 1 时（测试构建中总是）逐个顺序处理，
+    /// 否则并行处理，设置了 `max_threads` 时线程数不超过该值
+    pub fn process_files(
+        &self,
+        files: &[FileInfo],
+        progress_callback: impl Fn(&str, u8) + Sync + Send + 'static,
+    ) -> (Vec<FileProcessingResult>, f64) {
+        if self.runs_sequentially() || cfg!(test) {
+            return self.process_files_sequential(files, progress_callback);
+        }
+
+        let pool = self.config.max_threads.and_then(|threads| {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .inspect_err(|e| tracing::warn!("无法创建 {} 线程的线程池，改用默认线程池: {}", threads, e))
+                .ok()
+        });
+        match pool {
+            Some(pool) => pool.install(|| self.process_files_parallel(files, progress_callback)),
+            None => self.process_files_parallel(files, progress_callback),
+        }
+    }
+
+    /// 按配置是否逐个顺序处理文件（不含测试构建）
+    fn runs_sequentially(&self) -> bool {
+        self.config.force_sequential || self.config.max_threads == Some(1)
+    }
+
+    /// 在当前线程逐个处理文件，结果与 [`Self::process_files_parallel`] 一致，便于断点调试
+    pub fn process_files_sequential(
+        &self,
+        files: &[FileInfo],
+        progress_callback: impl Fn(&str, u8),
+    ) -> (Vec<FileProcessingResult>, f64) {
+        let start_time = Instant::now();
+        let total_rows: usize = files.iter().map(|f| f.row_count as usize).sum();
+        let processed_rows = Cell::new(0usize);
+
+        let file_progress_callback = |rows_processed: usize, current_file: &str| {
+            let total_processed = processed_rows.get() + rows_processed;
+            processed_rows.set(total_processed);
+            let progress = ((total_processed as f64 / total_rows as f64) * 100.0).min(100.0) as u8;
+            progress_callback(current_file, progress);
+        };
+
+        progress_callback("准备处理", 0);
+
+        let results: Vec<FileProcessingResult> = files
+            .iter()
+            .map(|file_info| {
+                let row_callback = (total_rows > 0).then_some(&file_progress_callback as RowProgressCallback);
+                let result = self.process_file_with_retry(file_info, row_callback);
+                (file_info.file_name.clone(), file_info.file_path.clone(), result)
+            })
+            .collect();
+
+        progress_callback("处理完成", 100);
+        let elapsed = start_time.elapsed().as_secs_f64();
+        (results, elapsed)
     }
 
     /// 并行处理多个文件（基于行数计算进度）
@@ -24,7 +221,7 @@ impl Processor {
         &self,
         files: &[FileInfo],
         progress_callback: impl Fn(&str, u8) + Sync + Send + 'static,
-    ) -> (Vec<(String, Result<Vec<ExtractResult>>)>, f64) {
+    ) -> (Vec<FileProcessingResult>, f64) {
         let start_time = Instant::now();
         let callback = Arc::new(progress_callback);
 
@@ -32,11 +229,11 @@ impl Processor {
         let total_rows: usize = files.iter().map(|f| f.row_count as usize).sum();
         if total_rows == 0 {
             callback("准备处理", 0);
-            let results: Vec<(String, Result<Vec<ExtractResult>>)> = files
+            let results: Vec<FileProcessingResult> = files
                 .iter()
                 .map(|file_info| {
-                    let result = self.process_file_with_progress(file_info, None);
-                    (file_info.file_name.clone(), result)
+                    let result = self.process_file_with_retry(file_info, None);
+                    (file_info.file_name.clone(), file_info.file_path.clone(), result)
                 })
                 .collect();
             callback("处理完成", 100);
@@ -48,9 +245,12 @@ impl Processor {
 
         callback("准备处理", 0);
 
-        let results: Vec<(String, Result<Vec<ExtractResult>>)> = files
-            .par_iter()
-            .map(|file_info| {
+        // 按列表顺序分派文件，使排在前面（优先级高）的文件先开始处理
+        let mut indexed_results: Vec<(usize, FileProcessingResult)> = files
+            .iter()
+            .enumerate()
+            .par_bridge()
+            .map(|(index, file_info)| {
                 // 为每个文件创建进度回调闭包
                 let callback_clone = Arc::clone(&callback);
                 let processed_rows_clone = Arc::clone(&processed_rows);
@@ -62,235 +262,525 @@ impl Processor {
                     callback_clone(current_file, progress);
                 };
 
-                let result = self.process_file_with_progress(file_info, Some(&file_progress_callback));
-                (file_info.file_name.clone(), result)
+                let result = self.process_file_with_retry(file_info, Some(&file_progress_callback));
+                (index, (file_info.file_name.clone(), file_info.file_path.clone(), result))
             })
             .collect();
 
+        indexed_results.sort_by_key(|(index, _)| *index);
+        let results: Vec<FileProcessingResult> = indexed_results
+            .into_iter()
+            .map(|(_, result)| result)
+            .collect();
+
         callback("处理完成", 100);
         let elapsed = start_time.elapsed().as_secs_f64();
         (results, elapsed)
     }
 
-    /// 处理单个文件（支持行级进度回调）
+    /// 处理单个文件并返回其结果，供逐文件流式写出使用
+    pub fn process_file(&self, file_info: &FileInfo) -> Result<Vec<ExtractResult>> {
+        self.process_file_with_retry(file_info, None)
+    }
+
+    /// 处理单个文件；开启 `retry_failed_files` 时失败后等待片刻重试一次（取消导致的失败不重试），
+    /// 重试成功记为文件警告，仍失败时错误信息注明已重试。
+    /// 首次处理已通过结果回调报告的结果在重试时不再重复报告
+    fn process_file_with_retry(
+        &self,
+        file_info: &FileInfo,
+        progress_callback: Option<RowProgressCallback>,
+    ) -> Result<Vec<ExtractResult>> {
+        let mut results_reported = 0usize;
+        let first_error = match catch_panic(|| {
+            self.process_file_with_progress(file_info, progress_callback, &mut results_reported)
+        }) {
+            Ok(results) => return Ok(results),
+            Err(e) if !self.config.retry_failed_files || self.control.is_cancelled() => return Err(e),
+            Err(e) => e,
+        };
+
+        tracing::warn!(
+            "处理文件 {} 失败，{} 毫秒后重试: {:#}",
+            file_info.file_name,
+            RETRY_DELAY.as_millis(),
+            first_error
+        );
+        thread::sleep(RETRY_DELAY);
+
+        match catch_panic(|| self.process_file_with_progress(file_info, progress_callback, &mut results_reported)) {
+            Ok(results) => {
+                let warning = format!("首次处理失败，已自动重试成功: {:#}", first_error);
+                tracing::info!("{}: {}", file_info.file_name, warning);
+                self.warnings
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .push((file_info.file_path.clone(), warning));
+                Ok(results)
+            }
+            Err(e) => {
+                tracing::error!("重试处理文件 {} 仍失败: {:#}", file_info.file_name, e);
+                Err(anyhow::anyhow!("{:#}（已自动重试一次）", e))
+            }
+        }
+    }
+
+    /// 处理单个文件（支持行级进度回调）；`results_reported` 为本文件已通过结果回调报告的结果数。
+    /// 扫描行数和逐文件、逐工作表统计只在处理成功后计入，失败的尝试不留下计数
     fn process_file_with_progress(
         &self,
         file_info: &FileInfo,
-        progress_callback: Option<&dyn Fn(usize, &str)>,
+        progress_callback: Option<RowProgressCallback>,
+        results_reported: &mut usize,
     ) -> Result<Vec<ExtractResult>> {
-        let mut reader = ExcelReader::open(&file_info.file_path)
-            .with_context(|| format!("无法打开文件: {}", file_info.file_name))?;
+        if self.control.wait_if_paused() {
+            bail!("处理已取消");
+        }
+
+        let mut reader = ExcelReader::open_with_password(&file_info.file_path, self.config.excel_password.as_deref())
+            .with_context(|| format!("无法打开文件: {}", file_info.file_name))?
+            .with_trim_empty_rows(self.config.trim_empty_rows)
+            .with_include_hidden(self.config.include_hidden)
+            .with_convert_date_cells(self.config.convert_date_cells);
 
         let extractor = InfoExtractor::new(self.config.clone());
         let mut all_results = Vec::new();
         let mut rows_processed = 0usize;
+        let mut rows_scanned = 0usize;
+        let mut sheet_stats = Vec::new();
         // 动态计算更新间隔：总行数的1%或最少100行
-        let update_interval = ((file_info.row_count as usize) / 100).max(100).min(500);
-
-        let sheet_names = reader.sheet_names();
+        let update_interval = ((file_info.row_count as usize) / 100).clamp(100, 500);
 
-        for sheet_name in &sheet_names {
-            let sheet_data = reader.read_sheet(sheet_name)?;
+        // 指定了目标列时记录缺少该列的工作表
+        let mut missing_sheets = Vec::new();
+        let mut sheet_count = 0usize;
 
-            let target_column = if self.config.target_column.is_empty() {
-                self.find_target_column(&sheet_data)?
-            } else {
-                self.config.target_column.clone()
-            };
+        // 读取线程按工作表读出数据，经有界 channel 交给当前线程提取，读下一个工作表与提取上一个重叠；
+        // 每个工作表整表交付，上下文窗口不受流水线影响
+        let sheet_names = reader.sheet_names();
+        let sheet_names = self.config.sheet_exclusion().filter(sheet_names);
+        if sheet_names.is_empty() {
+            bail!("所有工作表都已被排除");
+        }
+        thread::scope(|scope| -> Result<()> {
+            let (sender, receiver) = mpsc::sync_channel(PIPELINE_DEPTH);
+            scope.spawn(move || {
+                for sheet_name in sheet_names {
+                    let sheet_data = reader.read_sheet(&sheet_name);
+                    let failed = sheet_data.is_err();
+                    // 提取端提前结束（出错或取消）时发送失败，读取随之停止
+                    if sender.send((sheet_name, sheet_data)).is_err() || failed {
+                        break;
+                    }
+                }
+            });
 
-            let column_data = match sheet_data.get_column_by_name(&target_column) {
-                Ok(data) => data,
-                Err(_) => continue,
-            };
+            for (sheet_name, sheet_data) in receiver {
+                let sheet_data = sheet_data?;
+                sheet_count += 1;
 
-            for (row_index, cell_value) in column_data {
-                if cell_value.is_empty() {
+                let sheet_data = self.skip_to_header(sheet_data);
+                let Some(sheet) = self.sheet_context(file_info, &sheet_name, &sheet_data)? else {
+                    missing_sheets.push(sheet_name.clone());
                     continue;
-                }
+                };
 
-                let (phones, id_cards, bank_cards, names) = extractor.extract(&cell_value);
+                let stats_before = extractor.get_stats();
+                let results_before = all_results.len();
+                let mut sheet_rows = 0usize;
+                for (row_number, row) in sheet_data.iter_data_rows() {
+                    if self.control.wait_if_paused() {
+                        bail!("处理已取消");
+                    }
+                    sheet_rows += 1;
 
-                if !phones.is_empty() || !id_cards.is_empty() || !bank_cards.is_empty() || !names.is_empty() {
-                    let (context_before, context_after) = sheet_data
-                        .get_context(row_index, self.config.context_lines as usize);
+                    if row.get(sheet.col_index).is_none_or(String::is_empty) {
+                        continue;
+                    }
+                    all_results.extend(self.extract_row(&extractor, &sheet, row_number, row));
 
-                    let mut result = ExtractResult::new(
-                        &file_info.file_name,
-                        sheet_name,
-                        (row_index + 1) as u32,
-                    );
+                    rows_processed += 1;
+                    // 定期更新进度
+                    if rows_processed >= update_interval {
+                        if let Some(cb) = progress_callback {
+                            cb(rows_processed, &file_info.file_name);
+                        }
+                        self.report_results(&all_results, results_reported);
+                        rows_processed = 0;
+                    }
+                }
 
-                    result.source_text = cell_value;
-                    result.context_before = context_before;
-                    result.context_after = context_after;
-                    result.phone_numbers = phones;
-                    result.id_cards = id_cards;
-                    result.bank_cards = bank_cards;
-                    result.names = names;
+                rows_scanned += sheet_rows;
+                sheet_stats.push((
+                    sheet_name,
+                    SheetStatistics {
+                        rows_scanned: sheet_rows,
+                        result_rows: all_results.len() - results_before,
+                        cells: extractor.get_stats().since(&stats_before),
+                    },
+                ));
+                self.report_results(&all_results, results_reported);
+            }
 
-                    all_results.push(result);
-                }
+            Ok(())
+        })?;
 
-                rows_processed += 1;
-                // 定期更新进度
-                if rows_processed >= update_interval {
-                    if let Some(cb) = progress_callback {
-                        cb(rows_processed, &file_info.file_name);
-                    }
-                    rows_processed = 0;
-                }
+        if !missing_sheets.is_empty() {
+            if missing_sheets.len() == sheet_count {
+                bail!("所有工作表都不包含列: {}", self.config.target_column);
             }
+            let warning = format!("工作表 {} 不包含列: {}", missing_sheets.join("、"), self.config.target_column);
+            tracing::warn!("{}: {}", file_info.file_name, warning);
+            self.warnings
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push((file_info.file_path.clone(), warning));
         }
 
+        let file_key = file_info.file_path.display().to_string();
+        self.rows_scanned.fetch_add(rows_scanned, Ordering::Relaxed);
+        self.sheet_stats.lock().unwrap_or_else(PoisonError::into_inner).extend(
+            sheet_stats
+                .into_iter()
+                .map(|(sheet_name, stats)| ((file_key.clone(), sheet_name), stats)),
+        );
+        self.file_stats
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(file_key, extractor.get_stats());
+
         // 处理剩余的行
-        if rows_processed > 0 {
-            if let Some(cb) = progress_callback {
-                cb(rows_processed, &file_info.file_name);
-            }
+        if rows_processed > 0
+            && let Some(cb) = progress_callback
+        {
+            cb(rows_processed, &file_info.file_name);
         }
 
         Ok(all_results)
     }
 
-    fn find_target_column(&self, sheet_data: &crate::core::excel_reader::SheetData) -> Result<String> {
-        let columns = sheet_data.column_names();
-
-        for col in &columns {
-            if col.contains("消息内容") {
-                return Ok(col.clone());
-            }
+    /// 把 `reported` 之后的新结果交给结果回调；重试时前面的结果已报告过，不足 `reported` 条时不报告
+    fn report_results(&self, results: &[ExtractResult], reported: &mut usize) {
+        if results.len() <= *reported {
+            return;
         }
+        if let Some(callback) = &self.result_callback {
+            callback(&results[*reported..]);
+        }
+        *reported = results.len();
+    }
 
-        columns.first()
-            .cloned()
-            .ok_or_else(|| anyhow::anyhow!("工作表没有可用的列"))
+    /// 按配置自动识别表头时，丢弃表头之前的行
+    fn skip_to_header(&self, sheet_data: SheetData) -> SheetData {
+        let header_row = if self.config.auto_detect_header { sheet_data.detect_header_row() } else { 0 };
+        sheet_data.skip_rows(header_row)
     }
 
-    pub fn export_results(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
-        if results.is_empty() {
-            bail!("没有可导出的结果");
-        }
+    /// 定位工作表的目标列与附带列，目标列不存在时返回 `None`
+    fn sheet_context<'a>(
+        &self,
+        file_info: &'a FileInfo,
+        sheet_name: &'a str,
+        sheet_data: &'a SheetData,
+    ) -> Result<Option<SheetContext<'a>>> {
+        let target_column = if self.config.target_column.is_empty() {
+            self.find_target_column(sheet_data)?
+        } else {
+            self.config.target_column.clone()
+        };
 
-        let mut workbook = Workbook::new();
-        let worksheet = workbook.add_worksheet();
+        let Some(col_index) = sheet_data.get_column_index(&target_column) else {
+            return Ok(None);
+        };
 
-        self.write_headers(worksheet)?;
+        // 附带列在当前工作表中不存在时导出为空
+        let extra_columns = self
+            .config
+            .include_source_columns
+            .iter()
+            .map(|name| (name.clone(), sheet_data.get_column_index(name)))
+            .collect();
 
-        for (row_index, result) in results.iter().enumerate() {
-            let row = row_index as u32 + 1;
-            self.write_result_row(worksheet, row, result)?;
+        Ok(Some(SheetContext {
+            file_info,
+            sheet_name,
+            sheet_data,
+            col_index,
+            extra_columns,
+        }))
+    }
+
+    /// 提取一行目标列中的敏感信息，单元格为空或没有命中时返回 `None`
+    fn extract_row(
+        &self,
+        extractor: &InfoExtractor,
+        sheet: &SheetContext,
+        row_number: usize,
+        row: &[String],
+    ) -> Option<ExtractResult> {
+        let cell_value = row.get(sheet.col_index).map(String::as_str).unwrap_or_default();
+        if cell_value.is_empty() {
+            return None;
+        }
+
+        let text = apply_preprocessors(&self.preprocessors, cell_value);
+        let (phones, id_cards, bank_cards, names) = extractor.extract(&text);
+        let macau_ids = extractor.extract_macau_ids(&text);
+        let wechat_ids = extractor.extract_wechat_ids(&text);
+        let custom_matches = extractor.extract_custom_matches(&text);
+
+        if phones.is_empty()
+            && id_cards.is_empty()
+            && bank_cards.is_empty()
+            && names.is_empty()
+            && macau_ids.is_empty()
+            && wechat_ids.is_empty()
+            && custom_matches.is_empty()
+        {
+            return None;
         }
 
-        self.apply_formatting(worksheet)?;
+        // get_context 按数据行索引（不含表头）定位，Excel 行号需减去表头行和起始的 1
+        let (context_before, context_after) = sheet.sheet_data.get_context_with_separator(
+            row_number - 2,
+            self.config.context_before_lines as usize,
+            self.config.context_after_lines as usize,
+            &self.config.context_cell_sep,
+        );
 
-        workbook.save(output_path)
-            .with_context(|| format!("无法保存文件: {}", output_path.display()))?;
+        // 加回已用区域之前和表头之前被丢弃的行，得到工作表中的绝对行号和列号
+        let mut result = ExtractResult::new(
+            &sheet.file_info.file_name,
+            sheet.sheet_name,
+            (row_number + sheet.sheet_data.first_row) as u32,
+        );
 
-        tracing::info!("结果已导出到: {}", output_path.display());
-        Ok(())
+        result.source_path = sheet.file_info.file_path.display().to_string();
+        result.processing_timestamp = chrono::Local::now().to_rfc3339();
+        result.column_index = Some((sheet.col_index + sheet.sheet_data.first_col) as u32);
+        result.source_text = cell_value.to_string();
+        result.context_before = context_before;
+        result.context_after = context_after;
+        result.phone_numbers = phones;
+        result.id_cards = id_cards;
+        result.bank_cards = bank_cards;
+        result.names = names;
+        result.macau_ids = macau_ids;
+        result.wechat_ids = wechat_ids;
+        result.custom_matches = custom_matches;
+        let max_matches = self.config.max_matches_per_cell;
+        if max_matches > 0 && result.cap_matches(max_matches) {
+            tracing::warn!(
+                "{} / {} 第 {} 行命中过多，只保留前 {} 条，可能为异常数据",
+                result.source_file,
+                result.sheet_name,
+                result.row_number,
+                max_matches
+            );
+        }
+        result.extra_columns = sheet
+            .extra_columns
+            .iter()
+            .map(|(name, index)| {
+                let value = index.and_then(|i| row.get(i)).cloned().unwrap_or_default();
+                (name.clone(), value)
+            })
+            .collect();
+
+        Some(result)
     }
 
-    fn write_headers(&self, worksheet: &mut Worksheet) -> Result<()> {
-        const HEADERS: [&str; 14] = [
-            "源文件名", "工作表", "行号", "手机号", "手机号有效性",
-            "身份证号", "身份证有效性", "银行卡号", "银行卡有效性",
-            "姓名", "姓名有效性",
-            "源文本", "上文", "下文",
-        ];
+    /// 预览：只提取第一个未排除工作表的前 `sample_rows` 行（含表头），供配置时快速查看效果。
+    /// 预览不计入扫描行数等处理统计
+    pub fn preview_results(&self, file: &FileInfo, sample_rows: usize) -> Result<Vec<ExtractResult>> {
+        let mut reader = ExcelReader::open_with_password(&file.file_path, self.config.excel_password.as_deref())
+            .with_context(|| format!("无法打开文件: {}", file.file_name))?
+            .with_trim_empty_rows(self.config.trim_empty_rows)
+            .with_include_hidden(self.config.include_hidden)
+            .with_convert_date_cells(self.config.convert_date_cells);
 
-        let header_format = Format::new()
-            .set_bold()
-            .set_background_color("#4472C4")
-            .set_font_color(Color::White)
-            .set_border(FormatBorder::Thin);
+        let Some(sheet_name) = self.config.sheet_exclusion().filter(reader.sheet_names()).into_iter().next() else {
+            return Ok(Vec::new());
+        };
+        let mut sheet_data = self.skip_to_header(reader.read_sheet(&sheet_name)?);
+        sheet_data.rows.truncate(sample_rows);
 
-        for (col, header) in HEADERS.iter().enumerate() {
-            worksheet.write_string_with_format(0, col as u16, *header, &header_format)?;
-        }
+        let Some(sheet) = self.sheet_context(file, &sheet_name, &sheet_data)? else {
+            bail!("工作表 {} 不包含列: {}", sheet_name, self.config.target_column);
+        };
 
-        Ok(())
+        let extractor = InfoExtractor::new(self.config.clone());
+        Ok(sheet_data
+            .iter_data_rows()
+            .filter_map(|(row_number, row)| self.extract_row(&extractor, &sheet, row_number, row))
+            .collect())
     }
 
-    fn write_result_row(&self, worksheet: &mut Worksheet, row: u32, result: &ExtractResult) -> Result<()> {
-        let valid_format = Format::new().set_font_color(Color::Green);
-        let invalid_format = Format::new().set_font_color(Color::Red);
+    /// 抽样统计命中密度：读取第一个未排除工作表目标列的前 `sample_rows` 行，返回有命中的行占比。
+    /// 表头之前的行与正式处理一样跳过
+    ///
+    /// 抽样不调用姓名 API，避免导入时产生网络请求
+    pub fn sample_hit_density(&self, path: &Path, sample_rows: usize) -> Result<f64> {
+        let mut reader = ExcelReader::open_with_password(path, self.config.excel_password.as_deref())?
+            .with_trim_empty_rows(self.config.trim_empty_rows)
+            .with_include_hidden(self.config.include_hidden)
+            .with_convert_date_cells(self.config.convert_date_cells);
 
-        worksheet.write_string(row, 0, &result.source_file)?;
-        worksheet.write_string(row, 1, &result.sheet_name)?;
-        worksheet.write_number(row, 2, result.row_number)?;
-        worksheet.write_string(row, 3, result.phone_numbers_str())?;
+        let Some(sheet_name) = self.config.sheet_exclusion().filter(reader.sheet_names()).into_iter().next() else {
+            return Ok(0.0);
+        };
+        let sheet_data = self.skip_to_header(reader.read_sheet(&sheet_name)?);
 
-        Self::write_validity_cell(worksheet, row, 4, &result.phone_validity_str(), &valid_format, &invalid_format)?;
+        let target_column = if self.config.target_column.is_empty() {
+            self.find_target_column(&sheet_data)?
+        } else {
+            self.config.target_column.clone()
+        };
+        let Some(col_index) = sheet_data.get_column_index(&target_column) else {
+            return Ok(0.0);
+        };
 
-        worksheet.write_string(row, 5, result.id_cards_str())?;
-        Self::write_validity_cell(worksheet, row, 6, &result.id_card_validity_str(), &valid_format, &invalid_format)?;
+        let extractor = InfoExtractor::new(Config {
+            enable_name: false,
+            ..self.config.clone()
+        });
 
-        worksheet.write_string(row, 7, result.bank_cards_str())?;
-        Self::write_validity_cell(worksheet, row, 8, &result.bank_card_validity_str(), &valid_format, &invalid_format)?;
+        let mut sampled = 0usize;
+        let mut hits = 0usize;
+        for (_, row) in sheet_data.iter_data_rows().take(sample_rows) {
+            sampled += 1;
+            let cell_value = row.get(col_index).map(String::as_str).unwrap_or_default();
+            let text = apply_preprocessors(&self.preprocessors, cell_value);
+            let (phones, id_cards, bank_cards, _) = extractor.extract(&text);
+            if !phones.is_empty()
+                || !id_cards.is_empty()
+                || !bank_cards.is_empty()
+                || !extractor.extract_macau_ids(&text).is_empty()
+                || !extractor.extract_wechat_ids(&text).is_empty()
+                || !extractor.extract_custom_matches(&text).is_empty()
+            {
+                hits += 1;
+            }
+        }
 
-        worksheet.write_string(row, 9, result.names_str())?;
-        Self::write_validity_cell(worksheet, row, 10, &result.names_validity_str(), &valid_format, &invalid_format)?;
+        if sampled == 0 {
+            return Ok(0.0);
+        }
+        Ok(hits as f64 / sampled as f64)
+    }
 
-        worksheet.write_string(row, 11, &result.source_text)?;
-        worksheet.write_string(row, 12, result.context_before_str())?;
-        worksheet.write_string(row, 13, result.context_after_str())?;
+    /// 未指定目标列时按推荐优先级选列（与界面的列选择一致），没有推荐列时取第一列
+    fn find_target_column(&self, sheet_data: &SheetData) -> Result<String> {
+        let columns = sheet_data.column_names();
 
-        Ok(())
+        find_recommended_column(&columns)
+            .or(columns.first())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("工作表没有可用的列"))
     }
 
-    fn write_validity_cell(
-        worksheet: &mut Worksheet,
-        row: u32,
-        col: u16,
-        validity: &str,
-        valid_format: &Format,
-        invalid_format: &Format,
-    ) -> Result<()> {
-        if validity.contains("无效") {
-            worksheet.write_string_with_format(row, col, validity, invalid_format)?;
-        } else if !validity.is_empty() {
-            worksheet.write_string_with_format(row, col, validity, valid_format)?;
-        } else {
-            worksheet.write_string(row, col, "")?;
-        }
-        Ok(())
+    /// 导出为 xlsx，具体写入由 `XlsxExporter` 完成
+    pub fn export_results(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        XlsxExporter::new(self.config.clone()).export(results, output_path)
     }
 
-    fn apply_formatting(&self, worksheet: &mut Worksheet) -> Result<()> {
-        const COLUMN_WIDTHS: [(u16, f64); 14] = [
-            (0, 20.0), (1, 15.0), (2, 8.0), (3, 20.0), (4, 12.0),
-            (5, 22.0), (6, 12.0), (7, 22.0), (8, 12.0),
-            (9, 15.0), (10, 12.0),
-            (11, 50.0), (12, 30.0), (13, 30.0),
-        ];
+    /// 导出为 xlsx 字节，不落盘，便于直接作为 HTTP 响应返回
+    pub fn export_results_to_buffer(&self, results: &[ExtractResult]) -> Result<Vec<u8>> {
+        XlsxExporter::new(self.config.clone()).export_to_buffer(results)
+    }
 
-        for (col, width) in COLUMN_WIDTHS {
-            worksheet.set_column_width(col, width)?;
+    /// 导出为 Parquet 长表（每条命中一行）
+    pub fn export_results_parquet(&self, results: &[ExtractResult], output_path: &Path) -> Result<()> {
+        ParquetExporter::new(self.config.clone()).export(results, output_path)
+    }
+
+    /// 按源文件分组导出，每个源文件生成一个独立的结果文件
+    pub fn export_results_per_file(&self, results: &[ExtractResult], output_dir: &Path) -> Result<Vec<PathBuf>> {
+        if results.is_empty() {
+            bail!("没有可导出的结果");
         }
 
-        worksheet.set_freeze_panes(1, 0)?;
-        worksheet.autofilter(0, 0, 0, 13)?;
+        std::fs::create_dir_all(output_dir)
+            .with_context(|| format!("无法创建输出目录: {}", output_dir.display()))?;
 
-        Ok(())
+        let mut groups: BTreeMap<&str, Vec<ExtractResult>> = BTreeMap::new();
+        for result in results {
+            groups.entry(&result.source_file).or_default().push(result.clone());
+        }
+
+        let mut output_paths = Vec::with_capacity(groups.len());
+
+        for (source_file, group) in groups {
+            let stem = Path::new(source_file)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| source_file.to_string());
+            let base_name = format!("{}_结果", sanitize_filename(&stem));
+
+            let output_path = self.resolve_output_path(output_dir, &base_name);
+            self.export_results(&group, &output_path)?;
+            output_paths.push(output_path);
+        }
+
+        Ok(output_paths)
+    }
+
+    fn resolve_output_path(&self, output_dir: &Path, base_name: &str) -> PathBuf {
+        let path = output_dir.join(format!("{}.xlsx", base_name));
+
+        match self.config.export_conflict_strategy {
+            ExportConflictStrategy::Overwrite => path,
+            ExportConflictStrategy::AddSuffix => {
+                let mut candidate = path;
+                let mut index = 1;
+                while candidate.exists() {
+                    candidate = output_dir.join(format!("{}({}).xlsx", base_name, index));
+                    index += 1;
+                }
+                candidate
+            }
+        }
     }
 
     pub fn generate_statistics(&self, results: &[ExtractResult], elapsed_secs: f64) -> ProcessingStatistics {
         ProcessingStatistics {
-            total_results: results.len(),
-            total_phones: results.iter().map(|r| r.phone_numbers.len()).sum(),
-            valid_phones: results.iter().flat_map(|r| &r.phone_numbers).filter(|m| m.is_valid).count(),
-            total_id_cards: results.iter().map(|r| r.id_cards.len()).sum(),
-            valid_id_cards: results.iter().flat_map(|r| &r.id_cards).filter(|m| m.is_valid).count(),
-            total_bank_cards: results.iter().map(|r| r.bank_cards.len()).sum(),
-            valid_bank_cards: results.iter().flat_map(|r| &r.bank_cards).filter(|m| m.is_valid).count(),
-            total_names: results.iter().map(|r| r.names.len()).sum(),
-            valid_names: results.iter().flat_map(|r| &r.names).filter(|m| m.is_valid).count(),
-            elapsed_secs,
+            rows_scanned: self.rows_scanned.load(Ordering::Relaxed),
+            file_stats: self.file_stats.lock().unwrap_or_else(PoisonError::into_inner).clone(),
+            per_sheet: self.sheet_stats.lock().unwrap_or_else(PoisonError::into_inner).clone(),
+            ..ProcessingStatistics::from_results(results, elapsed_secs)
         }
     }
 }
 
-#[derive(Debug, Clone, Default)]
+/// 捕获单个文件处理中的 panic，转换为该文件的错误，避免影响其余文件
+fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .unwrap_or_else(|payload| Err(anyhow::anyhow!("处理时发生异常: {}", panic_message(&*payload))))
+}
+
+/// 提取 panic 携带的消息文本
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "未知错误".to_string()
+    }
+}
+
+/// 单个工作表的处理统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SheetStatistics {
+    /// 扫描的数据行数
+    pub rows_scanned: usize,
+    /// 含敏感信息的行数
+    pub result_rows: usize,
+    /// 单元格提取统计
+    pub cells: ExtractionStats,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct ProcessingStatistics {
     pub total_results: usize,
     pub total_phones: usize,
@@ -301,20 +791,265 @@ pub struct ProcessingStatistics {
     pub valid_bank_cards: usize,
     pub total_names: usize,
     pub valid_names: usize,
+    pub total_macau_ids: usize,
+    pub valid_macau_ids: usize,
+    pub total_wechat_ids: usize,
+    pub valid_wechat_ids: usize,
+    /// 扫描的数据行数（含目标列为空的行）
+    pub rows_scanned: usize,
     pub elapsed_secs: f64,
+    pub pivot: PivotStatistics,
+    /// 按文件路径的单元格提取统计
+    pub file_stats: BTreeMap<String, ExtractionStats>,
+    /// 按（文件路径, 工作表名）的统计；元组键无法作为 JSON 对象的键，不参与序列化
+    #[serde(skip)]
+    pub per_sheet: BTreeMap<(String, String), SheetStatistics>,
 }
 
 impl ProcessingStatistics {
+    /// 只按提取结果计算的统计，扫描行数和逐文件统计需由 `Processor` 补充
+    pub fn from_results(results: &[ExtractResult], elapsed_secs: f64) -> Self {
+        Self {
+            total_results: results.len(),
+            total_phones: results.iter().map(|r| r.phone_numbers.len()).sum(),
+            valid_phones: results.iter().flat_map(|r| &r.phone_numbers).filter(|m| m.is_valid).count(),
+            total_id_cards: results.iter().map(|r| r.id_cards.len()).sum(),
+            valid_id_cards: results.iter().flat_map(|r| &r.id_cards).filter(|m| m.is_valid).count(),
+            total_bank_cards: results.iter().map(|r| r.bank_cards.len()).sum(),
+            valid_bank_cards: results.iter().flat_map(|r| &r.bank_cards).filter(|m| m.is_valid).count(),
+            total_names: results.iter().map(|r| r.names.len()).sum(),
+            valid_names: results.iter().flat_map(|r| &r.names).filter(|m| m.is_valid).count(),
+            total_macau_ids: results.iter().map(|r| r.macau_ids.len()).sum(),
+            valid_macau_ids: results.iter().flat_map(|r| &r.macau_ids).filter(|m| m.is_valid).count(),
+            total_wechat_ids: results.iter().map(|r| r.wechat_ids.len()).sum(),
+            valid_wechat_ids: results.iter().flat_map(|r| &r.wechat_ids).filter(|m| m.is_valid).count(),
+            elapsed_secs,
+            pivot: PivotStatistics::from_results(results),
+            ..Default::default()
+        }
+    }
+
+    /// 累加另一批结果的计数和透视分组，用于处理中按增量汇总；耗时和逐文件统计不变
+    pub fn merge(&mut self, other: &Self) {
+        self.total_results += other.total_results;
+        self.total_phones += other.total_phones;
+        self.valid_phones += other.valid_phones;
+        self.total_id_cards += other.total_id_cards;
+        self.valid_id_cards += other.valid_id_cards;
+        self.total_bank_cards += other.total_bank_cards;
+        self.valid_bank_cards += other.valid_bank_cards;
+        self.total_names += other.total_names;
+        self.valid_names += other.valid_names;
+        self.total_macau_ids += other.total_macau_ids;
+        self.valid_macau_ids += other.valid_macau_ids;
+        self.total_wechat_ids += other.total_wechat_ids;
+        self.valid_wechat_ids += other.valid_wechat_ids;
+        self.rows_scanned += other.rows_scanned;
+        for (mine, theirs) in [
+            (&mut self.pivot.id_cards_by_province, &other.pivot.id_cards_by_province),
+            (&mut self.pivot.phones_by_carrier, &other.pivot.phones_by_carrier),
+        ] {
+            for (group, count) in theirs {
+                *mine.entry(group.clone()).or_default() += count;
+            }
+        }
+    }
+
+    /// 按文件路径分组的工作表统计，文件按路径、工作表按名称排序
+    pub fn per_sheet_by_file(&self) -> Vec<(&str, Vec<(&str, &SheetStatistics)>)> {
+        let mut groups: Vec<(&str, Vec<(&str, &SheetStatistics)>)> = Vec::new();
+        for ((file_name, sheet_name), stats) in &self.per_sheet {
+            match groups.last_mut() {
+                Some((last, sheets)) if *last == file_name.as_str() => sheets.push((sheet_name, stats)),
+                _ => groups.push((file_name, vec![(sheet_name, stats)])),
+            }
+        }
+        groups
+    }
+
     pub fn total_sensitive_info(&self) -> usize {
-        self.total_phones + self.total_id_cards + self.total_bank_cards + self.total_names
+        self.total_phones
+            + self.total_id_cards
+            + self.total_bank_cards
+            + self.total_names
+            + self.total_macau_ids
+            + self.total_wechat_ids
+    }
+
+    /// 含敏感信息的行占扫描行数的百分比，未扫描任何行时为 0
+    pub fn extraction_rate(&self) -> f64 {
+        if self.rows_scanned == 0 {
+            return 0.0;
+        }
+        (self.total_results as f64 / self.rows_scanned as f64 * 100.0).min(100.0)
+    }
+
+    /// 按类型的（名称, 总数, 有效数），最后一行为合计
+    fn summary_counts(&self) -> [(&'static str, usize, usize); 7] {
+        let rows = [
+            ("手机号", self.total_phones, self.valid_phones),
+            ("身份证号", self.total_id_cards, self.valid_id_cards),
+            ("银行卡号", self.total_bank_cards, self.valid_bank_cards),
+            ("姓名", self.total_names, self.valid_names),
+            ("澳门身份证号", self.total_macau_ids, self.valid_macau_ids),
+            ("微信号", self.total_wechat_ids, self.valid_wechat_ids),
+        ];
+        let total_valid = rows.iter().map(|(_, _, valid)| valid).sum();
+        let [phones, id_cards, bank_cards, names, macau_ids, wechat_ids] = rows;
+        [
+            phones,
+            id_cards,
+            bank_cards,
+            names,
+            macau_ids,
+            wechat_ids,
+            ("合计", self.total_sensitive_info(), total_valid),
+        ]
+    }
+
+    /// 各导出格式共用的汇总表，每行为 `[类型, 总数, 有效]`，最后一行为合计
+    pub fn summary_table(&self) -> Vec<[String; 3]> {
+        self.summary_counts()
+            .into_iter()
+            .map(|(label, total, valid)| [label.to_string(), total.to_string(), valid.to_string()])
+            .collect()
+    }
+
+    /// 汇总表的 Markdown 管道表格形式
+    pub fn summary_as_markdown_table(&self) -> String {
+        let mut lines = vec!["| 类型 | 总数 | 有效 |".to_string(), "| --- | ---: | ---: |".to_string()];
+        lines.extend(
+            self.summary_table()
+                .into_iter()
+                .map(|[label, total, valid]| format!("| {} | {} | {} |", label, total, valid)),
+        );
+        lines.join("\n")
+    }
+
+    /// 纯文本统计报告：概要数字加按类型分有效/无效的表格
+    pub fn to_report_string(&self) -> String {
+        let [types @ .., total] = self.summary_counts();
+
+        let mut lines = vec![
+            format!("扫描行数  {}", format_large_number(self.rows_scanned)),
+            format!("命中行数  {}（提取率 {:.1}%）", format_large_number(self.total_results), self.extraction_rate()),
+            format!("敏感信息  {}", format_large_number(self.total_sensitive_info())),
+            format!("耗时      {}", format_elapsed_time(self.elapsed_secs)),
+            report_border('┌', '┬', '┐'),
+            report_row(["类型", "总数", "有效", "无效"].map(String::from)),
+            report_border('├', '┼', '┤'),
+        ];
+        for row in types {
+            lines.push(report_counts_row(row));
+        }
+        lines.push(report_border('├', '┼', '┤'));
+        lines.push(report_counts_row(total));
+        lines.push(report_border('└', '┴', '┘'));
+
+        for (title, _, counts) in self.pivot.tables() {
+            let groups: Vec<String> = counts.iter().map(|(name, count)| format!("{} {}", name, count)).collect();
+            lines.push(format!("{}：{}", title, groups.join("，")));
+        }
+        lines.join("\n")
+    }
+
+    pub fn to_json_string(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// 透视分组计数，只统计有效号码
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PivotStatistics {
+    /// 身份证号按省份计数
+    pub id_cards_by_province: BTreeMap<String, usize>,
+    /// 手机号按运营商计数，未知号段计入 "未知"
+    pub phones_by_carrier: BTreeMap<String, usize>,
+}
+
+impl PivotStatistics {
+    pub fn from_results(results: &[ExtractResult]) -> Self {
+        let mut pivot = Self::default();
+
+        for id_card in results.iter().flat_map(|r| &r.id_cards).filter(|m| m.is_valid) {
+            if let Some(profile) = IdCardProfile::parse_now(&id_card.value) {
+                *pivot.id_cards_by_province.entry(profile.province.to_string()).or_default() += 1;
+            }
+        }
+
+        for phone in results.iter().flat_map(|r| &r.phone_numbers).filter(|m| m.is_valid) {
+            let carrier = Validator::phone_carrier(&phone.value).unwrap_or("未知");
+            *pivot.phones_by_carrier.entry(carrier.to_string()).or_default() += 1;
+        }
+
+        pivot
+    }
+
+    /// 各分组小表，跳过没有数据的小表
+    pub fn tables(&self) -> Vec<PivotTable<'_>> {
+        [
+            ("身份证号按省份", "省份", &self.id_cards_by_province),
+            ("手机号按运营商", "运营商", &self.phones_by_carrier),
+        ]
+        .into_iter()
+        .filter(|(_, _, counts)| !counts.is_empty())
+        .map(|(title, group, counts)| {
+            let mut rows: Vec<(&str, usize)> = counts.iter().map(|(k, v)| (k.as_str(), *v)).collect();
+            rows.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+            (title, group, rows)
+        })
+        .collect()
     }
 }
 
+/// 报告表格各列的显示宽度（不含两侧空格）
+const REPORT_COLUMN_WIDTHS: [usize; 4] = [12, 8, 8, 8];
+
+fn report_border(left: char, middle: char, right: char) -> String {
+    let segments: Vec<String> = REPORT_COLUMN_WIDTHS
+        .iter()
+        .map(|width| "─".repeat(width + 2))
+        .collect();
+    format!("{}{}{}", left, segments.join(&middle.to_string()), right)
+}
+
+/// 汇总表一行的（名称, 总数, 有效数）补上无效数后排版
+fn report_counts_row((label, total, valid): (&str, usize, usize)) -> String {
+    report_row([label.to_string(), total.to_string(), valid.to_string(), (total - valid).to_string()])
+}
+
+/// 表格行：首列左对齐，数字列右对齐；中文按两个字符宽度计算
+fn report_row(cells: [String; 4]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .zip(REPORT_COLUMN_WIDTHS)
+        .enumerate()
+        .map(|(index, (cell, width))| {
+            let padding = " ".repeat(width.saturating_sub(display_width(cell)));
+            if index == 0 {
+                format!(" {}{} ", cell, padding)
+            } else {
+                format!(" {}{} ", padding, cell)
+            }
+        })
+        .collect();
+    format!("│{}│", cells.join("│"))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars().map(|c| if c.is_ascii() { 1 } else { 2 }).sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::core::ExcelInfo;
+    use crate::models::{MatchInfo, PatternType};
+    use crate::test_support::scratch_dir;
+    use rust_xlsxwriter::Workbook;
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_processing_statistics() {
         let mut stats = ProcessingStatistics::default();
         stats.total_results = 10;
@@ -329,4 +1064,809 @@ mod tests {
 
         assert_eq!(stats.total_sensitive_info(), 36);
     }
+
+    #[test]
+    fn test_summary_table() {
+        let stats = ProcessingStatistics {
+            total_phones: 20,
+            valid_phones: 18,
+            total_bank_cards: 3,
+            valid_bank_cards: 2,
+            ..Default::default()
+        };
+
+        let table = stats.summary_table();
+        let labels: Vec<&str> = table.iter().map(|[label, _, _]| label.as_str()).collect();
+        assert_eq!(labels, ["手机号", "身份证号", "银行卡号", "姓名", "澳门身份证号", "微信号", "合计"]);
+        assert_eq!(table[0], ["手机号", "20", "18"].map(String::from));
+        assert_eq!(table[6], ["合计", "23", "20"].map(String::from));
+
+        let markdown = stats.summary_as_markdown_table();
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), table.len() + 2);
+        assert_eq!(lines[0], "| 类型 | 总数 | 有效 |");
+        assert!(lines[1].split('|').filter(|c| !c.is_empty()).all(|c| c.trim().trim_matches(':').chars().all(|ch| ch == '-')));
+        assert_eq!(lines[3], "| 身份证号 | 0 | 0 |");
+        assert!(lines.iter().all(|line| line.starts_with('|') && line.ends_with('|') && line.matches('|').count() == 4));
+    }
+
+    #[test]
+    fn test_report_string() {
+        let stats = ProcessingStatistics {
+            total_results: 10,
+            total_phones: 20,
+            valid_phones: 18,
+            total_id_cards: 5,
+            valid_id_cards: 5,
+            total_bank_cards: 3,
+            valid_bank_cards: 2,
+            rows_scanned: 40,
+            elapsed_secs: 1.5,
+            ..Default::default()
+        };
+
+        let report = stats.to_report_string();
+        assert!(!report.is_empty());
+        assert!(report.contains("扫描行数  40"));
+        assert!(report.contains("提取率 25.0%"));
+        assert!(report.contains("耗时      1.50秒"));
+        assert!(report.contains("│ 手机号       │       20 │       18 │        2 │"));
+        assert!(report.contains("│ 澳门身份证号 │        0 │        0 │        0 │"));
+        assert!(report.contains("│ 合计         │       28 │       25 │        3 │"));
+
+        // 表格各行显示宽度一致
+        let widths: Vec<usize> = report
+            .lines()
+            .filter(|line| line.starts_with(['┌', '│', '├', '└']))
+            .map(display_width_box)
+            .collect();
+        assert!(widths.windows(2).all(|w| w[0] == w[1]));
+
+        let json: serde_json::Value = serde_json::from_str(&stats.to_json_string().unwrap()).unwrap();
+        assert_eq!(json["total_phones"], 20);
+        assert_eq!(json["rows_scanned"], 40);
+    }
+
+    #[test]
+    fn test_pivot_statistics() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.id_cards = vec![
+            MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build(),
+            MatchInfo::builder("11010519491231002X", PatternType::IdCard).valid(true).build(),
+            MatchInfo::builder("440105199003072031", PatternType::IdCard).valid(false).build(),
+        ];
+        result.phone_numbers = vec![
+            MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build(),
+            MatchInfo::builder("18912345678", PatternType::Phone).valid(true).build(),
+            MatchInfo::builder("14012345678", PatternType::Phone).valid(true).build(),
+        ];
+
+        let pivot = PivotStatistics::from_results(&[result]);
+        assert_eq!(pivot.id_cards_by_province.get("北京"), Some(&2));
+        assert_eq!(pivot.id_cards_by_province.len(), 1);
+        assert_eq!(pivot.phones_by_carrier.get("中国移动"), Some(&1));
+        assert_eq!(pivot.phones_by_carrier.get("未知"), Some(&1));
+
+        let tables = pivot.tables();
+        assert_eq!(tables[0].0, "身份证号按省份");
+        assert_eq!(tables[1].2.len(), 3);
+        assert!(PivotStatistics::default().tables().is_empty());
+
+        let stats = ProcessingStatistics {
+            pivot,
+            ..Default::default()
+        };
+        assert!(stats.to_report_string().contains("身份证号按省份：北京 2"));
+    }
+
+    /// 制表符在终端中占一格
+    fn display_width_box(line: &str) -> usize {
+        line.chars().map(|c| if c.is_ascii() || ('─'..='╿').contains(&c) { 1 } else { 2 }).sum()
+    }
+
+    #[test]
+    fn test_rows_scanned_and_extraction_rate() {
+        let dir = scratch_dir();
+        let path = dir.path().join("rows_scanned.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = ["消息内容", "电话13907315286", "你好", "", "卡号4111111111111111"];
+        for (row, value) in rows.iter().enumerate() {
+            worksheet.write_string(row as u32, 0, *value).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let mut file_info = FileInfo::from_path(path.clone());
+        file_info.row_count = 4;
+
+        let processor = Processor::new(Config::default());
+        let (results, elapsed) = processor.process_files_parallel(&[file_info], |_, _| {});
+        let results: Vec<ExtractResult> = results.into_iter().flat_map(|(_, _, r)| r.unwrap()).collect();
+        let stats = processor.generate_statistics(&results, elapsed);
+
+        assert_eq!(stats.rows_scanned, 4);
+        assert_eq!(stats.total_results, 2);
+        assert_eq!(results[0].source_path, path.display().to_string());
+        assert!(stats.rows_scanned >= stats.total_results);
+        assert!((0.0..=100.0).contains(&stats.extraction_rate()));
+        assert_eq!(stats.extraction_rate(), 50.0);
+
+        assert_eq!(ProcessingStatistics::default().extraction_rate(), 0.0);
+    }
+
+    #[test]
+    fn test_target_column_follows_recommendation_priority() {
+        let dir = scratch_dir();
+        let path = dir.path().join("recommended.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        for (col, column) in [["姓名", "张三13812345678"], ["备注", "电话13907315286"]].iter().enumerate() {
+            for (row, value) in column.iter().enumerate() {
+                worksheet.write_string(row as u32, col as u16, *value).unwrap();
+            }
+        }
+        workbook.save(&path).unwrap();
+
+        // 未指定目标列时与界面推荐一致，选"备注"而不是第一列
+        let processor = Processor::new(Config {
+            target_column: String::new(),
+            ..Default::default()
+        });
+        let (results, _) = processor.process_files_sequential(&[FileInfo::from_path(path.clone())], |_, _| {});
+        let results = results.into_iter().next().unwrap().2.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source_text, "电话13907315286");
+    }
+
+    #[test]
+    fn test_pause_resume_and_cancel() {
+        let dir = scratch_dir();
+        let path = dir.path().join("pause.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "消息内容").unwrap();
+        worksheet.write_string(1, 0, "电话13907315286").unwrap();
+        workbook.save(&path).unwrap();
+
+        let mut file_info = FileInfo::from_path(path.clone());
+        file_info.row_count = 1;
+
+        // 暂停时处理线程阻塞，恢复后继续完成
+        let control = Arc::new(ProcessingControl::default());
+        control.pause();
+        let processor = Arc::new(Processor::new(Config::default()).with_control(Arc::clone(&control)));
+        let worker = {
+            let processor = Arc::clone(&processor);
+            let files = vec![file_info.clone()];
+            std::thread::spawn(move || processor.process_files_parallel(&files, |_, _| {}))
+        };
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        assert!(!worker.is_finished());
+        assert_eq!(processor.rows_scanned.load(Ordering::Relaxed), 0);
+
+        control.resume();
+        let (results, _) = worker.join().unwrap();
+        assert_eq!(results[0].2.as_ref().unwrap().len(), 1);
+
+        // 暂停中取消会唤醒处理线程并返回错误
+        let control = Arc::new(ProcessingControl::default());
+        control.pause();
+        let processor = Processor::new(Config::default()).with_control(Arc::clone(&control));
+        let worker = std::thread::spawn(move || processor.process_files_parallel(&[file_info], |_, _| {}));
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        control.cancel();
+        let (results, _) = worker.join().unwrap();
+        assert!(results[0].2.is_err());
+        assert!(control.wait_if_paused());
+    }
+
+    #[test]
+    fn test_pipelined_sheets_keep_order_and_context() {
+        let dir = scratch_dir();
+        let path = dir.path().join("pipeline.xlsx");
+
+        // 表头带工作表名前缀时只有第一页含目标列，否则三页表头相同
+        let save_workbook = |prefix_header: bool| {
+            let mut workbook = Workbook::new();
+            for sheet in ["第一页", "第二页", "第三页"] {
+                let worksheet = workbook.add_worksheet().set_name(sheet).unwrap();
+                let header = if prefix_header { format!("{}消息内容", sheet) } else { "消息内容".to_string() };
+                worksheet.write_string(0, 0, header).unwrap();
+                for (row, value) in ["上一行", "电话13907315286", "下一行"].iter().enumerate() {
+                    worksheet.write_string(row as u32 + 1, 0, format!("{}{}", sheet, value)).unwrap();
+                }
+            }
+            workbook.save(&path).unwrap();
+        };
+        save_workbook(true);
+
+        let config = Config {
+            context_before_lines: 1,
+            context_after_lines: 1,
+            target_column: "第一页消息内容".to_string(),
+            ..Default::default()
+        };
+        let processor = Processor::new(config);
+        let (results, _) = processor.process_files_sequential(&[FileInfo::from_path(path.clone())], |_, _| {});
+        let results: Vec<ExtractResult> = results.into_iter().flat_map(|(_, _, r)| r.unwrap()).collect();
+
+        // 只有第一页含目标列
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].context_before, vec!["第一页上一行"]);
+        assert_eq!(results[0].context_after, vec!["第一页下一行"]);
+
+        save_workbook(false);
+        let processor = Processor::new(Config {
+            context_before_lines: 1,
+            context_after_lines: 1,
+            ..Default::default()
+        });
+        let (results, _) = processor.process_files_sequential(&[FileInfo::from_path(path.clone())], |_, _| {});
+        let results: Vec<ExtractResult> = results.into_iter().flat_map(|(_, _, r)| r.unwrap()).collect();
+
+        let sheets: Vec<&str> = results.iter().map(|r| r.sheet_name.as_str()).collect();
+        assert_eq!(sheets, ["第一页", "第二页", "第三页"]);
+        for result in &results {
+            assert_eq!(result.context_before, vec![format!("{}上一行", result.sheet_name)]);
+            assert_eq!(result.context_after, vec![format!("{}下一行", result.sheet_name)]);
+        }
+        assert_eq!(processor.rows_scanned.load(Ordering::SeqCst), 9);
+    }
+
+    #[test]
+    fn test_text_preprocessors_applied_before_extraction() {
+        use crate::core::{StripHtmlPreprocessor, TrimWhitespacePreprocessor};
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("preprocess.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = ["消息内容", "<p>电话139<b>0731</b>5286</p>"];
+        for (row, value) in rows.iter().enumerate() {
+            worksheet.write_string(row as u32, 0, *value).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let files = [FileInfo::from_path(path.clone())];
+        let (results, _) = Processor::new(Config::default()).process_files_sequential(&files, |_, _| {});
+        assert!(results[0].2.as_ref().unwrap().is_empty());
+
+        let processor = Processor::new(Config::default())
+            .with_text_preprocessors(vec![Box::new(StripHtmlPreprocessor), Box::new(TrimWhitespacePreprocessor)]);
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let results = results[0].2.as_ref().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].phone_numbers[0].value, "13907315286");
+        // 导出的源文本仍为原文
+        assert_eq!(results[0].source_text, rows[1]);
+    }
+
+    #[test]
+    fn test_preview_results_reads_only_sample_rows() {
+        let dir = scratch_dir();
+        let path = dir.path().join("preview.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(0, 0, "消息内容").unwrap();
+        for row in 1..=30 {
+            worksheet.write_string(row, 0, format!("电话1390731{:04}", row)).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let processor = Processor::new(Config::default());
+        let file_info = FileInfo::from_path(path.clone());
+        let results = processor.preview_results(&file_info, 20).unwrap();
+        assert_eq!(results.len(), 19);
+        assert_eq!(results.last().unwrap().row_number, 20);
+
+        // 预览后正式处理，扫描行数只包含正式处理的行
+        let (results, _) = processor.process_files_sequential(&[file_info], |_, _| {});
+        let results = results.into_iter().next().unwrap().2.unwrap();
+        assert_eq!(processor.generate_statistics(&results, 0.0).rows_scanned, 30);
+    }
+
+    #[test]
+    fn test_missing_target_column_warns_or_fails() {
+        let dir = scratch_dir();
+        let path = dir.path().join("missing_column.xlsx");
+
+        let mut workbook = Workbook::new();
+        for (sheet, header) in [("客服", "消息内容"), ("附表", "备注"), ("销售", "消息内容"), ("说明", "备注")] {
+            let worksheet = workbook.add_worksheet().set_name(sheet).unwrap();
+            worksheet.write_string(0, 0, header).unwrap();
+            worksheet.write_string(1, 0, "电话13907315286").unwrap();
+        }
+        workbook.save(&path).unwrap();
+        let files = [FileInfo::from_path(path.clone())];
+
+        let processor = Processor::new(Config {
+            target_column: "消息内容".to_string(),
+            ..Default::default()
+        });
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        assert_eq!(results[0].2.as_ref().unwrap().len(), 2);
+        let warnings = processor.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].1, "工作表 附表、说明 不包含列: 消息内容");
+        assert!(processor.take_warnings().is_empty());
+
+        let stats = processor.generate_statistics(results[0].2.as_ref().unwrap(), 0.0);
+        let file_stats = stats.file_stats[&path.display().to_string()];
+        assert_eq!((file_stats.non_empty_cells, file_stats.cells_with_matches), (2, 2));
+
+        let processor = Processor::new(Config {
+            target_column: "聊天记录".to_string(),
+            ..Default::default()
+        });
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let error = results[0].2.as_ref().unwrap_err();
+        assert_eq!(error.to_string(), "所有工作表都不包含列: 聊天记录");
+        assert!(processor.take_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_excluded_sheets_skipped() {
+        let dir = scratch_dir();
+        let path = dir.path().join("excluded_sheets.xlsx");
+
+        let mut workbook = Workbook::new();
+        for (sheet, phone) in [("目录", "13907315286"), ("客服", "18612345678")] {
+            let worksheet = workbook.add_worksheet().set_name(sheet).unwrap();
+            worksheet.write_string(0, 0, "消息内容").unwrap();
+            worksheet.write_string(1, 0, format!("电话{}", phone)).unwrap();
+        }
+        workbook.save(&path).unwrap();
+        let files = [FileInfo::from_path(path.clone())];
+
+        let config = Config {
+            excluded_sheet_names: vec!["目录".to_string()],
+            ..Default::default()
+        };
+        let info = ExcelInfo::from_file_with_password(&path, None, &config.sheet_exclusion()).unwrap();
+        assert_eq!(info.sheet_names, ["客服"]);
+
+        let (results, _) = Processor::new(config).process_files_sequential(&files, |_, _| {});
+        let results = results[0].2.as_ref().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].sheet_name, "客服");
+        assert_eq!(results[0].phone_numbers[0].value, "18612345678");
+
+        let processor = Processor::new(Config {
+            excluded_sheet_name_patterns: vec!["*".to_string()],
+            ..Default::default()
+        });
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        assert_eq!(results[0].2.as_ref().unwrap_err().to_string(), "所有工作表都已被排除");
+    }
+
+    #[test]
+    fn test_per_sheet_statistics() {
+        let dir = scratch_dir();
+        let path = dir.path().join("per_sheet_stats.xlsx");
+
+        let mut workbook = Workbook::new();
+        let sheets = [
+            ("客服", vec!["电话13907315286", "你好"]),
+            ("销售", vec!["13907315286和18612345678", "无号码", "卡号4111111111111111"]),
+        ];
+        for (sheet, rows) in &sheets {
+            let worksheet = workbook.add_worksheet().set_name(*sheet).unwrap();
+            worksheet.write_string(0, 0, "消息内容").unwrap();
+            for (row, value) in rows.iter().enumerate() {
+                worksheet.write_string(row as u32 + 1, 0, *value).unwrap();
+            }
+        }
+        workbook.save(&path).unwrap();
+        let files = [FileInfo::from_path(path.clone())];
+
+        let processor = Processor::new(Config::default());
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let stats = processor.generate_statistics(results[0].2.as_ref().unwrap(), 0.0);
+
+        let file_key = path.display().to_string();
+        let service = stats.per_sheet[&(file_key.clone(), "客服".to_string())];
+        assert_eq!((service.rows_scanned, service.result_rows), (2, 1));
+        assert_eq!((service.cells.non_empty_cells, service.cells.total_matches), (2, 1));
+        let sales = stats.per_sheet[&(file_key.clone(), "销售".to_string())];
+        assert_eq!((sales.rows_scanned, sales.result_rows), (3, 2));
+        assert_eq!((sales.cells.cells_with_matches, sales.cells.total_matches), (2, 3));
+
+        let grouped = stats.per_sheet_by_file();
+        assert_eq!(grouped.len(), 1);
+        let sheet_names: Vec<&str> = grouped[0].1.iter().map(|(name, _)| *name).collect();
+        assert_eq!(sheet_names, ["客服", "销售"]);
+    }
+
+    #[test]
+    fn test_same_file_name_in_different_dirs_counted_apart() {
+        let root = scratch_dir();
+        let files: Vec<FileInfo> = [("一部", "电话13907315286"), ("二部", "18612345678和13800138000")]
+            .into_iter()
+            .map(|(dir, value)| {
+                let dir = root.path().join(dir);
+                std::fs::create_dir_all(&dir).unwrap();
+                let path = dir.join("聊天记录.xlsx");
+                let mut workbook = Workbook::new();
+                let worksheet = workbook.add_worksheet();
+                worksheet.write_string(0, 0, "消息内容").unwrap();
+                worksheet.write_string(1, 0, value).unwrap();
+                workbook.save(&path).unwrap();
+                FileInfo::from_path(path)
+            })
+            .collect();
+
+        let processor = Processor::new(Config::default());
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let results: Vec<ExtractResult> = results.into_iter().flat_map(|(_, _, r)| r.unwrap()).collect();
+        let stats = processor.generate_statistics(&results, 0.0);
+
+        assert_eq!(stats.file_stats.len(), 2);
+        assert_eq!(stats.file_stats[&files[0].file_path.display().to_string()].total_matches, 1);
+        assert_eq!(stats.file_stats[&files[1].file_path.display().to_string()].total_matches, 2);
+        assert_eq!(stats.per_sheet_by_file().len(), 2);
+    }
+
+    #[test]
+    fn test_result_callback_reports_every_result() {
+        let dir = scratch_dir();
+        let path = dir.path().join("result_callback.xlsx");
+
+        let mut workbook = Workbook::new();
+        for (sheet, value) in [("一月", "电话13907315286"), ("二月", "18612345678和身份证11010519491231002X")] {
+            let worksheet = workbook.add_worksheet().set_name(sheet).unwrap();
+            worksheet.write_string(0, 0, "消息内容").unwrap();
+            worksheet.write_string(1, 0, value).unwrap();
+        }
+        workbook.save(&path).unwrap();
+        let files = [FileInfo::from_path(path.clone())];
+
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let batches_for_callback = Arc::clone(&batches);
+        let processor = Processor::new(Config::default()).with_result_callback(move |results| {
+            batches_for_callback.lock().unwrap().push(ProcessingStatistics::from_results(results, 0.0));
+        });
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let results = results[0].2.as_ref().unwrap();
+
+        // 每个工作表结束时报告一批
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.len(), 2);
+        let mut live = ProcessingStatistics::default();
+        for batch in batches.iter() {
+            live.merge(batch);
+        }
+        let expected = ProcessingStatistics::from_results(results, 0.0);
+        assert_eq!(live.total_results, 2);
+        assert_eq!(live.total_sensitive_info(), expected.total_sensitive_info());
+        assert_eq!(live.valid_id_cards, expected.valid_id_cards);
+        assert_eq!(live.pivot, expected.pivot);
+    }
+
+    #[test]
+    fn test_cell_ref_is_absolute_when_range_is_offset() {
+        let dir = scratch_dir();
+        let path = dir.path().join("offset_range.xlsx");
+
+        // 已用区域从 C3 开始
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        worksheet.write_string(2, 2, "序号").unwrap();
+        worksheet.write_string(2, 3, "消息内容").unwrap();
+        worksheet.write_string(3, 2, "1").unwrap();
+        worksheet.write_string(3, 3, "你好").unwrap();
+        worksheet.write_string(4, 2, "2").unwrap();
+        worksheet.write_string(4, 3, "电话13907315286").unwrap();
+        workbook.save(&path).unwrap();
+
+        let processor = Processor::new(Config::default());
+        let results = processor.process_file(&FileInfo::from_path(path.clone())).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].row_number, 5);
+        assert_eq!(results[0].cell_ref().as_deref(), Some("Sheet1!D5"));
+    }
+
+    #[test]
+    fn test_row_number_kept_after_trimming_leading_rows() {
+        let dir = scratch_dir();
+        let path = dir.path().join("leading_blank.csv");
+        std::fs::write(&path, ",\n,\n序号,消息内容\n1,你好\n2,电话13907315286\n").unwrap();
+
+        let processor = Processor::new(Config::default());
+        let results = processor.process_file(&FileInfo::from_path(path.clone())).unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].row_number, 5);
+    }
+
+    #[test]
+    fn test_include_source_columns_exported() {
+        let dir = scratch_dir();
+        let source = dir.path().join("extra_columns.xlsx");
+        let output = dir.path().join("extra_columns_out.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = [
+            ["姓名", "发送时间", "消息内容"],
+            ["张三", "2024-01-01 10:00", "电话13907315286"],
+            ["李四", "2024-01-02 11:00", "你好"],
+        ];
+        for (row, values) in rows.iter().enumerate() {
+            for (col, value) in values.iter().enumerate() {
+                worksheet.write_string(row as u32, col as u16, *value).unwrap();
+            }
+        }
+        workbook.save(&source).unwrap();
+
+        let config = Config {
+            include_source_columns: vec!["发送时间".to_string(), "姓名".to_string(), "不存在".to_string()],
+            ..Default::default()
+        };
+        let processor = Processor::new(config);
+        let (results, _) = processor.process_files_parallel(&[FileInfo::from_path(source.clone())], |_, _| {});
+        let results: Vec<ExtractResult> = results.into_iter().flat_map(|(_, _, r)| r.unwrap()).collect();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].extra_columns["姓名"], "张三");
+        assert_eq!(results[0].extra_columns["不存在"], "");
+        assert_eq!(results[0].cell_ref().as_deref(), Some("Sheet1!C2"));
+
+        let timestamp = chrono::DateTime::parse_from_rfc3339(&results[0].processing_timestamp).unwrap();
+        let age = chrono::Local::now().fixed_offset() - timestamp;
+        assert!((0..5).contains(&age.num_seconds()), "处理时间偏差过大: {}", age);
+
+        processor.export_results(&results, &output).unwrap();
+        let sheet = ExcelReader::open(&output).unwrap().read_sheet("Sheet1").unwrap();
+        assert_eq!(sheet.rows[0][0], "处理时间");
+        assert_eq!(sheet.rows[1][0], results[0].processing_timestamp);
+        assert_eq!(sheet.rows[0][4], "单元格位置");
+        assert_eq!(sheet.rows[1][4], "Sheet1!C2");
+        assert_eq!(sheet.rows[0][18], "源文件路径");
+        assert_eq!(sheet.rows[0][19..], ["发送时间", "姓名", "不存在"]);
+        assert_eq!(sheet.rows[1][19..], ["2024-01-01 10:00", "张三", ""]);
+    }
+
+    #[test]
+    fn test_sequential_matches_parallel() {
+        let dir = scratch_dir();
+
+        let contents: [&[&str]; 2] = [
+            &["消息内容", "电话13907315286", "你好", "卡号4111111111111111"],
+            &["消息内容", "身份证11010519491231002X", "13812345678 和 13907315286"],
+        ];
+        let files: Vec<FileInfo> = contents
+            .iter()
+            .enumerate()
+            .map(|(index, rows)| {
+                let path = dir.path().join(format!("file_{}.xlsx", index));
+                let mut workbook = Workbook::new();
+                let worksheet = workbook.add_worksheet();
+                for (row, value) in rows.iter().enumerate() {
+                    worksheet.write_string(row as u32, 0, *value).unwrap();
+                }
+                workbook.save(&path).unwrap();
+
+                let mut file_info = FileInfo::from_path(path);
+                file_info.row_count = rows.len() as u32 - 1;
+                file_info
+            })
+            .collect();
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let progress_clone = Arc::clone(&progress);
+        let sequential = Processor::new(Config::default());
+        let (sequential_results, _) = sequential
+            .process_files_sequential(&files, move |_, value| progress_clone.lock().unwrap().push(value));
+        let parallel = Processor::new(Config::default());
+        let (parallel_results, _) = parallel.process_files_parallel(&files, |_, _| {});
+
+        // 处理时间两次运行必然不同，比较前清空
+        let to_json = |results: Vec<FileProcessingResult>| {
+            results
+                .into_iter()
+                .map(|(name, _, result)| {
+                    let mut result = result.unwrap();
+                    result.iter_mut().for_each(|r| r.processing_timestamp.clear());
+                    (name, serde_json::to_string(&result).unwrap())
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(to_json(sequential_results), to_json(parallel_results));
+        assert_eq!(sequential.rows_scanned.load(Ordering::SeqCst), parallel.rows_scanned.load(Ordering::SeqCst));
+
+        let progress = progress.lock().unwrap();
+        assert_eq!(progress.first(), Some(&0));
+        assert_eq!(progress.last(), Some(&100));
+        assert!(progress.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn test_export_results_per_file() {
+        let dir = scratch_dir();
+        let output_dir = dir.path().join("per_file");
+
+        let mut first = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        first.source_text = "13812345678".to_string();
+        let second = ExtractResult::new("b.xlsx", "Sheet1", 3);
+        let third = ExtractResult::new("a.xlsx", "Sheet1", 5);
+        let results = vec![first, second, third];
+
+        let processor = Processor::new(Config::default());
+        let paths = processor.export_results_per_file(&results, &output_dir).unwrap();
+        assert_eq!(paths, vec![output_dir.join("a_结果.xlsx"), output_dir.join("b_结果.xlsx")]);
+        assert!(paths.iter().all(|p| p.exists()));
+
+        // 默认策略：已存在时追加序号
+        let paths = processor.export_results_per_file(&results, &output_dir).unwrap();
+        assert_eq!(paths[0], output_dir.join("a_结果(1).xlsx"));
+
+        let processor = Processor::new(Config {
+            export_conflict_strategy: ExportConflictStrategy::Overwrite,
+            ..Default::default()
+        });
+        let paths = processor.export_results_per_file(&results, &output_dir).unwrap();
+        assert_eq!(paths[0], output_dir.join("a_结果.xlsx"));
+    }
+
+    #[test]
+    fn test_sample_hit_density() {
+        let dir = scratch_dir();
+        let path = dir.path().join("density.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = ["消息内容", "电话13907315286", "你好", "卡号4111111111111111", "再见", "稍后联系"];
+        for (row, value) in rows.iter().enumerate() {
+            worksheet.write_string(row as u32, 0, *value).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        let processor = Processor::new(Config::default());
+        let density = processor.sample_hit_density(&path, 4).unwrap();
+        assert!((density - 0.5).abs() < f64::EPSILON);
+
+        let density = processor.sample_hit_density(&path, 100).unwrap();
+        assert!((density - 0.4).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_sample_hit_density_skips_rows_before_header() {
+        let dir = scratch_dir();
+        let path = dir.path().join("density_header.xlsx");
+
+        let mut workbook = Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let rows = ["20240101", "消息内容", "电话13907315286", "你好"];
+        for (row, value) in rows.iter().enumerate() {
+            worksheet.write_string(row as u32, 0, *value).unwrap();
+        }
+        workbook.save(&path).unwrap();
+
+        // 第 0 行不是表头，与正式处理一样从第 1 行的表头开始抽样
+        let processor = Processor::new(Config {
+            auto_detect_header: true,
+            ..Default::default()
+        });
+        let density = processor.sample_hit_density(&path, 100).unwrap();
+        assert!((density - 0.5).abs() < f64::EPSILON);
+
+        // 不识别表头时第 0 行即表头，其中没有目标列
+        let processor = Processor::new(Config::default());
+        assert_eq!(processor.sample_hit_density(&path, 100).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_retry_failed_file_once() {
+        let dir = scratch_dir();
+        let late = dir.path().join("late.xlsx");
+        let missing = dir.path().join("missing.xlsx");
+        let files = [FileInfo::from_path(late.clone()), FileInfo::from_path(missing)];
+
+        // 首次处理时文件还不存在，重试前写入
+        let writer = {
+            let staging = dir.path().join("staging.xlsx");
+            let late = late.clone();
+            thread::spawn(move || {
+                thread::sleep(RETRY_DELAY / 5);
+                let mut workbook = Workbook::new();
+                let worksheet = workbook.add_worksheet();
+                worksheet.write_string(0, 0, "消息内容").unwrap();
+                worksheet.write_string(1, 0, "电话13907315286").unwrap();
+                workbook.save(&staging).unwrap();
+                std::fs::rename(&staging, &late).unwrap();
+            })
+        };
+
+        let processor = Processor::new(Config {
+            retry_failed_files: true,
+            ..Default::default()
+        });
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        writer.join().unwrap();
+
+        assert_eq!(results[0].2.as_ref().unwrap().len(), 1);
+        let warnings = processor.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].0, late);
+        assert!(warnings[0].1.starts_with("首次处理失败，已自动重试成功"));
+        assert!(results[1].2.as_ref().unwrap_err().to_string().ends_with("（已自动重试一次）"));
+
+        // 未开启时不重试
+        let processor = Processor::new(Config::default());
+        let (results, _) = processor.process_files_sequential(&files[1..], |_, _| {});
+        assert!(!results[0].2.as_ref().unwrap_err().to_string().contains("重试"));
+    }
+
+    #[test]
+    fn test_retry_does_not_double_count() {
+        let dir = scratch_dir();
+        let path = dir.path().join("retry_counts.xlsx");
+
+        let mut workbook = Workbook::new();
+        for (sheet, value) in [("一月", "电话13907315286"), ("二月", "18612345678")] {
+            let worksheet = workbook.add_worksheet().set_name(sheet).unwrap();
+            worksheet.write_string(0, 0, "消息内容").unwrap();
+            worksheet.write_string(1, 0, value).unwrap();
+            worksheet.write_string(2, 0, "你好").unwrap();
+        }
+        workbook.save(&path).unwrap();
+        let files = [FileInfo::from_path(path.clone())];
+
+        // 首次处理报告完第一个工作表后失败，重试成功
+        let calls = Arc::new(AtomicUsize::new(0));
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let (calls_for_callback, reported_for_callback) = (Arc::clone(&calls), Arc::clone(&reported));
+        let processor = Processor::new(Config {
+            retry_failed_files: true,
+            ..Default::default()
+        })
+        .with_result_callback(move |results| {
+            if calls_for_callback.fetch_add(1, Ordering::SeqCst) == 1 {
+                panic!("报告第二个工作表时失败");
+            }
+            reported_for_callback.lock().unwrap().extend(results.iter().map(|r| r.sheet_name.clone()));
+        });
+        let (results, _) = processor.process_files_sequential(&files, |_, _| {});
+        let stats = processor.generate_statistics(results[0].2.as_ref().unwrap(), 0.0);
+
+        assert_eq!(processor.take_warnings().len(), 1);
+        assert_eq!(results[0].2.as_ref().unwrap().len(), 2);
+        assert_eq!(*reported.lock().unwrap(), ["一月", "二月"]);
+        assert_eq!(stats.rows_scanned, 4);
+        assert_eq!(stats.per_sheet.len(), 2);
+        assert!(stats.per_sheet.values().all(|sheet| sheet.rows_scanned == 2));
+        assert_eq!(stats.file_stats[&path.display().to_string()].total_matches, 2);
+    }
+
+    #[test]
+    fn test_single_thread_runs_sequentially() {
+        let processor = |force_sequential, max_threads| {
+            Processor::new(Config {
+                force_sequential,
+                max_threads,
+                ..Default::default()
+            })
+        };
+        assert!(processor(false, Some(1)).runs_sequentially());
+        assert!(processor(true, Some(4)).runs_sequentially());
+        assert!(!processor(false, Some(4)).runs_sequentially());
+        assert!(!processor(false, None).runs_sequentially());
+    }
+
+    #[test]
+    fn test_catch_panic_converts_to_error() {
+        let result: Result<()> = catch_panic(|| panic!("单元格解析崩溃"));
+        let message = result.unwrap_err().to_string();
+        assert!(message.contains("单元格解析崩溃"));
+
+        let result = catch_panic(|| Ok(42));
+        assert_eq!(result.unwrap(), 42);
+    }
 }
@@ -0,0 +1,176 @@
+//! xlsx 工作表的行列隐藏属性
+//!
+//! calamine 读取单元格时不区分隐藏与否，也不提供行列的隐藏属性，
+//! 这里直接读取 zip 包中的 `xl/workbook.xml`、关系文件和工作表 XML。
+
+use anyhow::{Context, Result};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader as XmlReader;
+use std::collections::{HashMap, HashSet};
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+const WORKBOOK_PART: &str = "xl/workbook.xml";
+const WORKBOOK_RELS_PART: &str = "xl/_rels/workbook.xml.rels";
+
+/// 工作表中被隐藏的行和列（0 起始的绝对行列号）
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct HiddenCells {
+    rows: HashSet<u32>,
+    columns: HashSet<u32>,
+}
+
+impl HiddenCells {
+    /// 从 xlsx 包中读取指定工作表的隐藏行列
+    pub fn read<R: Read + Seek>(package: R, sheet_name: &str) -> Result<Self> {
+        let mut archive = ZipArchive::new(package).context("不是有效的 xlsx 包")?;
+        let part = sheet_part(&mut archive, sheet_name)?;
+        let xml = read_part(&mut archive, &part)?;
+        Self::from_sheet_xml(&xml)
+    }
+
+    /// 解析工作表 XML 中的 `<row hidden="1">` 和 `<col min max hidden="1">`
+    pub fn from_sheet_xml(xml: &[u8]) -> Result<Self> {
+        let mut hidden = Self::default();
+        // 省略 r 属性的行按顺序紧接上一行
+        let mut next_row = 0u32;
+
+        let mut reader = XmlReader::from_reader(xml);
+        loop {
+            match reader.read_event().context("工作表 XML 格式错误")? {
+                Event::Start(element) | Event::Empty(element) => match element.local_name().as_ref() {
+                    b"row" => {
+                        let attributes = xml_attributes(&element);
+                        let row = attributes
+                            .get("r")
+                            .and_then(|r| r.parse::<u32>().ok())
+                            .map_or(next_row, |r| r.saturating_sub(1));
+                        if is_true(attributes.get("hidden")) {
+                            hidden.rows.insert(row);
+                        }
+                        next_row = row + 1;
+                    }
+                    b"col" => {
+                        let attributes = xml_attributes(&element);
+                        let bound = |name: &str| attributes.get(name).and_then(|v| v.parse::<u32>().ok());
+                        if is_true(attributes.get("hidden"))
+                            && let (Some(min), Some(max)) = (bound("min"), bound("max"))
+                        {
+                            hidden.columns.extend(min.saturating_sub(1)..max);
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(hidden)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty() && self.columns.is_empty()
+    }
+
+    /// 单元格所在的行或列被隐藏
+    pub fn is_hidden(&self, row: u32, column: u32) -> bool {
+        self.rows.contains(&row) || self.columns.contains(&column)
+    }
+}
+
+/// 工作表名对应的 XML 部件路径，如 `xl/worksheets/sheet1.xml`
+fn sheet_part<R: Read + Seek>(archive: &mut ZipArchive<R>, sheet_name: &str) -> Result<String> {
+    let mut relation_id = None;
+    let workbook = read_part(archive, WORKBOOK_PART)?;
+    let mut reader = XmlReader::from_reader(workbook.as_slice());
+    loop {
+        match reader.read_event().context("workbook.xml 格式错误")? {
+            Event::Start(element) | Event::Empty(element) if element.local_name().as_ref() == b"sheet" => {
+                let mut attributes = xml_attributes(&element);
+                if attributes.get("name").map(String::as_str) == Some(sheet_name) {
+                    relation_id = attributes.remove("id");
+                    break;
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    let relation_id = relation_id.with_context(|| format!("workbook.xml 中没有工作表: {}", sheet_name))?;
+
+    let relations = read_part(archive, WORKBOOK_RELS_PART)?;
+    let mut reader = XmlReader::from_reader(relations.as_slice());
+    loop {
+        match reader.read_event().context("workbook.xml.rels 格式错误")? {
+            Event::Start(element) | Event::Empty(element) if element.local_name().as_ref() == b"Relationship" => {
+                let attributes = xml_attributes(&element);
+                if attributes.get("Id") == Some(&relation_id)
+                    && let Some(target) = attributes.get("Target")
+                {
+                    // 目标路径可能是包内绝对路径，也可能相对于 xl/ 目录
+                    return Ok(match target.strip_prefix('/') {
+                        Some(absolute) => absolute.to_string(),
+                        None => format!("xl/{}", target),
+                    });
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    anyhow::bail!("找不到工作表 {} 的 XML 部件", sheet_name)
+}
+
+fn read_part<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("xlsx 包中缺少 {}", name))?;
+    let mut content = Vec::new();
+    entry.read_to_end(&mut content)?;
+    Ok(content)
+}
+
+/// 元素属性（按本地名，值已还原实体）
+fn xml_attributes(element: &BytesStart) -> HashMap<String, String> {
+    element
+        .attributes()
+        .flatten()
+        .map(|attribute| {
+            let key = String::from_utf8_lossy(attribute.key.local_name().as_ref()).to_string();
+            let raw = String::from_utf8_lossy(&attribute.value);
+            let value = quick_xml::escape::unescape(&raw).map_or_else(|_| raw.to_string(), |v| v.to_string());
+            (key, value)
+        })
+        .collect()
+}
+
+fn is_true(value: Option<&String>) -> bool {
+    matches!(value.map(String::as_str), Some("1" | "true"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hidden_rows_and_columns() {
+        let xml = br#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+            <cols><col min="2" max="3" width="0" hidden="1"/><col min="5" max="5" width="9"/></cols>
+            <sheetData>
+                <row r="1"><c r="A1"><v>1</v></c></row>
+                <row r="4" hidden="1"><c r="A4"><v>2</v></c></row>
+                <row hidden="true"/>
+            </sheetData>
+        </worksheet>"#;
+
+        let hidden = HiddenCells::from_sheet_xml(xml).unwrap();
+        assert!(hidden.is_hidden(3, 0));
+        assert!(hidden.is_hidden(4, 0));
+        assert!(hidden.is_hidden(0, 1));
+        assert!(hidden.is_hidden(0, 2));
+        assert!(!hidden.is_hidden(0, 0));
+        assert!(!hidden.is_hidden(0, 4));
+        assert!(HiddenCells::from_sheet_xml(b"<worksheet/>").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+//! 银行卡号校验
+//!
+//! 去掉空格、连字符等分隔符后：
+//! 1. 按 BIN（卡号前几位）识别卡组织，长度须符合该卡组织的规定（如 Amex 15 位、银联 16-19 位），
+//!    无法识别卡组织时不超过 19 位；除 15 位 Amex 外还须不短于 `CardLengthPolicy` 的最短位数；
+//! 2. 全部为数字；
+//! 3. 通过 Luhn 校验：从右往左偶数位乘 2（大于 9 时减 9），各位之和为 10 的倍数。
+
+use super::ValidationError;
+use crate::models::CardLengthPolicy;
+use crate::utils::clean_digits;
+
+/// 按 BIN（卡号前几位）识别的卡组织
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CardNetwork {
+    AmericanExpress,
+    Visa,
+    Mastercard,
+    Jcb,
+    UnionPay,
+}
+
+impl CardNetwork {
+    /// 按卡号前缀识别，无法识别时为 `None`
+    fn from_bin(number: &str) -> Option<Self> {
+        let prefix = |len: usize| number.get(..len).and_then(|p| p.parse::<u32>().ok());
+
+        match (prefix(2)?, prefix(4)) {
+            (34 | 37, _) => Some(Self::AmericanExpress),
+            (40..=49, _) => Some(Self::Visa),
+            (51..=55, _) | (_, Some(2221..=2720)) => Some(Self::Mastercard),
+            (_, Some(3528..=3589)) => Some(Self::Jcb),
+            (62, _) => Some(Self::UnionPay),
+            _ => None,
+        }
+    }
+
+    fn allows_length(&self, len: usize) -> bool {
+        match self {
+            Self::AmericanExpress => len == 15,
+            Self::Visa => matches!(len, 13 | 16 | 19),
+            Self::Mastercard => len == 16,
+            Self::Jcb | Self::UnionPay => (16..=19).contains(&len),
+        }
+    }
+}
+
+pub fn validate(card_number: &str, policy: CardLengthPolicy) -> Result<(), ValidationError> {
+    let clean_number = clean_digits(card_number);
+
+    let len = clean_number.len();
+    let network = CardNetwork::from_bin(&clean_number);
+    let length_ok = (len >= policy.min_length() || network == Some(CardNetwork::AmericanExpress))
+        && match network {
+            Some(network) => network.allows_length(len),
+            None => len <= 19,
+        };
+    if !length_ok {
+        return Err(ValidationError::InvalidLength);
+    }
+
+    if !clean_number.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ValidationError::InvalidCharacters);
+    }
+
+    if !luhn_check(&clean_number) {
+        return Err(ValidationError::InvalidChecksum);
+    }
+
+    Ok(())
+}
+
+/// 15 位、34/37 开头的 American Express 卡号（按位数判断，不做校验）
+pub fn is_amex(card_number: &str) -> bool {
+    let clean_number = clean_digits(card_number);
+    clean_number.len() == 15 && CardNetwork::from_bin(&clean_number) == Some(CardNetwork::AmericanExpress)
+}
+
+fn luhn_check(number: &str) -> bool {
+    let digits: Vec<u32> = number
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .collect();
+
+    if digits.len() != number.len() {
+        return false;
+    }
+
+    let len = digits.len();
+    let mut sum: u32 = 0;
+
+    for (i, &digit) in digits.iter().enumerate().rev() {
+        let position_from_right = len - i;
+
+        if position_from_right.is_multiple_of(2) {
+            let doubled = digit * 2;
+            sum += if doubled > 9 { doubled - 9 } else { doubled };
+        } else {
+            sum += digit;
+        }
+    }
+
+    sum.is_multiple_of(10)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_network_from_bin() {
+        assert_eq!(CardNetwork::from_bin("378282246310005"), Some(CardNetwork::AmericanExpress));
+        assert!(is_amex("3714 496353 98431"));
+        assert!(!is_amex("4111111111111111"));
+        assert_eq!(CardNetwork::from_bin("2221000000000009"), Some(CardNetwork::Mastercard));
+        assert_eq!(CardNetwork::from_bin("3530111333300000"), Some(CardNetwork::Jcb));
+        assert_eq!(CardNetwork::from_bin("6225880123456789"), Some(CardNetwork::UnionPay));
+        assert_eq!(CardNetwork::from_bin("9000000000000009"), None);
+    }
+
+    #[test]
+    fn test_luhn_check() {
+        assert!(luhn_check("79927398713"));
+        assert!(luhn_check("4111111111111111"));
+        assert!(!luhn_check("79927398710"));
+    }
+}
@@ -0,0 +1,165 @@
+//! 18 位居民身份证号校验（GB 11643-1999）
+//!
+//! 前 17 位为数字，第 18 位为数字或 `X`，依次检查：
+//! 1. 前两位是有效的省级行政区划代码（可通过 [`validate_with_region_check`] 跳过）；
+//! 2. 第 7-14 位是 1900-2099 年间真实存在的出生日期（含闰年判断）；
+//! 3. 前 17 位按权重 `ID_WEIGHTS` 加权求和，模 11 的余数经 `ID_CHECK_CODES` 映射后等于第 18 位。
+
+use super::ValidationError;
+use crate::utils::{ID_CHECK_CODES, ID_WEIGHTS};
+
+/// 省级行政区划代码（身份证前两位）
+const PROVINCE_CODES: [u32; 34] = [
+    11, 12, 13, 14, 15, 21, 22, 23, 31, 32, 33, 34, 35, 36, 37, 41, 42, 43, 44, 45, 46, 50,
+    51, 52, 53, 54, 61, 62, 63, 64, 65, 71, 81, 82,
+];
+
+pub fn validate(id_card: &str) -> Result<(), ValidationError> {
+    validate_with_region_check(id_card, true)
+}
+
+/// `check_region` 为 false 时不检查地区码，前两位未分配的号码只要日期和校验码正确即有效
+pub fn validate_with_region_check(id_card: &str, check_region: bool) -> Result<(), ValidationError> {
+    if id_card.chars().count() != 18 {
+        return Err(ValidationError::InvalidLength);
+    }
+
+    let chars: Vec<char> = id_card.chars().collect();
+
+    // 检查前17位是否都是数字
+    if !chars.iter().take(17).all(|c| c.is_ascii_digit()) {
+        return Err(ValidationError::InvalidCharacters);
+    }
+
+    let last_char = chars[17];
+    if !last_char.is_ascii_digit() && last_char != 'X' && last_char != 'x' {
+        return Err(ValidationError::InvalidCharacters);
+    }
+
+    if check_region && !verify_region(&chars) {
+        return Err(ValidationError::InvalidRegion);
+    }
+
+    if !verify_birth_date(&chars) {
+        return Err(ValidationError::InvalidDate);
+    }
+
+    if !verify_checksum(&chars) {
+        return Err(ValidationError::InvalidChecksum);
+    }
+
+    Ok(())
+}
+
+fn verify_region(chars: &[char]) -> bool {
+    let province: String = chars[0..2].iter().collect();
+    province
+        .parse::<u32>()
+        .is_ok_and(|code| PROVINCE_CODES.contains(&code))
+}
+
+fn verify_checksum(chars: &[char]) -> bool {
+    let mut sum: i32 = 0;
+
+    for i in 0..17 {
+        let digit = match chars[i].to_digit(10) {
+            Some(d) => d as i32,
+            None => return false,
+        };
+        sum += digit * ID_WEIGHTS[i];
+    }
+
+    let remainder = (sum % 11) as usize;
+    let expected_check_code = ID_CHECK_CODES[remainder];
+
+    let last_char = chars[17].to_ascii_uppercase();
+    last_char == expected_check_code
+}
+
+fn verify_birth_date(chars: &[char]) -> bool {
+    let year_str: String = chars[6..10].iter().collect();
+    let month_str: String = chars[10..12].iter().collect();
+    let day_str: String = chars[12..14].iter().collect();
+
+    let year = match year_str.parse::<u32>() {
+        Ok(y) => y,
+        Err(_) => return false,
+    };
+    let month = match month_str.parse::<u32>() {
+        Ok(m) => m,
+        Err(_) => return false,
+    };
+    let day = match day_str.parse::<u32>() {
+        Ok(d) => d,
+        Err(_) => return false,
+    };
+
+    if !(1900..=2099).contains(&year) {
+        return false;
+    }
+
+    if !(1..=12).contains(&month) {
+        return false;
+    }
+
+    let days_in_month = days_in_month(year, month);
+    day >= 1 && day <= days_in_month
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            if (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400) {
+                29
+            } else {
+                28
+            }
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_id_card_detailed() {
+        assert_eq!(validate("110105199003072039"), Ok(()));
+        assert_eq!(validate("11010519900307"), Err(ValidationError::InvalidLength));
+        assert_eq!(validate("11010519900307203Y"), Err(ValidationError::InvalidCharacters));
+        assert_eq!(validate("1101051990030720A9"), Err(ValidationError::InvalidCharacters));
+        assert_eq!(validate("110105199003072038"), Err(ValidationError::InvalidChecksum));
+        assert_eq!(validate("110105199013072039"), Err(ValidationError::InvalidDate));
+        assert_eq!(validate("990105199003072039"), Err(ValidationError::InvalidRegion));
+    }
+
+    #[test]
+    fn test_region_checked_before_checksum() {
+        // 校验码正确但省级码不在白名单中的伪造号码
+        for id_card in ["000000199003072036", "910105199003072038", "830105199003072038"] {
+            assert_eq!(validate(id_card), Err(ValidationError::InvalidRegion), "{}", id_card);
+        }
+        assert_eq!(validate("810000199003072037"), Ok(()));
+    }
+
+    #[test]
+    fn test_region_check_can_be_skipped() {
+        for id_card in ["000000199003072036", "910105199003072038"] {
+            assert_eq!(validate_with_region_check(id_card, false), Ok(()), "{}", id_card);
+        }
+        // 跳过地区码后仍校验日期和校验码
+        assert_eq!(validate_with_region_check("990105199003072039", false), Err(ValidationError::InvalidChecksum));
+        assert_eq!(validate_with_region_check("000000199003072036", true), Err(ValidationError::InvalidRegion));
+    }
+
+    #[test]
+    fn test_days_in_month() {
+        assert_eq!(days_in_month(2020, 1), 31);
+        assert_eq!(days_in_month(2020, 2), 29);
+        assert_eq!(days_in_month(2021, 2), 28);
+        assert_eq!(days_in_month(2020, 4), 30);
+    }
+}
@@ -0,0 +1,22 @@
+//! 澳门居民身份证号校验
+//!
+//! 1/5/7 开头的 7 位数字加 1 位校验码，校验码可写在括号里（`1234567(5)`）。
+//! 前 7 位按权重 8..2 加权，加权和与校验码之和须为 9 的倍数。
+
+pub fn validate(id: &str) -> bool {
+    let digits: Vec<u32> = id.chars().filter_map(|c| c.to_digit(10)).collect();
+    let well_formed = match id.find('(') {
+        Some(open) => open == 7 && id.len() == 10 && id.ends_with(')'),
+        None => id.len() == 8,
+    };
+    if !well_formed || digits.len() != 8 || !matches!(digits[0], 1 | 5 | 7) {
+        return false;
+    }
+
+    let sum: u32 = digits[..7]
+        .iter()
+        .zip((2..=8).rev())
+        .map(|(digit, weight)| digit * weight)
+        .sum();
+    (sum + digits[7]).is_multiple_of(9)
+}
@@ -0,0 +1,276 @@
+//! 各类号码的校验
+//!
+//! 每种号码的校验算法在各自的子模块中实现并说明，对外统一通过 [`Validator`] 调用；
+//! 身份证加权系数、校验码表和 `clean_digits` 等与正则共用的常量和工具函数在 `utils::regex_patterns` 中。
+
+mod bank_card;
+mod id_card;
+mod macau_id;
+mod phone;
+mod suspicious;
+mod wechat_id;
+
+use crate::models::CardLengthPolicy;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// 号码校验失败原因（身份证号、手机号、银行卡号共用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Error)]
+#[allow(clippy::enum_variant_names)]
+pub enum ValidationError {
+    #[error("长度错误")]
+    InvalidLength,
+    #[error("包含非法字符")]
+    InvalidCharacters,
+    #[error("校验码错误")]
+    InvalidChecksum,
+    #[error("出生日期无效")]
+    InvalidDate,
+    #[error("地区码无效")]
+    InvalidRegion,
+    /// 手机号不以号段表中的有效前缀开头
+    #[error("号段无效")]
+    InvalidPrefix,
+}
+
+impl ValidationError {
+    pub const ALL: [ValidationError; 6] = [
+        ValidationError::InvalidLength,
+        ValidationError::InvalidCharacters,
+        ValidationError::InvalidChecksum,
+        ValidationError::InvalidDate,
+        ValidationError::InvalidRegion,
+        ValidationError::InvalidPrefix,
+    ];
+
+    /// 按显示文本（如 "校验码错误"）找回原因，用于读回导出的有效性列
+    pub fn from_reason(reason: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|e| e.to_string() == reason)
+    }
+}
+
+pub struct Validator;
+
+impl Validator {
+    pub fn validate_id_card(id_card: &str) -> bool {
+        Self::validate_id_card_detailed(id_card).is_ok()
+    }
+
+    pub fn validate_id_card_detailed(id_card: &str) -> Result<(), ValidationError> {
+        id_card::validate(id_card)
+    }
+
+    /// `check_region` 为 false 时不校验地区码，见 `Config::accept_unknown_id_region`
+    pub fn validate_id_card_with_region_check(id_card: &str, check_region: bool) -> Result<(), ValidationError> {
+        id_card::validate_with_region_check(id_card, check_region)
+    }
+
+    /// 按默认的 16-19 位策略校验，见 `validate_bank_card_with_policy`
+    pub fn validate_bank_card(card_number: &str) -> bool {
+        Self::validate_bank_card_with_policy(card_number, CardLengthPolicy::default())
+    }
+
+    /// 按卡组织的合法长度（如 Amex 15 位、银联 16-19 位）和 Luhn 校验；无法识别卡组织时要求不超过 19 位。
+    /// 短于策略最短位数的卡号（15 位 Amex 除外）一律无效，因此 13 位 Visa 卡号只在 `Extended13to19` 下有效
+    pub fn validate_bank_card_with_policy(card_number: &str, policy: CardLengthPolicy) -> bool {
+        Self::validate_bank_card_detailed(card_number, policy).is_ok()
+    }
+
+    pub fn validate_bank_card_detailed(card_number: &str, policy: CardLengthPolicy) -> Result<(), ValidationError> {
+        bank_card::validate(card_number, policy)
+    }
+
+    /// 15 位、34/37 开头的 American Express 卡号（按位数判断，不做校验）
+    pub fn is_amex_number(card_number: &str) -> bool {
+        bank_card::is_amex(card_number)
+    }
+
+    /// 澳门居民身份证号：1/5/7 开头的 7 位数字加 1 位校验码（可写作 `1234567(5)`）
+    pub fn validate_macau_id(id: &str) -> bool {
+        macau_id::validate(id)
+    }
+
+    /// 微信号：6-20 位，字母开头，由字母、数字、下划线和连字符组成
+    pub fn validate_wechat_id(id: &str) -> bool {
+        wechat_id::validate(id)
+    }
+
+    pub fn validate_phone(phone: &str) -> bool {
+        Self::validate_phone_detailed(phone).is_ok()
+    }
+
+    pub fn validate_phone_detailed(phone: &str) -> Result<(), ValidationError> {
+        phone::validate(phone)
+    }
+
+    /// 按号段表识别有效手机号所属运营商，无效号码或未知号段返回 `None`
+    pub fn phone_carrier(phone: &str) -> Option<&'static str> {
+        phone::carrier(phone)
+    }
+
+    /// 检测疑似占位/测试号码：存在长度不少于 `min_run` 的连续递增、
+    /// 连续递减、全相同或短周期重复的数字片段
+    pub fn is_suspicious_number(value: &str, min_run: usize) -> bool {
+        suspicious::is_suspicious(value, min_run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_id_card() {
+        // 110105199003072039 的校验码是正确的 (sum=190, 190%11=3, check_codes[3]='9')
+        assert!(Validator::validate_id_card("110105199003072039"));
+
+        // 无效的身份证号
+        assert!(!Validator::validate_id_card("11010519900307")); // 长度不足
+        assert!(!Validator::validate_id_card("110105199013072039")); // 无效月份
+        assert!(!Validator::validate_id_card("110105199003322039")); // 无效日期
+        assert!(!Validator::validate_id_card("11010519900307203Y")); // 无效校验码字符
+        assert!(!Validator::validate_id_card("11010519900307203X")); // 校验码错误 (应该是9)
+    }
+
+    #[test]
+    fn test_validate_bank_card() {
+        assert!(Validator::validate_bank_card("4111111111111111"));
+        assert!(Validator::validate_bank_card("5500000000000004"));
+        assert!(Validator::validate_bank_card("4111 1111 1111 1111"));
+
+        assert!(!Validator::validate_bank_card("6225880123456780"));
+        assert!(!Validator::validate_bank_card("622588012345678"));
+        assert!(!Validator::validate_bank_card("62258801234567890123"));
+    }
+
+    #[test]
+    fn test_phone_and_bank_card_detailed() {
+        assert_eq!(Validator::validate_phone_detailed("+86 139-0731-5286"), Ok(()));
+        assert_eq!(Validator::validate_phone_detailed("1390731528"), Err(ValidationError::InvalidLength));
+        assert_eq!(Validator::validate_phone_detailed("12812345678"), Err(ValidationError::InvalidPrefix));
+
+        let policy = CardLengthPolicy::default();
+        assert_eq!(Validator::validate_bank_card_detailed("4111 1111 1111 1111", policy), Ok(()));
+        assert_eq!(
+            Validator::validate_bank_card_detailed("4111111111111112", policy),
+            Err(ValidationError::InvalidChecksum)
+        );
+        assert_eq!(
+            Validator::validate_bank_card_detailed("4222222222222", policy),
+            Err(ValidationError::InvalidLength)
+        );
+
+        assert_eq!(ValidationError::from_reason("号段无效"), Some(ValidationError::InvalidPrefix));
+        assert_eq!(ValidationError::from_reason("未知"), None);
+    }
+
+    #[test]
+    fn test_card_length_policy() {
+        let standard = CardLengthPolicy::Standard16to19;
+        let extended = CardLengthPolicy::Extended13to19;
+
+        assert!(!Validator::validate_bank_card_with_policy("4222222222222", standard));
+        assert!(!Validator::validate_bank_card_with_policy("512345678901234", standard));
+        assert!(Validator::validate_bank_card_with_policy("4222222222222", extended));
+        assert!(!Validator::validate_bank_card_with_policy("4222222222223", extended));
+        // Visa 不允许 14 位，未知卡组织在扩展策略下也要满足 13 位以上
+        assert!(!Validator::validate_bank_card_with_policy("42222222222224", extended));
+        assert!(!Validator::validate_bank_card_with_policy("900000000001", extended));
+        // Amex 15 位不受策略影响
+        assert!(Validator::validate_bank_card_with_policy("378282246310005", standard));
+        assert!(Validator::validate_bank_card_with_policy("4111111111111111", extended));
+    }
+
+    #[test]
+    fn test_validate_bank_card_by_network() {
+        // American Express 15 位
+        assert!(Validator::validate_bank_card("378282246310005"));
+        assert!(Validator::validate_bank_card("3714 496353 98431"));
+        assert!(!Validator::validate_bank_card("378282246310004"));
+        // Amex 不接受 16 位，Mastercard 只接受 16 位
+        assert!(!Validator::validate_bank_card("3782822463100003"));
+        assert!(!Validator::validate_bank_card("5555555555554444000"));
+        assert!(Validator::validate_bank_card("5555555555554444"));
+        // 银联 19 位
+        assert!(Validator::validate_bank_card("6212345678901234569"));
+        // 未知卡组织沿用 16-19 位
+        assert!(Validator::validate_bank_card("9000000000000001"));
+        assert!(!Validator::validate_bank_card("900000000000001"));
+
+        assert!(Validator::is_amex_number("3714 496353 98431"));
+        assert!(!Validator::is_amex_number("4111111111111111"));
+    }
+
+    #[test]
+    fn test_validate_macau_id() {
+        assert!(Validator::validate_macau_id("12345675"));
+        assert!(Validator::validate_macau_id("1234567(5)"));
+        assert!(Validator::validate_macau_id("52152996"));
+        assert!(Validator::validate_macau_id("70000015"));
+
+        // 校验码错误
+        assert!(!Validator::validate_macau_id("12345674"));
+        assert!(!Validator::validate_macau_id("1234567(6)"));
+        assert!(!Validator::validate_macau_id("52152995"));
+        // 格式错误
+        assert!(!Validator::validate_macau_id("32152996"));
+        assert!(!Validator::validate_macau_id("1234567"));
+        assert!(!Validator::validate_macau_id("123456(7)5"));
+        assert!(!Validator::validate_macau_id("1234567(5"));
+    }
+
+    #[test]
+    fn test_validate_wechat_id() {
+        assert!(Validator::validate_wechat_id("johnny_123"));
+        assert!(Validator::validate_wechat_id("Lucy-2024"));
+        assert!(Validator::validate_wechat_id("abcdef"));
+        assert!(Validator::validate_wechat_id("a1234567890123456789"));
+
+        assert!(!Validator::validate_wechat_id("abcde"));
+        assert!(!Validator::validate_wechat_id("a12345678901234567890"));
+        assert!(!Validator::validate_wechat_id("1johnny"));
+        assert!(!Validator::validate_wechat_id("_johnny"));
+        assert!(!Validator::validate_wechat_id("john.ny"));
+        assert!(!Validator::validate_wechat_id("微信johnny"));
+    }
+
+    #[test]
+    fn test_phone_carrier() {
+        assert_eq!(Validator::phone_carrier("13812345678"), Some("中国移动"));
+        assert_eq!(Validator::phone_carrier("+86 186-1234-5678"), Some("中国联通"));
+        assert_eq!(Validator::phone_carrier("18912345678"), Some("中国电信"));
+        assert_eq!(Validator::phone_carrier("19212345678"), Some("中国广电"));
+        assert_eq!(Validator::phone_carrier("17012345678"), Some("虚拟运营商"));
+        assert_eq!(Validator::phone_carrier("14012345678"), None);
+        assert_eq!(Validator::phone_carrier("12812345678"), None);
+    }
+
+    #[test]
+    fn test_validate_phone() {
+        assert!(Validator::validate_phone("13812345678"));
+        assert!(Validator::validate_phone("138-1234-5678"));
+        assert!(Validator::validate_phone("15912345678"));
+        assert!(Validator::validate_phone("18612345678"));
+        assert!(Validator::validate_phone("+86 138-1234-5678"));
+        assert!(Validator::validate_phone("8613812345678"));
+        assert!(!Validator::validate_phone("8612812345678"));
+
+        assert!(!Validator::validate_phone("12812345678"));
+        assert!(!Validator::validate_phone("12345678"));
+        assert!(!Validator::validate_phone("23812345678"));
+    }
+
+    #[test]
+    fn test_is_suspicious_number() {
+        assert!(Validator::is_suspicious_number("1234567890", 6));
+        assert!(Validator::is_suspicious_number("1111111111", 6));
+        assert!(Validator::is_suspicious_number("9876543210", 6));
+        assert!(Validator::is_suspicious_number("1212121212", 6));
+        assert!(Validator::is_suspicious_number("138-1234-5678", 6));
+
+        assert!(!Validator::is_suspicious_number("13912876543", 7));
+        assert!(!Validator::is_suspicious_number("6225880137492615", 6));
+        assert!(!Validator::is_suspicious_number("110105199003072039", 6));
+        assert!(!Validator::is_suspicious_number("1234567890", 0));
+    }
+}
@@ -0,0 +1,37 @@
+//! 中国大陆手机号校验
+//!
+//! 去掉分隔符后为 11 位数字（带国家码 `86` 的 13 位号码去掉国家码后再判断），
+//! 且以号段表中的有效前缀开头。号段表默认内置，可用程序目录下的 `号段表.json` 覆盖，
+//! 运营商归属也按号段表查询。
+
+use super::ValidationError;
+use crate::core::phone_segments::PHONE_SEGMENTS;
+use crate::utils::clean_digits;
+
+pub fn validate(phone: &str) -> Result<(), ValidationError> {
+    let clean_number = clean_digits(phone);
+    // 带国家码 86 的号码按去掉国家码后的 11 位校验
+    let clean_number = match clean_number.strip_prefix("86") {
+        Some(national) if clean_number.len() == 13 => national.to_string(),
+        _ => clean_number,
+    };
+
+    if clean_number.len() != 11 {
+        return Err(ValidationError::InvalidLength);
+    }
+
+    // 有效前缀来自号段表（程序目录下的 `号段表.json`，缺失时用内置号段）
+    if !PHONE_SEGMENTS.has_valid_prefix(&clean_number) {
+        return Err(ValidationError::InvalidPrefix);
+    }
+
+    Ok(())
+}
+
+/// 按号段表识别有效手机号所属运营商，无效号码或未知号段返回 `None`
+pub fn carrier(phone: &str) -> Option<&'static str> {
+    validate(phone).ok()?;
+
+    let digits = clean_digits(phone);
+    PHONE_SEGMENTS.carrier(&digits[digits.len() - 11..])
+}
@@ -0,0 +1,48 @@
+//! 疑似占位/测试号码检测
+//!
+//! 只看号码中的数字：存在长度不少于 `min_run` 的连续递增、连续递减（按模 10，`90` 也算递增）、
+//! 全相同，或周期为 2、3 的重复片段（如 `121212`、`123123`）时视为疑似占位号码。
+
+use crate::utils::clean_digits;
+
+pub fn is_suspicious(value: &str, min_run: usize) -> bool {
+    if min_run < 2 {
+        return false;
+    }
+
+    let digits: Vec<u8> = clean_digits(value).bytes().map(|b| b - b'0').collect();
+    if digits.len() < min_run {
+        return false;
+    }
+
+    // 步长按模 10 计算，使 "1234567890" 也视为连续递增
+    for step in [0u8, 1, 9] {
+        let mut run = 1;
+        for pair in digits.windows(2) {
+            if (pair[1] + 10 - pair[0]) % 10 == step {
+                run += 1;
+                if run >= min_run {
+                    return true;
+                }
+            } else {
+                run = 1;
+            }
+        }
+    }
+
+    for period in 2..=3 {
+        let mut run = period;
+        for i in period..digits.len() {
+            if digits[i] == digits[i - period] {
+                run += 1;
+                if run >= min_run {
+                    return true;
+                }
+            } else {
+                run = period;
+            }
+        }
+    }
+
+    false
+}
@@ -0,0 +1,10 @@
+//! 微信号格式校验
+//!
+//! 微信号为 6-20 位，以字母开头，其余字符只能是字母、数字、下划线或连字符。
+//! 没有校验码，只能检查格式，无法确认账号真实存在。
+
+pub fn validate(id: &str) -> bool {
+    (6..=20).contains(&id.len())
+        && id.starts_with(|c: char| c.is_ascii_alphabetic())
+        && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
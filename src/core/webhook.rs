@@ -0,0 +1,140 @@
+//! 处理完成后向消息机器人推送统计摘要
+//!
+//! 企业微信和钉钉的群机器人都接受 `msgtype` 为 `text` 的 JSON 消息，但 `@` 提醒的字段不同：
+//! 企业微信用 `text.mentioned_mobile_list`，钉钉用 `at.atMobiles` 并要求正文中带 `@手机号`。
+//! 这里两组字段都写入，同一个 payload 可发给任一种机器人；`summary` 中是便于程序处理的原始数字。
+
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::ProcessingStatistics;
+use crate::models::Config;
+use crate::utils::format_elapsed_time;
+
+/// 提醒所有人时的写法
+const MENTION_ALL: &str = "@all";
+
+/// 推送的消息体；`mentions` 为要提醒的手机号，`@all` 表示提醒所有人
+pub fn build_payload(stats: &ProcessingStatistics, file_count: usize, mentions: &[String]) -> Value {
+    let mention_all = mentions.iter().any(|m| m == MENTION_ALL);
+    let mobiles: Vec<&str> = mentions
+        .iter()
+        .map(|m| m.trim())
+        .filter(|m| !m.is_empty() && *m != MENTION_ALL)
+        .collect();
+
+    let mut content = format!(
+        "敏感信息提取完成\n文件数：{}\n结果行数：{}\n手机号：{}\n身份证号：{}\n银行卡号：{}\n姓名：{}\n澳门身份证号：{}\n微信号：{}\n耗时：{}",
+        file_count,
+        stats.total_results,
+        stats.total_phones,
+        stats.total_id_cards,
+        stats.total_bank_cards,
+        stats.total_names,
+        stats.total_macau_ids,
+        stats.total_wechat_ids,
+        format_elapsed_time(stats.elapsed_secs),
+    );
+    if !mobiles.is_empty() {
+        content.push('\n');
+        content.push_str(&mobiles.iter().map(|m| format!("@{}", m)).collect::<Vec<_>>().join(" "));
+    }
+
+    let mut mentioned_mobile_list: Vec<&str> = mobiles.clone();
+    if mention_all {
+        mentioned_mobile_list.push(MENTION_ALL);
+    }
+
+    json!({
+        "msgtype": "text",
+        "text": {
+            "content": content,
+            "mentioned_mobile_list": mentioned_mobile_list,
+        },
+        "at": {
+            "atMobiles": mobiles,
+            "isAtAll": mention_all,
+        },
+        "summary": {
+            "files": file_count,
+            "results": stats.total_results,
+            "phones": stats.total_phones,
+            "id_cards": stats.total_id_cards,
+            "bank_cards": stats.total_bank_cards,
+            "names": stats.total_names,
+            "macau_ids": stats.total_macau_ids,
+            "wechat_ids": stats.total_wechat_ids,
+            "elapsed_secs": stats.elapsed_secs,
+        },
+    })
+}
+
+pub fn send(url: &str, payload: &Value) -> Result<()> {
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(30))
+        .build()
+        .context("无法创建 webhook 客户端")?;
+
+    let response = client.post(url).json(payload).send().context("webhook 请求失败")?;
+    if !response.status().is_success() {
+        bail!("webhook 返回 {}", response.status());
+    }
+    Ok(())
+}
+
+/// 配置了 `webhook_url` 时推送统计摘要；失败只记录日志，不影响处理结果
+pub fn notify_completion(config: &Config, stats: &ProcessingStatistics, file_count: usize) {
+    let url = config.webhook_url.trim();
+    if url.is_empty() {
+        return;
+    }
+
+    let payload = build_payload(stats, file_count, &config.webhook_mentions);
+    match send(url, &payload) {
+        Ok(()) => tracing::info!("已推送处理摘要到 webhook"),
+        Err(e) => tracing::warn!("推送处理摘要到 webhook 失败: {:#}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_payload() {
+        let stats = ProcessingStatistics {
+            total_results: 12,
+            total_phones: 8,
+            total_id_cards: 3,
+            elapsed_secs: 1.5,
+            ..Default::default()
+        };
+        let mentions = vec!["13800138000".to_string(), MENTION_ALL.to_string()];
+        let payload = build_payload(&stats, 2, &mentions);
+
+        assert_eq!(payload["msgtype"], "text");
+        let content = payload["text"]["content"].as_str().unwrap();
+        assert!(content.contains("文件数：2"));
+        assert!(content.contains("手机号：8"));
+        assert!(content.ends_with("@13800138000"));
+        assert_eq!(payload["text"]["mentioned_mobile_list"], json!(["13800138000", "@all"]));
+        assert_eq!(payload["at"]["atMobiles"], json!(["13800138000"]));
+        assert_eq!(payload["at"]["isAtAll"], true);
+        assert_eq!(payload["summary"]["id_cards"], 3);
+
+        let quiet = build_payload(&stats, 2, &[]);
+        assert!(!quiet["text"]["content"].as_str().unwrap().contains('@'));
+        assert_eq!(quiet["at"]["isAtAll"], false);
+    }
+
+    #[test]
+    fn test_send_failure_is_error() {
+        // 绑定后立即释放，得到一个无人监听的端口
+        let addr = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap();
+        let payload = build_payload(&ProcessingStatistics::default(), 0, &[]);
+        assert!(send(&format!("http://{}/hook", addr), &payload).is_err());
+    }
+}
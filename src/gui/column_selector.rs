@@ -1,6 +1,9 @@
 use eframe::egui;
 use egui::{Color32, RichText};
 
+use super::i18n::{tr, trf};
+use crate::core::{find_recommended_column, recommendation_rank};
+
 pub struct ColumnSelector<'a> {
     available_columns: &'a [String],
     selected_column: &'a mut String,
@@ -14,13 +17,15 @@ impl<'a> ColumnSelector<'a> {
         }
     }
 
-    pub fn show(&mut self, ui: &mut egui::Ui) {
+    /// 显示列选择框，返回用户本帧是否改选了列
+    pub fn show(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut changed = false;
         ui.horizontal(|ui| {
-            ui.label("目标列:");
+            ui.label(tr("column.label"));
 
             if self.available_columns.is_empty() {
                 ui.label(
-                    RichText::new("(导入文件后显示可用列)")
+                    RichText::new(tr("column.empty"))
                         .small()
                         .color(Color32::GRAY)
                 );
@@ -30,40 +35,41 @@ impl<'a> ColumnSelector<'a> {
                     .width(200.0)
                     .show_ui(ui, |ui| {
                         for col in self.available_columns {
-                            let is_recommended = col.contains("消息内容");
+                            let is_recommended = recommendation_rank(col).is_some();
 
-                            if is_recommended {
+                            let response = if is_recommended {
                                 ui.selectable_value(
                                     self.selected_column,
                                     col.clone(),
-                                    RichText::new(format!("⭐ {} (推荐)", col)),
-                                );
+                                    RichText::new(trf("column.recommended", &[col])),
+                                )
                             } else {
                                 ui.selectable_value(
                                     self.selected_column,
                                     col.clone(),
                                     col,
-                                );
-                            }
+                                )
+                            };
+                            changed |= response.changed();
                         }
                     });
 
                 ui.label(
-                    RichText::new(format!("({} 列可用)", self.available_columns.len()))
+                    RichText::new(trf("column.available", &[&self.available_columns.len()]))
                         .small()
                         .color(Color32::GRAY)
                 );
             }
         });
+        changed
     }
 }
 
-pub fn find_recommended_column(columns: &[String]) -> Option<&String> {
-    columns.iter().find(|col| col.contains("消息内容"))
-}
-
-pub fn smart_select_column(columns: &[String], current_selection: &mut String) {
-    if !current_selection.is_empty() && columns.contains(current_selection) {
+/// 自动选择目标列：用户手动选过且该列仍存在时保持不变；
+/// 否则优先选推荐列，没有推荐列时保留仍存在的当前列，再退回第一列
+pub fn smart_select_column(columns: &[String], current_selection: &mut String, user_selected: bool) {
+    let current_available = !current_selection.is_empty() && columns.contains(current_selection);
+    if current_available && user_selected {
         return;
     }
 
@@ -72,10 +78,14 @@ pub fn smart_select_column(columns: &[String], current_selection: &mut String) {
         return;
     }
 
-    if let Some(first) = columns.first() {
-        if !first.is_empty() {
-            *current_selection = first.clone();
-        }
+    if current_available {
+        return;
+    }
+
+    if let Some(first) = columns.first()
+        && !first.is_empty()
+    {
+        *current_selection = first.clone();
     }
 }
 
@@ -84,30 +94,49 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_find_recommended_column() {
+    fn test_smart_select_column() {
         let columns = vec![
             "姓名".to_string(),
             "消息内容".to_string(),
-            "时间".to_string(),
         ];
 
-        let recommended = find_recommended_column(&columns);
-        assert_eq!(recommended, Some(&"消息内容".to_string()));
+        let mut selected = String::new();
+        smart_select_column(&columns, &mut selected, false);
+        assert_eq!(selected, "消息内容");
+
+        selected = "姓名".to_string();
+        smart_select_column(&columns, &mut selected, true);
+        assert_eq!(selected, "姓名");
+
+        // 自动选中的列在出现推荐列后会被替换
+        smart_select_column(&columns, &mut selected, false);
+        assert_eq!(selected, "消息内容");
+
+        // 手动选的列已不存在时重新自动选择
+        selected = "备注".to_string();
+        smart_select_column(&columns, &mut selected, true);
+        assert_eq!(selected, "消息内容");
     }
 
     #[test]
-    fn test_smart_select_column() {
+    fn test_smart_select_prefers_keyword_column() {
         let columns = vec![
             "姓名".to_string(),
-            "消息内容".to_string(),
+            "备注内容".to_string(),
         ];
 
         let mut selected = String::new();
-        smart_select_column(&columns, &mut selected);
-        assert_eq!(selected, "消息内容");
+        smart_select_column(&columns, &mut selected, false);
+        assert_eq!(selected, "备注内容");
 
-        selected = "姓名".to_string();
-        smart_select_column(&columns, &mut selected);
-        assert_eq!(selected, "姓名");
+        let columns = vec![
+            "姓名".to_string(),
+            "文本".to_string(),
+            "说明".to_string(),
+        ];
+
+        let mut selected = String::new();
+        smart_select_column(&columns, &mut selected, false);
+        assert_eq!(selected, "说明");
     }
-}
\ No newline at end of file
+}
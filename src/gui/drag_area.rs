@@ -1,6 +1,9 @@
 use eframe::egui;
 use egui::{Color32, FontId, RichText, Vec2};
 
+use super::i18n::{tr, trf};
+use crate::utils::is_supported_file;
+
 pub struct DragArea;
 
 impl Default for DragArea {
@@ -17,8 +20,13 @@ impl DragArea {
     pub fn show(&mut self, ui: &mut egui::Ui) -> Option<Vec<std::path::PathBuf>> {
         let mut dropped_paths = None;
 
-        let is_dragging_file = ui.ctx().input(|i| {
-            !i.raw.hovered_files.is_empty()
+        let (is_dragging_file, unsupported_count) = ui.ctx().input(|i| {
+            let unsupported = i.raw.hovered_files
+                .iter()
+                .filter_map(|f| f.path.as_ref())
+                .filter(|path| !path.is_dir() && !is_supported_file(path))
+                .count();
+            (!i.raw.hovered_files.is_empty(), unsupported)
         });
 
         let background_color = if is_dragging_file {
@@ -42,25 +50,40 @@ impl DragArea {
                 ui.vertical_centered(|ui| {
                     if is_dragging_file {
                         ui.label(
-                            RichText::new("📥 释放以添加文件")
+                            RichText::new(tr("drag.release"))
                                 .font(FontId::proportional(20.0))
                                 .color(Color32::from_rgb(0x21, 0x96, 0xF3))
                         );
+                        if unsupported_count > 0 {
+                            ui.add_space(5.0);
+                            ui.label(
+                                RichText::new(tr("drag.supported"))
+                                    .color(Color32::from_rgb(0x99, 0x99, 0x99))
+                            );
+                        }
                     } else {
                         ui.label(
-                            RichText::new("📁 拖拽文件或文件夹到此处")
+                            RichText::new(tr("drag.prompt"))
                                 .font(FontId::proportional(18.0))
                                 .color(Color32::from_rgb(0x66, 0x66, 0x66))
                         );
                         ui.add_space(5.0);
                         ui.label(
-                            RichText::new("支持 .xlsx 文件")
+                            RichText::new(tr("drag.supported_detail"))
                                 .color(Color32::from_rgb(0x99, 0x99, 0x99))
                         );
                     }
                 });
             });
 
+        if is_dragging_file && unsupported_count > 0 {
+            ui.label(
+                RichText::new(trf("drag.unsupported", &[&unsupported_count]))
+                    .small()
+                    .color(Color32::from_rgb(0xFF, 0x98, 0x00))
+            );
+        }
+
         ui.ctx().input(|i| {
             if !i.raw.dropped_files.is_empty() {
                 let paths: Vec<std::path::PathBuf> = i.raw.dropped_files
@@ -1,4 +1,6 @@
+use super::i18n::{tr, trf};
 use crate::models::{FileInfo, FileStatus};
+use crate::utils::format_file_size;
 use eframe::egui;
 use egui::{Color32, RichText};
 
@@ -14,22 +16,19 @@ impl<'a> FileList<'a> {
     pub fn show(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
             ui.horizontal(|ui| {
-                ui.label(RichText::new("已选文件").strong());
+                ui.label(RichText::new(tr("files.title")).strong());
                 ui.label(format!("({})", self.files.len()));
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.small_button("清空").clicked() {
+                    ui.label(RichText::new("⌨").color(Color32::GRAY)).on_hover_text(tr("files.shortcuts"));
+                    if ui.small_button(tr("files.clear")).clicked() {
                         self.files.clear();
                     }
-                    if ui.small_button("取消全选").clicked() {
-                        for file in self.files.iter_mut() {
-                            file.selected = false;
-                        }
+                    if ui.small_button(tr("files.deselect_all")).on_hover_text("Ctrl+D").clicked() {
+                        Self::set_all_selected(self.files, false);
                     }
-                    if ui.small_button("全选").clicked() {
-                        for file in self.files.iter_mut() {
-                            file.selected = true;
-                        }
+                    if ui.small_button(tr("files.select_all")).on_hover_text("Ctrl+A").clicked() {
+                        Self::set_all_selected(self.files, true);
                     }
                 });
             });
@@ -37,26 +36,84 @@ impl<'a> FileList<'a> {
             egui::ScrollArea::vertical()
                 .max_height(200.0)
                 .show(ui, |ui| {
-                    for file in self.files.iter_mut() {
-                        Self::show_file_item(ui, file);
+                    let pointer = ui.ctx().pointer_interact_pos();
+                    let drag_source = (0..self.files.len()).find(|&i| ui.ctx().is_being_dragged(Self::handle_id(i)));
+                    let mut row_rects = Vec::with_capacity(self.files.len());
+                    let mut dropped = None;
+
+                    for (index, file) in self.files.iter_mut().enumerate() {
+                        let (rect, handle) = Self::show_file_item(ui, index, file);
+                        if handle.drag_stopped() {
+                            dropped = Some(index);
+                        }
+                        // 拖动经过的行高亮为放置位置
+                        let hovered = pointer.is_some_and(|p| rect.contains(p));
+                        if hovered && drag_source.is_some_and(|source| source != index) {
+                            ui.painter().rect_stroke(
+                                rect,
+                                4.0,
+                                ui.visuals().selection.stroke,
+                                egui::StrokeKind::Inside,
+                            );
+                        }
+                        row_rects.push(rect);
+                    }
+
+                    if let Some(source) = dropped
+                        && let Some(target) = pointer.and_then(|p| row_rects.iter().position(|r| r.contains(p)))
+                    {
+                        Self::swap_files(self.files, source, target);
                     }
 
                     if self.files.is_empty() {
                         ui.vertical_centered(|ui| {
                             ui.add_space(20.0);
-                            ui.label(RichText::new("暂无文件").color(Color32::GRAY));
-                            ui.label(RichText::new("拖拽文件到上方区域添加").color(Color32::GRAY).small());
+                            ui.label(RichText::new(tr("files.empty")).color(Color32::GRAY));
+                            ui.label(RichText::new(tr("files.empty_hint")).color(Color32::GRAY).small());
                         });
                     }
                 });
         });
     }
 
-    fn show_file_item(ui: &mut egui::Ui, file: &mut FileInfo) {
-        egui::Frame::default()
+    pub fn set_all_selected(files: &mut [FileInfo], selected: bool) {
+        for file in files {
+            file.selected = selected;
+        }
+    }
+
+    /// 移除勾选的文件，返回移除的数量
+    pub fn remove_selected(files: &mut Vec<FileInfo>) -> usize {
+        let before = files.len();
+        files.retain(|file| !file.selected);
+        before - files.len()
+    }
+
+    /// 交换两个文件的位置，用于拖动排序
+    pub fn swap_files(files: &mut [FileInfo], from: usize, to: usize) {
+        if from != to && from < files.len() && to < files.len() {
+            files.swap(from, to);
+        }
+    }
+
+    fn handle_id(index: usize) -> egui::Id {
+        egui::Id::new(("file_drag_handle", index))
+    }
+
+    /// 绘制一行文件，返回整行区域和拖动手柄的响应
+    fn show_file_item(ui: &mut egui::Ui, index: usize, file: &mut FileInfo) -> (egui::Rect, egui::Response) {
+        let frame = egui::Frame::default()
             .inner_margin(egui::Vec2::new(5.0, 2.0))
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
+                    let handle = ui.add(
+                        egui::Label::new(RichText::new("⠿").color(Color32::GRAY)).selectable(false),
+                    );
+                    let handle = ui
+                        .interact(handle.rect, Self::handle_id(index), egui::Sense::drag())
+                        .on_hover_cursor(egui::CursorIcon::Grab)
+                        .on_hover_text(tr("files.drag_hint"));
+
                     ui.checkbox(&mut file.selected, "");
 
                     ui.label("📄");
@@ -64,15 +121,63 @@ impl<'a> FileList<'a> {
 
                     if file.row_count > 0 {
                         ui.label(
-                            RichText::new(format!("({} 行)", file.row_count))
+                            RichText::new(trf("files.rows", &[&file.row_count]))
                                 .small()
                                 .color(Color32::GRAY)
                         );
                     }
 
-                    Self::show_status_tag(ui, &file.status);
-                });
+                    if let Some(badge) = Self::result_badge(file) {
+                        ui.label(RichText::new(badge).small().strong())
+                            .on_hover_text(tr("files.result_hint"));
+                    }
+
+                    let size = file.file_size.map(format_file_size).unwrap_or_else(|| "--".to_string());
+                    let modified = file
+                        .modified_time
+                        .map(|t| chrono::DateTime::<chrono::Local>::from(t).format("%Y-%m-%d %H:%M").to_string())
+                        .unwrap_or_else(|| "--".to_string());
+                    ui.label(
+                        RichText::new(format!("{} · {}", size, modified))
+                            .small()
+                            .color(Color32::GRAY)
+                    )
+                    .on_hover_text(tr("files.size_time_hint"));
+
+                    if let Some(density) = file.hit_density {
+                        ui.label(
+                            RichText::new(trf("files.density", &[&format!("{:.0}", density * 100.0)]))
+                                .small()
+                                .color(Color32::GRAY)
+                        )
+                        .on_hover_text(tr("files.density_hint"));
+                    }
+
+                    if file.metadata_loading {
+                        ui.spinner();
+                        ui.label(RichText::new(tr("files.loading")).small().color(Color32::GRAY));
+                    } else {
+                        Self::show_status_tag(ui, &file.status);
+                    }
+
+                    handle
+                })
+                .inner
             });
+        (frame.response.rect, frame.inner)
+    }
+
+    /// 处理完成文件的命中数徽标，其余状态不显示
+    fn result_badge(file: &FileInfo) -> Option<String> {
+        if !matches!(file.status, FileStatus::Completed | FileStatus::Warning(_)) {
+            return None;
+        }
+        let summary = file.result_summary?;
+        if summary.is_empty() {
+            Some(tr("files.no_hits").to_string())
+        } else {
+            Some(summary.badge())
+        }
     }
 
     fn show_status_tag(ui: &mut egui::Ui, status: &FileStatus) {
@@ -81,17 +186,25 @@ impl<'a> FileList<'a> {
 
         match status {
             FileStatus::Pending => {
-                text = "等待处理".to_string();
+                text = tr("files.pending").to_string();
                 color = Color32::GRAY;
             }
             FileStatus::Processing(_) => {
-                text = "处理中".to_string();
+                text = tr("files.processing").to_string();
                 color = Color32::from_rgb(0x21, 0x96, 0xF3);
             }
             FileStatus::Completed => {
-                text = "已完成".to_string();
+                text = tr("files.completed").to_string();
                 color = Color32::from_rgb(0x4C, 0xAF, 0x50);
             }
+            FileStatus::Skipped(reason) => {
+                text = format!("⏭ {}", reason);
+                color = Color32::GRAY;
+            }
+            FileStatus::Warning(msg) => {
+                text = format!("⚠ {}", msg);
+                color = Color32::from_rgb(0xFF, 0x98, 0x00);
+            }
             FileStatus::Error(msg) => {
                 ui.label(
                     RichText::new(format!("❌ {}", msg))
@@ -111,3 +224,54 @@ impl<'a> FileList<'a> {
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn run_frame(ctx: &egui::Context, files: &mut Vec<FileInfo>, events: Vec<egui::Event>) {
+        let input = egui::RawInput {
+            events,
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| {
+            egui::CentralPanel::default().show(ctx, |ui| FileList::new(files).show(ui));
+        });
+    }
+
+    fn pointer_button(pos: egui::Pos2, pressed: bool) -> egui::Event {
+        egui::Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: egui::Modifiers::default(),
+        }
+    }
+
+    #[test]
+    fn test_drag_handle_swaps_files() {
+        let ctx = egui::Context::default();
+        let mut files: Vec<FileInfo> =
+            ["a.xlsx", "b.xlsx", "c.xlsx"].into_iter().map(|n| FileInfo::from_path(PathBuf::from(n))).collect();
+
+        run_frame(&ctx, &mut files, Vec::new());
+        let handle_center = |index| ctx.read_response(FileList::handle_id(index)).unwrap().rect.center();
+        let (from, to) = (handle_center(0), handle_center(2));
+
+        // 按住第一行的手柄，分几帧拖到第三行后松开
+        run_frame(&ctx, &mut files, vec![egui::Event::PointerMoved(from), pointer_button(from, true)]);
+        for step in 1..=4 {
+            let pos = from + (to - from) * (step as f32 / 4.0);
+            run_frame(&ctx, &mut files, vec![egui::Event::PointerMoved(pos)]);
+        }
+        assert!(ctx.is_being_dragged(FileList::handle_id(0)));
+        run_frame(&ctx, &mut files, vec![pointer_button(to, false)]);
+
+        let names: Vec<&str> = files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(names, ["c.xlsx", "b.xlsx", "a.xlsx"]);
+
+        FileList::swap_files(&mut files, 0, 5);
+        assert_eq!(files[0].file_name, "c.xlsx");
+    }
+}
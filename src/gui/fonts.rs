@@ -0,0 +1,79 @@
+//! 界面字体和文字样式
+//!
+//! 字体定义和文字样式由纯函数生成，只有 [`apply`] 需要 egui 上下文，
+//! 无显示器的环境下也能测试前两者。
+
+use eframe::egui;
+use egui::{FontData, FontDefinitions, FontFamily, FontId, TextStyle};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+const CHINESE_FONT: &str = "chinese_font";
+
+/// 加载系统中文字体并设置文字样式
+pub fn apply(ctx: &egui::Context) {
+    ctx.set_fonts(font_definitions(load_chinese_font()));
+    ctx.all_styles_mut(|style| style.text_styles = text_styles());
+}
+
+/// 默认字体定义；给出中文字体时放在比例字体和等宽字体的最前面
+pub fn font_definitions(chinese_font: Option<Vec<u8>>) -> FontDefinitions {
+    let mut fonts = FontDefinitions::default();
+
+    if let Some(data) = chinese_font {
+        fonts.font_data.insert(CHINESE_FONT.to_owned(), Arc::new(FontData::from_owned(data)));
+
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().insert(0, CHINESE_FONT.to_owned());
+        }
+    }
+
+    fonts
+}
+
+pub fn text_styles() -> BTreeMap<TextStyle, FontId> {
+    [
+        (TextStyle::Heading, FontId::new(24.0, FontFamily::Proportional)),
+        (TextStyle::Body, FontId::new(16.0, FontFamily::Proportional)),
+        (TextStyle::Monospace, FontId::new(14.0, FontFamily::Monospace)),
+        (TextStyle::Button, FontId::new(16.0, FontFamily::Proportional)),
+        (TextStyle::Small, FontId::new(12.0, FontFamily::Proportional)),
+    ]
+    .into()
+}
+
+fn load_chinese_font() -> Option<Vec<u8>> {
+    #[cfg(windows)]
+    {
+        let font_paths = [
+            r"C:\Windows\Fonts\msyh.ttc",
+            r"C:\Windows\Fonts\simhei.ttf",
+            r"C:\Windows\Fonts\simsun.ttc",
+        ];
+
+        for path in &font_paths {
+            if let Ok(data) = std::fs::read(path) {
+                return Some(data);
+            }
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_definitions_prefer_chinese_font() {
+        let fonts = font_definitions(Some(vec![0u8; 4]));
+        assert!(fonts.font_data.contains_key(CHINESE_FONT));
+        assert_eq!(fonts.families[&FontFamily::Proportional][0], CHINESE_FONT);
+        assert_eq!(fonts.families[&FontFamily::Monospace][0], CHINESE_FONT);
+
+        let fallback = font_definitions(None);
+        assert!(!fallback.font_data.contains_key(CHINESE_FONT));
+        assert_eq!(text_styles()[&TextStyle::Body].size, 16.0);
+    }
+}
@@ -0,0 +1,377 @@
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+use crate::models::Language;
+
+thread_local! {
+    /// 当前界面语言，每帧由主窗口按配置同步（界面只在 UI 线程绘制）
+    static CURRENT_LANGUAGE: Cell<Language> = const { Cell::new(Language::Chinese) };
+}
+
+/// 文案表：(key, 中文, English)，`{}` 为按顺序替换的占位符
+const TEXTS: &[(&str, &str, &str)] = &[
+    // 主窗口
+    ("app.title", "敏感信息提取工具", "Sensitive Info Extractor"),
+    ("main.select_files", "📂 选择文件", "📂 Select files"),
+    ("main.select_folder", "📁 选择文件夹", "📁 Select folder"),
+    ("main.clear", "🗑 清空", "🗑 Clear"),
+    ("main.url", "🌐 URL:", "🌐 URL:"),
+    ("main.url_hint", "https://… 远程 xlsx 地址", "https://… link to a remote xlsx"),
+    ("main.download", "⬇ 下载并导入", "⬇ Download"),
+    ("main.import_config", "📂 导入配置", "📂 Load config"),
+    ("main.save_config", "📝 保存配置", "📝 Save config"),
+    ("main.config_format_hint", "保存配置使用的格式，导入时按扩展名自动识别", "Format used when saving; loading detects it from the file extension"),
+    ("main.config_filter", "配置文件", "Config files"),
+    ("main.file_filter", "表格文件", "Spreadsheets"),
+    ("main.pivot_title", "📊 分组统计", "📊 Group counts"),
+    ("main.copy_summary", "📋 复制汇总表", "📋 Copy summary table"),
+    ("main.file_stats_title", "📄 各文件命中率", "📄 Hit rate per file"),
+    ("main.result_view_title", "🔎 结果明细 ({} 行)", "🔎 Result rows ({})"),
+    ("main.file_hit_rate", "命中率 {}% ({}/{} 个单元格)", "Hit rate {}% ({}/{} cells)"),
+    ("main.file_cells_hint", "提取单元格 {} 个，命中 {} 处", "{} cells extracted, {} matches"),
+    ("main.sheet_stats_title", "📑 各工作表统计", "📑 Statistics per sheet"),
+    ("main.sheet_stats_row", "{}/{} 行含敏感信息", "{}/{} rows with sensitive info"),
+    ("main.summary_title", "提取结果摘要", "Extraction summary"),
+    ("main.no_results", "暂无结果 - 点击【开始处理】提取敏感信息", "No results yet - click [Start] to extract sensitive info"),
+    ("main.elapsed", "⏱ 耗时: {}", "⏱ Elapsed: {}"),
+    ("unit.hour", "小时", "h "),
+    ("unit.minute", "分", "m "),
+    ("unit.second", "秒", "s"),
+    ("main.total_results", "共 {} 条结果", "{} results in total"),
+    ("main.live_results", "处理中，已有 {} 条结果", "Processing, {} results so far"),
+    ("main.rows_scanned", "扫描行数: {} (含敏感信息 {}%)", "Rows scanned: {} ({}% contain sensitive info)"),
+    ("main.count_valid", "{} 个 (有效 {})", "{} (valid {})"),
+    ("main.count_trusted", "{} 个 (可信 {})", "{} (trusted {})"),
+    ("main.no_hits", "无命中", "No hits"),
+    ("main.sample_source", "{} 第 {} 行", "{} row {}"),
+    ("main.exclude_value", "加入排除列表", "Add to exclusion list"),
+    ("main.progress", "进度:", "Progress:"),
+    ("main.start", "▶ 开始处理", "▶ Start"),
+    ("main.preview", "🔭 预览 (前{}行)", "🔭 Preview (first {} rows)"),
+    ("main.preview_banner", "预览模式: 仅显示前{}行结果", "Preview mode: results from the first {} rows only"),
+    ("main.pause", "⏸ 暂停", "⏸ Pause"),
+    ("main.resume", "▶ 继续", "▶ Resume"),
+    ("main.cancel", "⏹ 取消", "⏹ Cancel"),
+    ("main.export", "💾 导出结果", "💾 Export"),
+    ("main.export_as", "💾 导出结果 ({})", "💾 Export ({})"),
+    ("export.xlsx", "📊 导出 Excel", "📊 Export Excel"),
+    ("export.csv", "📄 导出 CSV", "📄 Export CSV"),
+    ("export.json", "{ } 导出 JSON", "{ } Export JSON"),
+    ("export.parquet", "🗄 导出 Parquet", "🗄 Export Parquet"),
+    ("export.html", "🌐 导出 HTML", "🌐 Export HTML"),
+    ("main.export_per_file", "🗂 按文件导出", "🗂 Export per file"),
+    ("main.export_archive", "🗜 归档导出", "🗜 Export archive"),
+    ("main.failed_banner", "⚠ {} 个文件处理失败，点击查看详情", "⚠ {} file(s) failed, click for details"),
+    ("main.dismiss", "关闭提示", "Dismiss"),
+    // 状态消息
+    ("status.ready", "准备就绪 - 拖拽Excel文件到窗口", "Ready - drop Excel files onto the window"),
+    ("status.imported", "已导入 {} 个文件", "Imported {} file(s)"),
+    ("status.import_failed", "处理文件失败: {}", "Failed to load files: {}"),
+    ("status.downloading", "正在下载...", "Downloading..."),
+    ("status.download_failed", "下载失败: {}", "Download failed: {}"),
+    ("status.download_interrupted", "下载线程意外退出", "Download thread exited unexpectedly"),
+    ("status.no_files", "请先导入文件", "Please import files first"),
+    ("status.no_extraction_type", "请至少选择一种提取类型", "Select at least one extraction type"),
+    ("status.no_selected_files", "没有可处理的文件", "No files to process"),
+    ("status.preview_done", "预览 {}: {} 条结果", "Preview of {}: {} results"),
+    ("status.preview_failed", "预览失败: {}", "Preview failed: {}"),
+    ("status.processing", "正在处理...", "Processing..."),
+    ("status.paused", "已暂停，点击【继续】恢复处理", "Paused - click [Resume] to continue"),
+    ("status.cancelling", "正在取消...", "Cancelling..."),
+    ("status.cancelled", "处理已取消", "Processing cancelled"),
+    ("status.no_results_to_export", "没有可导出的结果", "No results to export"),
+    ("status.exported", "结果已导出到: {}", "Results exported to: {}"),
+    ("status.exported_per_file", "已按源文件导出 {} 个结果文件到: {}", "Exported {} result file(s) per source to: {}"),
+    ("status.exported_archive", "结果归档已导出到: {}", "Result archive exported to: {}"),
+    ("status.export_failed", "导出失败: {}", "Export failed: {}"),
+    ("status.config_loaded", "已载入配置: {}", "Config loaded: {}"),
+    ("status.config_saved", "配置已保存到: {}", "Config saved to: {}"),
+    ("status.config_failed", "配置读写失败: {}", "Config file error: {}"),
+    ("status.interrupted", "处理中断", "Processing interrupted"),
+    ("status.aborted", "处理异常终止: {}", "Processing aborted: {}"),
+    ("status.thread_exited", "处理线程意外退出", "Processing thread exited unexpectedly"),
+    ("status.completed", "提取完成，共 {} 条结果 (敏感信息: {} 条)，耗时 {}", "Done: {} results ({} sensitive items) in {}"),
+    ("status.streamed", "流式导出完成，共 {} 条结果已写入 {}", "Streaming done: {} results written to {}"),
+    ("status.stream_unsupported", "Parquet 格式不支持流式导出，请选择 Excel、CSV 或 JSON", "Parquet does not support streaming export; choose Excel, CSV or JSON"),
+    ("status.cleared", "已清空", "Cleared"),
+    ("status.excluded", "已将 {} 加入排除列表，重新处理后生效", "Added {} to the exclusion list; reprocess to apply"),
+    // 拖放区域
+    ("drag.release", "📥 释放以添加文件", "📥 Release to add files"),
+    ("drag.prompt", "📁 拖拽文件或文件夹到此处", "📁 Drop files or folders here"),
+    ("drag.supported", "支持 .xlsx / .xls / .csv / .ods / .zip", "Supports .xlsx / .xls / .csv / .ods / .zip"),
+    ("drag.supported_detail", "支持 .xlsx / .xls / .csv / .ods / .zip 文件，压缩包中的表格会自动解压", "Supports .xlsx / .xls / .csv / .ods / .zip; spreadsheets in archives are extracted automatically"),
+    ("drag.unsupported", "⚠ {} 个不支持的文件将被忽略", "⚠ {} unsupported file(s) will be ignored"),
+    // 文件列表
+    ("files.title", "已选文件", "Selected files"),
+    ("files.clear", "清空", "Clear"),
+    ("files.drag_hint", "拖动到另一行与之交换位置，决定处理顺序", "Drag onto another row to swap places and set the processing order"),
+    ("files.shortcuts", "快捷键：\nCtrl+A 全选\nCtrl+D 取消全选\nCtrl+Delete 移除勾选的文件\n处理中或输入文字时不生效", "Shortcuts:\nCtrl+A select all\nCtrl+D deselect all\nCtrl+Delete remove checked files\nInactive while processing or typing"),
+    ("status.files_removed", "已移除 {} 个文件", "Removed {} files"),
+    ("files.deselect_all", "取消全选", "Deselect all"),
+    ("files.select_all", "全选", "Select all"),
+    ("files.empty", "暂无文件", "No files"),
+    ("files.empty_hint", "拖拽文件到上方区域添加", "Drop files onto the area above"),
+    ("files.rows", "({} 行)", "({} rows)"),
+    ("files.result_hint", "命中数：📱手机号 🪪身份证 💳银行卡 👤姓名", "Hits: 📱phone 🪪ID card 💳bank card 👤name"),
+    ("files.no_hits", "无命中", "No hits"),
+    ("files.size_time_hint", "文件大小 · 修改时间", "File size · modified time"),
+    ("files.density", "密度 {}%", "Density {}%"),
+    ("files.density_hint", "抽样行中含敏感信息的比例", "Share of sampled rows containing sensitive info"),
+    ("files.loading", "读取中…", "Loading…"),
+    ("files.pending", "等待处理", "Pending"),
+    ("files.processing", "处理中", "Processing"),
+    ("files.completed", "已完成", "Done"),
+    ("files.password_required", "需要密码", "Password required"),
+    // 密码输入
+    ("password.title", "输入文件密码", "File password"),
+    ("password.prompt", "{} 已加密，请输入打开密码", "{} is encrypted, enter its password"),
+    ("password.ok", "确定", "OK"),
+    ("password.cancel", "取消", "Cancel"),
+    ("password.wrong", "密码错误，请重试", "Wrong password, please try again"),
+    // 列选择
+    ("column.label", "目标列:", "Target column:"),
+    ("column.empty", "(导入文件后显示可用列)", "(columns appear after importing files)"),
+    ("column.recommended", "⭐ {} (推荐)", "⭐ {} (recommended)"),
+    ("column.available", "({} 列可用)", "({} columns available)"),
+    ("column.sheet_label", "查看列名:", "Columns from:"),
+    ("column.all_files", "所有文件", "All files"),
+    // 设置面板
+    ("settings.title", "提取设置", "Extraction settings"),
+    ("settings.language", "界面语言:", "Language:"),
+    ("settings.context_before", "上文行数:", "Lines before:"),
+    ("settings.context_after", "下文行数:", "Lines after:"),
+    ("settings.lines", "行", "lines"),
+    ("settings.lines_suffix", " 行", " rows"),
+    ("settings.context_hint", "（提取时包含的前后上下文行数）", "(context lines included around each hit)"),
+    ("settings.context_cell_sep", "列分隔符:", "Column separator:"),
+    ("settings.context_row_sep", "行分隔符:", "Row separator:"),
+    ("settings.context_sep_hint", "上下文中同一行各列、多行之间的分隔符，\\n 表示换行，\\t 表示制表符；导出 CSV 时可改用不含逗号和换行的分隔符", "Separators between columns and between rows in context; \\n is a newline, \\t a tab. For CSV, pick separators without commas or newlines"),
+    ("settings.prioritize", "按命中密度优先处理", "Prioritize by hit density"),
+    ("settings.force_sequential", "逐个顺序处理文件", "Process files sequentially"),
+    ("settings.force_sequential_hint", "不使用多线程并行处理，便于调试；文件较少时开销也更小", "Process files one at a time without the thread pool; easier to debug and lighter for a few files"),
+    ("settings.max_threads", "最大线程数", "Max threads"),
+    ("settings.max_threads_hint", "并行处理文件的线程数上限，0 表示按 CPU 核数，1 表示逐个顺序处理", "Upper limit of threads for parallel processing; 0 uses the CPU core count, 1 processes files one at a time"),
+    ("settings.retry_failed_files", "失败的文件自动重试一次", "Retry failed files once"),
+    ("settings.retry_failed_files_hint", "文件被占用或读取出错时稍等片刻再试一次，仍失败才标记为错误", "When a file is locked or fails to read, wait briefly and try once more before marking it as failed"),
+    ("settings.stream_export", "流式导出", "Streaming export"),
+    ("settings.stream_export_hint", "每处理完一个文件就写入导出文件，不在内存中保留结果，适合超大批量；处理后不显示结果列表，JSON 按行写出为 .jsonl", "Write each file's results to the export file as soon as it is processed instead of keeping them in memory, for very large batches; no result list is shown afterwards and JSON is written as .jsonl"),
+    ("settings.auto_detect_header", "自动识别表头行", "Auto-detect header row"),
+    ("settings.auto_detect_header_hint", "第一行全是数字而第二行含文字时，以第二行为表头", "Use the second row as the header when the first row is all numbers and the second has text"),
+    ("settings.include_hidden", "提取隐藏的行和列", "Include hidden rows and columns"),
+    ("settings.include_hidden_hint", "关闭后只处理可见的单元格，表头行始终读取；仅对 xlsx/xlsm 生效", "When off, only visible cells are processed and the header row is always read; xlsx/xlsm only"),
+    ("settings.convert_date_cells", "日期单元格转为 YYYY-MM-DD", "Read date cells as YYYY-MM-DD"),
+    ("settings.convert_date_cells_hint", "日期格式的单元格按日期读取，而不是 44927 这样的序列号", "Date-formatted cells are read as dates instead of serial numbers such as 44927"),
+    ("settings.cell_length", "单元格长度:", "Cell length:"),
+    ("settings.cell_length_min", "最短", "min"),
+    ("settings.cell_length_max", "最长", "max"),
+    ("settings.max_matches", "单元格命中上限:", "Max matches per cell:"),
+    ("settings.max_matches_hint", "（超过时截断并标记为异常数据，0 为不限制）", "(extra matches are dropped and the row is flagged as anomalous, 0 = no limit)"),
+    ("settings.cell_length_hint", "过短的单元格不可能包含手机号、身份证号，直接跳过；超长单元格只提取开头部分", "Cells too short to hold a phone or ID number are skipped; overly long cells are only scanned up to the limit"),
+    ("settings.prioritize_hint", "导入时抽样前若干行统计命中密度，高密度文件先处理；完整处理仍覆盖全部行", "Samples the first rows on import and processes dense files first; all rows are still processed"),
+    ("settings.types", "提取类型:", "Extraction types:"),
+    ("settings.phone", "📱 手机号", "📱 Phone"),
+    ("settings.phone_hint", "匹配中国大陆手机号（11位，1开头）", "Mainland China mobile numbers (11 digits, starting with 1)"),
+    ("settings.id_card", "🪪 身份证号", "🪪 ID card"),
+    ("settings.id_card_hint", "匹配18位身份证号并验证校验码", "18-digit ID numbers with checksum validation"),
+    ("settings.bank_card", "💳 银行卡号", "💳 Bank card"),
+    ("settings.bank_card_hint", "匹配16-19位银行卡号并验证Luhn校验", "16-19 digit card numbers with Luhn validation"),
+    ("settings.name", "👤 姓名", "👤 Name"),
+    ("settings.name_hint", "通过 API 服务提取姓名（需配置 API 地址）", "Extract names via the API service (requires API address)"),
+    ("settings.strip_country_code", "手机号去掉 +86 前缀", "Strip +86 from phone numbers"),
+    ("settings.strip_country_code_hint", "开启后 \"+86 13812345678\" 记为 \"13812345678\"", "When on, \"+86 13812345678\" is recorded as \"13812345678\""),
+    ("settings.accept_unknown_region", "身份证不校验地区码", "Skip the ID card region check"),
+    ("settings.accept_unknown_region_hint", "开启后前两位不是已分配省级代码的身份证号只要日期和校验码正确即为有效", "When on, ID numbers whose first two digits are not an assigned province code are valid if the date and checksum are correct"),
+    ("settings.suspicious", "疑似占位检测长度:", "Placeholder detection length:"),
+    ("settings.suspicious_hint", "（连续递增/重复数字达到该长度时标注，0 为关闭）", "(flag runs of sequential/repeated digits of this length, 0 = off)"),
+    ("settings.macau_id", "🆔 澳门身份证", "🆔 Macau ID"),
+    ("settings.macau_id_hint", "匹配 1/5/7 开头的 8 位澳门居民身份证号并验证校验码", "8-digit Macau resident ID numbers starting with 1/5/7, with check digit validation"),
+    ("settings.wechat", "💬 微信号", "💬 WeChat ID"),
+    ("settings.wechat_hint", "匹配 \"微信号：\" 等前缀后 6-20 位、字母开头的微信号", "WeChat IDs of 6-20 characters starting with a letter, after a \"微信号：\" prefix"),
+    ("settings.bank_card_length", "银行卡号长度:", "Bank card length:"),
+    ("settings.min", "最少", "min"),
+    ("settings.max", "最多", "max"),
+    ("settings.length_hint", "（0 为不限，不满足的命中直接丢弃）", "(0 = unlimited, hits outside the range are dropped)"),
+    ("settings.card_length_policy", "银行卡号位数:", "Bank card digits:"),
+    ("settings.card_policy_standard", "16-19 位", "16-19"),
+    ("settings.card_policy_extended", "13-19 位", "13-19"),
+    ("settings.card_policy_extended_hint", "额外识别 13 位 Visa 老卡号和部分联名卡，可能增加误报", "Also recognize 13-digit legacy Visa and some co-branded cards; may add false positives"),
+    ("settings.no_type_warning", "⚠ 请至少选择一种提取类型", "⚠ Select at least one extraction type"),
+    ("settings.api_title", "API 设置（姓名提取）", "API settings (name extraction)"),
+    ("settings.webhook_title", "完成通知（Webhook）", "Completion webhook"),
+    ("settings.webhook_url", "机器人地址:", "Bot URL:"),
+    ("settings.webhook_mentions", "提醒手机号:", "Mention phones:"),
+    ("settings.webhook_hint", "处理完成后推送统计摘要，支持企业微信和钉钉群机器人；多个手机号用逗号分隔，@all 提醒所有人。发送失败只记录日志", "Posts a summary when processing finishes (WeCom and DingTalk bots); separate phones with commas, @all mentions everyone. Failures are only logged"),
+    ("settings.api_host", "API 地址:", "API address:"),
+    ("settings.api_host_hint", "（姓名提取服务地址）", "(name extraction service address)"),
+    ("settings.test_connection", "🔍 测试连接", "🔍 Test connection"),
+    ("settings.field_mapping", "字段映射", "Field mapping"),
+    ("settings.request_text_field", "请求文本字段:", "Request text field:"),
+    ("settings.names_path", "姓名列表路径:", "Names path:"),
+    ("settings.confidence_path", "置信度路径:", "Confidence path:"),
+    ("settings.positions_path", "位置路径:", "Positions path:"),
+    ("settings.mapping_hint", "路径用 . 分隔，如 data.names；置信度/位置留空表示服务不提供", "Use . to separate path segments, e.g. data.names; leave confidence/positions empty if not provided"),
+    ("settings.api_tip", "💡 提示: 姓名 API 需要运行服务端，地址格式: host:port", "💡 Tip: the name API needs a running server, address format: host:port"),
+    ("settings.output_filename", "导出文件名:", "Output file name:"),
+    ("settings.placeholders", "可用占位符: {source} {timestamp} {date} {time} {phone_count} {id_count}", "Placeholders: {source} {timestamp} {date} {time} {phone_count} {id_count}"),
+    ("settings.output_formats", "导出格式:", "Export formats:"),
+    ("settings.no_format_warning", "⚠ 请至少选择一种导出格式", "⚠ Select at least one export format"),
+    ("settings.exclude_title", "排除列表（{}）", "Exclusion list ({})"),
+    ("settings.exclude_empty", "暂无，可在结果摘要中右键命中值加入", "Empty; right-click a hit in the summary to add it"),
+    ("settings.exclude_hint", "号码，分隔符不限", "Value, any separators"),
+    ("settings.exclude_add", "添加", "Add"),
+    ("settings.exclude_remove", "移出排除列表", "Remove from exclusion list"),
+    ("settings.custom_patterns_title", "自定义规则（{}）", "Custom rules ({})"),
+    ("settings.custom_patterns_empty", "暂无，命中写入导出的“自定义规则命中”列", "None; hits go to the \"自定义规则命中\" export column"),
+    ("settings.custom_pattern_name", "规则名", "Rule name"),
+    ("settings.custom_pattern_regex", "正则表达式", "Regular expression"),
+    ("settings.custom_pattern_invalid", "⚠ 正则无效，该规则不参与提取", "⚠ Invalid regex; this rule is skipped"),
+    ("settings.custom_pattern_add", "添加规则", "Add rule"),
+    ("settings.excluded_sheets_title", "排除的工作表（{}）", "Excluded sheets ({})"),
+    ("settings.excluded_sheets_empty", "暂无，所有工作表都会扫描", "None; every sheet is scanned"),
+    ("settings.excluded_sheets_hint", "工作表名或通配符", "Sheet name or glob"),
+    ("settings.excluded_sheets_remove", "恢复扫描该工作表", "Scan this sheet again"),
+    ("settings.excluded_sheets_note", "不区分大小写；* 匹配任意字符，? 匹配单个字符", "Case-insensitive; * matches any text, ? a single character"),
+    ("settings.one_match_per_row", "每个命中单独一行", "One hit per row"),
+    ("settings.one_match_per_row_hint", "一格内有多个命中时拆成多行导出，源文本列只保留该命中附近的片段", "Split cells with several hits into separate rows, each with a source snippet around its hit"),
+    ("settings.export_source_path", "导出源文件完整路径", "Export full source file path"),
+    ("settings.export_source_path_hint", "区分不同目录下的同名文件；路径涉及隐私时可关闭", "Tells apart same-named files in different folders; turn off if paths are sensitive"),
+    ("settings.analytic_mask", "身份证分析型脱敏", "Analytic ID card masking"),
+    ("settings.analytic_mask_hint", "导出时身份证号显示为掩码，并附带省份、性别、年龄段列", "Export masked ID numbers with province, gender and age group columns"),
+    ("settings.encrypt_archive", "归档时加密命中值", "Encrypt matches in archives"),
+    ("settings.encrypt_archive_hint", "导出归档时命中值、源文本和上下文用口令加密（Argon2id + AES-256-GCM），其余列保持明文；算法参数写入运行信息，对方凭口令解密", "When exporting an archive, encrypt matches, source text and context with a passphrase (Argon2id + AES-256-GCM); other columns stay plain. Parameters are written to the run info so the recipient can decrypt with the passphrase"),
+    ("settings.passphrase_hint", "加密口令", "Passphrase"),
+    ("settings.truncate_source_text", "截断过长的源文本", "Truncate long source text"),
+    ("settings.truncate_source_text_hint", "xlsx 中源文本超过字数时只保留开头并加省略号，完整内容放在单元格批注中", "In xlsx, keep only the start of long source text with an ellipsis; the full text goes into a cell note"),
+    ("settings.chars_suffix", " 字", " chars"),
+    ("settings.text_column_width", "文本列最大宽度:", "Max text column width:"),
+    ("settings.wrap_text", "自动换行", "Wrap text"),
+    ("settings.pretty_format", "命中值分组显示", "Group digits in matches"),
+    ("settings.pretty_format_hint", "xlsx/csv 中手机号显示为 138 1234 5678，银行卡号每 4 位一组，身份证号不变；不影响校验", "In xlsx/csv, phones show as 138 1234 5678 and bank cards in groups of 4; ID cards unchanged; validation is unaffected"),
+    ("settings.split_valid_columns", "有效值和无效值分列导出", "Separate valid and invalid columns"),
+    ("settings.split_valid_columns_hint", "xlsx/csv 中每类命中拆成“有效手机号”“无效手机号”等两列，代替命中值和有效性两列", "In xlsx/csv, split each type into valid and invalid value columns instead of value and validity columns"),
+    ("settings.wrap_text_hint", "源文本和上下文列自动换行，行高由 Excel 按内容调整", "Wrap the source text and context columns; Excel adjusts row heights to fit"),
+    ("settings.header_background", "表头底色:", "Header fill:"),
+    ("settings.header_font_color", "表头字色:", "Header font:"),
+    ("settings.header_bold", "表头加粗", "Bold header"),
+    ("settings.valid_color", "“有效”颜色:", "\"Valid\" color:"),
+    ("settings.invalid_color", "“无效”颜色:", "\"Invalid\" color:"),
+    ("settings.reset_export_style", "恢复默认样式", "Reset style"),
+    ("settings.row_colors", "按命中类型设置行底色:", "Row fill by match type:"),
+    ("settings.row_colors_hint", "行中含有勾选的全部类型时使用该底色，按顺序取第一条匹配的规则", "A row uses the fill when it contains all checked types; the first matching rule wins"),
+    ("settings.row_color_exclusive", "不含其他类型", "No other types"),
+    ("settings.row_color_add", "添加底色规则", "Add fill rule"),
+    ("settings.annotation", "批注文件:", "Annotation file:"),
+    ("settings.annotation_hint", "第一列为行号或命中值、第二列为备注（首行为表头），导出时合并为“备注”列，未匹配的留空", "First column is a row number or matched value, second column is the note (first row is the header); merged into a \"Remark\" column on export, unmatched rows stay empty"),
+    ("settings.annotation_none", "未设置", "Not set"),
+    ("settings.annotation_pick", "📂 选择...", "📂 Choose..."),
+    ("settings.annotation_key", "匹配方式:", "Match by:"),
+    ("settings.annotation_key_row", "行号", "Row number"),
+    ("settings.annotation_key_value", "命中值", "Matched value"),
+    ("settings.conflict", "按文件导出遇到同名文件:", "When per-file export finds an existing file:"),
+    ("settings.conflict_suffix", "追加序号", "Add suffix"),
+    ("settings.conflict_overwrite", "覆盖", "Overwrite"),
+    ("settings.mask_title", "脱敏设置", "Masking"),
+    ("settings.enable_mask", "导出时脱敏手机号、身份证号、银行卡号", "Mask phone, ID card and bank card numbers on export"),
+    ("settings.keep_prefix", "保留前", "Keep first"),
+    ("settings.keep_suffix", "位，后", "and last"),
+    ("settings.digits", "位", "chars"),
+    ("settings.sample", "示例:", "Sample:"),
+    ("settings.sample_hint", "输入号码预览效果", "Type a number to preview"),
+    ("settings.summary_title", "当前配置摘要", "Current configuration"),
+    ("settings.summary_column", "• 目标列: {}", "• Target column: {}"),
+    ("settings.summary_auto", "(自动选择)", "(auto)"),
+    ("settings.summary_context", "• 上下文行数: 前 {} 行 / 后 {} 行", "• Context lines: {} before / {} after"),
+    ("settings.summary_types", "• 提取类型: {}", "• Extraction types: {}"),
+    ("settings.summary_none", "无", "none"),
+    ("settings.summary_api", "• API 地址: {}", "• API address: {}"),
+    // 敏感信息类型
+    ("settings.preview_title", "🔍 提取预览", "🔍 Extraction preview"),
+    ("settings.preview_hint", "输入或粘贴示例文本，按当前配置实时提取", "Type or paste sample text to extract with the current settings"),
+    ("settings.preview_empty", "未命中敏感信息", "No sensitive info found"),
+    ("settings.preview_valid", "有效", "valid"),
+    ("settings.preview_invalid", "无效", "invalid"),
+    // 结果明细
+    ("result_view.flat", "平铺", "Flat"),
+    ("result_view.tree", "按文件分组", "Group by file"),
+    ("result_view.file_node", "📄 {}（{} 行） {}", "📄 {} ({} rows) {}"),
+    ("result_view.sheet_node", "📑 {}（{} 行）", "📑 {} ({} rows)"),
+    ("result_view.row", "第 {} 行：{}", "Row {}: {}"),
+    // 日志面板
+    ("log.title", "📜 日志 ({})", "📜 Log ({})"),
+    ("log.level", "最低级别:", "Min level:"),
+    ("log.copy", "📋 复制", "📋 Copy"),
+    ("log.clear", "🗑 清空", "🗑 Clear"),
+    ("log.empty", "暂无日志", "No log entries"),
+    ("type.phone", "手机号", "Phone"),
+    ("type.id_card", "身份证号", "ID card"),
+    ("type.bank_card", "银行卡号", "Bank card"),
+    ("type.name", "姓名", "Name"),
+    ("type.macau_id", "澳门身份证号", "Macau ID"),
+    ("type.wechat_id", "微信号", "WeChat ID"),
+    ("type.custom", "自定义规则", "Custom rule"),
+];
+
+static TEXT_MAP: LazyLock<HashMap<&'static str, (&'static str, &'static str)>> =
+    LazyLock::new(|| TEXTS.iter().map(|&(key, zh, en)| (key, (zh, en))).collect());
+
+pub fn set_language(language: Language) {
+    CURRENT_LANGUAGE.with(|current| current.set(language));
+}
+
+pub fn current_language() -> Language {
+    CURRENT_LANGUAGE.with(Cell::get)
+}
+
+/// 按 key 取当前语言的文案，未收录的 key 原样返回
+pub fn tr(key: &'static str) -> &'static str {
+    match TEXT_MAP.get(key) {
+        Some(&(zh, en)) => match current_language() {
+            Language::Chinese => zh,
+            Language::English => en,
+        },
+        None => key,
+    }
+}
+
+/// 取文案并按顺序替换其中的 `{}` 占位符
+pub fn trf(key: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = tr(key).split("{}");
+    let mut result = parts.next().unwrap_or_default().to_string();
+
+    for (index, part) in parts.enumerate() {
+        if let Some(arg) = args.get(index) {
+            result.push_str(&arg.to_string());
+        }
+        result.push_str(part);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_keys_unique_and_placeholders_match() {
+        let mut keys = HashSet::new();
+        for (key, zh, en) in TEXTS {
+            assert!(keys.insert(key), "重复的 key: {}", key);
+            assert_eq!(zh.matches("{}").count(), en.matches("{}").count(), "占位符数量不一致: {}", key);
+        }
+    }
+
+    #[test]
+    fn test_translate_and_format() {
+        set_language(Language::English);
+        assert_eq!(tr("main.start"), "▶ Start");
+        assert_eq!(trf("status.imported", &[&3]), "Imported 3 file(s)");
+        assert_eq!(tr("unknown.key"), "unknown.key");
+
+        set_language(Language::Chinese);
+        assert_eq!(trf("status.exported_per_file", &[&2, &"/tmp"]), "已按源文件导出 2 个结果文件到: /tmp");
+    }
+}
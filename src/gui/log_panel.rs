@@ -0,0 +1,233 @@
+use eframe::egui;
+use egui::{Color32, RichText};
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Arc, LazyLock, Mutex, PoisonError};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use super::i18n::{tr, trf};
+
+/// 内存中最多保留的日志条数，超出后丢弃最早的
+const LOG_CAPACITY: usize = 2000;
+
+/// 界面日志面板共用的缓冲区
+static LOG_BUFFER: LazyLock<LogBuffer> = LazyLock::new(LogBuffer::default);
+
+/// 全局日志缓冲区，`LogLayer` 写入、`LogPanel` 读取
+pub fn log_buffer() -> LogBuffer {
+    LOG_BUFFER.clone()
+}
+
+#[derive(Debug, Clone)]
+pub struct LogEntry {
+    pub time: chrono::DateTime<chrono::Local>,
+    pub level: Level,
+    pub target: String,
+    pub message: String,
+}
+
+impl LogEntry {
+    /// 复制到剪贴板时的单行文本
+    fn to_line(&self) -> String {
+        format!("{} {:>5} {}: {}", self.time.format("%H:%M:%S%.3f"), self.level, self.target, self.message)
+    }
+}
+
+/// 固定容量的日志环形缓冲区
+#[derive(Debug, Clone)]
+pub struct LogBuffer {
+    entries: Arc<Mutex<VecDeque<LogEntry>>>,
+    capacity: usize,
+}
+
+impl Default for LogBuffer {
+    fn default() -> Self {
+        Self::with_capacity(LOG_CAPACITY)
+    }
+}
+
+impl LogBuffer {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    pub fn push(&self, entry: LogEntry) {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        if entries.len() == self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// 级别不低于 `min_level` 的日志（从旧到新）
+    pub fn filtered(&self, min_level: Level) -> Vec<LogEntry> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.iter().filter(|e| e.level <= min_level).cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+}
+
+/// 把 `tracing` 事件收集到 `LogBuffer` 的 layer
+pub struct LogLayer {
+    buffer: LogBuffer,
+}
+
+impl LogLayer {
+    pub fn new(buffer: LogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+/// 取出事件的 `message` 字段，其余字段以 `name=value` 追加在后
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        self.buffer.push(LogEntry {
+            time: chrono::Local::now(),
+            level: *metadata.level(),
+            target: metadata.target().to_string(),
+            message: visitor.message + &visitor.fields,
+        });
+    }
+}
+
+/// 可折叠的日志面板，按级别过滤、着色显示
+pub struct LogPanel {
+    buffer: LogBuffer,
+    /// 显示的最低级别
+    min_level: Level,
+}
+
+impl Default for LogPanel {
+    fn default() -> Self {
+        Self {
+            buffer: log_buffer(),
+            min_level: Level::INFO,
+        }
+    }
+}
+
+impl LogPanel {
+    pub fn show(&mut self, ui: &mut egui::Ui) {
+        let entries = self.buffer.filtered(self.min_level);
+
+        egui::CollapsingHeader::new(trf("log.title", &[&entries.len()]))
+            .id_salt("log_panel")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("log.level"));
+                    egui::ComboBox::from_id_salt("log_level")
+                        .selected_text(self.min_level.as_str())
+                        .show_ui(ui, |ui| {
+                            for level in [Level::ERROR, Level::WARN, Level::INFO, Level::DEBUG, Level::TRACE] {
+                                ui.selectable_value(&mut self.min_level, level, level.as_str());
+                            }
+                        });
+
+                    if ui.button(tr("log.copy")).clicked() {
+                        let text: Vec<String> = entries.iter().map(LogEntry::to_line).collect();
+                        ui.ctx().copy_text(text.join("\n"));
+                    }
+                    if ui.button(tr("log.clear")).clicked() {
+                        self.buffer.clear();
+                    }
+                });
+
+                egui::ScrollArea::vertical()
+                    .max_height(180.0)
+                    .stick_to_bottom(true)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        if entries.is_empty() {
+                            ui.label(RichText::new(tr("log.empty")).small().color(Color32::GRAY));
+                        }
+                        for entry in &entries {
+                            ui.label(
+                                RichText::new(entry.to_line())
+                                    .monospace()
+                                    .small()
+                                    .color(level_color(entry.level)),
+                            );
+                        }
+                    });
+            });
+    }
+}
+
+fn level_color(level: Level) -> Color32 {
+    match level {
+        Level::ERROR => Color32::from_rgb(0xF4, 0x43, 0x36),
+        Level::WARN => Color32::from_rgb(0xFF, 0x98, 0x00),
+        Level::INFO => Color32::from_rgb(0x4C, 0xAF, 0x50),
+        _ => Color32::GRAY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_layer_collects_and_filters_entries() {
+        let buffer = LogBuffer::with_capacity(3);
+        let subscriber = tracing_subscriber::registry().with(LogLayer::new(buffer.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::debug!("调试信息");
+            tracing::info!(file = "a.xlsx", "开始处理");
+            tracing::warn!("抽样失败");
+            tracing::error!("处理文件 {} 失败", "b.xlsx");
+        });
+
+        // 容量为 3，最早的一条被丢弃
+        let all = buffer.filtered(Level::TRACE);
+        let messages: Vec<&str> = all.iter().map(|e| e.message.as_str()).collect();
+        assert_eq!(messages, ["开始处理 file=a.xlsx", "抽样失败", "处理文件 b.xlsx 失败"]);
+
+        let warnings = buffer.filtered(Level::WARN);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[1].level, Level::ERROR);
+        assert!(warnings[1].to_line().contains("ERROR"));
+
+        buffer.clear();
+        assert!(buffer.filtered(Level::TRACE).is_empty());
+    }
+}
@@ -1,27 +1,60 @@
 use eframe::egui;
-use egui::{Color32, FontData, FontDefinitions, FontFamily, FontId, RichText, TextStyle};
-use std::collections::BTreeMap;
-use std::path::PathBuf;
-use std::sync::mpsc::{self, Receiver};
+use egui::{Color32, RichText};
+use std::path::{Path, PathBuf};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
-use crate::core::{ExcelInfo, ProcessingStatistics, Processor};
-use crate::models::{Config, ExtractResult, FileInfo, FileStatus};
-use crate::utils::{generate_output_filename_with_source, process_dropped_paths};
+use super::fonts;
+use super::i18n::{self, tr, trf};
+use crate::core::office_crypto::is_password_error;
+use crate::core::webhook;
+use crate::core::{
+    create_stream_writer, panic_message, stream_extension, Annotations, BatchProcessor, ExcelInfo, FieldCipher, Masker,
+    MultiExporter, ProcessingControl, ProcessingStatistics, Processor, ResultArchive,
+};
+use crate::models::{
+    Config, ConfigFormat, ExtractResult, FileInfo, FileMetadataResult, FileResultSummary, FileStatus, MaskRule,
+    MetadataError, OutputFormat, PatternType,
+};
+use crate::utils::{download_xlsx, format_elapsed_time_with_units, format_file_size, process_dropped_paths, render_filename_template, SUPPORTED_EXTENSIONS};
 
 enum ProcessingMessage {
     Progress(String, u8),
+    /// 处理中新到达的一批结果的统计，累加到实时统计
+    Partial(ProcessingStatistics),
+    /// 单个文件处理失败（文件路径, 错误信息）；同名文件可能来自不同目录，按路径对应
+    FileError(PathBuf, String),
+    /// 单个文件处理完成但有警告（文件路径, 警告信息）
+    FileWarning(PathBuf, String),
     Completed(Vec<ExtractResult>, ProcessingStatistics),
+    /// 流式导出完成，结果已写入文件（导出路径, 统计）
+    Streamed(PathBuf, ProcessingStatistics),
+    /// 用户取消了处理
+    Cancelled,
+    /// 处理线程异常终止
+    Failed(String),
 }
 
-use super::{smart_select_column, ColumnSelector, DragArea, FileList, SettingsPanel};
+enum DownloadMessage {
+    /// 已下载字节数, 总字节数（未知时为 `None`）
+    Progress(u64, Option<u64>),
+    Finished(Result<PathBuf, String>),
+}
+
+use super::{smart_select_column, ColumnSelector, DragArea, ExtractionPreview, FileList, LogPanel, ResultView, SettingsPanel};
 
 pub struct MainWindow {
     config: Config,
     files: Vec<FileInfo>,
     available_columns: Vec<String>,
+    /// 列名预览所选的文件和工作表，`None` 时列出所有文件的列
+    column_sheet: Option<(PathBuf, String)>,
     results: Vec<ExtractResult>,
     statistics: Option<ProcessingStatistics>,
+    /// 处理中按增量累加的统计，处理结束后清空
+    live_statistics: Option<ProcessingStatistics>,
     processing: bool,
     progress: u8,
     current_file: String,
@@ -30,169 +63,539 @@ pub struct MainWindow {
     drag_area: DragArea,
     processing_receiver: Option<Receiver<ProcessingMessage>>,
     processing_handle: Option<JoinHandle<()>>,
+    /// 处理中的暂停/取消控制
+    processing_control: Option<Arc<ProcessingControl>>,
     api_connection_status: Option<Result<String, String>>,
+    /// 最近一次连接测试的耗时（毫秒）
+    api_latency_ms: Option<u64>,
+    mask_preview_sample: String,
+    /// 设置面板中待加入排除列表的输入
+    exclude_input: String,
+    /// 设置面板中待加入排除工作表的输入
+    excluded_sheet_input: String,
+    extraction_preview: ExtractionPreview,
+    /// 结果明细（平铺/按文件分组）
+    result_view: ResultView,
+    log_panel: LogPanel,
+    /// 保存配置时使用的文件格式
+    config_format: ConfigFormat,
+    /// 本次处理失败的文件（文件名, 错误信息）
+    failed_files: Vec<(String, String)>,
+    failed_banner_dismissed: bool,
+    /// 等待输入密码的加密文件
+    password_queue: Vec<PathBuf>,
+    password_input: String,
+    password_error: Option<String>,
+    /// 待下载的远程文件地址
+    url_input: String,
+    download_receiver: Option<Receiver<DownloadMessage>>,
+    /// 下载中为 `Some`（已下载字节数, 总字节数）
+    download_progress: Option<(u64, Option<u64>)>,
+    /// 当前结果来自预览（只提取了前几行）
+    preview_mode: bool,
+    /// 用户在列选择框中手动选过目标列，之后导入文件不再自动改选
+    user_has_manually_selected: bool,
+    /// 后台读取文件元数据的结果通道，发送端交给各读取线程
+    metadata_sender: Sender<FileMetadataResult>,
+    metadata_receiver: Receiver<FileMetadataResult>,
 }
 
 impl Default for MainWindow {
     fn default() -> Self {
+        let (metadata_sender, metadata_receiver) = mpsc::channel();
         Self {
             config: Config::default(),
             files: Vec::new(),
             available_columns: Vec::new(),
+            column_sheet: None,
             results: Vec::new(),
             statistics: None,
+            live_statistics: None,
             processing: false,
             progress: 0,
             current_file: String::new(),
-            status_message: "准备就绪 - 拖拽xlsx文件到窗口".to_string(),
+            status_message: tr("status.ready").to_string(),
             error_message: None,
             drag_area: DragArea::new(),
             processing_receiver: None,
             processing_handle: None,
+            processing_control: None,
             api_connection_status: None,
+            api_latency_ms: None,
+            mask_preview_sample: "13812345678".to_string(),
+            exclude_input: String::new(),
+            excluded_sheet_input: String::new(),
+            extraction_preview: ExtractionPreview::default(),
+            result_view: ResultView::default(),
+            log_panel: LogPanel::default(),
+            config_format: ConfigFormat::default(),
+            failed_files: Vec::new(),
+            failed_banner_dismissed: false,
+            password_queue: Vec::new(),
+            password_input: String::new(),
+            password_error: None,
+            url_input: String::new(),
+            download_receiver: None,
+            download_progress: None,
+            preview_mode: false,
+            user_has_manually_selected: false,
+            metadata_sender,
+            metadata_receiver,
         }
     }
 }
 
 impl MainWindow {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        Self::setup_chinese_fonts(&cc.egui_ctx);
+        fonts::apply(&cc.egui_ctx);
         Self::default()
     }
 
-    fn setup_chinese_fonts(ctx: &egui::Context) {
-        use std::sync::Arc;
+    fn handle_dropped_files(&mut self, paths: &[PathBuf]) {
+        match process_dropped_paths(paths) {
+            Ok(xlsx_files) => {
+                let mut added_count = 0;
+                for path in xlsx_files {
+                    if !self.files.iter().any(|f| f.file_path == path) {
+                        // 列名和行数在后台读取，由 `poll_metadata` 补全
+                        FileInfo::load_metadata(
+                            path.clone(),
+                            self.config.excel_password.clone(),
+                            self.config.sheet_exclusion(),
+                            self.metadata_sender.clone(),
+                        );
+                        self.files.push(FileInfo::loading(path));
+                        added_count += 1;
+                    }
+                }
+
+                if added_count > 0 {
+                    self.status_message = trf("status.imported", &[&added_count]);
+                    self.error_message = None;
+                }
+            }
+            Err(e) => {
+                self.error_message = Some(trf("status.import_failed", &[&e]));
+            }
+        }
+    }
+
+    /// 取出后台读取完成的元数据，补全对应文件；读取失败的标记为出错或排入密码队列
+    fn poll_metadata(&mut self) {
+        let mut received = false;
+        while let Ok(metadata) = self.metadata_receiver.try_recv() {
+            received = true;
+            // 读取期间文件可能已被清空
+            let Some(file_info) = self.files.iter_mut().find(|f| f.metadata_loading && f.file_path == metadata.path)
+            else {
+                continue;
+            };
+            file_info.metadata_loading = false;
+            file_info.status = FileStatus::Pending;
+
+            match &metadata.error {
+                None => Self::apply_metadata(file_info, &metadata, &mut self.available_columns),
+                Some(MetadataError::PasswordRequired) => {
+                    // 输入密码前不参与处理
+                    file_info.selected = false;
+                    self.password_queue.push(metadata.path.clone());
+                }
+                Some(MetadataError::Failed(e)) => file_info.status = FileStatus::error(e.clone()),
+            }
+        }
 
-        let font_data = Self::load_chinese_font();
+        if received {
+            self.refresh_target_column();
+            if self.config.prioritize_by_density && !self.metadata_pending() {
+                self.sample_hit_densities();
+            }
+        }
+    }
 
-        let mut fonts = FontDefinitions::default();
+    /// 还有文件的元数据在后台读取中
+    fn metadata_pending(&self) -> bool {
+        self.files.iter().any(|f| f.metadata_loading)
+    }
 
-        if let Some(data) = font_data {
-            fonts.font_data.insert(
-                "chinese_font".to_owned(),
-                Arc::new(FontData::from_owned(data)),
-            );
+    fn apply_metadata(file_info: &mut FileInfo, metadata: &FileMetadataResult, available_columns: &mut Vec<String>) {
+        if !metadata.columns.is_empty() {
+            file_info.columns = metadata.columns.clone();
+            file_info.sheet_columns = metadata.sheet_columns.clone();
+            for col in &metadata.columns {
+                if !available_columns.contains(col) {
+                    available_columns.push(col.clone());
+                }
+            }
+        }
+        file_info.row_count = metadata.row_count;
+    }
 
-            fonts.families
-                .entry(FontFamily::Proportional)
-                .or_default()
-                .insert(0, "chinese_font".to_owned());
+    /// 加密文件解锁后补全文件信息并重新参与处理
+    fn unlock_file(&mut self, path: &PathBuf, info: &ExcelInfo) {
+        if let Some(file_info) = self.files.iter_mut().find(|f| &f.file_path == path) {
+            let metadata = FileMetadataResult::from_info(path.clone(), info);
+            Self::apply_metadata(file_info, &metadata, &mut self.available_columns);
+            file_info.selected = true;
+        }
+        self.refresh_target_column();
+    }
 
-            fonts.families
-                .entry(FontFamily::Monospace)
-                .or_default()
-                .insert(0, "chinese_font".to_owned());
+    /// 可用列变化后重新确定目标列；手动选的列已不在任何文件中时恢复自动选择
+    fn refresh_target_column(&mut self) {
+        if !self.available_columns.contains(&self.config.target_column) {
+            self.user_has_manually_selected = false;
         }
+        smart_select_column(
+            &self.available_columns,
+            &mut self.config.target_column,
+            self.user_has_manually_selected,
+        );
+    }
 
-        ctx.set_fonts(fonts);
+    /// 目标列下拉框中的可用列：选了工作表时只列出该表的列，所选文件已移除时退回所有文件的列
+    fn visible_columns(&self) -> &[String] {
+        self.column_sheet
+            .as_ref()
+            .and_then(|(path, sheet)| {
+                let file = self.files.iter().find(|f| &f.file_path == path)?;
+                file.sheet_columns.iter().find(|(name, _)| name == sheet)
+            })
+            .map_or(&self.available_columns, |(_, columns)| columns)
+    }
 
-        ctx.all_styles_mut(|style| {
-            let text_styles: BTreeMap<TextStyle, FontId> = [
-                (TextStyle::Heading, FontId::new(24.0, FontFamily::Proportional)),
-                (TextStyle::Body, FontId::new(16.0, FontFamily::Proportional)),
-                (TextStyle::Monospace, FontId::new(14.0, FontFamily::Monospace)),
-                (TextStyle::Button, FontId::new(16.0, FontFamily::Proportional)),
-                (TextStyle::Small, FontId::new(12.0, FontFamily::Proportional)),
-            ]
-                .into();
-            style.text_styles = text_styles;
+    /// 列名预览的文件和工作表下拉框，只列出已读取列名的文件
+    fn show_sheet_picker(&mut self, ui: &mut egui::Ui) {
+        let files: Vec<&FileInfo> = self.files.iter().filter(|f| !f.sheet_columns.is_empty()).collect();
+        if files.is_empty() {
+            return;
+        }
+
+        let mut selection = self.column_sheet.take();
+        let mut selected_file = selection
+            .as_ref()
+            .and_then(|(path, _)| files.iter().copied().find(|f| &f.file_path == path));
+        if selected_file.is_none() {
+            selection = None;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label(tr("column.sheet_label"));
+
+            let file_text = selected_file.map_or(tr("column.all_files"), |f| f.file_name.as_str());
+            egui::ComboBox::from_id_salt("column_file")
+                .selected_text(file_text)
+                .width(140.0)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(selection.is_none(), tr("column.all_files")).clicked() {
+                        selection = None;
+                        selected_file = None;
+                    }
+                    for &file in &files {
+                        let checked = selected_file.is_some_and(|f| f.file_path == file.file_path);
+                        if ui.selectable_label(checked, &file.file_name).clicked() && !checked {
+                            selection = Some((file.file_path.clone(), file.sheet_columns[0].0.clone()));
+                            selected_file = Some(file);
+                        }
+                    }
+                });
+
+            if let (Some(file), Some((_, sheet))) = (selected_file, &mut selection) {
+                egui::ComboBox::from_id_salt("column_sheet")
+                    .selected_text(sheet.as_str())
+                    .width(120.0)
+                    .show_ui(ui, |ui| {
+                        for (name, _) in &file.sheet_columns {
+                            ui.selectable_value(sheet, name.clone(), name);
+                        }
+                    });
+            }
         });
+
+        self.column_sheet = selection;
     }
 
-    fn load_chinese_font() -> Option<Vec<u8>> {
-        #[cfg(windows)]
-        {
-            let font_paths = [
-                r"C:\Windows\Fonts\msyh.ttc",
-                r"C:\Windows\Fonts\simhei.ttf",
-                r"C:\Windows\Fonts\simsun.ttc",
-            ];
+    /// 用输入的密码打开队首的加密文件；成功后用同一密码依次尝试其余加密文件
+    fn submit_password(&mut self) {
+        let Some(path) = self.password_queue.first().cloned() else {
+            return;
+        };
+        let password = std::mem::take(&mut self.password_input);
+        let exclusion = self.config.sheet_exclusion();
+
+        match ExcelInfo::from_file_with_password(&path, Some(&password), &exclusion) {
+            Ok(info) => {
+                self.password_queue.remove(0);
+                self.password_error = None;
+                self.unlock_file(&path, &info);
+
+                let queued = std::mem::take(&mut self.password_queue);
+                for queued_path in queued {
+                    match ExcelInfo::from_file_with_password(&queued_path, Some(&password), &exclusion) {
+                        Ok(info) => self.unlock_file(&queued_path, &info),
+                        Err(_) => self.password_queue.push(queued_path),
+                    }
+                }
 
-            for path in &font_paths {
-                if let Ok(data) = std::fs::read(path) {
-                    return Some(data);
+                self.config.excel_password = Some(password);
+            }
+            Err(e) if is_password_error(&e) => {
+                self.password_error = Some(tr("password.wrong").to_string());
+            }
+            Err(e) => {
+                self.password_queue.remove(0);
+                self.password_error = None;
+                if let Some(file_info) = self.files.iter_mut().find(|f| f.file_path == path) {
+                    file_info.status = FileStatus::error(e.to_string());
                 }
             }
         }
+    }
+
+    /// 放弃输入密码，队首文件标记为跳过
+    fn skip_password_file(&mut self) {
+        if self.password_queue.is_empty() {
+            return;
+        }
+        let path = self.password_queue.remove(0);
+        self.password_input.clear();
+        self.password_error = None;
 
-        None
+        if let Some(file_info) = self.files.iter_mut().find(|f| f.file_path == path) {
+            file_info.status = FileStatus::skipped(tr("files.password_required"));
+            file_info.selected = false;
+        }
     }
 
-    fn handle_dropped_files(&mut self, paths: &[PathBuf]) {
-        match process_dropped_paths(paths) {
-            Ok(xlsx_files) => {
-                let mut added_count = 0;
-                for path in xlsx_files {
-                    if !self.files.iter().any(|f| f.file_path == path) {
-                        let mut file_info = FileInfo::from_path(path);
-
-                        match ExcelInfo::from_file(&file_info.file_path) {
-                            Ok(info) => {
-                                if let Some(columns) = info.first_sheet_columns() {
-                                    file_info.columns = columns.clone();
-                                    for col in columns {
-                                        if !self.available_columns.contains(col) {
-                                            self.available_columns.push(col.clone());
-                                        }
-                                    }
-                                }
-                                file_info.row_count = info.total_row_count() as u32;
-                            }
-                            Err(e) => {
-                                file_info.status = FileStatus::error(e.to_string());
-                            }
-                        }
-                        self.files.push(file_info);
-                        added_count += 1;
-                    }
+    fn show_password_dialog(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.password_queue.first() else {
+            return;
+        };
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let mut submit = false;
+        let mut cancel = false;
+
+        egui::Window::new(tr("password.title"))
+            .id(egui::Id::new("password_dialog"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(trf("password.prompt", &[&file_name]));
+
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.password_input)
+                        .password(true)
+                        .desired_width(240.0),
+                );
+                response.request_focus();
+                if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                    submit = true;
+                }
+
+                if let Some(error) = &self.password_error {
+                    ui.label(RichText::new(error).small().color(Color32::from_rgb(0xF4, 0x43, 0x36)));
                 }
 
-                smart_select_column(&self.available_columns, &mut self.config.target_column);
+                ui.horizontal(|ui| {
+                    if ui.button(tr("password.ok")).clicked() {
+                        submit = true;
+                    }
+                    if ui.button(tr("password.cancel")).clicked() {
+                        cancel = true;
+                    }
+                });
+            });
 
-                if added_count > 0 {
-                    self.status_message = format!("已导入 {} 个文件", added_count);
-                    self.error_message = None;
+        if submit {
+            self.submit_password();
+        } else if cancel {
+            self.skip_password_file();
+        }
+    }
+
+    /// 在后台线程下载地址栏中的远程 xlsx，完成后按拖入文件的流程导入
+    fn start_download(&mut self) {
+        let url = self.url_input.trim().to_string();
+        if url.is_empty() || self.download_receiver.is_some() {
+            return;
+        }
+
+        let (sender, receiver) = mpsc::channel();
+        self.download_receiver = Some(receiver);
+        self.download_progress = Some((0, None));
+        self.status_message = tr("status.downloading").to_string();
+        self.error_message = None;
+
+        thread::spawn(move || {
+            let progress_sender = sender.clone();
+            let result = download_xlsx(&url, |downloaded, total| {
+                let _ = progress_sender.send(DownloadMessage::Progress(downloaded, total));
+            });
+            let _ = sender.send(DownloadMessage::Finished(result.map_err(|e| format!("{:#}", e))));
+        });
+    }
+
+    fn poll_download(&mut self) {
+        let Some(rx) = self.download_receiver.take() else {
+            return;
+        };
+
+        loop {
+            match rx.try_recv() {
+                Ok(DownloadMessage::Progress(downloaded, total)) => {
+                    self.download_progress = Some((downloaded, total));
+                }
+                Ok(DownloadMessage::Finished(result)) => {
+                    self.finish_download(result);
+                    return;
                 }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.finish_download(Err(tr("status.download_interrupted").to_string()));
+                    return;
+                }
+            }
+        }
+
+        self.download_receiver = Some(rx);
+    }
+
+    fn finish_download(&mut self, result: Result<PathBuf, String>) {
+        self.download_progress = None;
+        match result {
+            Ok(path) => {
+                self.url_input.clear();
+                self.handle_dropped_files(&[path]);
             }
             Err(e) => {
-                self.error_message = Some(format!("处理文件失败: {}", e));
+                self.status_message = tr("status.ready").to_string();
+                self.error_message = Some(trf("status.download_failed", &[&e]));
+            }
+        }
+    }
+
+    fn show_url_input(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("main.url"));
+            let downloading = self.download_progress.is_some();
+
+            let response = ui.add_enabled(
+                !downloading,
+                egui::TextEdit::singleline(&mut self.url_input)
+                    .desired_width(360.0)
+                    .hint_text(tr("main.url_hint")),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+
+            let can_download = !downloading && !self.url_input.trim().is_empty();
+            if ui.add_enabled(can_download, egui::Button::new(tr("main.download"))).clicked()
+                || (submitted && can_download)
+            {
+                self.start_download();
+            }
+
+            if let Some((downloaded, total)) = self.download_progress {
+                match total {
+                    Some(total) if total > 0 => {
+                        let fraction = downloaded as f32 / total as f32;
+                        ui.add(
+                            egui::ProgressBar::new(fraction)
+                                .text(format!("{} / {}", format_file_size(downloaded), format_file_size(total)))
+                                .desired_width(200.0),
+                        );
+                    }
+                    _ => {
+                        ui.spinner();
+                        ui.label(format_file_size(downloaded));
+                    }
+                }
+            }
+        });
+    }
+
+    /// 对尚未抽样的文件做命中密度抽样
+    fn sample_hit_densities(&mut self) {
+        let processor = Processor::new(self.config.clone());
+
+        for file in &mut self.files {
+            if file.hit_density.is_some() || file.metadata_loading || file.status.is_unprocessable() {
+                continue;
+            }
+
+            match processor.sample_hit_density(&file.file_path, self.config.density_sample_rows) {
+                Ok(density) => file.hit_density = Some(density),
+                Err(e) => tracing::warn!("抽样文件 {} 失败: {}", file.file_name, e),
             }
         }
     }
 
     fn start_processing(&mut self) {
         if self.files.is_empty() {
-            self.error_message = Some("请先导入文件".to_string());
+            self.error_message = Some(tr("status.no_files").to_string());
             return;
         }
 
         if !self.config.has_any_extraction_enabled() {
-            self.error_message = Some("请至少选择一种提取类型".to_string());
+            self.error_message = Some(tr("status.no_extraction_type").to_string());
             return;
         }
 
-        let files_to_process: Vec<FileInfo> = self.files
+        if self.config.prioritize_by_density {
+            self.sample_hit_densities();
+        }
+
+        let mut files_to_process: Vec<FileInfo> = self.files
             .iter()
-            .filter(|f| f.selected && !f.status.is_error())
+            .filter(|f| f.selected && !f.status.is_unprocessable())
             .cloned()
             .collect();
 
+        if self.config.prioritize_by_density {
+            files_to_process.sort_by(|a, b| {
+                b.hit_density
+                    .unwrap_or(0.0)
+                    .total_cmp(&a.hit_density.unwrap_or(0.0))
+            });
+        }
+
         if files_to_process.is_empty() {
-            self.error_message = Some("没有可处理的文件".to_string());
+            self.error_message = Some(tr("status.no_selected_files").to_string());
             return;
         }
 
+        let stream_output = if self.config.stream_export {
+            match self.stream_output(&files_to_process) {
+                Some(output) => Some(output),
+                None => {
+                    self.error_message = Some(tr("status.stream_unsupported").to_string());
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+
         self.processing = true;
         self.error_message = None;
-        self.status_message = "正在处理...".to_string();
+        self.status_message = tr("status.processing").to_string();
         self.progress = 0;
         self.current_file.clear();
         self.results.clear();
+        self.result_view.invalidate();
         self.statistics = None;
+        self.live_statistics = None;
+        self.preview_mode = false;
+        self.failed_files.clear();
+        self.failed_banner_dismissed = false;
 
         for file in &mut self.files {
-            if file.selected {
+            if file.selected && !file.status.is_unprocessable() {
                 file.status = FileStatus::processing(0);
+                file.result_summary = None;
             }
         }
 
@@ -200,44 +603,164 @@ impl MainWindow {
         self.processing_receiver = Some(receiver);
 
         let config = self.config.clone();
+        let webhook_config = (!config.webhook_url.trim().is_empty()).then(|| config.clone());
+        let control = Arc::new(ProcessingControl::default());
+        self.processing_control = Some(Arc::clone(&control));
 
         let handle = thread::spawn(move || {
-            let processor = Processor::new(config);
-
-            // 克隆 sender 用于并行处理中的进度回调
-            let sender_for_progress = sender.clone();
-
-            // 使用 rayon 并行处理文件，返回结果和耗时
-            let (results, elapsed_secs) = processor
-                .process_files_parallel(&files_to_process, move |file_name, progress| {
-                    let _ = sender_for_progress.send(ProcessingMessage::Progress(
-                        file_name.to_string(),
-                        progress,
-                    ));
-                });
+            let sender_for_panic = sender.clone();
 
-            let mut all_results = Vec::new();
-            for (file_name, result) in results {
-                match result {
-                    Ok(file_results) => {
-                        all_results.extend(file_results);
-                    }
-                    Err(e) => {
-                        tracing::error!("处理文件 {} 失败: {}", file_name, e);
+            let outcome = panic::catch_unwind(AssertUnwindSafe(move || {
+                if let Some((format, output_path)) = stream_output {
+                    stream_to_file(config, control, &files_to_process, format, output_path, &sender);
+                    return;
+                }
+
+                let sender_for_results = sender.clone();
+                let processor = Processor::new(config)
+                    .with_control(Arc::clone(&control))
+                    .with_result_callback(move |results| {
+                        let delta = ProcessingStatistics::from_results(results, 0.0);
+                        let _ = sender_for_results.send(ProcessingMessage::Partial(delta));
+                    });
+
+                // 克隆 sender 用于并行处理中的进度回调
+                let sender_for_progress = sender.clone();
+
+                // 默认使用 rayon 并行处理文件，返回结果和耗时
+                let (results, elapsed_secs) = processor
+                    .process_files(&files_to_process, move |file_name, progress| {
+                        let _ = sender_for_progress.send(ProcessingMessage::Progress(
+                            file_name.to_string(),
+                            progress,
+                        ));
+                    });
+
+                if control.is_cancelled() {
+                    let _ = sender.send(ProcessingMessage::Cancelled);
+                    return;
+                }
+
+                let mut all_results = Vec::new();
+                for (file_name, file_path, result) in results {
+                    match result {
+                        Ok(file_results) => {
+                            all_results.extend(file_results);
+                        }
+                        Err(e) => {
+                            tracing::error!("处理文件 {} 失败: {}", file_name, e);
+                            let _ = sender.send(ProcessingMessage::FileError(file_path, e.to_string()));
+                        }
                     }
                 }
-            }
 
-            let stats = processor.generate_statistics(&all_results, elapsed_secs);
-            let _ = sender.send(ProcessingMessage::Completed(all_results, stats));
+                for (file_path, warning) in processor.take_warnings() {
+                    let _ = sender.send(ProcessingMessage::FileWarning(file_path, warning));
+                }
+
+                let stats = processor.generate_statistics(&all_results, elapsed_secs);
+                tracing::info!("处理完成\n{}", stats.to_report_string());
+
+                // 推送在单独的线程中进行，界面回收处理线程时不必等待网络请求
+                if let Some(webhook_config) = webhook_config {
+                    let summary = stats.clone();
+                    let file_count = files_to_process.len();
+                    thread::spawn(move || webhook::notify_completion(&webhook_config, &summary, file_count));
+                }
+                let _ = sender.send(ProcessingMessage::Completed(all_results, stats));
+            }));
+
+            if let Err(payload) = outcome {
+                let message = panic_message(&*payload);
+                tracing::error!("处理线程异常终止: {}", message);
+                let _ = sender_for_panic.send(ProcessingMessage::Failed(message));
+            }
         });
 
         self.processing_handle = Some(handle);
     }
 
+    /// 流式导出的格式和路径：沿用导出菜单上次选择的格式，否则取配置的第一种导出格式；
+    /// 文件名按模板以第一个文件命名，不支持流式导出的格式返回 `None`
+    fn stream_output(&self, files: &[FileInfo]) -> Option<(OutputFormat, PathBuf)> {
+        let format = self.config
+            .last_export_format
+            .or_else(|| self.config.output_formats.first().copied())
+            .unwrap_or(OutputFormat::Xlsx);
+        if format == OutputFormat::Parquet {
+            return None;
+        }
+
+        let source_name = files
+            .first()
+            .and_then(|f| f.file_path.file_stem())
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_else(|| "result".to_string());
+        let file_stem = render_filename_template(
+            &self.config.output_filename_template,
+            &source_name,
+            &ProcessingStatistics::default(),
+        );
+        let path = std::env::current_dir()
+            .unwrap_or_default()
+            .join(format!("{}.{}", file_stem, stream_extension(format)));
+        Some((format, path))
+    }
+
+    /// 从 JSON/TOML 文件载入配置，保存格式随之切换为该文件的格式
+    fn import_config(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(tr("main.config_filter"), &["json", "toml"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        match Config::load(&path) {
+            Ok(mut config) => {
+                // 打开密码不写入配置文件，沿用当前会话中的
+                config.excel_password = self.config.excel_password.take();
+                config.archive_passphrase = self.config.archive_passphrase.take();
+                self.config = config;
+                self.config_format = ConfigFormat::from_path(&path).unwrap_or_default();
+                self.status_message = trf("status.config_loaded", &[&path.display()]);
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(trf("status.config_failed", &[&format!("{:#}", e)]));
+            }
+        }
+    }
+
+    fn save_config(&mut self) {
+        let extension = self.config_format.extension();
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter(self.config_format.label(), &[extension])
+            .set_file_name(format!("config.{}", extension))
+            .save_file()
+        else {
+            return;
+        };
+
+        match self.config.save(&path, self.config_format) {
+            Ok(()) => {
+                self.status_message = trf("status.config_saved", &[&path.display()]);
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(trf("status.config_failed", &[&format!("{:#}", e)]));
+            }
+        }
+    }
+
     fn export_results(&mut self) {
         if self.results.is_empty() {
-            self.error_message = Some("没有可导出的结果".to_string());
+            self.error_message = Some(tr("status.no_results_to_export").to_string());
+            return;
+        }
+
+        if let Err(e) = self.apply_annotations() {
+            self.error_message = Some(trf("status.export_failed", &[&format!("{:#}", e)]));
             return;
         }
 
@@ -246,95 +769,632 @@ impl MainWindow {
             .map(|r| r.source_file.clone())
             .unwrap_or_else(|| "result".to_string());
 
-        let source_name = source_name.trim_end_matches(".xlsx").trim_end_matches(".XLSX");
+        let source_name = std::path::Path::new(&source_name)
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or(source_name);
+
+        let stats = self.statistics.clone().unwrap_or_default();
+        let file_stem = render_filename_template(&self.config.output_filename_template, &source_name, &stats);
 
-        let output_path = std::env::current_dir()
+        let base_path = std::env::current_dir()
             .unwrap_or_default()
-            .join(generate_output_filename_with_source(source_name));
+            .join(file_stem);
+
+        let exporter = match self.config.last_export_format {
+            Some(format) => MultiExporter::for_format(&self.config, format),
+            None => MultiExporter::from_config(&self.config),
+        };
+
+        match exporter.export(&self.results, &base_path) {
+            Ok(paths) => {
+                let paths: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+                self.status_message = trf("status.exported", &[&paths.join(", ")]);
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(trf("status.export_failed", &[&e]));
+            }
+        }
+    }
+
+    /// 按批注文件填写各条结果的备注；未设置批注文件时清空，避免带出上次合并的备注
+    fn apply_annotations(&mut self) -> anyhow::Result<()> {
+        match &self.config.annotation_file {
+            Some(path) => {
+                let annotations = Annotations::load(path, self.config.annotation_key)?;
+                annotations.apply(&mut self.results);
+                tracing::info!("已从 {} 合并 {} 条批注", path.display(), annotations.len());
+            }
+            None => self.results.iter_mut().for_each(|r| r.remark.clear()),
+        }
+        Ok(())
+    }
+
+    /// 从导出菜单选择格式导出，并记住该格式供导出按钮沿用
+    fn export_results_as(&mut self, format: OutputFormat) {
+        self.config.last_export_format = Some(format);
+        self.export_results();
+    }
+
+    /// 有结果且未在处理中时才能导出，预览结果不完整，不能导出
+    fn can_export(&self) -> bool {
+        !self.results.is_empty() && !self.processing && !self.preview_mode
+    }
+
+    /// 已加载文件且尚未正式处理（或当前显示的是预览）时可以预览
+    fn can_preview(&self) -> bool {
+        !self.files.is_empty()
+            && !self.processing
+            && !self.metadata_pending()
+            && (self.statistics.is_none() || self.preview_mode)
+    }
+
+    /// 只提取第一个待处理文件的前几行，在结果摘要中显示
+    fn preview_first_file(&mut self) {
+        let Some(file) = self.files.iter().find(|f| f.selected && !f.status.is_unprocessable()) else {
+            self.error_message = Some(tr("status.no_selected_files").to_string());
+            return;
+        };
 
         let processor = Processor::new(self.config.clone());
+        let start_time = std::time::Instant::now();
+        match processor.preview_results(file, PREVIEW_ROWS) {
+            Ok(results) => {
+                self.statistics = Some(processor.generate_statistics(&results, start_time.elapsed().as_secs_f64()));
+                self.status_message = trf("status.preview_done", &[&file.file_name, &results.len()]);
+                self.results = results;
+                self.result_view.invalidate();
+                self.preview_mode = true;
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(trf("status.preview_failed", &[&format!("{:#}", e)]));
+            }
+        }
+    }
 
-        match processor.export_results(&self.results, &output_path) {
-            Ok(()) => {
-                self.status_message = format!("结果已导出到: {}", output_path.display());
+    fn export_results_per_file(&mut self) {
+        if self.results.is_empty() {
+            self.error_message = Some(tr("status.no_results_to_export").to_string());
+            return;
+        }
+
+        if let Err(e) = self.apply_annotations() {
+            self.error_message = Some(trf("status.export_failed", &[&format!("{:#}", e)]));
+            return;
+        }
+
+        let Some(output_dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        let processor = Processor::new(self.config.clone());
+
+        match processor.export_results_per_file(&self.results, &output_dir) {
+            Ok(paths) => {
+                self.status_message = trf(
+                    "status.exported_per_file",
+                    &[&paths.len(), &output_dir.display()],
+                );
                 self.error_message = None;
             }
             Err(e) => {
-                self.error_message = Some(format!("导出失败: {}", e));
+                self.error_message = Some(trf("status.export_failed", &[&e]));
+            }
+        }
+    }
+
+    /// 把结果、统计报告、运行信息和文件清单打包为一个 zip，便于整批交付
+    fn export_archive(&mut self) {
+        if self.results.is_empty() {
+            self.error_message = Some(tr("status.no_results_to_export").to_string());
+            return;
+        }
+
+        if let Err(e) = self.apply_annotations() {
+            self.error_message = Some(trf("status.export_failed", &[&format!("{:#}", e)]));
+            return;
+        }
+
+        let cipher = match self.config.archive_passphrase.as_deref().map(FieldCipher::new).transpose() {
+            Ok(cipher) => cipher,
+            Err(e) => {
+                self.error_message = Some(trf("status.export_failed", &[&format!("{:#}", e)]));
+                return;
+            }
+        };
+
+        let Some(output_dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        let stats = self.statistics.clone().unwrap_or_default();
+        let mut archive = ResultArchive::new(&self.config, &self.results, &stats, &self.files);
+        if let Some(cipher) = &cipher {
+            archive = archive.with_encryption(cipher);
+        }
+        match archive.export(&output_dir) {
+            Ok(path) => {
+                self.status_message = trf("status.exported_archive", &[&path.display()]);
+                self.error_message = None;
+            }
+            Err(e) => {
+                self.error_message = Some(trf("status.export_failed", &[&format!("{:#}", e)]));
+            }
+        }
+    }
+
+    /// 暂停或恢复处理，暂停期间进度保持不变
+    fn toggle_pause(&mut self) {
+        let Some(control) = &self.processing_control else {
+            return;
+        };
+
+        if control.is_paused() {
+            control.resume();
+            self.status_message = tr("status.processing").to_string();
+        } else {
+            control.pause();
+            self.status_message = tr("status.paused").to_string();
+        }
+    }
+
+    /// 请求取消处理（暂停中也可取消），处理线程退出后由 `Cancelled` 消息收尾
+    fn cancel_processing(&mut self) {
+        if let Some(control) = &self.processing_control {
+            control.cancel();
+            self.status_message = tr("status.cancelling").to_string();
+        }
+    }
+
+    fn is_paused(&self) -> bool {
+        self.processing_control.as_ref().is_some_and(|control| control.is_paused())
+    }
+
+    /// 处理线程异常终止时恢复就绪状态，未完成的文件标记为错误
+    fn fail_processing(&mut self, error: String) {
+        self.live_statistics = None;
+        self.processing = false;
+        self.processing_control = None;
+        self.status_message = tr("status.interrupted").to_string();
+        self.error_message = Some(trf("status.aborted", &[&error]));
+
+        for file in &mut self.files {
+            if matches!(file.status, FileStatus::Processing(_)) {
+                file.status = FileStatus::error(error.clone());
+            }
+        }
+    }
+
+    /// 文件列表快捷键：Ctrl+A 全选、Ctrl+D 取消全选、Ctrl+Delete 移除勾选的文件；
+    /// 处理中或文本框有焦点时不响应，以免抢走输入框的全选
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if self.processing || ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let (select_all, deselect_all, remove_selected) = ctx.input_mut(|i| {
+            (
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::A),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::D),
+                i.consume_key(egui::Modifiers::COMMAND, egui::Key::Delete),
+            )
+        });
+
+        if select_all {
+            FileList::set_all_selected(&mut self.files, true);
+        }
+        if deselect_all {
+            FileList::set_all_selected(&mut self.files, false);
+        }
+        if remove_selected {
+            let removed = FileList::remove_selected(&mut self.files);
+            if removed > 0 {
+                self.status_message = trf("status.files_removed", &[&removed]);
+            }
+        }
+    }
+
+    /// 取出工作线程的全部消息；处理结束或线程意外退出时回收线程
+    fn poll_processing(&mut self) {
+        let Some(rx) = self.processing_receiver.take() else {
+            return;
+        };
+
+        let mut finished = false;
+        loop {
+            let msg = match rx.try_recv() {
+                Ok(msg) => msg,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    // 线程退出却没有发送完成消息，视为异常终止
+                    if !finished {
+                        self.fail_processing(tr("status.thread_exited").to_string());
+                        finished = true;
+                    }
+                    break;
+                }
+            };
+
+            if self.handle_processing_message(msg) {
+                finished = true;
+            }
+        }
+
+        if finished {
+            if let Some(handle) = self.processing_handle.take() {
+                let _ = handle.join();
+            }
+        } else {
+            self.processing_receiver = Some(rx);
+        }
+    }
+
+    /// 处理来自工作线程的消息，返回处理是否已结束
+    fn handle_processing_message(&mut self, msg: ProcessingMessage) -> bool {
+        match msg {
+            ProcessingMessage::Progress(file_name, progress) => {
+                self.current_file = file_name;
+                self.progress = progress;
+                false
+            }
+            ProcessingMessage::Partial(delta) => {
+                self.live_statistics.get_or_insert_default().merge(&delta);
+                false
+            }
+            ProcessingMessage::FileError(file_path, error) => {
+                for file in &mut self.files {
+                    if file.file_path == file_path && file.selected {
+                        file.status = FileStatus::error(error.clone());
+                    }
+                }
+                self.failed_files.push((display_file_name(&file_path), error));
+                false
+            }
+            ProcessingMessage::FileWarning(file_path, warning) => {
+                for file in &mut self.files {
+                    if file.file_path == file_path && matches!(file.status, FileStatus::Processing(_)) {
+                        file.status = FileStatus::warning(warning.clone());
+                    }
+                }
+                false
+            }
+            ProcessingMessage::Completed(results, stats) => {
+                self.results = results;
+                self.result_view.invalidate();
+                let elapsed_str = format_elapsed(stats.elapsed_secs);
+                self.statistics = Some(stats.clone());
+                self.live_statistics = None;
+                self.processing = false;
+                self.processing_control = None;
+                self.progress = 100;
+                self.status_message = trf(
+                    "status.completed",
+                    &[&self.results.len(), &stats.total_sensitive_info(), &elapsed_str],
+                );
+
+                for file in &mut self.files {
+                    let processed = match file.status {
+                        FileStatus::Processing(_) => {
+                            file.status = FileStatus::completed();
+                            true
+                        }
+                        FileStatus::Warning(_) => true,
+                        _ => false,
+                    };
+                    // 同名文件可能来自不同目录，按源文件路径归属结果
+                    if processed {
+                        let file_path = file.file_path.display().to_string();
+                        file.result_summary = Some(FileResultSummary::from_results(
+                            self.results.iter().filter(|r| r.source_path == file_path),
+                        ));
+                    }
+                }
+
+                true
+            }
+            ProcessingMessage::Streamed(output_path, stats) => {
+                self.status_message = trf("status.streamed", &[&stats.total_results, &output_path.display()]);
+                self.statistics = Some(stats);
+                self.live_statistics = None;
+                self.processing = false;
+                self.processing_control = None;
+                self.progress = 100;
+
+                for file in &mut self.files {
+                    if matches!(file.status, FileStatus::Processing(_)) {
+                        file.status = FileStatus::completed();
+                    }
+                }
+
+                true
+            }
+            ProcessingMessage::Cancelled => {
+                self.live_statistics = None;
+                self.processing = false;
+                self.processing_control = None;
+                self.progress = 0;
+                self.status_message = tr("status.cancelled").to_string();
+
+                for file in &mut self.files {
+                    if matches!(file.status, FileStatus::Processing(_)) {
+                        file.status = FileStatus::Pending;
+                    }
+                }
+
+                true
+            }
+            ProcessingMessage::Failed(error) => {
+                self.fail_processing(error);
+                true
             }
         }
     }
 
+    /// 处理完成后失败文件提示的标题，无失败或已关闭时为 `None`
+    fn failed_files_banner_title(&self) -> Option<String> {
+        if self.processing || self.failed_banner_dismissed || self.failed_files.is_empty() {
+            return None;
+        }
+        Some(trf("main.failed_banner", &[&self.failed_files.len()]))
+    }
+
+    fn show_failed_files_banner(&mut self, ui: &mut egui::Ui) {
+        let Some(title) = self.failed_files_banner_title() else {
+            return;
+        };
+
+        let warning_color = Color32::from_rgb(0xFF, 0x98, 0x00);
+
+        egui::Frame::group(ui.style())
+            .stroke(egui::Stroke::new(1.0, warning_color))
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    egui::CollapsingHeader::new(RichText::new(title).color(warning_color))
+                        .id_salt("failed_files_banner")
+                        .default_open(false)
+                        .show(ui, |ui| {
+                            for (file_name, error) in &self.failed_files {
+                                ui.label(RichText::new(format!("• {}: {}", file_name, error)).small());
+                            }
+                        });
+
+                    if ui.small_button("✕").on_hover_text(tr("main.dismiss")).clicked() {
+                        self.failed_banner_dismissed = true;
+                    }
+                });
+            });
+    }
+
+    /// 处理中的实时统计：各类型命中数及其占全部命中的比例
+    fn show_live_dashboard(ui: &mut egui::Ui, stats: &ProcessingStatistics) {
+        ui.label(RichText::new(trf("main.live_results", &[&stats.total_results])).strong());
+        ui.separator();
+
+        let total = stats.total_sensitive_info().max(1) as f32;
+        let rows = [
+            (tr("type.phone"), stats.total_phones, stats.valid_phones),
+            (tr("type.id_card"), stats.total_id_cards, stats.valid_id_cards),
+            (tr("type.bank_card"), stats.total_bank_cards, stats.valid_bank_cards),
+            (tr("type.name"), stats.total_names, stats.valid_names),
+            (tr("type.macau_id"), stats.total_macau_ids, stats.valid_macau_ids),
+            (tr("type.wechat_id"), stats.total_wechat_ids, stats.valid_wechat_ids),
+        ];
+        egui::Grid::new("live_dashboard_grid").num_columns(2).show(ui, |ui| {
+            for (label, count, valid) in rows.into_iter().filter(|(_, count, _)| *count > 0) {
+                ui.label(label);
+                ui.add(
+                    egui::ProgressBar::new(count as f32 / total)
+                        .desired_width(240.0)
+                        .text(trf("main.count_valid", &[&count, &valid])),
+                );
+                ui.end_row();
+            }
+        });
+    }
+
+    /// 单个类型的摘要行，展开后显示前若干条脱敏示例及来源行号
+    ///
+    /// 示例值可右键加入排除列表，返回被选中的示例
+    fn show_type_summary<'s>(
+        ui: &mut egui::Ui,
+        label: &str,
+        counts: &str,
+        samples: &'s [MatchSample],
+    ) -> Option<&'s MatchSample> {
+        let mut excluded = None;
+        egui::CollapsingHeader::new(format!("{}: {}", label, counts))
+            .id_salt(("summary_samples", label))
+            .default_open(false)
+            .show(ui, |ui| {
+                if samples.is_empty() {
+                    ui.label(RichText::new(tr("main.no_hits")).small().color(Color32::GRAY));
+                }
+                for sample in samples {
+                    ui.horizontal(|ui| {
+                        let value = ui.add(
+                            egui::Label::new(RichText::new(&sample.value).monospace()).sense(egui::Sense::click())
+                        );
+                        value.context_menu(|ui| {
+                            if ui.button(tr("main.exclude_value")).clicked() {
+                                excluded = Some(sample);
+                                ui.close();
+                            }
+                        });
+                        ui.label(
+                            RichText::new(trf("main.sample_source", &[&sample.source_file, &sample.row_number]))
+                                .small()
+                                .color(Color32::GRAY)
+                        );
+                    });
+                }
+            });
+        excluded
+    }
+
+    /// 把摘要中右键选中的命中值加入排除列表，下次处理时生效
+    fn exclude_sample(&mut self, sample: &MatchSample) {
+        if self.config.add_exclude_value(&sample.raw_value) {
+            self.status_message = trf("status.excluded", &[&sample.value]);
+        }
+    }
+
     fn clear_all(&mut self) {
+        if let Some(control) = self.processing_control.take() {
+            control.cancel();
+        }
         self.files.clear();
         self.available_columns.clear();
+        self.column_sheet = None;
         self.results.clear();
+        self.result_view.invalidate();
         self.statistics = None;
-        self.config = Config::default();
-        self.status_message = "已清空".to_string();
+        self.live_statistics = None;
+        self.preview_mode = false;
+        self.user_has_manually_selected = false;
+        self.failed_files.clear();
+        self.password_queue.clear();
+        self.password_input.clear();
+        self.password_error = None;
+        // 清空只重置提取配置，界面语言和排除列表保持不变
+        self.config = Config {
+            language: self.config.language,
+            exclude_values: std::mem::take(&mut self.config.exclude_values),
+            ..Default::default()
+        };
+        self.status_message = tr("status.cleared").to_string();
         self.error_message = None;
         self.processing_receiver = None;
         self.processing_handle = None;
     }
 }
 
-impl eframe::App for MainWindow {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        let receiver = self.processing_receiver.take();
-        if let Some(rx) = receiver {
-            let mut should_restore = true;
-            let mut completed = false;
-
-            while let Ok(msg) = rx.try_recv() {
-                match msg {
-                    ProcessingMessage::Progress(file_name, progress) => {
-                        self.current_file = file_name;
-                        self.progress = progress;
-                    }
-                    ProcessingMessage::Completed(results, stats) => {
-                        self.results = results;
-                        let elapsed_str = if stats.elapsed_secs >= 60.0 {
-                            let mins = (stats.elapsed_secs / 60.0).floor() as u32;
-                            let secs = (stats.elapsed_secs % 60.0) as u32;
-                            format!("{}分{}秒", mins, secs)
-                        } else {
-                            format!("{:.2}秒", stats.elapsed_secs)
-                        };
-                        self.statistics = Some(stats.clone());
-                        self.processing = false;
-                        self.progress = 100;
-                        self.status_message = format!(
-                            "提取完成，共 {} 条结果 (敏感信息: {} 条)，耗时 {}",
-                            self.results.len(),
-                            stats.total_sensitive_info(),
-                            elapsed_str
-                        );
+/// 预览读取的行数（含表头）
+const PREVIEW_ROWS: usize = 20;
+
+/// 摘要明细中每种类型展示的示例数
+const SUMMARY_SAMPLE_LIMIT: usize = 5;
+
+/// 摘要中展示的命中示例
+#[derive(Debug, Clone, PartialEq)]
+struct MatchSample {
+    /// 脱敏后的命中值
+    value: String,
+    /// 原始命中值，加入排除列表时使用
+    raw_value: String,
+    source_file: String,
+    row_number: u32,
+}
 
-                        for file in &mut self.files {
-                            if file.selected {
-                                file.status = FileStatus::completed();
-                            }
-                        }
+/// 导出菜单中的格式选项
+/// 在处理线程中流式处理并写出到文件，只把进度、文件错误和统计发回界面
+fn stream_to_file(
+    config: Config,
+    control: Arc<ProcessingControl>,
+    files: &[FileInfo],
+    format: OutputFormat,
+    output_path: PathBuf,
+    sender: &Sender<ProcessingMessage>,
+) {
+    let outcome = create_stream_writer(&config, format, &output_path).and_then(|mut writer| {
+        BatchProcessor::new(config)
+            .with_control(Arc::clone(&control))
+            .process_and_stream(files, writer.as_mut(), |file_name, progress| {
+                let _ = sender.send(ProcessingMessage::Progress(file_name.to_string(), progress));
+            })
+    });
+
+    let summary = match outcome {
+        Ok(summary) => summary,
+        Err(e) => {
+            tracing::error!("流式导出失败: {:#}", e);
+            let _ = sender.send(ProcessingMessage::Failed(format!("{:#}", e)));
+            return;
+        }
+    };
 
-                        should_restore = false;
-                        completed = true;
-                    }
-                }
-            }
+    if control.is_cancelled() {
+        let _ = sender.send(ProcessingMessage::Cancelled);
+        return;
+    }
 
-            if should_restore && !completed {
-                self.processing_receiver = Some(rx);
-            }
+    for (file_path, error) in summary.failed_files {
+        let _ = sender.send(ProcessingMessage::FileError(file_path, error));
+    }
+    for (file_path, warning) in summary.warnings {
+        let _ = sender.send(ProcessingMessage::FileWarning(file_path, warning));
+    }
 
-            if !should_restore {
-                self.processing_handle = None;
-            }
-        }
+    tracing::info!("流式导出完成: {}\n{}", output_path.display(), summary.statistics.to_report_string());
+    let _ = sender.send(ProcessingMessage::Streamed(output_path, summary.statistics));
+}
+
+fn export_format_label(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Xlsx => tr("export.xlsx"),
+        OutputFormat::Csv => tr("export.csv"),
+        OutputFormat::Json => tr("export.json"),
+        OutputFormat::Parquet => tr("export.parquet"),
+        OutputFormat::Html => tr("export.html"),
+    }
+}
+
+/// 统计和失败文件按文件路径区分，界面只显示文件名
+fn display_file_name(path: impl AsRef<Path>) -> String {
+    let path = path.as_ref();
+    path.file_name()
+        .map_or_else(|| path.display().to_string(), |name| name.to_string_lossy().to_string())
+}
 
-        if self.processing {
+/// 耗时文案，使用当前语言的时、分、秒单位
+fn format_elapsed(elapsed_secs: f64) -> String {
+    format_elapsed_time_with_units(elapsed_secs, [tr("unit.hour"), tr("unit.minute"), tr("unit.second")])
+}
+
+/// 按结果顺序取某类命中的前 `limit` 条示例；号码按脱敏规则掩码，姓名仅保留首字
+fn match_samples(results: &[ExtractResult], pattern: PatternType, rule: &MaskRule, limit: usize) -> Vec<MatchSample> {
+    results
+        .iter()
+        .flat_map(|result| {
+            let matches = match pattern {
+                PatternType::Phone => &result.phone_numbers,
+                PatternType::IdCard => &result.id_cards,
+                PatternType::BankCard => &result.bank_cards,
+                PatternType::NameApi => &result.names,
+                PatternType::MacauId => &result.macau_ids,
+                PatternType::WechatId => &result.wechat_ids,
+                PatternType::Custom => &result.custom_matches,
+            };
+            matches.iter().map(move |m| (result, m))
+        })
+        .take(limit)
+        .map(|(result, m)| MatchSample {
+            value: match pattern {
+                PatternType::NameApi => Masker::mask(&m.value, 1, 0),
+                _ => Masker::mask_with_rule(&m.value, rule),
+            },
+            raw_value: m.value.clone(),
+            source_file: result.source_file.clone(),
+            row_number: result.row_number,
+        })
+        .collect()
+}
+
+impl eframe::App for MainWindow {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        i18n::set_language(self.config.language);
+
+        self.poll_processing();
+        self.poll_download();
+        self.poll_metadata();
+
+        if self.processing || self.download_progress.is_some() || self.metadata_pending() {
             ctx.request_repaint();
         }
 
+        self.handle_shortcuts(ctx);
+
         ctx.input(|i| {
             if !i.raw.dropped_files.is_empty() {
                 let paths: Vec<PathBuf> = i.raw.dropped_files
@@ -347,9 +1407,10 @@ impl eframe::App for MainWindow {
             }
         });
 
+        let mut excluded_sample = None;
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.vertical_centered(|ui| {
-                ui.heading("敏感信息提取工具");
+                ui.heading(tr("app.title"));
             });
             ui.separator();
 
@@ -360,24 +1421,44 @@ impl eframe::App for MainWindow {
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
-                if ui.button("📂 选择文件").clicked() {
-                    if let Some(paths) = rfd::FileDialog::new()
-                        .add_filter("Excel", &["xlsx"])
+                if ui.button(tr("main.select_files")).clicked()
+                    && let Some(paths) = rfd::FileDialog::new()
+                        .add_filter(tr("main.file_filter"), &SUPPORTED_EXTENSIONS)
                         .pick_files()
-                    {
-                        self.handle_dropped_files(&paths);
-                    }
+                {
+                    self.handle_dropped_files(&paths);
                 }
-                if ui.button("📁 选择文件夹").clicked() {
-                    if let Some(path) = rfd::FileDialog::new().pick_folder() {
-                        self.handle_dropped_files(&[path]);
-                    }
+                if ui.button(tr("main.select_folder")).clicked()
+                    && let Some(path) = rfd::FileDialog::new().pick_folder()
+                {
+                    self.handle_dropped_files(&[path]);
                 }
-                if ui.button("🗑 清空").clicked() {
+                if ui.button(tr("main.clear")).clicked() {
                     self.clear_all();
                 }
+
+                ui.separator();
+
+                if ui.button(tr("main.import_config")).clicked() {
+                    self.import_config();
+                }
+                if ui.button(tr("main.save_config")).clicked() {
+                    self.save_config();
+                }
+                egui::ComboBox::from_id_salt("config_format")
+                    .selected_text(self.config_format.label())
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        for format in ConfigFormat::ALL {
+                            ui.selectable_value(&mut self.config_format, format, format.label());
+                        }
+                    })
+                    .response
+                    .on_hover_text(tr("main.config_format_hint"));
             });
 
+            self.show_url_input(ui);
+
             ui.add_space(10.0);
 
             ui.horizontal(|ui| {
@@ -388,11 +1469,25 @@ impl eframe::App for MainWindow {
 
                     ui.add_space(10.0);
 
-                    ColumnSelector::new(&self.available_columns, &mut self.config.target_column).show(ui);
+                    self.show_sheet_picker(ui);
+
+                    let columns = self.visible_columns().to_vec();
+                    if ColumnSelector::new(&columns, &mut self.config.target_column).show(ui) {
+                        self.user_has_manually_selected = true;
+                    }
 
                     ui.add_space(10.0);
 
-                    SettingsPanel::new(&mut self.config, &mut self.api_connection_status).show(ui);
+                    SettingsPanel::new(
+                        &mut self.config,
+                        &mut self.api_connection_status,
+                        &mut self.api_latency_ms,
+                        &mut self.mask_preview_sample,
+                        &mut self.exclude_input,
+                        &mut self.excluded_sheet_input,
+                        &mut self.extraction_preview,
+                    )
+                    .show(ui);
                 });
 
                 ui.separator();
@@ -401,43 +1496,135 @@ impl eframe::App for MainWindow {
                     ui.set_min_width(500.0);
 
                     ui.group(|ui| {
-                        ui.heading("提取结果摘要");
+                        ui.heading(tr("main.summary_title"));
+
+                        if self.preview_mode {
+                            ui.label(
+                                RichText::new(trf("main.preview_banner", &[&PREVIEW_ROWS]))
+                                    .color(Color32::from_rgb(0xFF, 0x98, 0x00)),
+                            );
+                        }
 
-                        if self.results.is_empty() {
-                            ui.label("暂无结果 - 点击【开始处理】提取敏感信息");
+                        if let Some(stats) = self.live_statistics.as_ref().filter(|_| self.processing) {
+                            Self::show_live_dashboard(ui, stats);
+                        } else if self.results.is_empty() {
+                            ui.label(tr("main.no_results"));
                         } else if let Some(stats) = &self.statistics {
                             // 显示耗时
-                            let elapsed_str = if stats.elapsed_secs >= 60.0 {
-                                let mins = (stats.elapsed_secs / 60.0).floor() as u32;
-                                let secs = (stats.elapsed_secs % 60.0) as u32;
-                                format!("{}分{}秒", mins, secs)
-                            } else {
-                                format!("{:.2}秒", stats.elapsed_secs)
-                            };
+                            let elapsed_str = format_elapsed(stats.elapsed_secs);
                             ui.horizontal(|ui| {
-                                ui.label(RichText::new(format!("⏱ 耗时: {}", elapsed_str)).strong());
+                                ui.label(RichText::new(trf("main.elapsed", &[&elapsed_str])).strong());
+                                if ui.small_button(tr("main.copy_summary")).clicked() {
+                                    ui.ctx().copy_text(stats.summary_as_markdown_table());
+                                }
                             });
-                            ui.label(format!("共 {} 条结果", stats.total_results));
+                            ui.label(trf("main.total_results", &[&stats.total_results]));
+                            ui.label(trf(
+                                "main.rows_scanned",
+                                &[&stats.rows_scanned, &format!("{:.1}", stats.extraction_rate())],
+                            ));
                             ui.separator();
 
-                            ui.horizontal(|ui| {
-                                ui.label("手机号:");
-                                ui.label(format!("{} 个 (有效 {})", stats.total_phones, stats.valid_phones));
-                            });
-                            ui.horizontal(|ui| {
-                                ui.label("身份证号:");
-                                ui.label(format!("{} 个 (有效 {})", stats.total_id_cards, stats.valid_id_cards));
-                            });
-                            ui.horizontal(|ui| {
-                                ui.label("银行卡号:");
-                                ui.label(format!("{} 个 (有效 {})", stats.total_bank_cards, stats.valid_bank_cards));
-                            });
-                            if stats.total_names > 0 {
-                                ui.horizontal(|ui| {
-                                    ui.label("姓名:");
-                                    ui.label(format!("{} 个 (可信 {})", stats.total_names, stats.valid_names));
+                            let summaries = [
+                                (tr("type.phone"), PatternType::Phone, trf("main.count_valid", &[&stats.total_phones, &stats.valid_phones])),
+                                (tr("type.id_card"), PatternType::IdCard, trf("main.count_valid", &[&stats.total_id_cards, &stats.valid_id_cards])),
+                                (tr("type.bank_card"), PatternType::BankCard, trf("main.count_valid", &[&stats.total_bank_cards, &stats.valid_bank_cards])),
+                                (tr("type.name"), PatternType::NameApi, trf("main.count_trusted", &[&stats.total_names, &stats.valid_names])),
+                                (tr("type.macau_id"), PatternType::MacauId, trf("main.count_valid", &[&stats.total_macau_ids, &stats.valid_macau_ids])),
+                                (tr("type.wechat_id"), PatternType::WechatId, trf("main.count_valid", &[&stats.total_wechat_ids, &stats.valid_wechat_ids])),
+                            ];
+
+                            for (label, pattern, counts) in summaries {
+                                if pattern == PatternType::NameApi && stats.total_names == 0 {
+                                    continue;
+                                }
+                                if pattern == PatternType::MacauId && stats.total_macau_ids == 0 {
+                                    continue;
+                                }
+                                if pattern == PatternType::WechatId && stats.total_wechat_ids == 0 {
+                                    continue;
+                                }
+                                let samples = match_samples(&self.results, pattern, &self.config.mask_rule, SUMMARY_SAMPLE_LIMIT);
+                                if let Some(sample) = Self::show_type_summary(ui, label, &counts, &samples) {
+                                    excluded_sample = Some(sample.clone());
+                                }
+                            }
+
+                            let pivot_tables = stats.pivot.tables();
+                            if !pivot_tables.is_empty() {
+                                ui.separator();
+                                ui.collapsing(tr("main.pivot_title"), |ui| {
+                                    for (title, _, counts) in pivot_tables {
+                                        let groups: Vec<String> = counts
+                                            .iter()
+                                            .map(|(name, count)| format!("{} {}", name, count))
+                                            .collect();
+                                        ui.label(RichText::new(title).strong());
+                                        ui.label(groups.join("，"));
+                                    }
+                                });
+                            }
+
+                            if !stats.file_stats.is_empty() {
+                                ui.collapsing(tr("main.file_stats_title"), |ui| {
+                                    egui::Grid::new("file_stats_grid").striped(true).show(ui, |ui| {
+                                        for (file_path, file_stats) in &stats.file_stats {
+                                            ui.label(display_file_name(file_path)).on_hover_text(file_path);
+                                            ui.label(trf(
+                                                "main.file_hit_rate",
+                                                &[
+                                                    &format!("{:.1}", file_stats.hit_rate()),
+                                                    &file_stats.cells_with_matches,
+                                                    &file_stats.non_empty_cells,
+                                                ],
+                                            ))
+                                            .on_hover_text(trf(
+                                                "main.file_cells_hint",
+                                                &[&file_stats.cells_processed, &file_stats.total_matches],
+                                            ));
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                            }
+
+                            if !stats.per_sheet.is_empty() {
+                                ui.collapsing(tr("main.sheet_stats_title"), |ui| {
+                                    for (file_index, (file_path, sheets)) in
+                                        stats.per_sheet_by_file().into_iter().enumerate()
+                                    {
+                                        egui::CollapsingHeader::new(display_file_name(file_path))
+                                            .id_salt(("sheet_stats_file", file_index))
+                                            .show(ui, |ui| {
+                                                egui::Grid::new(("sheet_stats_grid", file_index))
+                                                    .striped(true)
+                                                    .show(ui, |ui| {
+                                                        for (sheet_name, sheet_stats) in sheets {
+                                                            ui.label(sheet_name);
+                                                            ui.label(trf(
+                                                                "main.sheet_stats_row",
+                                                                &[&sheet_stats.result_rows, &sheet_stats.rows_scanned],
+                                                            ));
+                                                            ui.label(trf(
+                                                                "main.file_hit_rate",
+                                                                &[
+                                                                    &format!("{:.1}", sheet_stats.cells.hit_rate()),
+                                                                    &sheet_stats.cells.cells_with_matches,
+                                                                    &sheet_stats.cells.non_empty_cells,
+                                                                ],
+                                                            ));
+                                                            ui.end_row();
+                                                        }
+                                                    });
+                                            });
+                                    }
                                 });
                             }
+
+                            ui.separator();
+                            ui.collapsing(trf("main.result_view_title", &[&self.results.len()]), |ui| {
+                                self.result_view.show(ui, &self.results, &self.config.mask_rule);
+                            });
                         }
                     });
                 });
@@ -447,7 +1634,7 @@ impl eframe::App for MainWindow {
 
             if self.processing || self.progress > 0 {
                 ui.horizontal(|ui| {
-                    ui.label("进度:");
+                    ui.label(tr("main.progress"));
                     let available_width = ui.available_width().min(300.0);
                     let progress = egui::ProgressBar::new(self.progress as f32 / 100.0)
                         .text(format!("{}%", self.progress.min(100)))
@@ -461,19 +1648,57 @@ impl eframe::App for MainWindow {
             ui.horizontal(|ui| {
                 let process_enabled = !self.files.is_empty()
                     && !self.processing
+                    && !self.metadata_pending()
                     && self.config.has_any_extraction_enabled();
 
-                if ui.add_enabled(process_enabled, egui::Button::new("▶ 开始处理")).clicked() {
+                if ui.add_enabled(process_enabled, egui::Button::new(tr("main.start"))).clicked() {
                     self.start_processing();
                 }
 
-                let export_enabled = !self.results.is_empty() && !self.processing;
-                if ui.add_enabled(export_enabled, egui::Button::new("💾 导出结果")).clicked() {
+                let preview_label = trf("main.preview", &[&PREVIEW_ROWS]);
+                if ui.add_enabled(self.can_preview(), egui::Button::new(preview_label)).clicked() {
+                    self.preview_first_file();
+                }
+
+                if self.processing {
+                    let pause_label = if self.is_paused() { tr("main.resume") } else { tr("main.pause") };
+                    if ui.button(pause_label).clicked() {
+                        self.toggle_pause();
+                    }
+                    if ui.button(tr("main.cancel")).clicked() {
+                        self.cancel_processing();
+                    }
+                }
+
+                let export_enabled = self.can_export();
+                let export_label = match self.config.last_export_format {
+                    Some(format) => trf("main.export_as", &[&format.label()]),
+                    None => tr("main.export").to_string(),
+                };
+                if ui.add_enabled(export_enabled, egui::Button::new(export_label)).clicked() {
                     self.export_results();
                 }
+                ui.add_enabled_ui(export_enabled, |ui| {
+                    ui.menu_button("▾", |ui| {
+                        for format in OutputFormat::ALL {
+                            if ui.button(export_format_label(format)).clicked() {
+                                self.export_results_as(format);
+                                ui.close();
+                            }
+                        }
+                    });
+                });
+                if ui.add_enabled(export_enabled, egui::Button::new(tr("main.export_per_file"))).clicked() {
+                    self.export_results_per_file();
+                }
+                if ui.add_enabled(export_enabled, egui::Button::new(tr("main.export_archive"))).clicked() {
+                    self.export_archive();
+                }
             });
 
             ui.add_space(5.0);
+            self.show_failed_files_banner(ui);
+
             ui.separator();
             ui.horizontal(|ui| {
                 ui.label(&self.status_message);
@@ -481,6 +1706,502 @@ impl eframe::App for MainWindow {
                     ui.label(RichText::new(err).color(Color32::from_rgb(0xF4, 0x43, 0x36)));
                 }
             });
+
+            self.log_panel.show(ui);
         });
+
+        if let Some(sample) = excluded_sample {
+            self.exclude_sample(&sample);
+        }
+
+        self.show_password_dialog(ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MatchInfo;
+
+    fn processing_window(file_names: &[&str]) -> MainWindow {
+        let mut window = MainWindow {
+            processing: true,
+            ..Default::default()
+        };
+        for name in file_names {
+            let mut file = FileInfo::from_path(PathBuf::from(name));
+            file.status = FileStatus::processing(0);
+            window.files.push(file);
+        }
+        window
+    }
+
+    #[test]
+    fn test_export_enabled_only_with_results_and_idle() {
+        let mut window = MainWindow::default();
+        assert!(!window.can_export());
+
+        window.results.push(ExtractResult::new("a.xlsx", "Sheet1", 2));
+        assert!(window.can_export());
+
+        window.processing = true;
+        assert!(!window.can_export());
+    }
+
+    #[test]
+    fn test_preview_mode_blocks_export() {
+        let mut window = MainWindow::default();
+        assert!(!window.can_preview());
+
+        window.files.push(FileInfo::from_path(PathBuf::from("a.xlsx")));
+        assert!(window.can_preview());
+
+        window.results.push(ExtractResult::new("a.xlsx", "Sheet1", 2));
+        window.statistics = Some(ProcessingStatistics::default());
+        window.preview_mode = true;
+        assert!(window.can_preview());
+        assert!(!window.can_export());
+
+        // 正式处理完成后不再预览
+        window.preview_mode = false;
+        assert!(!window.can_preview());
+        assert!(window.can_export());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_export_menu_remembers_format() {
+        let mut window = MainWindow::default();
+        window.export_results_as(OutputFormat::Csv);
+
+        // 没有结果时不导出，但仍记住所选格式
+        assert_eq!(window.config.last_export_format, Some(OutputFormat::Csv));
+        assert!(window.error_message.is_some());
+    }
+
+    #[test]
+    fn test_manual_column_selection_survives_new_files() {
+        let mut window = MainWindow {
+            available_columns: vec!["姓名".to_string(), "备注".to_string()],
+            ..Default::default()
+        };
+        window.refresh_target_column();
+        assert_eq!(window.config.target_column, "备注");
+
+        // 手动改选后，新文件带来的推荐列不覆盖用户的选择
+        window.config.target_column = "姓名".to_string();
+        window.user_has_manually_selected = true;
+        window.available_columns.push("消息内容".to_string());
+        window.refresh_target_column();
+        assert_eq!(window.config.target_column, "姓名");
+
+        // 未手动选择时按推荐列自动改选
+        window.user_has_manually_selected = false;
+        window.refresh_target_column();
+        assert_eq!(window.config.target_column, "消息内容");
+    }
+
+    #[test]
+    fn test_column_list_follows_selected_sheet() {
+        let mut window = MainWindow::default();
+        let path = PathBuf::from("/data/a.xlsx");
+        window.files.push(FileInfo::loading(path.clone()));
+        let columns = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        window
+            .metadata_sender
+            .send(FileMetadataResult {
+                path: path.clone(),
+                columns: columns(&["姓名", "消息内容"]),
+                sheet_columns: vec![
+                    ("客服".to_string(), columns(&["姓名", "消息内容"])),
+                    ("附表".to_string(), columns(&["编号", "备注"])),
+                ],
+                row_count: 3,
+                error: None,
+            })
+            .unwrap();
+        window.poll_metadata();
+        assert_eq!(window.visible_columns(), columns(&["姓名", "消息内容"]));
+
+        window.column_sheet = Some((path, "附表".to_string()));
+        assert_eq!(window.visible_columns(), columns(&["编号", "备注"]));
+
+        // 所选文件被移除后退回所有文件的列
+        window.files.clear();
+        assert_eq!(window.visible_columns(), columns(&["姓名", "消息内容"]));
+    }
+
+    #[test]
+    fn test_metadata_loaded_in_background() {
+        let mut window = MainWindow::default();
+        let path = PathBuf::from("/data/a.xlsx");
+        let locked = PathBuf::from("/data/locked.xlsx");
+        window.files.push(FileInfo::loading(path.clone()));
+        window.files.push(FileInfo::loading(locked.clone()));
+        assert!(window.metadata_pending());
+        assert!(!window.can_preview());
+
+        window
+            .metadata_sender
+            .send(FileMetadataResult {
+                path,
+                columns: vec!["姓名".to_string(), "消息内容".to_string()],
+                sheet_columns: vec![("Sheet1".to_string(), vec!["姓名".to_string(), "消息内容".to_string()])],
+                row_count: 12,
+                error: None,
+            })
+            .unwrap();
+        window.poll_metadata();
+        assert_eq!(window.files[0].row_count, 12);
+        assert_eq!(window.files[0].status, FileStatus::Pending);
+        assert_eq!(window.config.target_column, "消息内容");
+        assert!(window.metadata_pending());
+
+        window
+            .metadata_sender
+            .send(FileMetadataResult::failed(locked.clone(), MetadataError::PasswordRequired))
+            .unwrap();
+        window.poll_metadata();
+        assert!(!window.metadata_pending());
+        assert!(!window.files[1].selected);
+        assert_eq!(window.password_queue, [locked]);
+        assert!(window.can_preview());
+    }
+
+    #[test]
+    fn test_cancel_password_skips_file() {
+        let mut window = MainWindow::default();
+        let mut file = FileInfo::from_path(PathBuf::from("locked.xlsx"));
+        file.selected = false;
+        window.password_queue.push(file.file_path.clone());
+        window.files.push(file);
+        window.password_input = "typo".to_string();
+
+        window.skip_password_file();
+
+        assert!(window.password_queue.is_empty());
+        assert!(window.password_input.is_empty());
+        assert_eq!(window.files[0].status, FileStatus::Skipped("需要密码".to_string()));
+        assert!(window.files[0].status.is_unprocessable());
+        assert!(!window.files[0].selected);
+    }
+
+    #[test]
+    fn test_file_error_shows_banner_and_keeps_results() {
+        let mut window = processing_window(&["ok.xlsx", "broken.xlsx"]);
+
+        let finished = window.handle_processing_message(ProcessingMessage::FileError(
+            PathBuf::from("broken.xlsx"),
+            "无法打开文件: broken.xlsx".to_string(),
+        ));
+        assert!(!finished);
+        // 处理中不显示提示
+        assert!(window.failed_files_banner_title().is_none());
+
+        let results = vec![ExtractResult::new("ok.xlsx", "Sheet1", 2)];
+        let finished = window.handle_processing_message(ProcessingMessage::Completed(
+            results,
+            ProcessingStatistics::default(),
+        ));
+        assert!(finished);
+
+        assert_eq!(window.results.len(), 1);
+        assert!(matches!(window.files[0].status, FileStatus::Completed));
+        assert!(matches!(window.files[1].status, FileStatus::Error(_)));
+        assert_eq!(
+            window.failed_files_banner_title().as_deref(),
+            Some("⚠ 1 个文件处理失败，点击查看详情")
+        );
+
+        window.failed_banner_dismissed = true;
+        assert!(window.failed_files_banner_title().is_none());
+    }
+
+    #[test]
+    fn test_partial_statistics_accumulate_while_processing() {
+        let mut window = processing_window(&["a.xlsx"]);
+
+        let mut first = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        first.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let mut second = ExtractResult::new("a.xlsx", "Sheet1", 3);
+        second.phone_numbers = vec![MatchInfo::builder("1390731528", PatternType::Phone).build()];
+        second.bank_cards = vec![MatchInfo::builder("4111111111111111", PatternType::BankCard).valid(true).build()];
+
+        for batch in [vec![first.clone()], vec![second.clone()]] {
+            let delta = ProcessingStatistics::from_results(&batch, 0.0);
+            assert!(!window.handle_processing_message(ProcessingMessage::Partial(delta)));
+        }
+        let live = window.live_statistics.as_ref().unwrap();
+        assert_eq!(live.total_results, 2);
+        assert_eq!((live.total_phones, live.valid_phones), (2, 1));
+        assert_eq!(live.total_bank_cards, 1);
+        // 结果列表仍在完成时一次性填充
+        assert!(window.results.is_empty());
+
+        let stats = ProcessingStatistics::from_results(&[first.clone(), second.clone()], 1.0);
+        window.handle_processing_message(ProcessingMessage::Completed(vec![first, second], stats));
+        assert!(window.live_statistics.is_none());
+        assert_eq!(window.statistics.as_ref().unwrap().total_phones, 2);
+    }
+
+    #[test]
+    fn test_streamed_keeps_statistics_without_results() {
+        let mut window = processing_window(&["a.xlsx", "b.xlsx"]);
+        window.handle_processing_message(ProcessingMessage::FileError(PathBuf::from("b.xlsx"), "无法打开".into()));
+
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let stats = ProcessingStatistics::from_results(&[result], 1.0);
+        let finished = window.handle_processing_message(ProcessingMessage::Streamed(PathBuf::from("out.csv"), stats));
+
+        assert!(finished);
+        assert!(!window.processing);
+        assert!(window.results.is_empty());
+        assert_eq!(window.statistics.as_ref().unwrap().total_phones, 1);
+        assert!(matches!(window.files[0].status, FileStatus::Completed));
+        assert!(matches!(window.files[1].status, FileStatus::Error(_)));
+    }
+
+    #[test]
+    fn test_stream_output_skips_parquet() {
+        let mut window = processing_window(&["a.xlsx"]);
+        window.config.output_formats = vec![OutputFormat::Json];
+        let (format, path) = window.stream_output(&window.files).unwrap();
+        assert_eq!(format, OutputFormat::Json);
+        assert_eq!(path.extension().unwrap(), "jsonl");
+
+        window.config.last_export_format = Some(OutputFormat::Parquet);
+        assert!(window.stream_output(&window.files).is_none());
+    }
+
+    #[test]
+    fn test_file_warning_kept_after_completion() {
+        let mut window = processing_window(&["a.xlsx", "b.xlsx"]);
+
+        let finished = window.handle_processing_message(ProcessingMessage::FileWarning(
+            PathBuf::from("b.xlsx"),
+            "工作表 附表 不包含列: 消息内容".to_string(),
+        ));
+        assert!(!finished);
+        assert!(matches!(window.files[1].status, FileStatus::Warning(_)));
+
+        let mut result = ExtractResult::new("b.xlsx", "Sheet1", 2);
+        result.source_path = "b.xlsx".to_string();
+        result.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        window.handle_processing_message(ProcessingMessage::Completed(vec![result], ProcessingStatistics::default()));
+
+        assert_eq!(window.files[0].status, FileStatus::Completed);
+        assert_eq!(window.files[1].status, FileStatus::Warning("工作表 附表 不包含列: 消息内容".to_string()));
+        assert_eq!(window.files[1].result_summary.unwrap().phones, 1);
+        assert!(!window.files[1].status.is_unprocessable());
+    }
+
+    #[test]
+    fn test_file_error_and_warning_follow_file_path() {
+        let mut window = processing_window(&["客服/a.xlsx", "销售/a.xlsx", "财务/a.xlsx"]);
+
+        window.handle_processing_message(ProcessingMessage::FileError(PathBuf::from("销售/a.xlsx"), "无法打开".into()));
+        window.handle_processing_message(ProcessingMessage::FileWarning(
+            PathBuf::from("财务/a.xlsx"),
+            "工作表 附表 不包含列: 消息内容".into(),
+        ));
+
+        assert!(matches!(window.files[0].status, FileStatus::Processing(_)));
+        assert!(matches!(window.files[1].status, FileStatus::Error(_)));
+        assert!(matches!(window.files[2].status, FileStatus::Warning(_)));
+        assert_eq!(window.failed_files, vec![("a.xlsx".to_string(), "无法打开".to_string())]);
+    }
+
+    #[test]
+    fn test_completed_files_get_result_summary() {
+        let mut window = processing_window(&["a.xlsx", "b.xlsx"]);
+
+        let mut first = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        first.source_path = "a.xlsx".to_string();
+        first.phone_numbers = vec![
+            MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build(),
+            MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build(),
+        ];
+        first.bank_cards = vec![MatchInfo::builder("4111111111111111", PatternType::BankCard).valid(true).build()];
+        let mut second = ExtractResult::new("a.xlsx", "Sheet1", 5);
+        second.source_path = "a.xlsx".to_string();
+        second.phone_numbers = vec![MatchInfo::builder("15912345678", PatternType::Phone).valid(true).build()];
+        second.id_cards = vec![
+            MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build(),
+            MatchInfo::builder("11010519491231002X", PatternType::IdCard).valid(true).build(),
+        ];
+
+        window.handle_processing_message(ProcessingMessage::Completed(
+            vec![first, second],
+            ProcessingStatistics::default(),
+        ));
+
+        let summary = window.files[0].result_summary.unwrap();
+        assert_eq!(
+            summary,
+            FileResultSummary { phones: 3, id_cards: 2, bank_cards: 1, names: 0 }
+        );
+        assert_eq!(summary.badge(), "📱3 🪪2 💳1");
+
+        let empty = window.files[1].result_summary.unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.badge(), "");
+    }
+
+    #[test]
+    fn test_same_named_files_get_their_own_result_summary() {
+        let mut window = processing_window(&["客服/a.xlsx", "销售/a.xlsx"]);
+
+        let mut service = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        service.source_path = "客服/a.xlsx".to_string();
+        service.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let mut sales = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        sales.source_path = "销售/a.xlsx".to_string();
+        sales.bank_cards = vec![MatchInfo::builder("4111111111111111", PatternType::BankCard).valid(true).build()];
+
+        window.handle_processing_message(ProcessingMessage::Completed(
+            vec![service, sales],
+            ProcessingStatistics::default(),
+        ));
+
+        assert_eq!(window.files[0].result_summary.unwrap().badge(), "📱1");
+        assert_eq!(window.files[1].result_summary.unwrap().badge(), "💳1");
+    }
+
+    #[test]
+    fn test_match_samples_masked_with_source() {
+        let mut first = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        first.phone_numbers = vec![
+            MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build(),
+            MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build(),
+        ];
+        first.names = vec![MatchInfo::builder("张三丰", PatternType::NameApi).valid(true).build()];
+        let mut second = ExtractResult::new("b.xlsx", "Sheet1", 7);
+        second.phone_numbers = vec![MatchInfo::builder("15912345678", PatternType::Phone).valid(true).build()];
+        let results = vec![first, second];
+
+        let samples = match_samples(&results, PatternType::Phone, &MaskRule::default(), 2);
+        assert_eq!(
+            samples,
+            vec![
+                MatchSample {
+                    value: "138****5678".to_string(),
+                    raw_value: "13812345678".to_string(),
+                    source_file: "a.xlsx".to_string(),
+                    row_number: 2,
+                },
+                MatchSample {
+                    value: "139****5286".to_string(),
+                    raw_value: "13907315286".to_string(),
+                    source_file: "a.xlsx".to_string(),
+                    row_number: 2,
+                },
+            ]
+        );
+
+        let samples = match_samples(&results, PatternType::Phone, &MaskRule::default(), 5);
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[2].row_number, 7);
+
+        let names = match_samples(&results, PatternType::NameApi, &MaskRule::default(), 5);
+        assert_eq!(names[0].value, "张**");
+
+        assert!(match_samples(&results, PatternType::BankCard, &MaskRule::default(), 5).is_empty());
+    }
+
+    #[test]
+    fn test_exclude_sample_kept_across_clear() {
+        let mut window = MainWindow::default();
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.phone_numbers = vec![MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build()];
+        let samples = match_samples(&[result], PatternType::Phone, &MaskRule::default(), 1);
+
+        window.exclude_sample(&samples[0]);
+        assert_eq!(window.config.exclude_values, ["13812345678"]);
+        assert!(window.status_message.contains("138****5678"));
+
+        window.clear_all();
+        assert_eq!(window.config.exclude_values, ["13812345678"]);
+    }
+
+    #[test]
+    fn test_pause_then_cancel_resets_files() {
+        let mut window = processing_window(&["a.xlsx"]);
+        let control = Arc::new(ProcessingControl::default());
+        window.processing_control = Some(Arc::clone(&control));
+
+        window.toggle_pause();
+        assert!(window.is_paused());
+        window.cancel_processing();
+        assert!(control.is_cancelled());
+
+        assert!(window.handle_processing_message(ProcessingMessage::Cancelled));
+        assert!(!window.processing);
+        assert!(window.processing_control.is_none());
+        assert_eq!(window.files[0].status, FileStatus::Pending);
+        assert_eq!(window.status_message, "处理已取消");
+    }
+
+    #[test]
+    fn test_no_banner_without_failures() {
+        let mut window = processing_window(&["ok.xlsx"]);
+        window.handle_processing_message(ProcessingMessage::Completed(Vec::new(), ProcessingStatistics::default()));
+        assert!(window.failed_files_banner_title().is_none());
+    }
+
+    /// 在无窗口的 egui 上下文中注入一次按键并处理快捷键
+    fn press_shortcut(window: &mut MainWindow, ctx: &egui::Context, key: egui::Key) {
+        let modifiers = egui::Modifiers { ctrl: true, command: true, ..Default::default() };
+        let input = egui::RawInput {
+            modifiers,
+            events: vec![egui::Event::Key { key, physical_key: None, pressed: true, repeat: false, modifiers }],
+            ..Default::default()
+        };
+        let _ = ctx.run(input, |ctx| window.handle_shortcuts(ctx));
+    }
+
+    #[test]
+    fn test_file_list_shortcuts() {
+        let ctx = egui::Context::default();
+        let mut window = MainWindow::default();
+        for name in ["a.xlsx", "b.xlsx", "c.xlsx"] {
+            let mut file = FileInfo::from_path(PathBuf::from(name));
+            file.selected = false;
+            window.files.push(file);
+        }
+
+        press_shortcut(&mut window, &ctx, egui::Key::A);
+        assert!(window.files.iter().all(|f| f.selected));
+
+        press_shortcut(&mut window, &ctx, egui::Key::D);
+        assert!(window.files.iter().all(|f| !f.selected));
+
+        window.files[1].selected = true;
+        press_shortcut(&mut window, &ctx, egui::Key::Delete);
+        let names: Vec<&str> = window.files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(names, ["a.xlsx", "c.xlsx"]);
+
+        // 处理中不响应
+        window.processing = true;
+        press_shortcut(&mut window, &ctx, egui::Key::A);
+        assert!(window.files.iter().all(|f| !f.selected));
+    }
+
+    #[test]
+    fn test_poll_processing_detects_thread_exit() {
+        let mut window = processing_window(&["a.xlsx"]);
+        let (sender, receiver) = mpsc::channel();
+        window.processing_receiver = Some(receiver);
+
+        sender.send(ProcessingMessage::Progress("a.xlsx".to_string(), 40)).unwrap();
+        window.poll_processing();
+        assert_eq!(window.progress, 40);
+        assert!(window.processing_receiver.is_some());
+
+        // 工作线程没有发送完成消息就退出
+        drop(sender);
+        window.poll_processing();
+        assert!(!window.processing);
+        assert!(window.processing_receiver.is_none());
+        assert!(matches!(window.files[0].status, FileStatus::Error(_)));
+    }
+}
@@ -1,11 +1,17 @@
 mod column_selector;
 mod drag_area;
 mod file_list;
+mod fonts;
+mod i18n;
+mod log_panel;
 mod main_window;
+mod result_view;
 mod settings_panel;
 
 pub use column_selector::{smart_select_column, ColumnSelector};
 pub use drag_area::DragArea;
 pub use file_list::FileList;
+pub use log_panel::{log_buffer, LogLayer, LogPanel};
 pub use main_window::MainWindow;
-pub use settings_panel::SettingsPanel;
+pub use result_view::ResultView;
+pub use settings_panel::{ExtractionPreview, SettingsPanel};
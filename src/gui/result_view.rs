@@ -0,0 +1,197 @@
+use eframe::egui;
+
+use super::i18n::{tr, trf};
+use crate::core::Masker;
+use crate::models::{ExtractResult, FileResultSummary, MaskRule};
+
+/// 结果明细列表的最大高度
+const VIEW_MAX_HEIGHT: f32 = 260.0;
+
+/// 结果明细的显示方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResultViewMode {
+    /// 所有结果行依次排列
+    #[default]
+    Flat,
+    /// 按源文件 → 工作表 → 行折叠
+    Tree,
+}
+
+/// 同一工作表的结果在结果列表中的下标
+#[derive(Debug, PartialEq)]
+struct SheetGroup {
+    sheet_name: String,
+    indices: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq)]
+struct FileGroup {
+    file_name: String,
+    summary: FileResultSummary,
+    row_count: usize,
+    sheets: Vec<SheetGroup>,
+}
+
+/// 按源文件、工作表分组，保持各自首次出现的顺序
+fn group_results(results: &[ExtractResult]) -> Vec<FileGroup> {
+    let mut groups: Vec<FileGroup> = Vec::new();
+    for (index, result) in results.iter().enumerate() {
+        let file = match groups.iter().position(|g| g.file_name == result.source_file) {
+            Some(position) => &mut groups[position],
+            None => {
+                groups.push(FileGroup {
+                    file_name: result.source_file.clone(),
+                    summary: FileResultSummary::default(),
+                    row_count: 0,
+                    sheets: Vec::new(),
+                });
+                groups.last_mut().expect("刚插入的分组")
+            }
+        };
+        file.row_count += 1;
+
+        match file.sheets.iter_mut().find(|s| s.sheet_name == result.sheet_name) {
+            Some(sheet) => sheet.indices.push(index),
+            None => file.sheets.push(SheetGroup {
+                sheet_name: result.sheet_name.clone(),
+                indices: vec![index],
+            }),
+        }
+    }
+
+    for group in &mut groups {
+        let indices = group.sheets.iter().flat_map(|s| &s.indices);
+        group.summary = FileResultSummary::from_results(indices.map(|&i| &results[i]));
+    }
+    groups
+}
+
+/// 单条结果的显示文本：行号加脱敏后的命中值，姓名仅保留首字
+fn row_text(result: &ExtractResult, rule: &MaskRule) -> String {
+    let values: Vec<String> = result
+        .phone_numbers
+        .iter()
+        .chain(&result.id_cards)
+        .chain(&result.bank_cards)
+        .chain(&result.macau_ids)
+        .chain(&result.wechat_ids)
+        .chain(&result.custom_matches)
+        .map(|m| Masker::mask_with_rule(&m.value, rule))
+        .chain(result.names.iter().map(|m| Masker::mask(&m.value, 1, 0)))
+        .collect();
+    trf("result_view.row", &[&result.row_number, &values.join("，")])
+}
+
+/// 结果明细：平铺列表或按文件分组的树，两种视图都只绘制可见的行
+#[derive(Default)]
+pub struct ResultView {
+    mode: ResultViewMode,
+    /// 分组缓存及其对应的结果条数
+    groups: Option<(usize, Vec<FileGroup>)>,
+}
+
+impl ResultView {
+    /// 结果列表被替换后调用，下次绘制时重新分组
+    pub fn invalidate(&mut self) {
+        self.groups = None;
+    }
+
+    pub fn show(&mut self, ui: &mut egui::Ui, results: &[ExtractResult], rule: &MaskRule) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.mode, ResultViewMode::Flat, tr("result_view.flat"));
+            ui.selectable_value(&mut self.mode, ResultViewMode::Tree, tr("result_view.tree"));
+        });
+
+        match self.mode {
+            ResultViewMode::Flat => Self::show_flat(ui, results, rule),
+            ResultViewMode::Tree => self.show_tree(ui, results, rule),
+        }
+    }
+
+    fn show_flat(ui: &mut egui::Ui, results: &[ExtractResult], rule: &MaskRule) {
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        egui::ScrollArea::vertical()
+            .id_salt("result_view_flat")
+            .max_height(VIEW_MAX_HEIGHT)
+            .auto_shrink([false, true])
+            .show_rows(ui, row_height, results.len(), |ui, range| {
+                for result in &results[range] {
+                    ui.label(format!("{} / {} / {}", result.source_file, result.sheet_name, row_text(result, rule)));
+                }
+            });
+    }
+
+    /// 折叠的节点不绘制子节点；展开的工作表内也只绘制可见的行
+    fn show_tree(&mut self, ui: &mut egui::Ui, results: &[ExtractResult], rule: &MaskRule) {
+        if self.groups.as_ref().is_none_or(|(len, _)| *len != results.len()) {
+            self.groups = Some((results.len(), group_results(results)));
+        }
+        let Some((_, groups)) = &self.groups else {
+            return;
+        };
+
+        let row_height = ui.text_style_height(&egui::TextStyle::Body);
+        egui::ScrollArea::vertical()
+            .id_salt("result_view_tree")
+            .max_height(VIEW_MAX_HEIGHT)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                for (file_index, file) in groups.iter().enumerate() {
+                    let file_label = trf(
+                        "result_view.file_node",
+                        &[&file.file_name, &file.row_count, &file.summary.badge()],
+                    );
+                    egui::CollapsingHeader::new(file_label)
+                        .id_salt(("result_file", file_index))
+                        .show(ui, |ui| {
+                            for (sheet_index, sheet) in file.sheets.iter().enumerate() {
+                                let sheet_label =
+                                    trf("result_view.sheet_node", &[&sheet.sheet_name, &sheet.indices.len()]);
+                                egui::CollapsingHeader::new(sheet_label)
+                                    .id_salt(("result_sheet", file_index, sheet_index))
+                                    .show(ui, |ui| {
+                                        egui::ScrollArea::vertical()
+                                            .id_salt(("result_rows", file_index, sheet_index))
+                                            .max_height(VIEW_MAX_HEIGHT / 2.0)
+                                            .auto_shrink([false, true])
+                                            .show_rows(ui, row_height, sheet.indices.len(), |ui, range| {
+                                                for &index in &sheet.indices[range] {
+                                                    ui.label(row_text(&results[index], rule));
+                                                }
+                                            });
+                                    });
+                            }
+                        });
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{MatchInfo, PatternType};
+
+    #[test]
+    fn test_group_results_by_file_and_sheet() {
+        let mut results = vec![
+            ExtractResult::new("a.xlsx", "Sheet1", 2),
+            ExtractResult::new("b.xlsx", "Sheet1", 3),
+            ExtractResult::new("a.xlsx", "Sheet2", 4),
+            ExtractResult::new("a.xlsx", "Sheet1", 5),
+        ];
+        results[0].phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        results[3].phone_numbers = vec![MatchInfo::builder("18612345678", PatternType::Phone).valid(true).build()];
+
+        let groups = group_results(&results);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].file_name, "a.xlsx");
+        assert_eq!(groups[0].row_count, 3);
+        assert_eq!(groups[0].summary.phones, 2);
+        let sheets: Vec<(&str, &[usize])> =
+            groups[0].sheets.iter().map(|s| (s.sheet_name.as_str(), s.indices.as_slice())).collect();
+        assert_eq!(sheets, [("Sheet1", &[0, 3][..]), ("Sheet2", &[2][..])]);
+        assert_eq!(groups[1].sheets[0].indices, [1]);
+        assert!(groups[1].summary.is_empty());
+    }
+}
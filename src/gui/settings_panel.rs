@@ -1,53 +1,258 @@
-use crate::core::NameExtractor;
-use crate::models::Config;
+use super::i18n::{self, tr, trf};
+use crate::core::{InfoExtractor, Masker, NameExtractor};
+use crate::models::{AnnotationKey, CardLengthPolicy, Config, CustomPattern, ExportConflictStrategy, ExportStyle, Language, LengthLimit, MatchInfo, OutputFormat, PatternType, RowColorRule};
 use eframe::egui;
 use egui::{Color32, RichText};
+use std::time::{Duration, Instant};
+
+/// 预览文本停止输入多久后才重新提取，避免每次按键都在界面线程上提取（可能请求姓名 API）
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(300);
+/// 行底色规则可选的命中类型
+const ROW_COLOR_TYPES: [PatternType; 6] = [
+    PatternType::Phone,
+    PatternType::IdCard,
+    PatternType::BankCard,
+    PatternType::NameApi,
+    PatternType::MacauId,
+    PatternType::WechatId,
+];
+
+/// 提取预览的状态，跨帧保存在主窗口中
+#[derive(Default)]
+pub struct ExtractionPreview {
+    pub text: String,
+    /// 最近一次编辑文本的时间
+    edited_at: Option<Instant>,
+    /// 上次提取时的文本与配置快照，都未变化时复用结果
+    computed_for: Option<(String, String)>,
+    matches: Vec<MatchInfo>,
+}
+
+impl ExtractionPreview {
+    fn mark_edited(&mut self) {
+        self.edited_at = Some(Instant::now());
+    }
+
+    /// 文本或配置变化后重新提取；文本刚编辑过时推迟到防抖时间之后，返回仍需等待的时长
+    fn refresh(&mut self, config: &Config) -> Option<Duration> {
+        let snapshot = (self.text.clone(), serde_json::to_string(config).unwrap_or_default());
+        if self.computed_for.as_ref() == Some(&snapshot) {
+            return None;
+        }
+
+        if let Some(remaining) = self
+            .edited_at
+            .and_then(|edited_at| PREVIEW_DEBOUNCE.checked_sub(edited_at.elapsed()))
+            .filter(|remaining| !remaining.is_zero())
+        {
+            return Some(remaining);
+        }
+
+        let extractor = InfoExtractor::new(config.clone());
+        let (phones, id_cards, bank_cards, names) = extractor.extract(&self.text);
+        let macau_ids = extractor.extract_macau_ids(&self.text);
+        let wechat_ids = extractor.extract_wechat_ids(&self.text);
+        let custom_matches = extractor.extract_custom_matches(&self.text);
+
+        let mut matches = [phones, id_cards, bank_cards, names, macau_ids, wechat_ids, custom_matches].concat();
+        matches.sort_by_key(|m| m.position);
+        self.matches = matches;
+        self.computed_for = Some(snapshot);
+        None
+    }
+}
 
 pub struct SettingsPanel<'a> {
     config: &'a mut Config,
     connection_status: &'a mut Option<Result<String, String>>,
+    /// 最近一次连接测试成功时的耗时
+    latency_ms: &'a mut Option<u64>,
+    mask_sample: &'a mut String,
+    exclude_input: &'a mut String,
+    sheet_input: &'a mut String,
+    preview: &'a mut ExtractionPreview,
 }
 
 impl<'a> SettingsPanel<'a> {
-    pub fn new(config: &'a mut Config, connection_status: &'a mut Option<Result<String, String>>) -> Self {
+    pub fn new(
+        config: &'a mut Config,
+        connection_status: &'a mut Option<Result<String, String>>,
+        latency_ms: &'a mut Option<u64>,
+        mask_sample: &'a mut String,
+        exclude_input: &'a mut String,
+        sheet_input: &'a mut String,
+        preview: &'a mut ExtractionPreview,
+    ) -> Self {
         Self {
             config,
             connection_status,
+            latency_ms,
+            mask_sample,
+            exclude_input,
+            sheet_input,
+            preview,
         }
     }
 
     pub fn show(&mut self, ui: &mut egui::Ui) {
         ui.group(|ui| {
-            ui.label(RichText::new("提取设置").strong());
+            ui.label(RichText::new(tr("settings.title")).strong());
+
+            self.show_language_setting(ui);
 
             self.show_context_lines_setting(ui);
 
+            self.show_priority_setting(ui);
+
             ui.add_space(8.0);
 
             self.show_extraction_types_setting(ui);
 
             ui.add_space(8.0);
 
+            self.show_exclude_setting(ui);
+
+            self.show_custom_patterns_setting(ui);
+
+            self.show_excluded_sheets_setting(ui);
+
+            ui.add_space(8.0);
+
             self.show_api_setting(ui);
 
             ui.add_space(8.0);
 
+            self.show_webhook_setting(ui);
+
+            ui.add_space(8.0);
+
+            self.show_output_setting(ui);
+
+            ui.add_space(8.0);
+
+            self.show_mask_setting(ui);
+
+            ui.add_space(8.0);
+
+            self.show_extraction_preview(ui);
+
+            ui.add_space(8.0);
+
             self.show_config_summary(ui);
         });
     }
 
+    /// 界面语言选择，切换后下一帧即生效
+    fn show_language_setting(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.language"));
+            for language in Language::ALL {
+                if ui.radio_value(&mut self.config.language, language, language.label()).changed() {
+                    i18n::set_language(language);
+                }
+            }
+        });
+    }
+
     fn show_context_lines_setting(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            ui.label("上下文行数:");
+            ui.label(tr("settings.context_before"));
 
-            let slider = egui::Slider::new(&mut self.config.context_lines, 0..=10)
-                .text("行")
+            let slider = egui::Slider::new(&mut self.config.context_before_lines, 0..=10)
+                .text(tr("settings.lines"))
                 .step_by(1.0);
 
             ui.add(slider);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.context_after"));
+
+            let slider = egui::Slider::new(&mut self.config.context_after_lines, 0..=10)
+                .text(tr("settings.lines"))
+                .step_by(1.0);
+
+            ui.add(slider);
+
+            ui.label(
+                RichText::new(tr("settings.context_hint"))
+                    .small()
+                    .color(Color32::GRAY)
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.context_cell_sep"));
+            separator_edit(ui, &mut self.config.context_cell_sep);
+            ui.label(tr("settings.context_row_sep"));
+            separator_edit(ui, &mut self.config.context_row_sep);
+            ui.label(RichText::new("ⓘ").color(Color32::GRAY)).on_hover_text(tr("settings.context_sep_hint"));
+        });
+    }
+
+    fn show_priority_setting(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.config.prioritize_by_density, tr("settings.prioritize"))
+                .on_hover_text(tr("settings.prioritize_hint"));
+
+            ui.add_enabled(
+                self.config.prioritize_by_density,
+                egui::DragValue::new(&mut self.config.density_sample_rows)
+                    .range(10..=10000)
+                    .suffix(tr("settings.lines_suffix")),
+            );
+        });
+
+        ui.checkbox(&mut self.config.force_sequential, tr("settings.force_sequential"))
+            .on_hover_text(tr("settings.force_sequential_hint"));
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.max_threads")).on_hover_text(tr("settings.max_threads_hint"));
+            let mut raw = self.config.max_threads.unwrap_or(0);
+            if ui
+                .add_enabled(!self.config.force_sequential, egui::DragValue::new(&mut raw).range(0..=256))
+                .changed()
+            {
+                self.config.max_threads = (raw > 0).then_some(raw);
+            }
+        });
+
+        ui.checkbox(&mut self.config.retry_failed_files, tr("settings.retry_failed_files"))
+            .on_hover_text(tr("settings.retry_failed_files_hint"));
+
+        ui.checkbox(&mut self.config.stream_export, tr("settings.stream_export"))
+            .on_hover_text(tr("settings.stream_export_hint"));
+
+        ui.checkbox(&mut self.config.auto_detect_header, tr("settings.auto_detect_header"))
+            .on_hover_text(tr("settings.auto_detect_header_hint"));
+
+        ui.checkbox(&mut self.config.include_hidden, tr("settings.include_hidden"))
+            .on_hover_text(tr("settings.include_hidden_hint"));
+
+        ui.checkbox(&mut self.config.convert_date_cells, tr("settings.convert_date_cells"))
+            .on_hover_text(tr("settings.convert_date_cells_hint"));
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.cell_length")).on_hover_text(tr("settings.cell_length_hint"));
+            ui.label(tr("settings.cell_length_min"));
+            ui.add(
+                egui::DragValue::new(&mut self.config.min_cell_length)
+                    .range(0..=64)
+                    .suffix(tr("settings.chars_suffix")),
+            );
+            ui.label(tr("settings.cell_length_max"));
+            ui.add(
+                egui::DragValue::new(&mut self.config.max_cell_length)
+                    .range(100..=1_000_000)
+                    .suffix(tr("settings.chars_suffix")),
+            );
+        });
 
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.max_matches"));
+            ui.add(egui::DragValue::new(&mut self.config.max_matches_per_cell).range(0..=10000));
             ui.label(
-                RichText::new("（提取时包含的上下文行数）")
+                RichText::new(tr("settings.max_matches_hint"))
                     .small()
                     .color(Color32::GRAY)
             );
@@ -55,37 +260,136 @@ impl<'a> SettingsPanel<'a> {
     }
 
     fn show_extraction_types_setting(&mut self, ui: &mut egui::Ui) {
-        ui.label("提取类型:");
+        ui.label(tr("settings.types"));
 
         ui.horizontal_wrapped(|ui| {
-            let phone_checkbox = ui.checkbox(&mut self.config.enable_phone, "📱 手机号");
-            phone_checkbox.on_hover_text("匹配中国大陆手机号（11位，1开头）");
+            let phone_checkbox = ui.checkbox(&mut self.config.enable_phone, tr("settings.phone"));
+            phone_checkbox.on_hover_text(tr("settings.phone_hint"));
+
+            let id_card_checkbox = ui.checkbox(&mut self.config.enable_id_card, tr("settings.id_card"));
+            id_card_checkbox.on_hover_text(tr("settings.id_card_hint"));
+
+            let bank_card_checkbox = ui.checkbox(&mut self.config.enable_bank_card, tr("settings.bank_card"));
+            bank_card_checkbox.on_hover_text(tr("settings.bank_card_hint"));
+
+            let name_checkbox = ui.checkbox(&mut self.config.enable_name, tr("settings.name"));
+            name_checkbox.on_hover_text(tr("settings.name_hint"));
+
+            let macau_id_checkbox = ui.checkbox(&mut self.config.enable_macau_id, tr("settings.macau_id"));
+            macau_id_checkbox.on_hover_text(tr("settings.macau_id_hint"));
+
+            let wechat_checkbox = ui.checkbox(&mut self.config.enable_wechat, tr("settings.wechat"));
+            wechat_checkbox.on_hover_text(tr("settings.wechat_hint"));
+        });
+
+        ui.add_enabled(
+            self.config.enable_phone,
+            egui::Checkbox::new(&mut self.config.strip_phone_country_code, tr("settings.strip_country_code")),
+        )
+        .on_hover_text(tr("settings.strip_country_code_hint"));
 
-            let id_card_checkbox = ui.checkbox(&mut self.config.enable_id_card, "🪪 身份证号");
-            id_card_checkbox.on_hover_text("匹配18位身份证号并验证校验码");
+        ui.add_enabled(
+            self.config.enable_id_card,
+            egui::Checkbox::new(&mut self.config.accept_unknown_id_region, tr("settings.accept_unknown_region")),
+        )
+        .on_hover_text(tr("settings.accept_unknown_region_hint"));
 
-            let bank_card_checkbox = ui.checkbox(&mut self.config.enable_bank_card, "💳 银行卡号");
-            bank_card_checkbox.on_hover_text("匹配16-19位银行卡号并验证Luhn校验");
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.suspicious"));
+            ui.add(egui::DragValue::new(&mut self.config.suspicious_run_length).range(0..=18));
+            ui.label(
+                RichText::new(tr("settings.suspicious_hint"))
+                    .small()
+                    .color(Color32::GRAY)
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.bank_card_length"));
+            ui.label(tr("settings.min"));
+            Self::optional_length_input(ui, &mut self.config.bank_card_length.min_len);
+            ui.label(tr("settings.max"));
+            Self::optional_length_input(ui, &mut self.config.bank_card_length.max_len);
+            ui.label(
+                RichText::new(tr("settings.length_hint"))
+                    .small()
+                    .color(Color32::GRAY)
+            );
+        });
 
-            let name_checkbox = ui.checkbox(&mut self.config.enable_name, "👤 姓名");
-            name_checkbox.on_hover_text("通过 API 服务提取姓名（需配置 API 地址）");
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.card_length_policy"));
+            ui.radio_value(
+                &mut self.config.card_length_policy,
+                CardLengthPolicy::Standard16to19,
+                tr("settings.card_policy_standard"),
+            );
+            ui.radio_value(
+                &mut self.config.card_length_policy,
+                CardLengthPolicy::Extended13to19,
+                tr("settings.card_policy_extended"),
+            )
+            .on_hover_text(tr("settings.card_policy_extended_hint"));
         });
 
         if !self.config.has_any_extraction_enabled() {
             ui.label(
-                RichText::new("⚠ 请至少选择一种提取类型")
+                RichText::new(tr("settings.no_type_warning"))
                     .small()
                     .color(Color32::from_rgb(0xFF, 0x98, 0x00))
             );
         }
     }
 
+    /// 可选长度输入，0 表示不限
+    fn optional_length_input(ui: &mut egui::Ui, value: &mut Option<usize>) {
+        let mut raw = value.unwrap_or(0);
+        if ui.add(egui::DragValue::new(&mut raw).range(0..=32)).changed() {
+            *value = (raw > 0).then_some(raw);
+        }
+    }
+
+    /// 处理完成后推送摘要的机器人地址和要提醒的手机号
+    fn show_webhook_setting(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr("settings.webhook_title"))
+            .id_salt("webhook_setting")
+            .default_open(!self.config.webhook_url.is_empty())
+            .show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(tr("settings.webhook_url"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.config.webhook_url)
+                            .desired_width(260.0)
+                            .hint_text("https://qyapi.weixin.qq.com/cgi-bin/webhook/send?key=..."),
+                    );
+                });
+                ui.horizontal(|ui| {
+                    ui.label(tr("settings.webhook_mentions"));
+                    let mut mentions = self.config.webhook_mentions.join(",");
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut mentions)
+                            .desired_width(200.0)
+                            .hint_text("13800138000,@all"),
+                    );
+                    if response.changed() {
+                        self.config.webhook_mentions = mentions
+                            .split([',', '，'])
+                            .map(|m| m.trim().to_string())
+                            .filter(|m| !m.is_empty())
+                            .collect();
+                    }
+                });
+                ui.label(RichText::new(tr("settings.webhook_hint")).small().color(Color32::GRAY));
+            });
+    }
+
     fn show_api_setting(&mut self, ui: &mut egui::Ui) {
-        egui::CollapsingHeader::new("API 设置（姓名提取）")
+        egui::CollapsingHeader::new(tr("settings.api_title"))
+            .id_salt("api_setting")
             .default_open(self.config.enable_name)
             .show(ui, |ui| {
                 ui.horizontal(|ui| {
-                    ui.label("API 地址:");
+                    ui.label(tr("settings.api_host"));
 
                     ui.add_enabled(
                         self.config.enable_name,
@@ -95,7 +399,7 @@ impl<'a> SettingsPanel<'a> {
                     );
 
                     ui.label(
-                        RichText::new("（姓名提取服务地址）")
+                        RichText::new(tr("settings.api_host_hint"))
                             .small()
                             .color(Color32::GRAY)
                     );
@@ -105,9 +409,11 @@ impl<'a> SettingsPanel<'a> {
                 ui.horizontal(|ui| {
                     let test_enabled = self.config.enable_name && !self.config.api_host.is_empty();
 
-                    if ui.add_enabled(test_enabled, egui::Button::new("🔍 测试连接")).clicked() {
+                    if ui.add_enabled(test_enabled, egui::Button::new(tr("settings.test_connection"))).clicked() {
                         let extractor = NameExtractor::new(self.config.api_host.clone(), true);
-                        *self.connection_status = Some(extractor.check_connection());
+                        let result = extractor.check_connection();
+                        *self.latency_ms = result.as_ref().ok().map(|(_, latency)| latency.as_millis() as u64);
+                        *self.connection_status = Some(result.map(|(msg, _)| msg));
                     }
 
                     // 显示连接状态
@@ -115,6 +421,9 @@ impl<'a> SettingsPanel<'a> {
                         match status {
                             Ok(msg) => {
                                 ui.label(RichText::new(format!("✓ {}", msg)).color(Color32::GREEN));
+                                if let Some(ms) = *self.latency_ms {
+                                    ui.label(RichText::new(format!("({}ms)", ms)).color(latency_color(ms)));
+                                }
                             }
                             Err(err) => {
                                 ui.label(RichText::new(format!("✗ {}", err)).color(Color32::RED));
@@ -123,9 +432,41 @@ impl<'a> SettingsPanel<'a> {
                     }
                 });
 
+                egui::CollapsingHeader::new(tr("settings.field_mapping"))
+                    .id_salt("api_field_mapping_header")
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        let mapping = &mut self.config.api_field_mapping;
+                        let fields = [
+                            (tr("settings.request_text_field"), &mut mapping.request_text_field, "text"),
+                            (tr("settings.names_path"), &mut mapping.names_path, "names"),
+                            (tr("settings.confidence_path"), &mut mapping.confidence_path, "confidence"),
+                            (tr("settings.positions_path"), &mut mapping.positions_path, "positions"),
+                        ];
+
+                        egui::Grid::new("api_field_mapping").num_columns(2).show(ui, |ui| {
+                            for (label, value, hint) in fields {
+                                ui.label(label);
+                                ui.add_enabled(
+                                    self.config.enable_name,
+                                    egui::TextEdit::singleline(value)
+                                        .desired_width(160.0)
+                                        .hint_text(hint),
+                                );
+                                ui.end_row();
+                            }
+                        });
+
+                        ui.label(
+                            RichText::new(tr("settings.mapping_hint"))
+                                .small()
+                                .color(Color32::GRAY)
+                        );
+                    });
+
                 if self.config.enable_name {
                     ui.label(
-                        RichText::new("💡 提示: 姓名 API 需要运行服务端，地址格式: host:port")
+                        RichText::new(tr("settings.api_tip"))
                             .small()
                             .color(Color32::from_rgb(0x21, 0x96, 0xF3))
                     );
@@ -133,44 +474,542 @@ impl<'a> SettingsPanel<'a> {
             });
     }
 
-    fn show_config_summary(&self, ui: &mut egui::Ui) {
-        egui::CollapsingHeader::new("当前配置摘要")
+    /// 排除列表：逐条删除，或输入新值后回车/点添加
+    fn show_exclude_setting(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(trf("settings.exclude_title", &[&self.config.exclude_values.len()]))
+            .id_salt("exclude_setting")
             .default_open(false)
             .show(ui, |ui| {
-                ui.vertical(|ui| {
-                    ui.label(RichText::new(format!(
-                        "• 目标列: {}",
-                        if self.config.target_column.is_empty() {
-                            "(自动选择)".to_string()
+                if self.config.exclude_values.is_empty() {
+                    ui.label(RichText::new(tr("settings.exclude_empty")).small().color(Color32::GRAY));
+                }
+
+                let mut removed = None;
+                for (index, value) in self.config.exclude_values.iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.label(RichText::new(value).monospace());
+                        if ui.small_button("✖").on_hover_text(tr("settings.exclude_remove")).clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                }
+                if let Some(index) = removed {
+                    self.config.exclude_values.remove(index);
+                }
+
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(self.exclude_input)
+                            .desired_width(160.0)
+                            .hint_text(tr("settings.exclude_hint")),
+                    );
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button(tr("settings.exclude_add")).clicked() || submitted)
+                        && self.config.add_exclude_value(self.exclude_input)
+                    {
+                        self.exclude_input.clear();
+                    }
+                });
+            });
+    }
+
+    /// 自定义规则：规则名、正则和命中长度约束，正则无效时提示
+    fn show_custom_patterns_setting(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(trf("settings.custom_patterns_title", &[&self.config.custom_patterns.len()]))
+            .id_salt("custom_patterns_setting")
+            .default_open(false)
+            .show(ui, |ui| {
+                if self.config.custom_patterns.is_empty() {
+                    ui.label(RichText::new(tr("settings.custom_patterns_empty")).small().color(Color32::GRAY));
+                }
+
+                let mut removed = None;
+                for (index, pattern) in self.config.custom_patterns.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        ui.add(
+                            egui::TextEdit::singleline(&mut pattern.name)
+                                .desired_width(80.0)
+                                .hint_text(tr("settings.custom_pattern_name")),
+                        );
+                        ui.add(
+                            egui::TextEdit::singleline(&mut pattern.regex)
+                                .desired_width(160.0)
+                                .font(egui::TextStyle::Monospace)
+                                .hint_text(tr("settings.custom_pattern_regex")),
+                        );
+                        ui.label(tr("settings.min"));
+                        Self::optional_length_input(ui, &mut pattern.length.min_len);
+                        ui.label(tr("settings.max"));
+                        Self::optional_length_input(ui, &mut pattern.length.max_len);
+                        if ui.small_button("✖").clicked() {
+                            removed = Some(index);
+                        }
+                    });
+                    if regex::Regex::new(&pattern.regex).is_err() {
+                        ui.label(
+                            RichText::new(tr("settings.custom_pattern_invalid"))
+                                .small()
+                                .color(Color32::from_rgb(0xF4, 0x43, 0x36)),
+                        );
+                    }
+                }
+                if let Some(index) = removed {
+                    self.config.custom_patterns.remove(index);
+                }
+
+                if ui.button(tr("settings.custom_pattern_add")).clicked() {
+                    self.config.custom_patterns.push(CustomPattern {
+                        name: String::new(),
+                        regex: String::new(),
+                        length: LengthLimit::default(),
+                    });
+                }
+                if !self.config.custom_patterns.is_empty() {
+                    ui.label(RichText::new(tr("settings.length_hint")).small().color(Color32::GRAY));
+                }
+            });
+    }
+
+    /// 排除的工作表：名称和通配符分列显示，共用一个输入框
+    fn show_excluded_sheets_setting(&mut self, ui: &mut egui::Ui) {
+        let count = self.config.excluded_sheet_names.len() + self.config.excluded_sheet_name_patterns.len();
+        egui::CollapsingHeader::new(trf("settings.excluded_sheets_title", &[&count]))
+            .id_salt("excluded_sheets_setting")
+            .default_open(false)
+            .show(ui, |ui| {
+                if count == 0 {
+                    ui.label(RichText::new(tr("settings.excluded_sheets_empty")).small().color(Color32::GRAY));
+                }
+
+                for list in [&mut self.config.excluded_sheet_names, &mut self.config.excluded_sheet_name_patterns] {
+                    let mut removed = None;
+                    for (index, entry) in list.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(entry).monospace());
+                            if ui.small_button("✖").on_hover_text(tr("settings.excluded_sheets_remove")).clicked() {
+                                removed = Some(index);
+                            }
+                        });
+                    }
+                    if let Some(index) = removed {
+                        list.remove(index);
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(self.sheet_input)
+                            .desired_width(160.0)
+                            .hint_text(tr("settings.excluded_sheets_hint")),
+                    );
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button(tr("settings.exclude_add")).clicked() || submitted)
+                        && self.config.add_excluded_sheet(self.sheet_input)
+                    {
+                        self.sheet_input.clear();
+                    }
+                });
+                ui.label(RichText::new(tr("settings.excluded_sheets_note")).small().color(Color32::GRAY));
+            });
+    }
+
+    fn show_output_setting(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.output_filename"));
+
+            ui.add(
+                egui::TextEdit::singleline(&mut self.config.output_filename_template)
+                    .desired_width(200.0)
+                    .hint_text("{source}_{timestamp}"),
+            );
+
+            ui.label(RichText::new("ⓘ").color(Color32::GRAY)).on_hover_text(tr("settings.placeholders"));
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.output_formats"));
+
+            for format in OutputFormat::ALL {
+                let mut selected = self.config.output_formats.contains(&format);
+                if ui.checkbox(&mut selected, format.label()).changed() {
+                    if selected {
+                        self.config.output_formats.push(format);
+                    } else {
+                        self.config.output_formats.retain(|f| *f != format);
+                    }
+                }
+            }
+        });
+
+        if self.config.output_formats.is_empty() {
+            ui.label(
+                RichText::new(tr("settings.no_format_warning"))
+                    .small()
+                    .color(Color32::from_rgb(0xFF, 0x98, 0x00))
+            );
+        }
+
+        ui.checkbox(&mut self.config.one_match_per_row, tr("settings.one_match_per_row"))
+            .on_hover_text(tr("settings.one_match_per_row_hint"));
+
+        ui.checkbox(&mut self.config.export_source_path, tr("settings.export_source_path"))
+            .on_hover_text(tr("settings.export_source_path_hint"));
+
+        ui.checkbox(&mut self.config.id_card_analytic_mask, tr("settings.analytic_mask"))
+            .on_hover_text(tr("settings.analytic_mask_hint"));
+
+        ui.horizontal(|ui| {
+            let mut encrypt = self.config.archive_passphrase.is_some();
+            if ui.checkbox(&mut encrypt, tr("settings.encrypt_archive"))
+                .on_hover_text(tr("settings.encrypt_archive_hint"))
+                .changed()
+            {
+                self.config.archive_passphrase = encrypt.then(String::new);
+            }
+            if let Some(passphrase) = &mut self.config.archive_passphrase {
+                ui.add(
+                    egui::TextEdit::singleline(passphrase)
+                        .password(true)
+                        .desired_width(160.0)
+                        .hint_text(tr("settings.passphrase_hint")),
+                );
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let mut truncate = self.config.truncate_source_text.is_some();
+            if ui.checkbox(&mut truncate, tr("settings.truncate_source_text"))
+                .on_hover_text(tr("settings.truncate_source_text_hint"))
+                .changed()
+            {
+                self.config.truncate_source_text = truncate.then_some(200);
+            }
+            if let Some(max_chars) = &mut self.config.truncate_source_text {
+                ui.add(egui::DragValue::new(max_chars).range(10..=32000).suffix(tr("settings.chars_suffix")));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.text_column_width"));
+            ui.add(egui::Slider::new(&mut self.config.text_column_max_width, 10.0..=100.0));
+            ui.checkbox(&mut self.config.wrap_text_in_export, tr("settings.wrap_text"))
+                .on_hover_text(tr("settings.wrap_text_hint"));
+        });
+
+        ui.checkbox(&mut self.config.pretty_format_output, tr("settings.pretty_format"))
+            .on_hover_text(tr("settings.pretty_format_hint"));
+
+        ui.checkbox(&mut self.config.split_valid_columns, tr("settings.split_valid_columns"))
+            .on_hover_text(tr("settings.split_valid_columns_hint"));
+
+        self.show_export_style_setting(ui);
+
+        self.show_annotation_setting(ui);
+
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.conflict"));
+            ui.radio_value(
+                &mut self.config.export_conflict_strategy,
+                ExportConflictStrategy::AddSuffix,
+                tr("settings.conflict_suffix"),
+            );
+            ui.radio_value(
+                &mut self.config.export_conflict_strategy,
+                ExportConflictStrategy::Overwrite,
+                tr("settings.conflict_overwrite"),
+            );
+        });
+    }
+
+    /// xlsx 表头、有效性颜色和按命中类型的行底色；配置中无法解析的颜色按默认色显示
+    fn show_export_style_setting(&mut self, ui: &mut egui::Ui) {
+        fn color_button(ui: &mut egui::Ui, label: &str, hex: &mut String, fallback: &str) {
+            ui.label(label);
+            let mut rgb = ExportStyle::parse_color(hex)
+                .or_else(|| ExportStyle::parse_color(fallback))
+                .unwrap_or_default();
+            if ui.color_edit_button_srgb(&mut rgb).changed() {
+                *hex = ExportStyle::format_color(rgb);
+            }
+        }
+
+        let defaults = ExportStyle::default();
+        let style = &mut self.config.export_style;
+        ui.horizontal(|ui| {
+            color_button(
+                ui,
+                tr("settings.header_background"),
+                &mut style.header_background,
+                &defaults.header_background,
+            );
+            color_button(
+                ui,
+                tr("settings.header_font_color"),
+                &mut style.header_font_color,
+                &defaults.header_font_color,
+            );
+            ui.checkbox(&mut style.header_bold, tr("settings.header_bold"));
+        });
+        ui.horizontal(|ui| {
+            color_button(ui, tr("settings.valid_color"), &mut style.valid_color, &defaults.valid_color);
+            color_button(ui, tr("settings.invalid_color"), &mut style.invalid_color, &defaults.invalid_color);
+            if ui.button(tr("settings.reset_export_style")).clicked() {
+                *style = defaults.clone();
+            }
+        });
+
+        ui.label(tr("settings.row_colors")).on_hover_text(tr("settings.row_colors_hint"));
+        let mut removed = None;
+        for (index, rule) in style.row_colors.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                color_button(ui, "", &mut rule.color, "#FFFFFF");
+                for pattern in ROW_COLOR_TYPES {
+                    let mut selected = rule.types.contains(&pattern);
+                    if ui.checkbox(&mut selected, Self::type_badge(pattern).0).changed() {
+                        if selected {
+                            rule.types.push(pattern);
                         } else {
-                            self.config.target_column.clone()
+                            rule.types.retain(|&p| p != pattern);
                         }
-                    )).small());
+                    }
+                }
+                ui.checkbox(&mut rule.exclusive, tr("settings.row_color_exclusive"));
+                if ui.small_button("✖").clicked() {
+                    removed = Some(index);
+                }
+            });
+        }
+        if let Some(index) = removed {
+            style.row_colors.remove(index);
+        }
+        if ui.button(tr("settings.row_color_add")).clicked() {
+            style.row_colors.push(RowColorRule {
+                types: vec![PatternType::IdCard],
+                exclusive: false,
+                color: "#FCE4D6".to_string(),
+            });
+        }
+    }
+
+    /// 人工批注文件：导出时按行号或命中值合并为“备注”列
+    fn show_annotation_setting(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(tr("settings.annotation")).on_hover_text(tr("settings.annotation_hint"));
+
+            let file_name = self
+                .config
+                .annotation_file
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .map(|name| name.to_string_lossy().to_string());
+            ui.label(
+                RichText::new(file_name.as_deref().unwrap_or(tr("settings.annotation_none")))
+                    .color(Color32::GRAY),
+            );
+
+            if ui.button(tr("settings.annotation_pick")).clicked()
+                && let Some(path) = rfd::FileDialog::new()
+                    .add_filter("Excel / CSV", &["xlsx", "xls", "csv"])
+                    .pick_file()
+            {
+                self.config.annotation_file = Some(path);
+            }
+            if self.config.annotation_file.is_some() && ui.button("✖").clicked() {
+                self.config.annotation_file = None;
+            }
+        });
+
+        if self.config.annotation_file.is_some() {
+            ui.horizontal(|ui| {
+                ui.label(tr("settings.annotation_key"));
+                ui.radio_value(&mut self.config.annotation_key, AnnotationKey::RowNumber, tr("settings.annotation_key_row"));
+                ui.radio_value(&mut self.config.annotation_key, AnnotationKey::Value, tr("settings.annotation_key_value"));
+            });
+        }
+    }
 
-                    ui.label(RichText::new(format!(
-                        "• 上下文行数: {} 行",
-                        self.config.context_lines
+    fn show_mask_setting(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr("settings.mask_title"))
+            .id_salt("mask_setting")
+            .default_open(self.config.enable_mask)
+            .show(ui, |ui| {
+                ui.checkbox(&mut self.config.enable_mask, tr("settings.enable_mask"));
+
+                ui.horizontal(|ui| {
+                    ui.label(tr("settings.keep_prefix"));
+                    ui.add(egui::DragValue::new(&mut self.config.mask_rule.keep_prefix).range(0..=10));
+                    ui.label(tr("settings.keep_suffix"));
+                    ui.add(egui::DragValue::new(&mut self.config.mask_rule.keep_suffix).range(0..=10));
+                    ui.label(tr("settings.digits"));
+                });
+
+                // 实时预览：仅调用脱敏函数，不涉及文件
+                ui.horizontal(|ui| {
+                    ui.label(tr("settings.sample"));
+                    ui.add(
+                        egui::TextEdit::singleline(self.mask_sample)
+                            .desired_width(160.0)
+                            .hint_text(tr("settings.sample_hint")),
+                    );
+
+                    if !self.mask_sample.is_empty() {
+                        let preview = Masker::mask_with_rule(self.mask_sample.trim(), &self.config.mask_rule);
+                        ui.label("→");
+                        ui.label(RichText::new(preview).monospace().strong());
+                    }
+                });
+            });
+    }
+
+    /// 按当前配置对示例文本做提取，便于处理大文件前确认配置
+    fn show_extraction_preview(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr("settings.preview_title"))
+            .id_salt("extraction_preview")
+            .default_open(false)
+            .show(ui, |ui| {
+                let response = ui.add(
+                    egui::TextEdit::multiline(&mut self.preview.text)
+                        .desired_rows(3)
+                        .desired_width(f32::INFINITY)
+                        .hint_text(tr("settings.preview_hint")),
+                );
+                if response.changed() {
+                    self.preview.mark_edited();
+                }
+
+                if let Some(remaining) = self.preview.refresh(self.config) {
+                    ui.ctx().request_repaint_after(remaining);
+                }
+
+                if self.preview.text.trim().is_empty() {
+                    return;
+                }
+                if self.preview.matches.is_empty() {
+                    ui.label(RichText::new(tr("settings.preview_empty")).small().color(Color32::GRAY));
+                    return;
+                }
+
+                for m in &self.preview.matches {
+                    ui.horizontal(|ui| {
+                        let (label, color) = Self::type_badge(m.source_pattern);
+                        ui.label(RichText::new(format!(" {} ", label)).small().color(Color32::WHITE).background_color(color));
+                        ui.label(RichText::new(&m.value).monospace());
+                        if m.is_valid {
+                            ui.label(RichText::new(tr("settings.preview_valid")).small().color(Color32::from_rgb(0x4C, 0xAF, 0x50)));
+                        } else {
+                            ui.label(RichText::new(tr("settings.preview_invalid")).small().color(Color32::from_rgb(0xF4, 0x43, 0x36)));
+                        }
+                    });
+                }
+            });
+    }
+
+    fn type_badge(pattern: PatternType) -> (&'static str, Color32) {
+        match pattern {
+            PatternType::Phone => (tr("type.phone"), Color32::from_rgb(0x21, 0x96, 0xF3)),
+            PatternType::IdCard => (tr("type.id_card"), Color32::from_rgb(0x9C, 0x27, 0xB0)),
+            PatternType::BankCard => (tr("type.bank_card"), Color32::from_rgb(0xFF, 0x98, 0x00)),
+            PatternType::NameApi => (tr("type.name"), Color32::from_rgb(0x00, 0x96, 0x88)),
+            PatternType::MacauId => (tr("type.macau_id"), Color32::from_rgb(0x79, 0x55, 0x48)),
+            PatternType::WechatId => (tr("type.wechat_id"), Color32::from_rgb(0x4C, 0xAF, 0x50)),
+            PatternType::Custom => (tr("type.custom"), Color32::from_rgb(0x60, 0x7D, 0x8B)),
+        }
+    }
+
+    fn show_config_summary(&self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new(tr("settings.summary_title"))
+            .id_salt("config_summary")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.vertical(|ui| {
+                    let target_column = if self.config.target_column.is_empty() {
+                        tr("settings.summary_auto")
+                    } else {
+                        self.config.target_column.as_str()
+                    };
+                    ui.label(RichText::new(trf("settings.summary_column", &[&target_column])).small());
+
+                    ui.label(RichText::new(trf(
+                        "settings.summary_context",
+                        &[&self.config.context_before_lines, &self.config.context_after_lines],
                     )).small());
 
                     let types: Vec<&str> = [
-                        if self.config.enable_phone { Some("手机号") } else { None },
-                        if self.config.enable_id_card { Some("身份证号") } else { None },
-                        if self.config.enable_bank_card { Some("银行卡号") } else { None },
-                        if self.config.enable_name { Some("姓名") } else { None },
+                        if self.config.enable_phone { Some(tr("type.phone")) } else { None },
+                        if self.config.enable_id_card { Some(tr("type.id_card")) } else { None },
+                        if self.config.enable_bank_card { Some(tr("type.bank_card")) } else { None },
+                        if self.config.enable_name { Some(tr("type.name")) } else { None },
+                        if self.config.enable_macau_id { Some(tr("type.macau_id")) } else { None },
+                        if self.config.enable_wechat { Some(tr("type.wechat_id")) } else { None },
+                        if self.config.custom_patterns.is_empty() { None } else { Some(tr("type.custom")) },
                     ].iter().filter_map(|&x| x).collect();
 
-                    ui.label(RichText::new(format!(
-                        "• 提取类型: {}",
-                        if types.is_empty() { "无".to_string() } else { types.join(", ") }
-                    )).small());
+                    let types = if types.is_empty() { tr("settings.summary_none").to_string() } else { types.join(", ") };
+                    ui.label(RichText::new(trf("settings.summary_types", &[&types])).small());
 
                     if self.config.enable_name {
-                        ui.label(RichText::new(format!(
-                            "• API 地址: {}",
-                            self.config.api_host
-                        )).small());
+                        ui.label(RichText::new(trf("settings.summary_api", &[&self.config.api_host])).small());
                     }
                 });
             });
     }
-}
\ No newline at end of file
+}
+
+/// 分隔符输入框：换行和制表符以 `\n`、`\t` 显示和输入
+fn separator_edit(ui: &mut egui::Ui, separator: &mut String) {
+    let mut text = separator.replace('\n', "\\n").replace('\t', "\\t");
+    if ui.add(egui::TextEdit::singleline(&mut text).desired_width(48.0)).changed() {
+        *separator = text.replace("\\n", "\n").replace("\\t", "\t");
+    }
+}
+
+/// 连接耗时的颜色：不超过 100ms 为绿色，不超过 500ms 为黄色，更慢为红色
+fn latency_color(ms: u64) -> Color32 {
+    match ms {
+        0..=100 => Color32::GREEN,
+        101..=500 => Color32::YELLOW,
+        _ => Color32::RED,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_color() {
+        assert_eq!(latency_color(23), Color32::GREEN);
+        assert_eq!(latency_color(100), Color32::GREEN);
+        assert_eq!(latency_color(101), Color32::YELLOW);
+        assert_eq!(latency_color(500), Color32::YELLOW);
+        assert_eq!(latency_color(501), Color32::RED);
+    }
+
+    #[test]
+    fn test_preview_respects_flags_and_debounce() {
+        let mut preview = ExtractionPreview {
+            text: "电话13907315286，卡号4111111111111111".to_string(),
+            ..Default::default()
+        };
+        let mut config = Config::default();
+
+        assert!(preview.refresh(&config).is_none());
+        let values: Vec<&str> = preview.matches.iter().map(|m| m.value.as_str()).collect();
+        assert_eq!(values, ["13907315286", "4111111111111111"]);
+
+        config.enable_bank_card = false;
+        preview.refresh(&config);
+        assert_eq!(preview.matches.len(), 1);
+        assert_eq!(preview.matches[0].source_pattern, PatternType::Phone);
+
+        // 刚编辑过的文本等防抖时间过后才重新提取
+        preview.text.push_str("，备用13812345678");
+        preview.mark_edited();
+        assert!(preview.refresh(&config).is_some());
+        assert_eq!(preview.matches.len(), 1);
+
+        preview.edited_at = Some(Instant::now() - PREVIEW_DEBOUNCE);
+        assert!(preview.refresh(&config).is_none());
+        assert_eq!(preview.matches.len(), 2);
+    }
+}
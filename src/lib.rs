@@ -1,3 +1,6 @@
 pub mod core;
 pub mod models;
-pub mod utils;
\ No newline at end of file
+pub mod utils;
+
+#[cfg(test)]
+mod test_support;
@@ -1,15 +1,27 @@
 // 在 release 模式下隐藏控制台窗口
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod core;
 mod gui;
-mod models;
-mod utils;
+
+use sensitive_info_extractor::{core, models, utils};
 
 use eframe::egui;
+use tracing::Level;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::prelude::*;
 
 fn main() -> eframe::Result<()> {
-    tracing_subscriber::fmt::init();
+    // 控制台输出之外，日志同时收集到界面的日志面板（release 模式没有控制台）；本程序的调试日志只进面板
+    tracing_subscriber::registry()
+        .with(tracing_subscriber::fmt::layer().with_filter(LevelFilter::INFO))
+        .with(
+            gui::LogLayer::new(gui::log_buffer()).with_filter(
+                Targets::new()
+                    .with_default(Level::INFO)
+                    .with_target(env!("CARGO_CRATE_NAME"), Level::DEBUG),
+            ),
+        )
+        .init();
 
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
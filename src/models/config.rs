@@ -1,32 +1,830 @@
-use serde::{Deserialize, Serialize};
+use crate::models::{ExtractResult, PatternType};
+use crate::utils::normalize_value;
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fs;
+use std::path::{Path, PathBuf};
 
+/// 导出文件已存在时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ExportConflictStrategy {
+    /// 覆盖已有文件
+    Overwrite,
+    /// 在文件名后追加序号，如 `xxx_结果(1).xlsx`
+    #[default]
+    AddSuffix,
+}
+
+/// 批注文件第一列的匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum AnnotationKey {
+    /// 按 Excel 行号匹配
+    #[default]
+    RowNumber,
+    /// 按命中值（规范化后）匹配，一行有多个命中时取第一个有批注的
+    Value,
+}
+
+/// 命中值的长度约束，按规范化长度（去除空白、`-` 和 `.` 分隔符）判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LengthLimit {
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+}
+
+impl LengthLimit {
+    /// 命中值是否满足约束，未设置的一端不限制
+    pub fn allows(&self, value: &str) -> bool {
+        let len = value
+            .chars()
+            .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '.'))
+            .count();
+
+        self.min_len.is_none_or(|min| len >= min) && self.max_len.is_none_or(|max| len <= max)
+    }
+}
+
+/// 自定义提取规则：正则命中后再按长度约束过滤
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomPattern {
+    /// 规则名，随命中一起导出
+    pub name: String,
+    pub regex: String,
+    /// 命中值的长度约束，不满足的命中直接丢弃，不计入统计
+    #[serde(default)]
+    pub length: LengthLimit,
+}
+
+/// 银行卡号的最短位数策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CardLengthPolicy {
+    /// 16-19 位（另识别 15 位 American Express 卡号）
+    #[default]
+    Standard16to19,
+    /// 13-19 位，额外识别 13 位 Visa 等老卡号和部分联名卡
+    Extended13to19,
+}
+
+impl CardLengthPolicy {
+    /// 卡号最短位数（15 位 American Express 卡号不受此限制）
+    pub fn min_length(&self) -> usize {
+        match self {
+            CardLengthPolicy::Standard16to19 => 16,
+            CardLengthPolicy::Extended13to19 => 13,
+        }
+    }
+}
+
+/// 结果导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputFormat {
+    Xlsx,
+    Csv,
+    Json,
+    Parquet,
+    Html,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 5] = [
+        OutputFormat::Xlsx,
+        OutputFormat::Csv,
+        OutputFormat::Json,
+        OutputFormat::Parquet,
+        OutputFormat::Html,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            OutputFormat::Xlsx => "Excel (.xlsx)",
+            OutputFormat::Csv => "CSV (.csv)",
+            OutputFormat::Json => "JSON (.json)",
+            OutputFormat::Parquet => "Parquet (.parquet)",
+            OutputFormat::Html => "HTML (.html)",
+        }
+    }
+}
+
+/// 配置文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConfigFormat {
+    #[default]
+    Json,
+    Toml,
+}
+
+impl ConfigFormat {
+    pub const ALL: [ConfigFormat; 2] = [ConfigFormat::Json, ConfigFormat::Toml];
+
+    /// 按扩展名识别格式（不区分大小写）
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "json" => Some(ConfigFormat::Json),
+            "toml" => Some(ConfigFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// 文件扩展名（不含点）
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "json",
+            ConfigFormat::Toml => "toml",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Toml => "TOML",
+        }
+    }
+}
+
+/// 界面语言
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Chinese,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Chinese, Language::English];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Language::Chinese => "中文",
+            Language::English => "English",
+        }
+    }
+}
+
+/// 脱敏规则：保留首尾若干位，中间替换为掩码
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MaskRule {
+    pub keep_prefix: usize,
+    pub keep_suffix: usize,
+}
+
+impl Default for MaskRule {
+    fn default() -> Self {
+        Self {
+            keep_prefix: 3,
+            keep_suffix: 4,
+        }
+    }
+}
+
+/// 导出 xlsx 的表头样式与有效性配色，颜色写作 `#RRGGBB`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportStyle {
+    pub header_background: String,
+    pub header_font_color: String,
+    pub header_bold: bool,
+    /// “有效”的字体颜色
+    pub valid_color: String,
+    /// “无效”的字体颜色
+    pub invalid_color: String,
+    /// 按命中类型给结果行着色，取第一条匹配的规则
+    pub row_colors: Vec<RowColorRule>,
+}
+
+/// 结果行底色规则：行中含有 `types` 中的全部类型时使用 `color`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowColorRule {
+    pub types: Vec<PatternType>,
+    /// 为 true 时该行不能再含其他类型，如“只含手机号”
+    #[serde(default)]
+    pub exclusive: bool,
+    pub color: String,
+}
+
+impl RowColorRule {
+    pub fn matches(&self, result: &ExtractResult) -> bool {
+        let present = [
+            (PatternType::Phone, &result.phone_numbers),
+            (PatternType::IdCard, &result.id_cards),
+            (PatternType::BankCard, &result.bank_cards),
+            (PatternType::NameApi, &result.names),
+            (PatternType::MacauId, &result.macau_ids),
+            (PatternType::WechatId, &result.wechat_ids),
+        ];
+        !self.types.is_empty()
+            && present.iter().all(|(pattern, matches)| {
+                let required = self.types.contains(pattern);
+                if required {
+                    !matches.is_empty()
+                } else {
+                    !self.exclusive || matches.is_empty()
+                }
+            })
+    }
+}
+
+impl Default for ExportStyle {
+    fn default() -> Self {
+        Self {
+            header_background: "#4472C4".to_string(),
+            header_font_color: "#FFFFFF".to_string(),
+            header_bold: true,
+            valid_color: "#008000".to_string(),
+            invalid_color: "#FF0000".to_string(),
+            row_colors: Vec::new(),
+        }
+    }
+}
+
+impl ExportStyle {
+    /// 解析 `#RRGGBB`（`#` 可省略），格式错误时返回 `None`
+    pub fn parse_color(hex: &str) -> Option<[u8; 3]> {
+        let hex = hex.trim().trim_start_matches('#');
+        if hex.len() != 6 {
+            return None;
+        }
+        let value = u32::from_str_radix(hex, 16).ok()?;
+        let [_, r, g, b] = value.to_be_bytes();
+        Some([r, g, b])
+    }
+
+    pub fn format_color([r, g, b]: [u8; 3]) -> String {
+        format!("#{:02X}{:02X}{:02X}", r, g, b)
+    }
+}
+
+/// 姓名提取 API 的请求/响应字段映射
+///
+/// 响应字段为以 `.` 分隔的路径（如 `data.names`），数组元素可用下标访问（如 `results.0.names`）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiFieldMapping {
+    pub request_text_field: String,
+    pub names_path: String,
+    /// 为空表示服务不返回置信度，全部视为可信
+    pub confidence_path: String,
+    /// 为空表示服务不返回位置
+    pub positions_path: String,
+}
+
+impl Default for ApiFieldMapping {
+    fn default() -> Self {
+        Self {
+            request_text_field: "text".to_string(),
+            names_path: "names".to_string(),
+            confidence_path: "confidence".to_string(),
+            positions_path: "positions".to_string(),
+        }
+    }
+}
+
+/// 缺省的字段取默认值，JSON 与 TOML 共用同一套字段名
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(remote = "Self", default)]
 pub struct Config {
-    pub context_lines: u32,
+    /// 命中行之前包含的上下文行数
+    #[serde(default = "default_context_lines")]
+    pub context_before_lines: u32,
+    /// 命中行之后包含的上下文行数
+    #[serde(default = "default_context_lines")]
+    pub context_after_lines: u32,
+    /// 上下文中同一行各列之间的分隔符
+    pub context_cell_sep: String,
+    /// 上下文多行之间的分隔符（导出时使用）
+    pub context_row_sep: String,
     pub target_column: String,
+    /// 读取工作表时去掉首尾的全空行（如只有样式没有数据的模板行）
+    pub trim_empty_rows: bool,
+    /// 提取隐藏行列中的数据；关闭后只处理可见单元格（仅 xlsx/xlsm）
+    pub include_hidden: bool,
+    /// 日期格式单元格按 `YYYY-MM-DD` 读取，而不是 Excel 的日期序列号
+    pub convert_date_cells: bool,
+    /// 短于该字符数的单元格不做正则匹配
+    pub min_cell_length: usize,
+    /// 单元格超过该字符数时只提取开头部分，避免超长文本拖慢正则
+    pub max_cell_length: usize,
+    /// 单个单元格最多保留的命中数，超过时截断并标记为异常数据（0 表示不限制）
+    pub max_matches_per_cell: usize,
     pub enable_phone: bool,
     pub enable_id_card: bool,
     pub enable_bank_card: bool,
     pub enable_name: bool,
+    /// 提取澳门居民身份证号
+    #[serde(default)]
+    pub enable_macau_id: bool,
+    /// 提取“微信号”前缀后的微信号
+    #[serde(default)]
+    pub enable_wechat: bool,
+    /// 手机号命中去掉 `+86` 国家码，只保留 11 位号码部分
+    #[serde(default)]
+    pub strip_phone_country_code: bool,
+    /// 身份证号前两位不是已分配的省级代码时仍按日期和校验码判定有效，默认判为地区码无效
+    #[serde(default)]
+    pub accept_unknown_id_region: bool,
     pub api_host: String,
+    pub api_field_mapping: ApiFieldMapping,
+    /// 处理完成后推送统计摘要的机器人地址，为空时不推送
+    pub webhook_url: String,
+    /// 推送时要 `@` 的手机号，`@all` 表示所有人
+    pub webhook_mentions: Vec<String>,
+    /// 银行卡号长度约束，不满足的命中直接丢弃
+    #[serde(default)]
+    pub bank_card_length: LengthLimit,
+    /// 银行卡号最短位数策略，默认 16-19 位
+    #[serde(default)]
+    pub card_length_policy: CardLengthPolicy,
+    /// 自定义提取规则，正则无效的规则不参与提取
+    #[serde(default)]
+    pub custom_patterns: Vec<CustomPattern>,
+    /// 确定为误报的值（公司总机、测试卡等），按规范化值比对，命中后直接剔除
+    #[serde(default)]
+    pub exclude_values: Vec<String>,
+    /// 不扫描的工作表名（不区分大小写），如“目录”“模板”
+    pub excluded_sheet_names: Vec<String>,
+    /// 不扫描的工作表名通配符，`*` 匹配任意字符，`?` 匹配单个字符
+    pub excluded_sheet_name_patterns: Vec<String>,
+    /// 疑似占位号码的最小规律长度（0 表示关闭检测）
+    pub suspicious_run_length: u32,
+    /// 导出文件名模板（不含扩展名）
+    pub output_filename_template: String,
+    /// 按源文件分别导出时，同名文件的处理策略
+    pub export_conflict_strategy: ExportConflictStrategy,
+    /// 分析型脱敏：身份证号导出为掩码，并附带省份、性别、年龄段
+    pub id_card_analytic_mask: bool,
+    /// 导出时对手机号、身份证号、银行卡号脱敏
+    pub enable_mask: bool,
+    pub mask_rule: MaskRule,
+    /// 导入时抽样统计命中密度，高密度文件优先处理
+    pub prioritize_by_density: bool,
+    /// 抽样行数
+    pub density_sample_rows: usize,
+    /// 导出格式，可多选
+    pub output_formats: Vec<OutputFormat>,
+    /// 上次在导出菜单中选择的格式，点击导出按钮时沿用；为空时按 `output_formats` 导出
+    #[serde(default)]
+    pub last_export_format: Option<OutputFormat>,
+    /// 人工批注文件（第一列为行号或命中值，第二列为备注），设置后导出时合并为“备注”列
+    #[serde(default)]
+    pub annotation_file: Option<PathBuf>,
+    #[serde(default)]
+    pub annotation_key: AnnotationKey,
+    /// 导出时附带的源数据列（按列名），追加在标准列之后
+    #[serde(default)]
+    pub include_source_columns: Vec<String>,
+    /// 一行内多个命中拆成多行导出，每行带该命中附近的源文本片段
+    #[serde(default)]
+    pub one_match_per_row: bool,
+    /// xlsx 导出时源文本超过该字符数则截断并加省略号，完整内容放在单元格批注中
+    #[serde(default)]
+    pub truncate_source_text: Option<usize>,
+    /// xlsx 导出时文本列（源文本、上下文）的最大列宽
+    pub text_column_max_width: f64,
+    /// 表格导出（xlsx、csv）中手机号按 3-4-4、银行卡号按 4 位分组显示，不影响内部值和校验
+    pub pretty_format_output: bool,
+    /// 表格导出（xlsx、csv）中每类命中拆成有效值和无效值两列，代替命中值和有效性两列
+    #[serde(default)]
+    pub split_valid_columns: bool,
+    /// xlsx 导出时源文本和上下文列自动换行，行高随内容由 Excel 自适应
+    pub wrap_text_in_export: bool,
+    /// xlsx 导出的表头样式和有效性配色
+    pub export_style: ExportStyle,
+    /// 按前两行内容推测表头所在行，用于第一行是数据而非表头的工作表
+    #[serde(default)]
+    pub auto_detect_header: bool,
+    /// 逐个顺序处理文件而不使用并行线程池，便于调试
+    #[serde(default)]
+    pub force_sequential: bool,
+    /// 并行处理文件的最大线程数，`None` 时按 CPU 核数；为 1 时逐个顺序处理
+    #[serde(default)]
+    pub max_threads: Option<usize>,
+    /// 文件处理失败时（如文件被占用）稍等片刻后自动重试一次
+    #[serde(default)]
+    pub retry_failed_files: bool,
+    /// 流式导出：每处理完一个文件就写入导出文件，不在内存中保留全部结果，适合超大批量
+    #[serde(default)]
+    pub stream_export: bool,
+    /// 导出源文件的完整路径，路径本身涉及隐私时可关闭
+    pub export_source_path: bool,
+    /// 带密码 xlsx 的打开密码，仅保存在内存中
+    #[serde(skip)]
+    pub excel_password: Option<String>,
+    /// 导出归档时加密命中值所用的口令，仅保存在内存中；`None` 表示不加密
+    #[serde(skip)]
+    pub archive_passphrase: Option<String>,
+    /// 界面语言，切换后立即生效
+    #[serde(default)]
+    pub language: Language,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
-            context_lines: 2,
+            context_before_lines: default_context_lines(),
+            context_after_lines: default_context_lines(),
+            context_cell_sep: DEFAULT_CONTEXT_CELL_SEP.to_string(),
+            context_row_sep: DEFAULT_CONTEXT_ROW_SEP.to_string(),
             target_column: "消息内容".to_string(),
+            trim_empty_rows: true,
+            include_hidden: true,
+            convert_date_cells: true,
+            min_cell_length: 8,
+            max_cell_length: 10000,
+            max_matches_per_cell: 100,
             enable_phone: true,
             enable_id_card: true,
             enable_bank_card: true,
             enable_name: false,
+            enable_macau_id: false,
+            enable_wechat: false,
+            strip_phone_country_code: false,
+            accept_unknown_id_region: false,
             api_host: "localhost:8080".to_string(),
+            api_field_mapping: ApiFieldMapping::default(),
+            webhook_url: String::new(),
+            webhook_mentions: Vec::new(),
+            bank_card_length: LengthLimit::default(),
+            card_length_policy: CardLengthPolicy::default(),
+            custom_patterns: Vec::new(),
+            exclude_values: Vec::new(),
+            excluded_sheet_names: Vec::new(),
+            excluded_sheet_name_patterns: Vec::new(),
+            suspicious_run_length: 6,
+            output_filename_template: "{source}_{timestamp}".to_string(),
+            export_conflict_strategy: ExportConflictStrategy::default(),
+            id_card_analytic_mask: false,
+            enable_mask: false,
+            mask_rule: MaskRule::default(),
+            prioritize_by_density: false,
+            density_sample_rows: 200,
+            output_formats: vec![OutputFormat::Xlsx],
+            last_export_format: None,
+            annotation_file: None,
+            annotation_key: AnnotationKey::default(),
+            include_source_columns: Vec::new(),
+            one_match_per_row: false,
+            truncate_source_text: None,
+            text_column_max_width: 50.0,
+            wrap_text_in_export: true,
+            pretty_format_output: false,
+            split_valid_columns: false,
+            export_style: ExportStyle::default(),
+            auto_detect_header: false,
+            force_sequential: false,
+            max_threads: None,
+            retry_failed_files: false,
+            stream_export: false,
+            export_source_path: true,
+            excel_password: None,
+            archive_passphrase: None,
+            language: Language::default(),
         }
     }
 }
 
+fn default_context_lines() -> u32 {
+    2
+}
+
+/// 上下文行内列分隔符的默认值
+pub const DEFAULT_CONTEXT_CELL_SEP: &str = " | ";
+/// 上下文行间分隔符的默认值
+pub const DEFAULT_CONTEXT_ROW_SEP: &str = "\n";
+
+impl Serialize for Config {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Config::serialize(self, serializer)
+    }
+}
+
+/// 兼容旧配置：单一的 `context_lines` 迁移为前后相同的行数
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct CompatConfig {
+            context_lines: Option<u32>,
+            #[serde(flatten, with = "Config")]
+            config: Config,
+        }
+
+        let CompatConfig { context_lines, mut config } = CompatConfig::deserialize(deserializer)?;
+        if let Some(lines) = context_lines {
+            config.context_before_lines = lines;
+            config.context_after_lines = lines;
+        }
+        Ok(config)
+    }
+}
+
 impl Config {
     pub fn has_any_extraction_enabled(&self) -> bool {
-        self.enable_phone || self.enable_id_card || self.enable_bank_card || self.enable_name
+        self.enable_phone
+            || self.enable_id_card
+            || self.enable_bank_card
+            || self.enable_name
+            || self.enable_macau_id
+            || self.enable_wechat
+            || !self.custom_patterns.is_empty()
+    }
+
+    /// 读取配置文件，按扩展名区分 JSON（`.json`）与 TOML（`.toml`）
+    pub fn load(path: &Path) -> Result<Self> {
+        match ConfigFormat::from_path(path) {
+            Some(ConfigFormat::Json) => {
+                let content = fs::read_to_string(path)
+                    .with_context(|| format!("无法读取配置文件: {}", path.display()))?;
+                serde_json::from_str(&content)
+                    .with_context(|| format!("配置文件格式错误: {}", path.display()))
+            }
+            Some(ConfigFormat::Toml) => Self::from_toml(path),
+            None => bail!("不支持的配置文件类型: {}", path.display()),
+        }
+    }
+
+    /// 按指定格式保存配置
+    pub fn save(&self, path: &Path, format: ConfigFormat) -> Result<()> {
+        match format {
+            ConfigFormat::Json => {
+                let content = serde_json::to_string_pretty(self)?;
+                fs::write(path, content)
+                    .with_context(|| format!("无法写入配置文件: {}", path.display()))
+            }
+            ConfigFormat::Toml => self.save_toml(path),
+        }
+    }
+
+    pub fn from_toml(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("无法读取配置文件: {}", path.display()))?;
+        toml::from_str(&content).with_context(|| format!("配置文件格式错误: {}", path.display()))
+    }
+
+    pub fn save_toml(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)?;
+        fs::write(path, content).with_context(|| format!("无法写入配置文件: {}", path.display()))
+    }
+
+    /// 加入排除列表；值为空或规范化后已在列表中时不加入，返回 false
+    pub fn add_exclude_value(&mut self, value: &str) -> bool {
+        let normalized = normalize_value(value);
+        if normalized.is_empty()
+            || self.exclude_values.iter().any(|v| normalize_value(v) == normalized)
+        {
+            return false;
+        }
+        self.exclude_values.push(value.trim().to_string());
+        true
+    }
+
+    /// 加入排除的工作表；含 `*` 或 `?` 的作为通配符，其余按名称。为空或已存在时返回 false
+    pub fn add_excluded_sheet(&mut self, entry: &str) -> bool {
+        let entry = entry.trim();
+        let list = if entry.contains(['*', '?']) {
+            &mut self.excluded_sheet_name_patterns
+        } else {
+            &mut self.excluded_sheet_names
+        };
+        if entry.is_empty() || list.iter().any(|e| e.eq_ignore_ascii_case(entry)) {
+            return false;
+        }
+        list.push(entry.to_string());
+        true
+    }
+
+    pub fn sheet_exclusion(&self) -> SheetExclusion {
+        SheetExclusion::new(&self.excluded_sheet_names, &self.excluded_sheet_name_patterns)
+    }
+}
+
+/// 按名称或通配符排除的工作表，比较时不区分大小写
+#[derive(Debug, Clone, Default)]
+pub struct SheetExclusion {
+    names: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl SheetExclusion {
+    pub fn new(names: &[String], patterns: &[String]) -> Self {
+        Self {
+            names: names.iter().map(|n| n.trim().to_lowercase()).filter(|n| !n.is_empty()).collect(),
+            patterns: patterns.iter().filter_map(|p| glob_regex(p.trim())).collect(),
+        }
+    }
+
+    pub fn is_excluded(&self, sheet_name: &str) -> bool {
+        let name = sheet_name.trim().to_lowercase();
+        self.names.contains(&name) || self.patterns.iter().any(|p| p.is_match(&name))
+    }
+
+    /// 去掉被排除的工作表，保持原有顺序
+    pub fn filter(&self, sheet_names: Vec<String>) -> Vec<String> {
+        sheet_names.into_iter().filter(|name| !self.is_excluded(name)).collect()
+    }
+}
+
+/// 通配符转为整串匹配的正则，其余字符按字面匹配
+fn glob_regex(pattern: &str) -> Option<Regex> {
+    if pattern.is_empty() {
+        return None;
+    }
+    let body: String = pattern
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            c => regex::escape(&c.to_lowercase().to_string()),
+        })
+        .collect();
+    Regex::new(&format!("^{}$", body)).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::scratch_dir;
+
+    #[test]
+    fn test_length_limit() {
+        let limit = LengthLimit {
+            min_len: Some(16),
+            max_len: Some(17),
+        };
+        assert!(limit.allows("6225 8801 2345 6789"));
+        assert!(limit.allows("6225-8801-2345-67891"));
+        assert!(limit.allows("6225.8801.2345.6789"));
+        assert!(!limit.allows("622588012345678901"));
+        assert!(!limit.allows("622588012345678"));
+        assert!(LengthLimit::default().allows("1"));
+    }
+
+    #[test]
+    fn test_add_exclude_value() {
+        let mut config = Config::default();
+        assert!(config.add_exclude_value(" 138-1234-5678 "));
+        assert_eq!(config.exclude_values, ["138-1234-5678"]);
+        assert!(!config.add_exclude_value("+86 13812345678"));
+        assert!(!config.add_exclude_value(" - "));
+        assert!(config.add_exclude_value("4111111111111111"));
+        assert_eq!(config.exclude_values.len(), 2);
+    }
+
+    #[test]
+    fn test_sheet_exclusion() {
+        let mut config = Config::default();
+        assert!(config.add_excluded_sheet(" 目录 "));
+        assert!(config.add_excluded_sheet("Template*"));
+        assert!(config.add_excluded_sheet("sheet?"));
+        assert!(!config.add_excluded_sheet("目录"));
+        assert!(!config.add_excluded_sheet("  "));
+        assert_eq!(config.excluded_sheet_names, ["目录"]);
+        assert_eq!(config.excluded_sheet_name_patterns, ["Template*", "sheet?"]);
+
+        let exclusion = config.sheet_exclusion();
+        assert!(exclusion.is_excluded("目录"));
+        assert!(exclusion.is_excluded("template_2024"));
+        assert!(exclusion.is_excluded("Sheet9"));
+        assert!(!exclusion.is_excluded("Sheet10"));
+        assert!(!exclusion.is_excluded("目录1"));
+        assert!(!exclusion.is_excluded("my.template"));
+        let names = ["目录", "客服", "Template"].map(String::from).to_vec();
+        assert_eq!(exclusion.filter(names), ["客服"]);
+        assert!(!SheetExclusion::default().is_excluded("目录"));
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let config = Config {
+            context_before_lines: 1,
+            target_column: "内容".to_string(),
+            enable_name: true,
+            bank_card_length: LengthLimit {
+                min_len: Some(16),
+                max_len: None,
+            },
+            exclude_values: vec!["4001234567".to_string()],
+            output_formats: vec![OutputFormat::Csv, OutputFormat::Parquet],
+            include_source_columns: vec!["姓名".to_string()],
+            mask_rule: MaskRule {
+                keep_prefix: 2,
+                keep_suffix: 2,
+            },
+            language: Language::English,
+            ..Default::default()
+        };
+
+        let text = toml::to_string_pretty(&config).unwrap();
+        let restored: Config = toml::from_str(&text).unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&config).unwrap()
+        );
+
+        let dir = scratch_dir();
+
+        let path = dir.path().join("config.toml");
+        config.save_toml(&path).unwrap();
+        let loaded = Config::load(&path).unwrap();
+        assert_eq!(loaded.target_column, "内容");
+        assert_eq!(loaded.language, Language::English);
+    }
+
+    #[test]
+    fn test_toml_missing_fields_use_defaults() {
+        let config: Config = toml::from_str(
+            r#"
+            # 只改目标列和导出格式
+            target_column = "短信"
+            output_formats = ["Csv"]
+            "#,
+        )
+        .unwrap();
+
+        let defaults = Config::default();
+        assert_eq!(config.target_column, "短信");
+        assert_eq!(config.output_formats, [OutputFormat::Csv]);
+        assert_eq!(config.context_before_lines, defaults.context_before_lines);
+        assert_eq!(config.enable_phone, defaults.enable_phone);
+        assert_eq!(config.api_host, defaults.api_host);
+        assert_eq!(config.density_sample_rows, defaults.density_sample_rows);
+    }
+
+    #[test]
+    fn test_export_style_colors() {
+        assert_eq!(ExportStyle::parse_color("#4472C4"), Some([0x44, 0x72, 0xC4]));
+        assert_eq!(ExportStyle::parse_color("ff0000"), Some([0xFF, 0, 0]));
+        assert_eq!(ExportStyle::parse_color("#12345"), None);
+        assert_eq!(ExportStyle::parse_color("#GGGGGG"), None);
+        assert_eq!(ExportStyle::format_color([0x44, 0x72, 0xC4]), "#4472C4");
+    }
+
+    #[test]
+    fn test_row_color_rule() {
+        use crate::models::MatchInfo;
+
+        let mut phone_only = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        phone_only.phone_numbers = vec![MatchInfo::builder("13907315286", PatternType::Phone).valid(true).build()];
+        let mut with_id_card = phone_only.clone();
+        with_id_card.id_cards = vec![MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build()];
+
+        let id_card = RowColorRule { types: vec![PatternType::IdCard], exclusive: false, color: "#FCE4D6".into() };
+        let only_phone = RowColorRule { types: vec![PatternType::Phone], exclusive: true, color: "#E2EFDA".into() };
+        assert!(id_card.matches(&with_id_card));
+        assert!(!id_card.matches(&phone_only));
+        assert!(only_phone.matches(&phone_only));
+        assert!(!only_phone.matches(&with_id_card));
+
+        let empty = RowColorRule { types: Vec::new(), exclusive: true, color: "#FFFFFF".into() };
+        assert!(!empty.matches(&phone_only));
+    }
+
+    #[test]
+    fn test_config_format_from_path() {
+        assert_eq!(ConfigFormat::from_path(Path::new("a.json")), Some(ConfigFormat::Json));
+        assert_eq!(ConfigFormat::from_path(Path::new("a.TOML")), Some(ConfigFormat::Toml));
+        assert_eq!(ConfigFormat::from_path(Path::new("a.yaml")), None);
+        assert!(Config::load(Path::new("a.yaml")).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_legacy_context_lines_migrated() {
+        let mut value = serde_json::to_value(Config::default()).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("context_before_lines");
+        object.remove("context_after_lines");
+        object.insert("context_lines".to_string(), 5.into());
+
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.context_before_lines, 5);
+        assert_eq!(config.context_after_lines, 5);
+    }
+
+    #[test]
+    fn test_context_lines_round_trip() {
+        let config = Config {
+            context_before_lines: 1,
+            context_after_lines: 4,
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("\"context_lines\""));
+
+        let restored: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.context_before_lines, 1);
+        assert_eq!(restored.context_after_lines, 4);
+    }
+
+    #[test]
+    fn test_language_defaults_to_chinese() {
+        let mut value = serde_json::to_value(Config {
+            language: Language::English,
+            ..Default::default()
+        })
+        .unwrap();
+        let restored: Config = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(restored.language, Language::English);
+
+        value.as_object_mut().unwrap().remove("language");
+        let config: Config = serde_json::from_value(value).unwrap();
+        assert_eq!(config.language, Language::Chinese);
+    }
+}
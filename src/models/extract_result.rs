@@ -1,42 +1,182 @@
+use crate::core::validators::ValidationError;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
+/// 单元格命中数超过上限被截断时的提示
+pub const TOO_MANY_MATCHES_NOTE: &str = "该单元格命中过多，可能为异常数据";
+
+/// 命中值的来源规则，便于排查误报
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PatternType {
+    Phone,
+    IdCard,
+    BankCard,
+    NameApi,
+    MacauId,
+    WechatId,
+    /// 配置中的自定义规则，规则名见 [`MatchInfo::rule`]
+    Custom,
+}
+
+impl PatternType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Phone => "PHONE",
+            Self::IdCard => "ID_CARD",
+            Self::BankCard => "BANK_CARD",
+            Self::NameApi => "NAME_API",
+            Self::MacauId => "MACAU_ID",
+            Self::WechatId => "WECHAT_ID",
+            Self::Custom => "CUSTOM",
+        }
+    }
+
+    /// 命中来自外部 API 而非本地正则
+    pub fn is_api(&self) -> bool {
+        matches!(self, Self::NameApi)
+    }
+}
+
+/// 命中值；JSON 中省略取默认值的可选字段，批量导出时减小体积
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MatchInfo {
     pub value: String,
     pub is_valid: bool,
     pub position: (usize, usize),
+    #[serde(rename = "pattern")]
+    pub source_pattern: PatternType,
+    /// 疑似占位/测试数据（连续递增、全相同等规律号码）
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub suspicious: bool,
+    /// 校验失败的具体原因（身份证号、手机号、银行卡号）
+    #[serde(rename = "validation_error", default, skip_serializing_if = "Option::is_none")]
+    pub error_detail: Option<ValidationError>,
+    /// 来源给出的置信度（如姓名 API），正则命中为 `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// 命中的自定义规则名，内置类型为 `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
 }
 
 impl MatchInfo {
-    pub fn new(value: impl Into<String>, is_valid: bool, start: usize, end: usize) -> Self {
-        Self {
-            value: value.into(),
-            is_valid,
-            position: (start, end),
+    /// 按字段名构建命中，未设置的字段取默认值（无效、无位置、非占位、无置信度）
+    pub fn builder(value: impl Into<String>, source_pattern: PatternType) -> MatchInfoBuilder {
+        MatchInfoBuilder {
+            info: Self {
+                value: value.into(),
+                is_valid: false,
+                position: (0, 0),
+                source_pattern,
+                suspicious: false,
+                error_detail: None,
+                confidence: None,
+                rule: None,
+            },
         }
     }
 
-    pub fn simple(value: impl Into<String>, is_valid: bool) -> Self {
-        Self {
-            value: value.into(),
-            is_valid,
-            position: (0, 0),
-        }
+    /// 合并去重时比较的置信度：未给出时有效命中为 1，无效命中为 0
+    pub fn confidence_score(&self) -> f64 {
+        self.confidence.unwrap_or(if self.is_valid { 1.0 } else { 0.0 })
+    }
+
+    /// 是否带有在源文本中的实际位置
+    pub fn is_located(&self) -> bool {
+        self.position.1 > self.position.0
+    }
+}
+
+/// [`MatchInfo`] 的构建器，由 [`MatchInfo::builder`] 创建
+#[derive(Debug, Clone)]
+pub struct MatchInfoBuilder {
+    info: MatchInfo,
+}
+
+impl MatchInfoBuilder {
+    pub fn valid(mut self, is_valid: bool) -> Self {
+        self.info.is_valid = is_valid;
+        self
+    }
+
+    /// 在源文本中的字节区间
+    pub fn position(mut self, start: usize, end: usize) -> Self {
+        self.info.position = (start, end);
+        self
+    }
+
+    pub fn suspicious(mut self, suspicious: bool) -> Self {
+        self.info.suspicious = suspicious;
+        self
+    }
+
+    /// 校验失败的具体原因
+    pub fn error_detail(mut self, error_detail: Option<ValidationError>) -> Self {
+        self.info.error_detail = error_detail;
+        self
+    }
+
+    pub fn confidence(mut self, confidence: f64) -> Self {
+        self.info.confidence = Some(confidence);
+        self
+    }
+
+    /// 自定义规则名
+    pub fn rule(mut self, rule: impl Into<String>) -> Self {
+        self.info.rule = Some(rule.into());
+        self
+    }
+
+    pub fn build(self) -> MatchInfo {
+        self.info
     }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExtractResult {
     pub source_file: String,
+    /// 源文件完整路径，区分不同目录下的同名文件
+    #[serde(default)]
+    pub source_path: String,
     pub sheet_name: String,
     pub row_number: u32,
+    /// 目标列的列索引（从 0 开始），与 `row_number` 一起定位单元格
+    #[serde(default)]
+    pub column_index: Option<u32>,
     pub phone_numbers: Vec<MatchInfo>,
     pub id_cards: Vec<MatchInfo>,
     pub bank_cards: Vec<MatchInfo>,
     pub names: Vec<MatchInfo>,
+    /// 澳门居民身份证号
+    #[serde(default)]
+    pub macau_ids: Vec<MatchInfo>,
+    /// 微信号
+    #[serde(default)]
+    pub wechat_ids: Vec<MatchInfo>,
+    /// 自定义规则的命中
+    #[serde(default)]
+    pub custom_matches: Vec<MatchInfo>,
     pub source_text: String,
     pub context_before: Vec<String>,
     pub context_after: Vec<String>,
+    /// 按配置附带的源数据列（列名 -> 单元格值）
+    #[serde(default)]
+    pub extra_columns: HashMap<String, String>,
+    /// 导出时从批注文件合并的人工备注
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub remark: String,
+    /// 提取该行的时间（RFC 3339），用于审计及与历史结果对照
+    #[serde(rename = "timestamp", default)]
+    pub processing_timestamp: String,
+    /// 命中数超过 `max_matches_per_cell`，只保留了前面的部分
+    #[serde(default)]
+    pub matches_truncated: bool,
 }
 
 impl ExtractResult {
@@ -47,18 +187,38 @@ impl ExtractResult {
     ) -> Self {
         Self {
             source_file: source_file.into(),
+            source_path: String::new(),
             sheet_name: sheet_name.into(),
             row_number,
+            column_index: None,
             phone_numbers: Vec::new(),
             id_cards: Vec::new(),
             bank_cards: Vec::new(),
             names: Vec::new(),
+            macau_ids: Vec::new(),
+            wechat_ids: Vec::new(),
+            custom_matches: Vec::new(),
             source_text: String::new(),
             context_before: Vec::new(),
             context_after: Vec::new(),
+            extra_columns: HashMap::new(),
+            remark: String::new(),
+            processing_timestamp: String::new(),
+            matches_truncated: false,
         }
     }
 
+    /// A1 样式的单元格引用，如 `Sheet1!C1234`；列索引未知时为 `None`
+    pub fn cell_ref(&self) -> Option<String> {
+        let column = self.column_index?;
+        Some(format!(
+            "{}!{}{}",
+            quote_sheet_name(&self.sheet_name),
+            column_letters(column),
+            self.row_number
+        ))
+    }
+
     pub fn phone_numbers_str(&self) -> String {
         format_matches(&self.phone_numbers)
     }
@@ -75,6 +235,14 @@ impl ExtractResult {
         format_matches(&self.names)
     }
 
+    pub fn macau_ids_str(&self) -> String {
+        format_matches(&self.macau_ids)
+    }
+
+    pub fn wechat_ids_str(&self) -> String {
+        format_matches(&self.wechat_ids)
+    }
+
     pub fn phone_validity_str(&self) -> String {
         format_validity(&self.phone_numbers)
     }
@@ -91,6 +259,35 @@ impl ExtractResult {
         format_validity(&self.names)
     }
 
+    pub fn macau_id_validity_str(&self) -> String {
+        format_validity(&self.macau_ids)
+    }
+
+    pub fn wechat_id_validity_str(&self) -> String {
+        format_validity(&self.wechat_ids)
+    }
+
+    pub fn suspicious_str(&self) -> String {
+        self.phone_numbers
+            .iter()
+            .chain(&self.id_cards)
+            .chain(&self.bank_cards)
+            .chain(&self.macau_ids)
+            .filter(|m| m.suspicious)
+            .map(|m| m.value.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// 异常标记列的文本，命中被截断时为提示语
+    pub fn anomaly_str(&self) -> String {
+        if self.matches_truncated {
+            TOO_MANY_MATCHES_NOTE.to_string()
+        } else {
+            String::new()
+        }
+    }
+
     pub fn context_before_str(&self) -> String {
         self.context_before.join("\n")
     }
@@ -98,6 +295,290 @@ impl ExtractResult {
     pub fn context_after_str(&self) -> String {
         self.context_after.join("\n")
     }
+
+    /// 命中总数
+    pub fn match_count(&self) -> usize {
+        self.phone_numbers.len()
+            + self.id_cards.len()
+            + self.bank_cards.len()
+            + self.names.len()
+            + self.macau_ids.len()
+            + self.wechat_ids.len()
+            + self.custom_matches.len()
+    }
+
+    /// 命中总数超过 `max` 时按手机号、身份证号、银行卡号、姓名、澳门身份证号、微信号、自定义规则的顺序保留前 `max` 条，
+    /// 并标记为已截断；返回是否发生截断
+    pub fn cap_matches(&mut self, max: usize) -> bool {
+        if self.match_count() <= max {
+            return false;
+        }
+
+        let mut remaining = max;
+        for matches in [
+            &mut self.phone_numbers,
+            &mut self.id_cards,
+            &mut self.bank_cards,
+            &mut self.names,
+            &mut self.macau_ids,
+            &mut self.wechat_ids,
+            &mut self.custom_matches,
+        ] {
+            matches.truncate(remaining);
+            remaining -= matches.len();
+        }
+        self.matches_truncated = true;
+        true
+    }
+
+    /// 拆成每条命中一行：`source_text` 换为该命中附近的片段，上下文沿用整行的
+    pub fn split_per_match(&self) -> Vec<ExtractResult> {
+        let single = |pick: fn(&mut ExtractResult) -> &mut Vec<MatchInfo>, m: &MatchInfo| {
+            let mut result = ExtractResult {
+                phone_numbers: Vec::new(),
+                id_cards: Vec::new(),
+                bank_cards: Vec::new(),
+                names: Vec::new(),
+                macau_ids: Vec::new(),
+                wechat_ids: Vec::new(),
+                custom_matches: Vec::new(),
+                source_text: match_snippet(&self.source_text, m),
+                ..self.clone()
+            };
+            pick(&mut result).push(m.clone());
+            result
+        };
+
+        let mut rows = Vec::with_capacity(self.match_count());
+        rows.extend(self.phone_numbers.iter().map(|m| single(|r| &mut r.phone_numbers, m)));
+        rows.extend(self.id_cards.iter().map(|m| single(|r| &mut r.id_cards, m)));
+        rows.extend(self.bank_cards.iter().map(|m| single(|r| &mut r.bank_cards, m)));
+        rows.extend(self.names.iter().map(|m| single(|r| &mut r.names, m)));
+        rows.extend(self.macau_ids.iter().map(|m| single(|r| &mut r.macau_ids, m)));
+        rows.extend(self.wechat_ids.iter().map(|m| single(|r| &mut r.wechat_ids, m)));
+        rows.extend(self.custom_matches.iter().map(|m| single(|r| &mut r.custom_matches, m)));
+        rows
+    }
+
+    /// 序列化为一行制表符分隔文本，列序与导出表头一致
+    ///
+    /// 字段内的 `\`、制表符与换行转义为 `\\`、`\t`、`\n`、`\r`，保证一条结果只占一行
+    pub fn to_csv_row(&self) -> String {
+        let fields = [
+            self.processing_timestamp.clone(),
+            self.source_file.clone(),
+            self.sheet_name.clone(),
+            self.row_number.to_string(),
+            self.cell_ref().unwrap_or_default(),
+            self.phone_numbers_str(),
+            self.phone_validity_str(),
+            self.id_cards_str(),
+            self.id_card_validity_str(),
+            self.bank_cards_str(),
+            self.bank_card_validity_str(),
+            self.names_str(),
+            self.names_validity_str(),
+            self.source_text.clone(),
+            self.context_before_str(),
+            self.context_after_str(),
+            self.suspicious_str(),
+            self.anomaly_str(),
+        ];
+        fields.iter().map(|f| escape_field(f)).collect::<Vec<_>>().join("\t")
+    }
+
+    /// 解析 `to_csv_row` 生成的行
+    ///
+    /// 行内不含命中位置，解析出的命中位置为 `(0, 0)`，附带列为空；
+    /// 兼容不含处理时间列、异常标记列的旧格式行
+    pub fn from_csv_row(row: &str) -> Result<Self> {
+        let mut fields: Vec<String> = row
+            .trim_end_matches(['\r', '\n'])
+            .split('\t')
+            .map(unescape_field)
+            .collect();
+        if fields.len() == CSV_ROW_COLUMNS - 2 {
+            fields.insert(0, String::new());
+        }
+        if fields.len() == CSV_ROW_COLUMNS - 1 {
+            fields.push(String::new());
+        }
+        if fields.len() != CSV_ROW_COLUMNS {
+            bail!("列数错误：应为 {} 列，实际 {} 列", CSV_ROW_COLUMNS, fields.len());
+        }
+
+        let row_number = fields[3]
+            .parse()
+            .with_context(|| format!("行号无效: {}", fields[3]))?;
+        let column_index = match fields[4].as_str() {
+            "" => None,
+            cell_ref => Some(
+                parse_column_letters(cell_ref)
+                    .with_context(|| format!("单元格位置无效: {}", cell_ref))?,
+            ),
+        };
+        let suspicious: HashSet<&str> = split_list(&fields[16]).collect();
+        let matches = |column: usize, pattern: PatternType| -> Result<Vec<MatchInfo>> {
+            let values: Vec<&str> = split_list(&fields[column]).collect();
+            let validity: Vec<&str> = split_list(&fields[column + 1]).collect();
+            if values.len() != validity.len() {
+                bail!("第 {} 列命中值与有效性数量不一致", column + 1);
+            }
+            values
+                .into_iter()
+                .zip(validity)
+                .map(|(value, validity)| {
+                    let (is_valid, error_detail) = match parse_validity(validity) {
+                        Some(parsed) => parsed,
+                        None => bail!("无法识别的有效性: {}", validity),
+                    };
+                    Ok(MatchInfo::builder(value, pattern)
+                        .valid(is_valid)
+                        .suspicious(suspicious.contains(value))
+                        .error_detail(error_detail)
+                        .build())
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            column_index,
+            phone_numbers: matches(5, PatternType::Phone)?,
+            id_cards: matches(7, PatternType::IdCard)?,
+            bank_cards: matches(9, PatternType::BankCard)?,
+            names: matches(11, PatternType::NameApi)?,
+            source_text: fields[13].clone(),
+            context_before: split_lines(&fields[14]),
+            context_after: split_lines(&fields[15]),
+            processing_timestamp: fields[0].clone(),
+            matches_truncated: !fields[17].is_empty(),
+            ..Self::new(fields[1].clone(), fields[2].clone(), row_number)
+        })
+    }
+}
+
+/// `to_csv_row` 的列数
+const CSV_ROW_COLUMNS: usize = 18;
+
+fn escape_field(field: &str) -> String {
+    let mut escaped = String::with_capacity(field.len());
+    for c in field.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape_field(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// 拆分 `, ` 连接的列表，空字段视为空列表
+fn split_list(field: &str) -> impl Iterator<Item = &str> {
+    field.split(", ").filter(|_| !field.is_empty())
+}
+
+fn split_lines(field: &str) -> Vec<String> {
+    if field.is_empty() {
+        Vec::new()
+    } else {
+        field.split('\n').map(str::to_string).collect()
+    }
+}
+
+/// 列索引（从 0 开始）转为列字母：0 → A，25 → Z，26 → AA
+pub fn column_letters(index: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = index as u64 + 1;
+    while n > 0 {
+        n -= 1;
+        letters.push(char::from(b'A' + (n % 26) as u8));
+        n /= 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// 从单元格引用（如 `Sheet1!AB12`）中解析列索引
+fn parse_column_letters(cell_ref: &str) -> Option<u32> {
+    let cell = cell_ref.rsplit('!').next()?;
+    let letters: Vec<u8> = cell.bytes().take_while(u8::is_ascii_uppercase).collect();
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .iter()
+        .try_fold(0u32, |acc, &b| acc.checked_mul(26)?.checked_add(u32::from(b - b'A') + 1))
+        .map(|n| n - 1)
+}
+
+/// 工作表名含空格、符号或以数字开头时按 Excel 规则加单引号，名称中的单引号写成两个
+fn quote_sheet_name(name: &str) -> String {
+    let plain = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if plain {
+        name.to_string()
+    } else {
+        format!("'{}'", name.replace('\'', "''"))
+    }
+}
+
+/// 片段在命中值两侧各保留的字符数
+const SNIPPET_CONTEXT_CHARS: usize = 10;
+
+/// 命中值在源文本中的片段，两侧各带若干字符，截断处以 `…` 标出
+///
+/// 位置缺失或越界（如姓名 API 未返回位置）时按值查找，仍找不到则返回整段文本
+fn match_snippet(text: &str, m: &MatchInfo) -> String {
+    let (start, end) = m.position;
+    let span = if end > start && text.get(start..end).is_some() {
+        Some((start, end))
+    } else {
+        text.find(&m.value).map(|start| (start, start + m.value.len()))
+    };
+    let Some((start, end)) = span else {
+        return text.to_string();
+    };
+
+    let snippet_start = text[..start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map_or(0, |(index, _)| index);
+    let snippet_end = text[end..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map_or(text.len(), |(index, _)| end + index);
+
+    let mut snippet = String::new();
+    if snippet_start > 0 {
+        snippet.push('…');
+    }
+    snippet.push_str(&text[snippet_start..snippet_end]);
+    if snippet_end < text.len() {
+        snippet.push('…');
+    }
+    snippet
 }
 
 fn format_matches(matches: &[MatchInfo]) -> String {
@@ -108,10 +589,177 @@ fn format_matches(matches: &[MatchInfo]) -> String {
         .join(", ")
 }
 
+/// 有效性列：无效且有具体原因时写作 "无效（校验码错误）"
 fn format_validity(matches: &[MatchInfo]) -> String {
     matches
         .iter()
-        .map(|m| if m.is_valid { "有效" } else { "无效" })
+        .map(|m| match (m.is_valid, m.error_detail) {
+            (true, _) => "有效".to_string(),
+            (false, Some(reason)) => format!("无效（{}）", reason),
+            (false, None) => "无效".to_string(),
+        })
         .collect::<Vec<_>>()
         .join(", ")
-}
\ No newline at end of file
+}
+
+/// `format_validity` 的逆过程，无法识别时为 `None`
+fn parse_validity(validity: &str) -> Option<(bool, Option<ValidationError>)> {
+    match validity {
+        "有效" => Some((true, None)),
+        "无效" => Some((false, None)),
+        other => {
+            let reason = other.strip_prefix("无效（")?.strip_suffix('）')?;
+            Some((false, Some(ValidationError::from_reason(reason)?)))
+        }
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_match_info_json_omits_defaults() {
+        let plain = MatchInfo::builder("13907315286", PatternType::Phone).valid(true).position(2, 13).build();
+        let json = serde_json::to_value(&plain).unwrap();
+        let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(keys.len(), 4);
+        assert!(keys.iter().all(|k| ["value", "is_valid", "position", "pattern"].contains(k)));
+
+        let detailed = MatchInfo::builder("110105199003072038", PatternType::IdCard)
+            .error_detail(Some(ValidationError::InvalidChecksum))
+            .suspicious(true)
+            .confidence(0.5)
+            .build();
+        let json = serde_json::to_value(&detailed).unwrap();
+        assert_eq!(json["is_valid"], false);
+        assert_eq!(json["suspicious"], true);
+        assert_eq!(json["validation_error"], "InvalidChecksum");
+        assert_eq!(json["confidence"], 0.5);
+
+        let restored: MatchInfo = serde_json::from_value(serde_json::to_value(&plain).unwrap()).unwrap();
+        assert!(!restored.suspicious);
+        assert_eq!(restored.error_detail, None);
+        assert_eq!(restored.confidence, None);
+    }
+
+    #[test]
+    fn test_csv_row_round_trip() {
+        let mut result = ExtractResult::new("客户\t消息.xlsx", "Sheet1", 42);
+        result.column_index = Some(27);
+        result.processing_timestamp = "2024-05-01T10:30:00+08:00".to_string();
+        result.matches_truncated = true;
+        result.phone_numbers = vec![
+            MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build(),
+            MatchInfo::builder("12345678901", PatternType::Phone).valid(false).build(),
+        ];
+        result.phone_numbers[1].suspicious = true;
+        result.phone_numbers[1].error_detail = Some(ValidationError::InvalidPrefix);
+        result.id_cards = vec![MatchInfo::builder("110105199003072039", PatternType::IdCard).valid(true).build()];
+        result.bank_cards = vec![
+            MatchInfo::builder("6225880123456789", PatternType::BankCard).valid(false).build(),
+            MatchInfo::builder("6222021234567890123", PatternType::BankCard).valid(true).build(),
+        ];
+        result.names = vec![MatchInfo::builder("张三", PatternType::NameApi).valid(true).build()];
+        result.source_text = "第一行\n第二行 C:\\temp".to_string();
+        result.context_before = vec!["上一行".to_string(), String::new()];
+
+        let row = result.to_csv_row();
+        assert!(!row.contains('\n'));
+        assert_eq!(result.phone_validity_str(), "有效, 无效（号段无效）");
+        assert_eq!(row.split('\t').count(), CSV_ROW_COLUMNS);
+
+        let restored = ExtractResult::from_csv_row(&row).unwrap();
+        assert_eq!(restored.processing_timestamp, result.processing_timestamp);
+        assert!(restored.matches_truncated);
+        assert_eq!(restored.source_file, result.source_file);
+        assert_eq!(restored.sheet_name, result.sheet_name);
+        assert_eq!(restored.row_number, result.row_number);
+        assert_eq!(restored.column_index, Some(27));
+        assert_eq!(restored.source_text, result.source_text);
+        assert_eq!(restored.context_before, result.context_before);
+        assert!(restored.context_after.is_empty());
+
+        let summary = |matches: &[MatchInfo]| {
+            matches
+                .iter()
+                .map(|m| (m.value.clone(), m.is_valid, m.suspicious, m.source_pattern, m.error_detail))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(summary(&restored.phone_numbers), summary(&result.phone_numbers));
+        assert_eq!(summary(&restored.id_cards), summary(&result.id_cards));
+        assert_eq!(summary(&restored.bank_cards), summary(&result.bank_cards));
+        assert_eq!(summary(&restored.names), summary(&result.names));
+        assert_eq!(restored.to_csv_row(), row);
+
+        // 旧格式行没有处理时间列和异常标记列
+        let legacy = row.split_once('\t').unwrap().1.rsplit_once('\t').unwrap().0;
+        let restored = ExtractResult::from_csv_row(legacy).unwrap();
+        assert!(restored.processing_timestamp.is_empty());
+        assert!(!restored.matches_truncated);
+        assert_eq!(restored.row_number, 42);
+    }
+
+    #[test]
+    fn test_cap_matches() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 2);
+        result.phone_numbers = vec![MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build()];
+        result.bank_cards = (0..5)
+            .map(|i| MatchInfo::builder(format!("622588012345678{}", i), PatternType::BankCard).valid(false).build())
+            .collect();
+
+        assert!(!result.cap_matches(6));
+        assert!(result.anomaly_str().is_empty());
+
+        assert!(result.cap_matches(3));
+        assert_eq!(result.phone_numbers.len(), 1);
+        assert_eq!(result.bank_cards.len(), 2);
+        assert_eq!(result.match_count(), 3);
+        assert_eq!(result.anomaly_str(), TOO_MANY_MATCHES_NOTE);
+    }
+
+    #[test]
+    fn test_column_letters() {
+        assert_eq!(column_letters(0), "A");
+        assert_eq!(column_letters(2), "C");
+        assert_eq!(column_letters(25), "Z");
+        assert_eq!(column_letters(26), "AA");
+        assert_eq!(column_letters(27), "AB");
+        assert_eq!(column_letters(51), "AZ");
+        assert_eq!(column_letters(52), "BA");
+        assert_eq!(column_letters(701), "ZZ");
+        assert_eq!(column_letters(702), "AAA");
+        assert_eq!(column_letters(16383), "XFD");
+
+        for index in [0, 25, 26, 701, 702, 16383] {
+            assert_eq!(parse_column_letters(&column_letters(index)), Some(index));
+        }
+        assert_eq!(parse_column_letters("'My Sheet'!XFD3"), Some(16383));
+        assert_eq!(parse_column_letters("Sheet1!12"), None);
+    }
+
+    #[test]
+    fn test_cell_ref() {
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 1234);
+        assert_eq!(result.cell_ref(), None);
+
+        result.column_index = Some(2);
+        assert_eq!(result.cell_ref().as_deref(), Some("Sheet1!C1234"));
+
+        result.sheet_name = "客户 名单".to_string();
+        assert_eq!(result.cell_ref().as_deref(), Some("'客户 名单'!C1234"));
+        result.sheet_name = "Bob's".to_string();
+        assert_eq!(result.cell_ref().as_deref(), Some("'Bob''s'!C1234"));
+        result.sheet_name = "2024".to_string();
+        assert_eq!(result.cell_ref().as_deref(), Some("'2024'!C1234"));
+    }
+
+    #[test]
+    fn test_from_csv_row_rejects_malformed() {
+        assert!(ExtractResult::from_csv_row("a\tb\t1").is_err());
+
+        let mut result = ExtractResult::new("a.xlsx", "Sheet1", 1);
+        result.phone_numbers = vec![MatchInfo::builder("13812345678", PatternType::Phone).valid(true).build()];
+        let row = result.to_csv_row().replace("有效", "未知");
+        assert!(ExtractResult::from_csv_row(&row).is_err());
+    }
+}
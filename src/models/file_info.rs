@@ -1,5 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::SystemTime;
+
+use super::{ExtractResult, SheetExclusion};
+use crate::core::office_crypto::is_password_error;
+use crate::core::ExcelInfo;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[derive(Default)]
@@ -9,6 +16,10 @@ pub enum FileStatus {
     Processing(u8),
     Completed,
     Error(String),
+    /// 未处理而跳过（如加密文件未提供密码）
+    Skipped(String),
+    /// 已处理但有需要注意的问题（如部分工作表缺少目标列）
+    Warning(String),
 }
 
 
@@ -25,9 +36,56 @@ impl FileStatus {
         Self::Error(message.into())
     }
 
+    pub fn skipped(reason: impl Into<String>) -> Self {
+        Self::Skipped(reason.into())
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self::Warning(message.into())
+    }
+
     pub fn is_error(&self) -> bool {
         matches!(self, Self::Error(_))
     }
+
+    /// 出错或被跳过的文件不参与处理
+    pub fn is_unprocessable(&self) -> bool {
+        matches!(self, Self::Error(_) | Self::Skipped(_))
+    }
+}
+
+/// 单个文件处理完成后各类敏感信息的命中数
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileResultSummary {
+    pub phones: usize,
+    pub id_cards: usize,
+    pub bank_cards: usize,
+    pub names: usize,
+}
+
+impl FileResultSummary {
+    pub fn from_results<'a>(results: impl IntoIterator<Item = &'a ExtractResult>) -> Self {
+        results.into_iter().fold(Self::default(), |summary, r| Self {
+            phones: summary.phones + r.phone_numbers.len(),
+            id_cards: summary.id_cards + r.id_cards.len(),
+            bank_cards: summary.bank_cards + r.bank_cards.len(),
+            names: summary.names + r.names.len(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// 紧凑徽标，如 "📱3 🪪2 💳1"，省略数量为 0 的类型
+    pub fn badge(&self) -> String {
+        [("📱", self.phones), ("🪪", self.id_cards), ("💳", self.bank_cards), ("👤", self.names)]
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(icon, count)| format!("{}{}", icon, count))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,9 +93,70 @@ pub struct FileInfo {
     pub file_path: PathBuf,
     pub file_name: String,
     pub columns: Vec<String>,
+    /// 各工作表的列名，按工作表顺序，供切换工作表查看列名
+    #[serde(default)]
+    pub sheet_columns: Vec<(String, Vec<String>)>,
     pub row_count: u32,
     pub status: FileStatus,
     pub selected: bool,
+    /// 抽样得到的命中密度（有命中的行占比）
+    pub hit_density: Option<f64>,
+    /// 文件大小（字节），读取元数据失败时为 `None`
+    pub file_size: Option<u64>,
+    /// 最后修改时间，读取元数据失败时为 `None`
+    pub modified_time: Option<SystemTime>,
+    /// 处理完成后的命中数，未处理时为 `None`
+    #[serde(default)]
+    pub result_summary: Option<FileResultSummary>,
+    /// 列名和行数正在后台读取
+    #[serde(default)]
+    pub metadata_loading: bool,
+}
+
+/// 后台读取文件元数据失败的原因
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetadataError {
+    /// 加密文件，需要打开密码
+    PasswordRequired,
+    Failed(String),
+}
+
+/// 后台读取的文件元数据，按 `path` 对应到文件列表中的文件
+#[derive(Debug, Clone)]
+pub struct FileMetadataResult {
+    pub path: PathBuf,
+    /// 第一个工作表的列名
+    pub columns: Vec<String>,
+    /// 各工作表的列名，按工作表顺序
+    pub sheet_columns: Vec<(String, Vec<String>)>,
+    pub row_count: u32,
+    pub error: Option<MetadataError>,
+}
+
+impl FileMetadataResult {
+    pub fn from_info(path: PathBuf, info: &ExcelInfo) -> Self {
+        Self {
+            path,
+            columns: info.first_sheet_columns().cloned().unwrap_or_default(),
+            sheet_columns: info
+                .sheet_names
+                .iter()
+                .map(|name| (name.clone(), info.sheet_columns.get(name).cloned().unwrap_or_default()))
+                .collect(),
+            row_count: info.total_row_count() as u32,
+            error: None,
+        }
+    }
+
+    pub fn failed(path: PathBuf, error: MetadataError) -> Self {
+        Self {
+            path,
+            columns: Vec::new(),
+            sheet_columns: Vec::new(),
+            row_count: 0,
+            error: Some(error),
+        }
+    }
 }
 
 impl FileInfo {
@@ -47,13 +166,47 @@ impl FileInfo {
             .map(|n| n.to_string_lossy().to_string())
             .unwrap_or_default();
 
+        let metadata = std::fs::metadata(&path).ok();
+
         Self {
             file_path: path,
             file_name,
             columns: Vec::new(),
+            sheet_columns: Vec::new(),
             row_count: 0,
             status: FileStatus::Pending,
             selected: true,
+            hit_density: None,
+            file_size: metadata.as_ref().map(|m| m.len()),
+            modified_time: metadata.and_then(|m| m.modified().ok()),
+            result_summary: None,
+            metadata_loading: false,
+        }
+    }
+
+    /// 刚加入列表、元数据尚未读取的文件，读取期间显示为处理中
+    pub fn loading(path: PathBuf) -> Self {
+        Self {
+            status: FileStatus::processing(0),
+            metadata_loading: true,
+            ..Self::from_path(path)
         }
     }
+
+    /// 在后台线程读取列名和行数，读完后经 `tx` 发回，大文件不再阻塞界面
+    pub fn load_metadata(
+        path: PathBuf,
+        password: Option<String>,
+        exclusion: SheetExclusion,
+        tx: Sender<FileMetadataResult>,
+    ) {
+        thread::spawn(move || {
+            let result = match ExcelInfo::from_file_with_password(&path, password.as_deref(), &exclusion) {
+                Ok(info) => FileMetadataResult::from_info(path, &info),
+                Err(e) if is_password_error(&e) => FileMetadataResult::failed(path, MetadataError::PasswordRequired),
+                Err(e) => FileMetadataResult::failed(path, MetadataError::Failed(e.to_string())),
+            };
+            let _ = tx.send(result);
+        });
+    }
 }
\ No newline at end of file
@@ -2,6 +2,10 @@ mod config;
 mod extract_result;
 mod file_info;
 
-pub use config::Config;
-pub use extract_result::{ExtractResult, MatchInfo};
-pub use file_info::{FileInfo, FileStatus};
+pub use config::{
+    AnnotationKey, ApiFieldMapping, CardLengthPolicy, Config, ConfigFormat, CustomPattern, ExportConflictStrategy, ExportStyle,
+    Language, LengthLimit, MaskRule, OutputFormat, RowColorRule, SheetExclusion, DEFAULT_CONTEXT_CELL_SEP,
+    DEFAULT_CONTEXT_ROW_SEP,
+};
+pub use extract_result::{ExtractResult, MatchInfo, MatchInfoBuilder, PatternType};
+pub use file_info::{FileInfo, FileMetadataResult, FileResultSummary, FileStatus, MetadataError};
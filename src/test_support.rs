@@ -0,0 +1,9 @@
+//! 单元测试共用的辅助函数
+
+use tempfile::TempDir;
+
+/// 测试用的临时目录，返回的 [`TempDir`] 离开作用域时连同其中的文件一起删除，
+/// 测试失败提前返回时也不会留下文件
+pub fn scratch_dir() -> TempDir {
+    tempfile::Builder::new().prefix("sie_").tempdir().expect("无法创建临时目录")
+}
@@ -0,0 +1,251 @@
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::Client;
+use reqwest::redirect::Policy;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::sanitize_filename;
+use crate::core::office_crypto::is_encrypted_ooxml;
+
+/// 最多跟随的重定向次数
+const MAX_REDIRECTS: usize = 10;
+
+/// 每次读取的块大小，也是进度回调的粒度
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// 下载远程 xlsx 到临时目录，返回本地文件路径
+///
+/// `progress` 收到（已下载字节数, 总字节数），服务端未给出长度时总数为 `None`。
+/// 响应不是 2xx，或下载内容不是 xlsx（含加密的 xlsx）时返回错误并删除已下载的内容
+pub fn download_xlsx(url: &str, mut progress: impl FnMut(u64, Option<u64>)) -> Result<PathBuf> {
+    let url = url.trim();
+    if !url.starts_with("http://") && !url.starts_with("https://") {
+        bail!("只支持 http/https 地址: {}", url);
+    }
+
+    let client = Client::builder()
+        .redirect(Policy::limited(MAX_REDIRECTS))
+        .connect_timeout(Duration::from_secs(10))
+        .timeout(Duration::from_secs(300))
+        .build()
+        .context("无法创建下载客户端")?;
+
+    let mut response = client.get(url).send().map_err(|e| {
+        if e.is_timeout() {
+            anyhow::anyhow!("下载超时: {}", url)
+        } else if e.is_redirect() {
+            anyhow::anyhow!("重定向次数过多: {}", url)
+        } else {
+            anyhow::anyhow!("无法连接: {} ({})", url, e)
+        }
+    })?;
+
+    let status = response.status();
+    if !status.is_success() {
+        bail!("下载失败: HTTP {} ({})", status, url);
+    }
+
+    // 文件名按重定向后的最终地址取
+    let file_name = download_file_name(response.url().path());
+    let output_path = unique_download_path(&file_name)?;
+    let total = response.content_length();
+
+    let written = write_response(&mut response, &output_path, total, &mut progress);
+    if let Err(e) = written.and_then(|_| ensure_xlsx(&output_path)) {
+        let _ = fs::remove_file(&output_path);
+        return Err(e);
+    }
+
+    Ok(output_path)
+}
+
+fn write_response(
+    response: &mut impl Read,
+    output_path: &Path,
+    total: Option<u64>,
+    progress: &mut impl FnMut(u64, Option<u64>),
+) -> Result<()> {
+    let mut output = fs::File::create(output_path)
+        .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut downloaded = 0u64;
+    progress(0, total);
+
+    loop {
+        let read = response.read(&mut buffer).context("下载中断")?;
+        if read == 0 {
+            break;
+        }
+        output
+            .write_all(&buffer[..read])
+            .with_context(|| format!("无法写入文件: {}", output_path.display()))?;
+        downloaded += read as u64;
+        progress(downloaded, total);
+    }
+
+    output.flush()?;
+    Ok(())
+}
+
+/// 由 URL 路径的最后一段得到文件名，统一使用 .xlsx 扩展名
+fn download_file_name(url_path: &str) -> String {
+    let segment = url_path.rsplit('/').next().unwrap_or_default();
+    let segment = percent_decode(segment);
+    let stem = Path::new(&segment)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let stem = if stem.is_empty() { "download".to_string() } else { sanitize_filename(&stem) };
+    format!("{}.xlsx", stem)
+}
+
+/// 还原 URL 中 `%XX` 编码的 UTF-8 字符，无法解码时保持原样
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| text.to_string())
+}
+
+/// 下载目录中不与已有文件重名的路径
+fn unique_download_path(file_name: &str) -> Result<PathBuf> {
+    let dir = std::env::temp_dir()
+        .join("sensitive_info_extractor")
+        .join(format!("downloads_{}", std::process::id()));
+    fs::create_dir_all(&dir).with_context(|| format!("无法创建目录: {}", dir.display()))?;
+
+    let stem = file_name.trim_end_matches(".xlsx");
+    let mut candidate = dir.join(file_name);
+    let mut index = 1;
+    while candidate.exists() {
+        candidate = dir.join(format!("{}({}).xlsx", stem, index));
+        index += 1;
+    }
+    Ok(candidate)
+}
+
+/// 确认文件是 xlsx：包含工作簿的 zip 包，或加密后的 OOXML 复合文件
+fn ensure_xlsx(path: &Path) -> Result<()> {
+    let is_workbook_zip = fs::File::open(path)
+        .ok()
+        .and_then(|file| zip::ZipArchive::new(file).ok())
+        .is_some_and(|mut archive| archive.by_name("xl/workbook.xml").is_ok());
+
+    if !is_workbook_zip && !is_encrypted_ooxml(path) {
+        bail!("下载的内容不是 xlsx 文件");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    /// 本地 HTTP 服务：按顺序对每个连接返回一条预设响应
+    fn serve(responses: Vec<Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let read = stream.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                stream.write_all(&response).unwrap();
+            }
+        });
+
+        format!("http://{}", address)
+    }
+
+    fn http_response(status: &str, headers: &str, body: &[u8]) -> Vec<u8> {
+        let mut response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n",
+            status,
+            body.len(),
+            headers
+        )
+        .into_bytes();
+        response.extend_from_slice(body);
+        response
+    }
+
+    fn xlsx_bytes() -> Vec<u8> {
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        workbook.add_worksheet().write_string(0, 0, "消息内容").unwrap();
+        workbook.save_to_buffer().unwrap()
+    }
+
+    #[test]
+    fn test_download_follows_redirect() {
+        let body = xlsx_bytes();
+        let base = serve(vec![
+            http_response("302 Found", "Location: /files/%E5%AE%A2%E6%88%B7.xlsx\r\n", b""),
+            http_response("200 OK", "", &body),
+        ]);
+
+        let mut last_progress = (0, None);
+        let path = download_xlsx(&format!("{}/share?id=1", base), |done, total| {
+            last_progress = (done, total);
+        })
+        .unwrap();
+
+        assert_eq!(path.file_name().unwrap(), "客户.xlsx");
+        assert_eq!(fs::read(&path).unwrap(), body);
+        assert_eq!(last_progress, (body.len() as u64, Some(body.len() as u64)));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_download_rejects_error_status_and_non_xlsx() {
+        let base = serve(vec![
+            http_response("404 Not Found", "", b"not found"),
+            http_response("200 OK", "Content-Type: text/html\r\n", b"<html>login</html>"),
+        ]);
+
+        let error = download_xlsx(&format!("{}/a.xlsx", base), |_, _| {}).unwrap_err();
+        assert!(error.to_string().contains("404"));
+
+        let error = download_xlsx(&format!("{}/b.xlsx", base), |_, _| {}).unwrap_err();
+        assert!(error.to_string().contains("不是 xlsx"));
+
+        assert!(download_xlsx("ftp://example.com/a.xlsx", |_, _| {}).is_err());
+    }
+
+    #[test]
+    fn test_download_file_name() {
+        assert_eq!(download_file_name("/bucket/report.xlsx"), "report.xlsx");
+        assert_eq!(download_file_name("/bucket/%E6%8A%A5%E8%A1%A8.xlsx"), "报表.xlsx");
+        assert_eq!(download_file_name("/download"), "download.xlsx");
+        assert_eq!(download_file_name("/"), "download.xlsx");
+        assert_eq!(download_file_name("/a%3Fb.xlsx"), "a_b.xlsx");
+    }
+}
@@ -1,13 +1,73 @@
+use crate::core::ProcessingStatistics;
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-pub fn is_xlsx_file(path: &Path) -> bool {
+/// 可直接读取的表格扩展名（.xlsm/.xlam 中的宏内容会被忽略）
+pub const SUPPORTED_SHEET_EXTENSIONS: [&str; 6] = ["xlsx", "xlsm", "xlam", "xls", "ods", "csv"];
+
+/// 可导入的全部扩展名，.zip 导入时解压出其中的表格文件
+pub const SUPPORTED_EXTENSIONS: [&str; 7] = ["xlsx", "xlsm", "xlam", "xls", "ods", "csv", "zip"];
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
     path.extension()
-        .map(|ext| ext.eq_ignore_ascii_case("xlsx"))
+        .map(|ext| extensions.iter().any(|supported| ext.eq_ignore_ascii_case(supported)))
         .unwrap_or(false)
 }
 
+pub fn is_supported_file(path: &Path) -> bool {
+    has_extension(path, &SUPPORTED_EXTENSIONS)
+}
+
+fn is_zip_file(path: &Path) -> bool {
+    has_extension(path, &["zip"])
+}
+
+/// 把压缩包中的表格文件解压到临时目录，返回解压出的文件路径
+pub fn extract_zip_archive(path: &Path) -> Result<Vec<PathBuf>> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("无法打开压缩包: {}", path.display()))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .with_context(|| format!("无法读取压缩包: {}", path.display()))?;
+
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let output_dir = std::env::temp_dir()
+        .join("sensitive_info_extractor")
+        .join(format!("{}_{}", sanitize_filename(&stem), std::process::id()));
+
+    let mut extracted = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index)?;
+
+        // enclosed_name 会拒绝包含 .. 或绝对路径的条目
+        let Some(relative_path) = entry.enclosed_name() else {
+            continue;
+        };
+        if entry.is_dir() || !has_extension(&relative_path, &SUPPORTED_SHEET_EXTENSIONS) {
+            continue;
+        }
+
+        let output_path = output_dir.join(relative_path);
+        if let Some(parent) = output_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("无法创建目录: {}", parent.display()))?;
+        }
+
+        let mut output = fs::File::create(&output_path)
+            .with_context(|| format!("无法创建文件: {}", output_path.display()))?;
+        std::io::copy(&mut entry, &mut output)
+            .with_context(|| format!("解压失败: {}", output_path.display()))?;
+
+        extracted.push(output_path);
+    }
+
+    Ok(extracted)
+}
+
 pub fn scan_xlsx_files(dir: &Path) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
 
@@ -35,13 +95,13 @@ fn scan_xlsx_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()>
         let path = entry.path();
 
         if path.is_dir() {
-            if let Some(name) = path.file_name() {
-                if name.to_string_lossy().starts_with('.') {
-                    continue;
-                }
+            if let Some(name) = path.file_name()
+                && name.to_string_lossy().starts_with('.')
+            {
+                continue;
             }
             scan_xlsx_files_recursive(&path, files)?;
-        } else if is_xlsx_file(&path) {
+        } else if is_supported_file(&path) {
             files.push(path);
         }
     }
@@ -49,27 +109,128 @@ fn scan_xlsx_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()>
     Ok(())
 }
 
-pub fn generate_output_filename_with_source(source_name: &str) -> String {
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    format!("{}_{}.xlsx", source_name, timestamp)
+/// 替换文件名中的非法字符，并去除 Windows 不允许的结尾点和空格
+pub fn sanitize_filename(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| match c {
+            '\\' | '/' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+
+    let trimmed = sanitized.trim().trim_end_matches(['.', ' ']);
+    if trimmed.is_empty() {
+        "result".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// 按模板生成输出文件名（不含扩展名）
+///
+/// 支持占位符: `{source}`, `{timestamp}`, `{date}`, `{time}`, `{phone_count}`, `{id_count}`
+pub fn render_filename_template(template: &str, source: &str, stats: &ProcessingStatistics) -> String {
+    let now = chrono::Local::now();
+
+    let rendered = template
+        .replace("{source}", source)
+        .replace("{timestamp}", &now.format("%Y%m%d_%H%M%S").to_string())
+        .replace("{date}", &now.format("%Y%m%d").to_string())
+        .replace("{time}", &now.format("%H%M%S").to_string())
+        .replace("{phone_count}", &stats.total_phones.to_string())
+        .replace("{id_count}", &stats.total_id_cards.to_string());
+
+    sanitize_filename(&rendered)
+}
+
+/// 人类可读的文件大小，如 "2.3 MB"
+pub fn format_file_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// 中文耗时，如 "1分30秒"、"0.52秒"
+pub fn format_elapsed_time(secs: f64) -> String {
+    format_elapsed_time_with_units(secs, ["小时", "分", "秒"])
+}
+
+/// 按给定的时、分、秒单位格式化耗时：不足 10 秒保留两位小数，其余取整秒，为 0 的高位省略
+pub fn format_elapsed_time_with_units(secs: f64, [hour, minute, second]: [&str; 3]) -> String {
+    let secs = secs.max(0.0);
+    if secs < 10.0 {
+        return format!("{:.2}{}", secs, second);
+    }
+
+    let total = secs as u64;
+    let (hours, mins, secs) = (total / 3600, total % 3600 / 60, total % 60);
+    if hours > 0 {
+        format!("{}{}{}{}{}{}", hours, hour, mins, minute, secs, second)
+    } else if mins > 0 {
+        format!("{}{}{}{}", mins, minute, secs, second)
+    } else {
+        format!("{}{}", secs, second)
+    }
+}
+
+/// 大数以 "万"、"亿" 为单位显示，如 "1.2万"；一万以下原样显示
+pub fn format_large_number(n: usize) -> String {
+    let count = n as f64;
+    // 单位按舍入后的值选择：万位舍入到一位小数后满一万即进位为亿，避免出现 "10000万"
+    let (value, unit) = if (count / 1e3).round() >= 1e5 {
+        (count / 1e8, "亿")
+    } else if n >= 10_000 {
+        (count / 1e4, "万")
+    } else {
+        return n.to_string();
+    };
+
+    let text = format!("{:.1}", value);
+    format!("{}{}", text.strip_suffix(".0").unwrap_or(&text), unit)
 }
 
 pub fn process_dropped_paths(paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
-    let mut xlsx_files = Vec::new();
+    let mut candidates = Vec::new();
 
     for path in paths {
         if path.is_dir() {
             let files = scan_xlsx_files(path)?;
-            xlsx_files.extend(files);
-        } else if is_xlsx_file(path) {
-            xlsx_files.push(path.clone());
+            candidates.extend(files);
+        } else if is_supported_file(path) {
+            candidates.push(path.clone());
+        }
+    }
+
+    let mut sheet_files = Vec::new();
+
+    for path in candidates {
+        if is_zip_file(&path) {
+            match extract_zip_archive(&path) {
+                Ok(files) => sheet_files.extend(files),
+                Err(e) => tracing::warn!("跳过无法解压的压缩包 {}: {}", path.display(), e),
+            }
+        } else {
+            sheet_files.push(path);
         }
     }
 
-    xlsx_files.sort();
-    xlsx_files.dedup();
+    sheet_files.sort();
+    sheet_files.dedup();
 
-    Ok(xlsx_files)
+    Ok(sheet_files)
 }
 
 #[cfg(test)]
@@ -77,17 +238,81 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_is_xlsx_file() {
-        assert!(is_xlsx_file(Path::new("test.xlsx")));
-        assert!(is_xlsx_file(Path::new("test.XLSX")));
-        assert!(!is_xlsx_file(Path::new("test.xls")));
-        assert!(!is_xlsx_file(Path::new("test.txt")));
+    fn test_is_supported_file() {
+        assert!(is_supported_file(Path::new("test.xlsx")));
+        assert!(is_supported_file(Path::new("test.XLSX")));
+        assert!(is_supported_file(Path::new("test.xlsm")));
+        assert!(is_supported_file(Path::new("test.XLSM")));
+        assert!(is_supported_file(Path::new("addin.xlam")));
+        assert!(is_supported_file(Path::new("test.xls")));
+        assert!(is_supported_file(Path::new("test.ods")));
+        assert!(is_supported_file(Path::new("test.CSV")));
+        assert!(is_supported_file(Path::new("archive.zip")));
+        assert!(!is_supported_file(Path::new("test.txt")));
+        assert!(!is_supported_file(Path::new("test")));
     }
 
     #[test]
-    fn test_generate_output_filename_with_source() {
-        let filename = generate_output_filename_with_source("测试文件");
+    fn test_render_default_template() {
+        let filename = render_filename_template("{source}_{timestamp}", "测试文件", &ProcessingStatistics::default());
         assert!(filename.starts_with("测试文件_"));
-        assert!(filename.ends_with(".xlsx"));
+        assert_eq!(filename.len(), "测试文件_".len() + "20260101_120000".len());
+    }
+
+    #[test]
+    fn test_render_filename_template_with_counts() {
+        let stats = ProcessingStatistics {
+            total_phones: 12,
+            total_id_cards: 3,
+            ..Default::default()
+        };
+        let date = chrono::Local::now().format("%Y%m%d").to_string();
+
+        let filename = render_filename_template("{source}_{phone_count}phones_{date}", "聊天:记录", &stats);
+        assert_eq!(filename, format!("聊天_记录_12phones_{}", date));
+
+        let filename = render_filename_template("{id_count}/{source}?", "a", &stats);
+        assert_eq!(filename, "3_a_");
+    }
+
+    #[test]
+    fn test_format_elapsed_time() {
+        assert_eq!(format_elapsed_time(0.0), "0.00秒");
+        assert_eq!(format_elapsed_time(0.523), "0.52秒");
+        assert_eq!(format_elapsed_time(9.5), "9.50秒");
+        assert_eq!(format_elapsed_time(59.99), "59秒");
+        assert_eq!(format_elapsed_time(60.0), "1分0秒");
+        assert_eq!(format_elapsed_time(90.4), "1分30秒");
+        assert_eq!(format_elapsed_time(3661.0), "1小时1分1秒");
+        assert_eq!(format_elapsed_time_with_units(3661.0, ["h ", "m ", "s"]), "1h 1m 1s");
+    }
+
+    #[test]
+    fn test_format_large_number() {
+        assert_eq!(format_large_number(0), "0");
+        assert_eq!(format_large_number(9999), "9999");
+        assert_eq!(format_large_number(10000), "1万");
+        assert_eq!(format_large_number(12345), "1.2万");
+        assert_eq!(format_large_number(99_999), "10万");
+        assert_eq!(format_large_number(99_950_000), "9995万");
+        assert_eq!(format_large_number(99_999_999), "1亿");
+        assert_eq!(format_large_number(123_456_789), "1.2亿");
+        assert_eq!(format_large_number(300_000_000), "3亿");
+    }
+
+    #[test]
+    fn test_format_file_size() {
+        assert_eq!(format_file_size(0), "0 B");
+        assert_eq!(format_file_size(1023), "1023 B");
+        assert_eq!(format_file_size(1536), "1.5 KB");
+        assert_eq!(format_file_size(2_411_724), "2.3 MB");
+        assert_eq!(format_file_size(5 * 1024 * 1024 * 1024), "5.0 GB");
+    }
+
+    #[test]
+    fn test_sanitize_filename() {
+        assert_eq!(sanitize_filename("a<b>c|d"), "a_b_c_d");
+        assert_eq!(sanitize_filename("结果. "), "结果");
+        assert_eq!(sanitize_filename("  "), "result");
     }
 }
\ No newline at end of file
@@ -1,5 +1,7 @@
 mod regex_patterns;
 mod file_utils;
+mod download;
 
+pub use download::*;
 pub use file_utils::*;
 pub use regex_patterns::*;
@@ -23,6 +23,10 @@ pub static PHONE: LazyLock<Regex> = LazyLock::new(|| {
         .unwrap()
 });
 
+/// 手机号开头的国家码 `+86`（含其后的分隔符）
+pub static PHONE_COUNTRY_CODE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\+?86[-\s]?").unwrap());
+
 /// 身份证号匹配
 pub static ID_CARD: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
@@ -42,7 +46,10 @@ pub static ID_CARD: LazyLock<Regex> = LazyLock::new(|| {
         .unwrap()
 });
 
-/// 银行卡号匹配（16-19位）
+/// 银行卡号匹配（16-19位，34/37 开头按 4-6-5 分组的 15 位 American Express 卡号，以及 13-15 位的短卡号）
+///
+/// 分隔符可以是 `-` 或空白（含全角空格）；`.` 只在整个卡号都按 4 位分组时接受，避免把 `1234.5678` 之类的小数当作卡号。
+/// 短卡号的分支放在最后，避免截断 4-6-5 分组的 Amex 卡号；是否保留由 `CardLengthPolicy` 决定
 pub static BANK_CARD: LazyLock<Regex> = LazyLock::new(|| {
     Regex::new(
         r"(?x)
@@ -53,6 +60,33 @@ pub static BANK_CARD: LazyLock<Regex> = LazyLock::new(|| {
             \d{4}[-\s]?
             \d{4}
             (?:[-\s]?\d{1,3})?
+        |
+            \d{4}\.\d{4}\.\d{4}\.\d{4}
+            (?:\.\d{1,3})?
+        |
+            3[47]\d{2}[-\s]?
+            \d{6}[-\s]?
+            \d{5}
+        |
+            \d{4}[-\s]?
+            \d{4}[-\s]?
+            \d{4}[-\s]?
+            \d{1,3}
+        )
+        (?:$|\D)
+        ",
+    )
+        .unwrap()
+});
+
+/// 澳门居民身份证号匹配：1/5/7 开头共 8 位，校验码可写在括号中，如 `1234567(5)`
+pub static MACAU_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?x)
+        (?:^|\D)
+        (?P<macau_id>
+            [157]\d{6}
+            (?:\(\d\)|\d)
         )
         (?:$|\D)
         ",
@@ -60,6 +94,21 @@ pub static BANK_CARD: LazyLock<Regex> = LazyLock::new(|| {
         .unwrap()
 });
 
+/// 微信号匹配：只在“微信”“微信号：”等前缀之后查找，字母开头、由字母数字下划线和连字符组成；
+/// 不带前缀的字母数字串误报太多，不予匹配。长度是否为 6-20 位由校验判断
+pub static WECHAT_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?x)
+        微信号?\s*[：:]?\s*
+        (?P<wechat_id>
+            [A-Za-z][A-Za-z0-9_-]{1,39}
+        )
+        (?:$|[^A-Za-z0-9_-])
+        ",
+    )
+        .unwrap()
+});
+
 pub const ID_WEIGHTS: [i32; 17] = [7, 9, 10, 5, 8, 4, 2, 1, 6, 3, 7, 9, 10, 5, 8, 4, 2];
 pub const ID_CHECK_CODES: [char; 11] = ['1', '0', 'X', '9', '8', '7', '6', '5', '4', '3', '2'];
 
@@ -67,28 +116,64 @@ pub fn clean_digits(s: &str) -> String {
     NON_DIGIT.replace_all(s, "").into_owned()
 }
 
+/// 身份证号的规范形式：末位校验码 `x` 统一为大写 `X`
+pub fn normalize_id_card(s: &str) -> String {
+    match s.strip_suffix('x') {
+        Some(body) => format!("{}X", body),
+        None => s.to_string(),
+    }
+}
+
+/// 命中值的规范化形式，用于排除列表比对：只保留字母数字并转大写，13 位 `86` 开头的号码去掉国家码
+pub fn normalize_value(s: &str) -> String {
+    let normalized: String = s
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_uppercase)
+        .collect();
+    match normalized.strip_prefix("86") {
+        Some(phone) if normalized.len() == 13 && phone.bytes().all(|b| b.is_ascii_digit()) => {
+            phone.to_string()
+        }
+        _ => normalized,
+    }
+}
+
+/// 按命名分组逐个取出命中（值, 起始, 结束）
+///
+/// 模式两端的边界会消耗一个字符，下一次从命名分组末尾而非整个匹配末尾继续查找，
+/// 两个命中之间只隔一个字符（如“13907315286和18612345678”）时后一个不会漏掉
+fn captures_named<'t>(regex: &Regex, text: &'t str, group: &str) -> Vec<(&'t str, usize, usize)> {
+    let mut found = Vec::new();
+    let mut start = 0;
+    while start <= text.len() {
+        let Some(m) = regex.captures_at(text, start).and_then(|c| c.name(group)) else {
+            break;
+        };
+        found.push((m.as_str(), m.start(), m.end()));
+        start = m.end();
+    }
+    found
+}
+
 pub fn extract_phones(text: &str) -> Vec<(&str, usize, usize)> {
-    PHONE
-        .captures_iter(text)
-        .filter_map(|c| c.name("phone").map(|m| (m.as_str(), m.start(), m.end())))
-        .collect()
+    captures_named(&PHONE, text, "phone")
 }
 
 pub fn extract_id_cards(text: &str) -> Vec<(&str, usize, usize)> {
-    ID_CARD
-        .captures_iter(text)
-        .filter_map(|c| c.name("id_card").map(|m| (m.as_str(), m.start(), m.end())))
-        .collect()
+    captures_named(&ID_CARD, text, "id_card")
 }
 
 pub fn extract_bank_cards(text: &str) -> Vec<(&str, usize, usize)> {
-    BANK_CARD
-        .captures_iter(text)
-        .filter_map(|c| {
-            c.name("bank_card")
-                .map(|m| (m.as_str(), m.start(), m.end()))
-        })
-        .collect()
+    captures_named(&BANK_CARD, text, "bank_card")
+}
+
+pub fn extract_macau_ids(text: &str) -> Vec<(&str, usize, usize)> {
+    captures_named(&MACAU_ID, text, "macau_id")
+}
+
+pub fn extract_wechat_ids(text: &str) -> Vec<(&str, usize, usize)> {
+    captures_named(&WECHAT_ID, text, "wechat_id")
 }
 
 #[cfg(test)]
@@ -102,6 +187,14 @@ mod tests {
         assert!(!PHONE.is_match("12812345678"));
     }
 
+    #[test]
+    fn adjacent_matches_share_boundary() {
+        let phones: Vec<&str> = extract_phones("13907315286和18612345678").into_iter().map(|(v, _, _)| v).collect();
+        assert_eq!(phones, ["13907315286", "18612345678"]);
+        let cards = extract_bank_cards("4111111111111111,6217000010012345678");
+        assert_eq!(cards.len(), 2);
+    }
+
     #[test]
     fn phone_chinese() {
         let r = extract_phones("联系13812345678请拨打");
@@ -125,6 +218,30 @@ mod tests {
         assert!(BANK_CARD.is_match("6225880123456789"));
         assert!(BANK_CARD.is_match("6225 8801 2345 6789"));
         assert!(!BANK_CARD.is_match("622588012345"));
+        assert!(BANK_CARD.is_match("4222 2222 2222 2"));
+    }
+
+    #[test]
+    fn bank_card_separators() {
+        let r = extract_bank_cards("卡号6225.8801.2345.6789，备用卡6225\u{3000}8801\u{3000}2345\u{3000}6789");
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[0].0, "6225.8801.2345.6789");
+        assert_eq!(r[1].0, "6225\u{3000}8801\u{3000}2345\u{3000}6789");
+
+        // 小数和不按 4 位分组的点号不是卡号
+        assert!(extract_bank_cards("金额1234.5678元").is_empty());
+        assert!(extract_bank_cards("62258.801234.56789").is_empty());
+        assert_eq!(extract_bank_cards("余额6225880123456789.50")[0].0, "6225880123456789");
+    }
+
+    #[test]
+    fn bank_card_amex() {
+        let r = extract_bank_cards("Amex卡3782 822463 10005已绑定，另一张371449635398431");
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[0].0, "3782 822463 10005");
+        assert_eq!(r[1].0, "371449635398431");
+        // 13-15 位数字也作为候选匹配，是否报告由卡号长度策略决定
+        assert_eq!(extract_bank_cards("编号512345678901234")[0].0, "512345678901234");
     }
 
     #[test]
@@ -133,9 +250,46 @@ mod tests {
         assert_eq!(r[0].0, "6225880123456789");
     }
 
+    #[test]
+    fn macau_id() {
+        let r = extract_macau_ids("澳门身份证1234567(5)，另一张52152996");
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[0].0, "1234567(5)");
+        assert_eq!(r[1].0, "52152996");
+
+        // 开头不是 1/5/7，或嵌在更长的数字串中
+        assert!(extract_macau_ids("编号32152996").is_empty());
+        assert!(extract_macau_ids("13812345678").is_empty());
+        assert!(extract_macau_ids("订单1234567512").is_empty());
+    }
+
+    #[test]
+    fn wechat_id() {
+        let r = extract_wechat_ids("微信号: johnny_123，备用微信：Lucy-2024");
+        assert_eq!(r.len(), 2);
+        assert_eq!(r[0].0, "johnny_123");
+        assert_eq!(r[1].0, "Lucy-2024");
+        assert_eq!(extract_wechat_ids("加微信wxid_abc123")[0].0, "wxid_abc123");
+
+        // 没有前缀，或前缀后不是字母开头
+        assert!(extract_wechat_ids("随机字母abc").is_empty());
+        assert!(extract_wechat_ids("账号 johnny_123").is_empty());
+        assert!(extract_wechat_ids("微信号：13812345678").is_empty());
+    }
+
     #[test]
     fn clean() {
         assert_eq!(clean_digits("138-1234-5678"), "13812345678");
         assert_eq!(clean_digits("6225 8801 2345 6789"), "6225880123456789");
+        assert_eq!(clean_digits("6225.8801.2345.6789"), "6225880123456789");
+        assert_eq!(clean_digits("6225\u{3000}8801\u{3000}2345\u{3000}6789"), "6225880123456789");
+    }
+
+    #[test]
+    fn normalize() {
+        assert_eq!(normalize_value("138-1234-5678"), "13812345678");
+        assert_eq!(normalize_value("+86 138 1234 5678"), "13812345678");
+        assert_eq!(normalize_value("11010519900307888x"), "11010519900307888X");
+        assert_eq!(normalize_value("6225 8801 2345 6789"), "6225880123456789");
     }
 }
@@ -0,0 +1,198 @@
+#!/usr/bin/env python3
+"""生成带密码的 xlsx 样例（ECMA-376 Agile Encryption），供 office_crypto 的解密测试使用。
+
+加密与复合文件的写入按 MS-OFFCRYPTO / MS-CFB 独立实现，不依赖被测的 Rust 代码；
+参数取 Excel 的默认值：Excel 2010 为 SHA-1 + AES-128，Excel 2013 起为 SHA-512 + AES-256，
+口令哈希迭代 100000 次，盐和密钥随机生成，并带 dataIntegrity 节点。
+
+用法: python3 make_encrypted_fixtures.py  （需要 cryptography）
+"""
+
+import base64
+import hashlib
+import hmac
+import io
+import os
+import struct
+import zipfile
+
+from cryptography.hazmat.primitives.ciphers import Cipher, algorithms, modes
+
+PASSWORD = "密码123"
+SPIN_COUNT = 100000
+SEGMENT_LENGTH = 4096
+
+BLOCK_KEYS = {
+    "verifier_input": bytes([0xFE, 0xA7, 0xD2, 0x76, 0x3B, 0x4B, 0x9E, 0x79]),
+    "verifier_hash": bytes([0xD7, 0xAA, 0x0F, 0x6D, 0x30, 0x61, 0x34, 0x4E]),
+    "key_value": bytes([0x14, 0x6E, 0x0B, 0xE7, 0xAB, 0xAC, 0xD0, 0xD6]),
+    "integrity_key": bytes([0x5F, 0xB2, 0xAD, 0x01, 0x0C, 0xB9, 0xE1, 0xF6]),
+    "integrity_value": bytes([0xA0, 0x67, 0x7F, 0x02, 0xB2, 0x2C, 0x84, 0x33]),
+}
+
+
+def plain_workbook():
+    """最小的 xlsx：一个工作表，表头加若干行内联字符串，未压缩以跨越多个加密分段"""
+    rows = ['<row r="1"><c r="A1" t="inlineStr"><is><t>消息内容</t></is></c></row>',
+            '<row r="2"><c r="A2" t="inlineStr"><is><t>电话13907315286</t></is></c></row>']
+    for r in range(3, 203):
+        rows.append(f'<row r="{r}"><c r="A{r}" t="inlineStr"><is><t>第{r}行备注</t></is></c></row>')
+    files = {
+        "[Content_Types].xml": (
+            '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+            '<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">'
+            '<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>'
+            '<Default Extension="xml" ContentType="application/xml"/>'
+            '<Override PartName="/xl/workbook.xml" '
+            'ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/>'
+            '<Override PartName="/xl/worksheets/sheet1.xml" '
+            'ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>'
+            '</Types>'),
+        "_rels/.rels": (
+            '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+            '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">'
+            '<Relationship Id="rId1" '
+            'Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" '
+            'Target="xl/workbook.xml"/></Relationships>'),
+        "xl/workbook.xml": (
+            '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+            '<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" '
+            'xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships">'
+            '<sheets><sheet name="Sheet1" sheetId="1" r:id="rId1"/></sheets></workbook>'),
+        "xl/_rels/workbook.xml.rels": (
+            '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+            '<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">'
+            '<Relationship Id="rId1" '
+            'Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" '
+            'Target="worksheets/sheet1.xml"/></Relationships>'),
+        "xl/worksheets/sheet1.xml": (
+            '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>'
+            '<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">'
+            '<sheetData>' + "".join(rows) + '</sheetData></worksheet>'),
+    }
+    buffer = io.BytesIO()
+    with zipfile.ZipFile(buffer, "w", zipfile.ZIP_STORED) as archive:
+        for name, content in files.items():
+            archive.writestr(name, content.encode("utf-8"))
+    return buffer.getvalue()
+
+
+def aes_cbc(key, iv, data):
+    encryptor = Cipher(algorithms.AES(key), modes.CBC(iv)).encryptor()
+    return encryptor.update(data) + encryptor.finalize()
+
+
+def pad(data, block_size):
+    return data + b"\0" * (-len(data) % block_size)
+
+
+def fit(data, length):
+    return data[:length] + b"\x36" * (length - len(data))
+
+
+def encrypt_package(plain, hash_name, key_bits):
+    digest = lambda *parts: hashlib.new(hash_name, b"".join(parts)).digest()
+    hash_size = len(digest())
+    key_bytes = key_bits // 8
+
+    key_salt = os.urandom(16)
+    password_salt = os.urandom(16)
+    secret_key = os.urandom(key_bytes)
+    verifier_input = os.urandom(16)
+
+    h = digest(password_salt, PASSWORD.encode("utf-16-le"))
+    for iterator in range(SPIN_COUNT):
+        h = digest(struct.pack("<I", iterator), h)
+    derive = lambda block_key: fit(digest(h, block_key), key_bytes)
+
+    encrypted_input = aes_cbc(derive(BLOCK_KEYS["verifier_input"]), password_salt, verifier_input)
+    encrypted_hash = aes_cbc(derive(BLOCK_KEYS["verifier_hash"]), password_salt, pad(digest(verifier_input), 16))
+    encrypted_key = aes_cbc(derive(BLOCK_KEYS["key_value"]), password_salt, pad(secret_key, 16))
+
+    package = struct.pack("<Q", len(plain))
+    for index in range(0, len(plain), SEGMENT_LENGTH):
+        iv = fit(digest(key_salt, struct.pack("<I", index // SEGMENT_LENGTH)), 16)
+        package += aes_cbc(secret_key, iv, pad(plain[index:index + SEGMENT_LENGTH], 16))
+
+    hmac_key = os.urandom(hash_size)
+    hmac_value = hmac.new(hmac_key, package, hash_name).digest()
+    integrity_iv = lambda block_key: fit(digest(key_salt, block_key), 16)
+    encrypted_hmac_key = aes_cbc(secret_key, integrity_iv(BLOCK_KEYS["integrity_key"]), pad(hmac_key, 16))
+    encrypted_hmac_value = aes_cbc(secret_key, integrity_iv(BLOCK_KEYS["integrity_value"]), pad(hmac_value, 16))
+
+    b64 = lambda data: base64.b64encode(data).decode("ascii")
+    params = lambda salt: (
+        f'saltSize="16" blockSize="16" keyBits="{key_bits}" hashSize="{hash_size}" '
+        f'cipherAlgorithm="AES" cipherChaining="ChainingModeCBC" hashAlgorithm="{hash_name.upper()}" '
+        f'saltValue="{b64(salt)}"')
+    xml = (
+        '<?xml version="1.0" encoding="UTF-8" standalone="yes"?>\r\n'
+        '<encryption xmlns="http://schemas.microsoft.com/office/2006/encryption" '
+        'xmlns:p="http://schemas.microsoft.com/office/2006/keyEncryptor/password" '
+        'xmlns:c="http://schemas.microsoft.com/office/2006/keyEncryptor/certificate">'
+        f'<keyData {params(key_salt)}/>'
+        f'<dataIntegrity encryptedHmacKey="{b64(encrypted_hmac_key)}" '
+        f'encryptedHmacValue="{b64(encrypted_hmac_value)}"/>'
+        '<keyEncryptors><keyEncryptor uri="http://schemas.microsoft.com/office/2006/keyEncryptor/password">'
+        f'<p:encryptedKey spinCount="{SPIN_COUNT}" {params(password_salt)} '
+        f'encryptedVerifierHashInput="{b64(encrypted_input)}" '
+        f'encryptedVerifierHashValue="{b64(encrypted_hash)}" '
+        f'encryptedKeyValue="{b64(encrypted_key)}"/>'
+        '</keyEncryptor></keyEncryptors></encryption>')
+    info = struct.pack("<HHI", 4, 4, 0x40) + xml.encode("utf-8")
+    return info, package
+
+
+FREE, END, FAT, NOSTREAM = 0xFFFFFFFF, 0xFFFFFFFE, 0xFFFFFFFD, 0xFFFFFFFF
+
+
+def dir_entry(name, kind, left=NOSTREAM, right=NOSTREAM, child=NOSTREAM, start=0, size=0):
+    encoded = (name + "\0").encode("utf-16-le") if name else b""
+    return (encoded.ljust(64, b"\0") + struct.pack("<HBB3I", len(encoded), kind, 1, left, right, child)
+            + b"\0" * 36 + struct.pack("<IQ", start, size))
+
+
+def compound_file(info, package):
+    """MS-CFB 版本 3：扇区 0 为 FAT，1 为目录，2 为 MiniFAT，随后是迷你流容器和 EncryptedPackage"""
+    mini_sectors = -(-len(info) // 64)
+    mini_stream = info.ljust(mini_sectors * 64, b"\0")
+    container_sectors = -(-len(mini_stream) // 512)
+    package_sectors = -(-len(package) // 512)
+    container_start = 3
+    package_start = container_start + container_sectors
+    total_sectors = package_start + package_sectors
+    assert total_sectors <= 128 and mini_sectors <= 128
+
+    chain = lambda start, count: [start + i + 1 for i in range(count - 1)] + [END]
+    fat = [FAT, END, END] + chain(container_start, container_sectors) + chain(package_start, package_sectors)
+    fat += [FREE] * (128 - len(fat))
+    minifat = chain(0, mini_sectors) + [FREE] * (128 - mini_sectors)
+
+    # 同级按名称长度再按大写比较排序：EncryptionInfo < EncryptedPackage
+    directory = (dir_entry("Root Entry", 5, child=2, start=container_start, size=len(mini_stream))
+                 + dir_entry("EncryptionInfo", 2, start=0, size=len(info))
+                 + dir_entry("EncryptedPackage", 2, left=1, start=package_start, size=len(package))
+                 + dir_entry("", 0))
+
+    header = (bytes.fromhex("D0CF11E0A1B11AE1") + b"\0" * 16
+              + struct.pack("<HHHHH", 0x3E, 3, 0xFFFE, 9, 6) + b"\0" * 6
+              + struct.pack("<IIIIIIIIII", 0, 1, 1, 0, 4096, 2, 1, END, 0, 0)
+              + struct.pack("<108I", *[FREE] * 108))
+    assert len(header) == 512
+
+    return (header + struct.pack("<128I", *fat) + directory + struct.pack("<128I", *minifat)
+            + mini_stream.ljust(container_sectors * 512, b"\0") + package.ljust(package_sectors * 512, b"\0"))
+
+
+def main():
+    here = os.path.dirname(os.path.abspath(__file__))
+    plain = plain_workbook()
+    for file_name, hash_name, key_bits in [("encrypted_sha1_aes128.xlsx", "sha1", 128),
+                                           ("encrypted_sha512_aes256.xlsx", "sha512", 256)]:
+        info, package = encrypt_package(plain, hash_name, key_bits)
+        with open(os.path.join(here, file_name), "wb") as f:
+            f.write(compound_file(info, package))
+
+
+if __name__ == "__main__":
+    main()
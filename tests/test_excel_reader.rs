@@ -0,0 +1,229 @@
+use rust_xlsxwriter::{Format, Workbook};
+use sensitive_info_extractor::core::{ExcelInfo, ExcelReader};
+use sensitive_info_extractor::utils::process_dropped_paths;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// 在临时目录生成测试用工作簿（内容为 xlsx，扩展名由调用方指定）
+fn create_workbook(dir: &Path, file_name: &str, rows: &[&[&str]]) -> PathBuf {
+    let path = dir.join(file_name);
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    for (row, values) in rows.iter().enumerate() {
+        for (col, value) in values.iter().enumerate() {
+            worksheet.write_string(row as u32, col as u16, *value).unwrap();
+        }
+    }
+    workbook.save(&path).unwrap();
+
+    path
+}
+
+/// 测试用临时目录，离开作用域时连同其中的文件一起删除
+fn test_dir() -> TempDir {
+    tempfile::Builder::new().prefix("sie_test_").tempdir().unwrap()
+}
+
+fn create_fixture(dir: &Path, file_name: &str) -> PathBuf {
+    create_workbook(dir, file_name, &[&["姓名", "消息内容"], &["张三", "电话13812345678"]])
+}
+
+#[test]
+fn test_read_xlsm_file() {
+    let dir = test_dir();
+    let path = create_fixture(dir.path(), "macro_enabled.xlsm");
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_names = reader.sheet_names();
+    assert_eq!(sheet_names.len(), 1);
+
+    let sheet_data = reader.read_sheet(&sheet_names[0]).unwrap();
+    let column = sheet_data.get_column_by_name("消息内容").unwrap();
+    assert_eq!(column, vec![(1, "电话13812345678".to_string())]);
+
+    let info = ExcelInfo::from_file(&path).unwrap();
+    assert_eq!(info.first_sheet_columns().unwrap(), &vec!["姓名".to_string(), "消息内容".to_string()]);
+}
+
+#[test]
+fn test_dropped_xlsm_file_accepted() {
+    let dir = test_dir();
+    let path = create_fixture(dir.path(), "dropped.xlsm");
+
+    let files = process_dropped_paths(std::slice::from_ref(&path)).unwrap();
+    assert_eq!(files, vec![path]);
+}
+
+#[test]
+fn test_row_count_includes_header() {
+    let dir = test_dir();
+    let path = create_workbook(
+        dir.path(),
+        "row_count.xlsx",
+        &[&["消息内容"], &["第一行"], &["第二行"], &["第三行"]],
+    );
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_name = reader.sheet_names()[0].clone();
+    assert_eq!(reader.row_count(&sheet_name).unwrap(), 4);
+    assert_eq!(reader.data_row_count(&sheet_name, 1).unwrap(), 3);
+    assert_eq!(reader.data_row_count(&sheet_name, 10).unwrap(), 0);
+
+    let info = ExcelInfo::from_file(&path).unwrap();
+    assert_eq!(info.total_row_count(), 3);
+}
+
+#[test]
+fn test_row_count_from_dimension() {
+    let dir = test_dir();
+    let path = create_workbook(
+        dir.path(),
+        "dimension.xlsx",
+        &[&["姓名", "消息内容"], &["张三", "第一行"], &["李四", "第二行"], &["王五", "第三行"]],
+    );
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_name = reader.sheet_names()[0].clone();
+    assert_eq!(reader.declared_row_count(&sheet_name).unwrap(), Some(4));
+    assert_eq!(reader.row_count_from_dimension(&sheet_name).unwrap(), reader.row_count(&sheet_name).unwrap());
+
+    // CSV 没有 dimension，回退为完整读取
+    let csv_path = dir.path().join("dimension.csv");
+    std::fs::write(&csv_path, "消息内容\n第一行\n第二行\n").unwrap();
+    let mut reader = ExcelReader::open(&csv_path).unwrap();
+    assert_eq!(reader.declared_row_count("dimension").unwrap(), None);
+    assert_eq!(reader.row_count_from_dimension("dimension").unwrap(), 3);
+}
+
+#[test]
+fn test_asymmetric_context() {
+    let dir = test_dir();
+    let path = create_workbook(
+        dir.path(),
+        "context.xlsx",
+        &[&["消息内容"], &["第一行"], &["第二行"], &["第三行"], &["第四行"], &["第五行"]],
+    );
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_name = reader.sheet_names()[0].clone();
+    let sheet_data = reader.read_sheet(&sheet_name).unwrap();
+
+    let (before, after) = sheet_data.get_context(2, 1, 2);
+    assert_eq!(before, vec!["第二行".to_string()]);
+    assert_eq!(after, vec!["第四行".to_string(), "第五行".to_string()]);
+
+    let (before, after) = sheet_data.get_context(0, 3, 0);
+    assert!(before.is_empty());
+    assert!(after.is_empty());
+}
+
+#[test]
+fn test_context_cell_separator() {
+    let dir = test_dir();
+    let path = create_workbook(
+        dir.path(),
+        "context_sep.xlsx",
+        &[&["姓名", "消息内容"], &["张三", "上一行"], &["李四", "命中行"], &["王五", "下一行"]],
+    );
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_name = reader.sheet_names()[0].clone();
+    let sheet_data = reader.read_sheet(&sheet_name).unwrap();
+
+    let (before, after) = sheet_data.get_context(1, 1, 1);
+    assert_eq!(before, vec!["张三 | 上一行".to_string()]);
+    assert_eq!(after, vec!["王五 | 下一行".to_string()]);
+
+    let (before, _) = sheet_data.get_context_with_separator(1, 1, 0, "\t");
+    assert_eq!(before, vec!["张三\t上一行".to_string()]);
+}
+
+#[test]
+fn test_date_cells_converted() {
+    let dir = test_dir();
+    let path = dir.path().join("dates.xlsx");
+
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    for (col, header) in ["日期", "时间", "编号", "消息内容"].iter().enumerate() {
+        worksheet.write_string(0, col as u16, *header).unwrap();
+    }
+    worksheet.write_number_with_format(1, 0, 44927.0, &Format::new().set_num_format("yyyy-mm-dd")).unwrap();
+    worksheet.write_number_with_format(1, 1, 45000.75, &Format::new().set_num_format("yyyy/m/d hh:mm")).unwrap();
+    worksheet.write_number(1, 2, 44927.0).unwrap();
+    worksheet.write_string(1, 3, "电话13812345678").unwrap();
+    workbook.save(&path).unwrap();
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_data = reader.read_sheet("Sheet1").unwrap();
+    assert_eq!(sheet_data.rows[1], vec!["2023-01-01", "2023-03-15", "44927", "电话13812345678"]);
+
+    // 关闭后保留序列号，未设日期格式的数字始终按数字读取
+    let mut reader = ExcelReader::open(&path).unwrap().with_convert_date_cells(false);
+    let sheet_data = reader.read_sheet("Sheet1").unwrap();
+    assert_eq!(sheet_data.rows[1][0], "44927");
+    assert_eq!(sheet_data.rows[1][2], "44927");
+}
+
+#[test]
+fn test_read_csv_file() {
+    let dir = test_dir();
+    let path = dir.path().join("messages.csv");
+    std::fs::write(&path, "\u{FEFF}姓名,消息内容\r\n张三,\"电话13812345678, 请回电\"\r\n").unwrap();
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    assert_eq!(reader.sheet_names(), vec!["messages".to_string()]);
+
+    let sheet_data = reader.read_sheet("messages").unwrap();
+    let column = sheet_data.get_column_by_name("消息内容").unwrap();
+    assert_eq!(column, vec![(1, "电话13812345678, 请回电".to_string())]);
+
+    let info = ExcelInfo::from_file(&path).unwrap();
+    assert_eq!(info.total_row_count(), 1);
+}
+
+#[test]
+fn test_read_csv_with_bom_and_padded_headers() {
+    let dir = test_dir();
+    let path = dir.path().join("google_sheets_export.csv");
+    std::fs::write(
+        &path,
+        "\u{FEFF}\u{FEFF}消息内容, \" 发送时间 \"\n请联系13812345678,2024-01-01\n",
+    )
+    .unwrap();
+
+    let mut reader = ExcelReader::open(&path).unwrap();
+    let sheet_data = reader.read_sheet("google_sheets_export").unwrap();
+
+    assert_eq!(sheet_data.column_names(), vec!["消息内容", "发送时间"]);
+    assert_eq!(sheet_data.get_column_index("消息内容"), Some(0));
+    assert_eq!(sheet_data.get_column_index("发送时间"), Some(1));
+    assert_eq!(sheet_data.rows[1][0], "请联系13812345678");
+
+    let columns = reader.read_column_names("google_sheets_export").unwrap();
+    assert_eq!(columns[0], "消息内容");
+}
+
+#[test]
+fn test_dropped_zip_extracts_sheets() {
+    let dir = test_dir();
+    let workbook_path = create_fixture(dir.path(), "zipped.xlsx");
+    let zip_path = dir.path().join("archive.zip");
+
+    let mut archive = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+    let options = zip::write::SimpleFileOptions::default();
+    archive.start_file("data/zipped.xlsx", options).unwrap();
+    archive.write_all(&std::fs::read(&workbook_path).unwrap()).unwrap();
+    archive.start_file("readme.txt", options).unwrap();
+    archive.write_all(b"ignored").unwrap();
+    archive.finish().unwrap();
+
+    let files = process_dropped_paths(&[zip_path, dir.path().join("notes.txt")]).unwrap();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].ends_with("data/zipped.xlsx"));
+
+    let reader = ExcelReader::open(&files[0]).unwrap();
+    assert_eq!(reader.sheet_names().len(), 1);
+}
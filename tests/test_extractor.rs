@@ -1,5 +1,6 @@
-use sensitive_info_extractor::core::InfoExtractor;
-use sensitive_info_extractor::models::Config;
+use sensitive_info_extractor::core::{InfoExtractor, NameExtractor};
+use sensitive_info_extractor::models::{Config, PatternType};
+use std::collections::HashMap;
 use sensitive_info_extractor::utils::{extract_bank_cards, extract_id_cards};
 
 fn create_extractor() -> InfoExtractor {
@@ -75,6 +76,7 @@ fn test_extract_no_sensitive_info() {
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_config_phone_only() {
     let mut config = Config::default();
     config.enable_phone = true;
@@ -94,6 +96,7 @@ fn test_config_phone_only() {
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_config_id_card_only() {
     let mut config = Config::default();
     config.enable_phone = false;
@@ -118,6 +121,7 @@ fn test_config_id_card_only() {
 }
 
 #[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_config_bank_card_only() {
     let mut config = Config::default();
     config.enable_phone = false;
@@ -142,6 +146,30 @@ fn test_config_bank_card_only() {
 }
 
 #[test]
+fn test_name_extraction_with_mock() {
+    let config = Config {
+        enable_name: true,
+        ..Default::default()
+    };
+    let text = "张三的电话是13812345678";
+    let responses = HashMap::from([(text.to_string(), vec!["张三".to_string()])]);
+
+    let extractor = InfoExtractor::new(config).with_name_extractor(NameExtractor::new_mock(responses));
+
+    let (phones, _, _, names) = extractor.extract(text);
+
+    assert_eq!(phones.len(), 1);
+    assert_eq!(names.len(), 1);
+    assert_eq!(names[0].value, "张三");
+    assert_eq!(names[0].position, (0, "张三".len()));
+    assert_eq!(names[0].source_pattern, PatternType::NameApi);
+
+    let (_, _, _, names) = extractor.extract("没有姓名的文本");
+    assert!(names.is_empty());
+}
+
+#[test]
+#[allow(clippy::field_reassign_with_default)]
 fn test_config_all_disabled() {
     let mut config = Config::default();
     config.enable_phone = false;
@@ -1,4 +1,4 @@
-use sensitive_info_extractor::core::validator::Validator;
+use sensitive_info_extractor::core::validators::Validator;
 
 #[test]
 fn test_validate_id_card_valid() {